@@ -0,0 +1,3 @@
+/// Generation of Rust struct definitions from sample documents
+/// Used to bootstrap typed configuration loading from an example file
+pub mod rust;
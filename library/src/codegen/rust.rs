@@ -0,0 +1,390 @@
+//! Rust type generation from sample documents.
+//! generate_rust merges the shape of one or more sample trees and writes Rust struct
+//! definitions that can load them: each dictionary becomes a struct (named after its key
+//! in PascalCase, with a trailing "s" dropped for the items of an array), booleans bool,
+//! integers i64 (u64 when a sample exceeds i64::MAX and none is negative), numbers that
+//! are floats in any sample f64, strings String and arrays Vec. A key missing from some
+//! samples or null in any is an Option, and values whose type differs between samples, or
+//! that are only ever null or empty, are kept as yaml_lib::Node. Field names are
+//! snake_case, with a rename attribute where that differs from the key; keys that would
+//! share a name get a numeric suffix ("my_key_2"), keys already in snake_case keeping
+//! theirs. Comments are ignored.
+
+use crate::nodes::node::{Node, Numeric};
+use std::collections::BTreeMap;
+
+/// The derive macros generated structs carry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CodegenDerives {
+    /// yaml_lib's FromNode and ToNode, with #[yaml(rename)] attributes
+    #[default]
+    Yaml,
+    /// serde's Deserialize and Serialize, with #[serde(rename)] attributes
+    Serde,
+}
+
+/// Default name of the struct generated for the root of the samples.
+pub const DEFAULT_ROOT_STRUCT: &str = "Config";
+
+/// Options used to configure Rust code generation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RustCodegenOptions {
+    /// Name of the struct generated for the root dictionary
+    pub root_name: String,
+    /// The derives written on each struct
+    pub derives: CodegenDerives,
+}
+
+impl RustCodegenOptions {
+    /// Creates a new RustCodegenOptions instance with default settings.
+    ///
+    /// # Returns
+    /// RustCodegenOptions for a "Config" root struct deriving FromNode and ToNode.
+    pub fn new() -> Self {
+        Self { root_name: DEFAULT_ROOT_STRUCT.to_string(), derives: CodegenDerives::Yaml }
+    }
+}
+
+impl Default for RustCodegenOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The merged shape of the values seen at one place in the samples.
+#[derive(Clone, Debug, PartialEq)]
+enum Shape {
+    /// No value seen yet
+    Unknown,
+    /// Only nulls seen
+    Null,
+    Boolean,
+    /// Integers that all fit i64, and whether any is negative
+    Integer { negative: bool },
+    /// Integers, some above i64::MAX and none negative
+    UInteger,
+    Float,
+    Str,
+    /// An array, with the merged shape of its items
+    Array(Box<Shape>),
+    /// A dictionary, with the merged shape of each key and whether every sample had it
+    Struct(BTreeMap<String, (Shape, bool)>),
+    /// A value that may be null or missing
+    Optional(Box<Shape>),
+    /// Values of different types
+    Mixed,
+}
+
+/// Returns the shape of a single value.
+fn shape_of(node: &Node) -> Shape {
+    match node {
+        Node::Boolean(_) => Shape::Boolean,
+        Node::Number(Numeric::Float(_)) => Shape::Float,
+        Node::Number(number) => match number.as_wide_integer().unwrap_or_default() {
+            value if value > i64::MAX as i128 => Shape::UInteger,
+            value => Shape::Integer { negative: value < 0 },
+        },
+        Node::Str(_) => Shape::Str,
+        Node::Array(items) => Shape::Array(Box::new(
+            items
+                .iter()
+                .filter(|item| !item.is_comment())
+                .fold(Shape::Unknown, |shape, item| merge(shape, shape_of(item))),
+        )),
+        Node::Dictionary(_) => Shape::Struct(
+            node.entries()
+                .filter(|(_, value)| !value.is_comment())
+                .map(|(key, value)| (key.to_string(), (shape_of(value), true)))
+                .collect(),
+        ),
+        Node::None | Node::Comment(_) | Node::Document(_) => Shape::Null,
+    }
+}
+
+/// Returns a shape covering the values of two shapes.
+fn merge(a: Shape, b: Shape) -> Shape {
+    match (a, b) {
+        (Shape::Unknown, shape) | (shape, Shape::Unknown) => shape,
+        (Shape::Null, Shape::Null) => Shape::Null,
+        (Shape::Null, Shape::Optional(shape)) | (Shape::Optional(shape), Shape::Null) => Shape::Optional(shape),
+        (Shape::Null, shape) | (shape, Shape::Null) => Shape::Optional(Box::new(shape)),
+        (Shape::Optional(a), Shape::Optional(b)) => Shape::Optional(Box::new(merge(*a, *b))),
+        (Shape::Optional(a), b) | (b, Shape::Optional(a)) => Shape::Optional(Box::new(merge(*a, b))),
+        (Shape::Integer { negative: a }, Shape::Integer { negative: b }) => Shape::Integer { negative: a || b },
+        (Shape::Integer { negative: false }, Shape::UInteger)
+        | (Shape::UInteger, Shape::Integer { negative: false }) => Shape::UInteger,
+        (Shape::Integer { .. } | Shape::UInteger, Shape::Float)
+        | (Shape::Float, Shape::Integer { .. } | Shape::UInteger) => Shape::Float,
+        (Shape::Array(a), Shape::Array(b)) => Shape::Array(Box::new(merge(*a, *b))),
+        (Shape::Struct(mut a), Shape::Struct(b)) => {
+            for (_, (_, always)) in a.iter_mut().filter(|(key, _)| !b.contains_key(*key)) {
+                *always = false;
+            }
+            for (key, (shape, always)) in b {
+                match a.remove(&key) {
+                    Some((existing, present)) => a.insert(key, (merge(existing, shape), present && always)),
+                    None => a.insert(key, (shape, false)),
+                };
+            }
+            Shape::Struct(a)
+        }
+        (a, b) if a == b => a,
+        _ => Shape::Mixed,
+    }
+}
+
+/// Returns a key as a struct name in PascalCase.
+fn pascal_case(key: &str) -> String {
+    let mut name = String::new();
+    for word in key.split(|c: char| !c.is_ascii_alphanumeric()).filter(|word| !word.is_empty()) {
+        let mut chars = word.chars();
+        name.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+        name.push_str(chars.as_str());
+    }
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, 'T');
+    }
+    name
+}
+
+/// Returns a key as a field name in snake_case, as a raw identifier if it is a keyword.
+fn snake_case(key: &str) -> String {
+    const KEYWORDS: [&str; 35] = [
+        "as", "async", "await", "box", "break", "const", "continue", "dyn", "else", "enum", "extern", "false", "fn",
+        "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+        "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    ];
+    let mut name = String::new();
+    let mut previous_lower = false;
+    for c in key.chars() {
+        if c.is_ascii_alphanumeric() {
+            if c.is_ascii_uppercase() && previous_lower {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+            previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        } else {
+            if !name.is_empty() && !name.ends_with('_') {
+                name.push('_');
+            }
+            previous_lower = false;
+        }
+    }
+    let name = name.trim_end_matches('_').to_string();
+    match name.as_str() {
+        "" => "field".to_string(),
+        "self" | "super" | "crate" => format!("{}_", name),
+        _ if name.starts_with(|c: char| c.is_ascii_digit()) => format!("_{}", name),
+        _ if KEYWORDS.contains(&name.as_str()) => format!("r#{}", name),
+        _ => name,
+    }
+}
+
+/// Returns the field name of each key in order, adding a numeric suffix where keys would
+/// otherwise share a name. Keys whose name is the key itself are named first so they keep it.
+fn field_names<'a>(keys: impl Iterator<Item = &'a String> + Clone) -> Vec<String> {
+    let mut taken: Vec<String> = keys.clone().filter(|key| snake_case(key) == **key).cloned().collect();
+    keys.map(|key| {
+        let field = snake_case(key);
+        if field == *key {
+            return field;
+        }
+        let base = field.trim_start_matches("r#").to_string();
+        let mut name = field;
+        let mut suffix = 2;
+        while taken.contains(&name) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        taken.push(name.clone());
+        name
+    })
+    .collect()
+}
+
+/// Writer collecting the struct definitions for a shape.
+struct Generator<'a> {
+    options: &'a RustCodegenOptions,
+    /// Struct definitions in the order they are written, the root first
+    structs: Vec<String>,
+    /// Names already taken by structs
+    names: Vec<String>,
+}
+
+impl Generator<'_> {
+    /// Returns a struct name based on a key that no other struct has.
+    fn unique_name(&mut self, base: String) -> String {
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while self.names.contains(&name) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+        self.names.push(name.clone());
+        name
+    }
+
+    /// Returns the Rust type of a shape, generating the structs it needs.
+    fn type_of(&mut self, shape: &Shape, name: &str) -> String {
+        match shape {
+            Shape::Boolean => "bool".to_string(),
+            Shape::Integer { .. } => "i64".to_string(),
+            Shape::UInteger => "u64".to_string(),
+            Shape::Float => "f64".to_string(),
+            Shape::Str => "String".to_string(),
+            Shape::Array(items) => {
+                let singular = name.strip_suffix('s').filter(|stem| stem.len() > 1 && !stem.ends_with('s'));
+                format!("Vec<{}>", self.type_of(items, singular.unwrap_or(name)))
+            }
+            Shape::Struct(fields) => self.structure(fields, pascal_case(name)),
+            Shape::Optional(shape) => format!("Option<{}>", self.type_of(shape, name)),
+            Shape::Unknown | Shape::Null | Shape::Mixed => "yaml_lib::Node".to_string(),
+        }
+    }
+
+    /// Generates a struct for a dictionary shape and returns its name.
+    fn structure(&mut self, fields: &BTreeMap<String, (Shape, bool)>, base: String) -> String {
+        let name = self.unique_name(base);
+        let index = self.structs.len();
+        self.structs.push(String::new());
+        let (derives, attribute) = match self.options.derives {
+            CodegenDerives::Yaml => ("FromNode, ToNode", "yaml"),
+            CodegenDerives::Serde => ("Deserialize, Serialize", "serde"),
+        };
+        let mut text = format!("#[derive(Debug, Clone, PartialEq, {})]\npub struct {} {{\n", derives, name);
+        let names = field_names(fields.keys());
+        for ((key, (shape, always)), field) in fields.iter().zip(names) {
+            let shape = match shape {
+                Shape::Optional(_) | Shape::Null | Shape::Mixed => shape.clone(),
+                _ if !always => Shape::Optional(Box::new(shape.clone())),
+                _ => shape.clone(),
+            };
+            if field.trim_start_matches("r#") != key {
+                text.push_str(&format!("    #[{}(rename = {:?})]\n", attribute, key));
+            }
+            text.push_str(&format!("    pub {}: {},\n", field, self.type_of(&shape, key)));
+        }
+        text.push_str("}\n");
+        self.structs[index] = text;
+        name
+    }
+}
+
+/// Generates Rust struct definitions able to load every sample.
+///
+/// # Arguments
+/// * `samples` - Sample trees, each a Dictionary or a stream of dictionaries
+/// * `options` - Root struct name and derive settings
+///
+/// # Returns
+/// Source text with a use line for the derives followed by the root struct and the
+/// structs it refers to, or an error if a sample is not a dictionary.
+pub fn generate_rust(samples: &[Node], options: &RustCodegenOptions) -> Result<String, String> {
+    let mut shape = Shape::Unknown;
+    let documents = samples.iter().flat_map(|sample| match sample {
+        Node::Document(documents) => documents.iter().filter(|document| !document.is_comment()).collect(),
+        _ => vec![sample],
+    });
+    for document in documents {
+        if !matches!(document, Node::Dictionary(_)) {
+            return Err(format!("Rust structs can only be generated from dictionaries, not a {}", document.kind()));
+        }
+        shape = merge(shape, shape_of(document));
+    }
+    let Shape::Struct(fields) = shape else {
+        return Err("Rust structs need at least one sample".to_string());
+    };
+    let mut generator = Generator { options, structs: Vec::new(), names: Vec::new() };
+    generator.structure(&fields, options.root_name.clone());
+    let header = match options.derives {
+        CodegenDerives::Yaml => "use yaml_lib::{FromNode, ToNode};\n",
+        CodegenDerives::Serde => "use serde::{Deserialize, Serialize};\n",
+    };
+    Ok(format!("{}\n{}", header, generator.structs.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_rust_works() {
+        let first = Node::dict()
+            .key("name", "web")
+            .key("maxConnections", 10)
+            .key("type", "http")
+            .key("servers", vec![Node::dict().key("host", "a").key("port", 80).build()])
+            .key("ratio", 1)
+            .key("extra", Node::None)
+            .build();
+        let second = Node::dict()
+            .key("name", "db")
+            .key("maxConnections", 20)
+            .key("type", "tcp")
+            .key("servers", vec![Node::dict().key("host", "b").key("weight", 0.5).build()])
+            .key("ratio", 0.5)
+            .key("tags", vec!["x"])
+            .build();
+        let expected = concat!(
+            "use yaml_lib::{FromNode, ToNode};\n",
+            "\n",
+            "#[derive(Debug, Clone, PartialEq, FromNode, ToNode)]\n",
+            "pub struct Config {\n",
+            "    pub extra: yaml_lib::Node,\n",
+            "    #[yaml(rename = \"maxConnections\")]\n",
+            "    pub max_connections: i64,\n",
+            "    pub name: String,\n",
+            "    pub ratio: f64,\n",
+            "    pub servers: Vec<Server>,\n",
+            "    pub tags: Option<Vec<String>>,\n",
+            "    pub r#type: String,\n",
+            "}\n",
+            "\n",
+            "#[derive(Debug, Clone, PartialEq, FromNode, ToNode)]\n",
+            "pub struct Server {\n",
+            "    pub host: String,\n",
+            "    pub port: Option<i64>,\n",
+            "    pub weight: Option<f64>,\n",
+            "}\n",
+        );
+        assert_eq!(generate_rust(&[first, second], &RustCodegenOptions::new()).unwrap(), expected);
+    }
+
+    #[test]
+    fn generate_rust_with_serde_and_errors() {
+        let sample = Node::dict().key("db", Node::dict().key("self", true).build()).key("2fa", "x").build();
+        let options = RustCodegenOptions { root_name: "App".to_string(), derives: CodegenDerives::Serde };
+        let text = generate_rust(&[sample], &options).unwrap();
+        assert!(text.starts_with("use serde::{Deserialize, Serialize};\n\n#[derive(Debug, Clone, PartialEq, Deser"));
+        assert!(text.contains("    #[serde(rename = \"2fa\")]\n    pub _2fa: String,\n    pub db: Db,\n"));
+        assert!(text.contains("    #[serde(rename = \"self\")]\n    pub self_: bool,\n"));
+        let error = generate_rust(&[Node::from(vec![1])], &options).unwrap_err();
+        assert_eq!(error, "Rust structs can only be generated from dictionaries, not a array");
+        assert!(generate_rust(&[], &options).is_err());
+    }
+
+    #[test]
+    fn generate_rust_names_and_integer_widths() {
+        let sample = Node::dict()
+            .key("my-key", 1)
+            .key("my_key", 2)
+            .key("My Key", 3)
+            .key("big", u64::MAX)
+            .key("count", 1)
+            .key("offset", u64::MAX)
+            .build();
+        let second = Node::dict().key("count", u64::MAX).key("offset", -1).build();
+        let text = generate_rust(&[sample, second], &RustCodegenOptions::new()).unwrap();
+        let expected = concat!(
+            "    #[yaml(rename = \"My Key\")]\n",
+            "    pub my_key_2: Option<i64>,\n",
+            "    pub big: Option<u64>,\n",
+            "    pub count: u64,\n",
+            "    #[yaml(rename = \"my-key\")]\n",
+            "    pub my_key_3: Option<i64>,\n",
+            "    pub my_key: Option<i64>,\n",
+            "    pub offset: yaml_lib::Node,\n",
+        );
+        assert!(text.contains(expected), "{}", text);
+    }
+}
@@ -0,0 +1,218 @@
+//! Three-way merge of node trees.
+//! merge3 combines the changes two sides made to a common base the way git merges text:
+//! a value changed on only one side takes that side's value, dictionaries changed on both
+//! sides are merged key by key, and arrays of unchanged length item by item. Anything
+//! else changed differently on both sides is a conflict, resolved in favour of ours.
+//! Values are compared with Node::strict_eq, so changing a number's type (80 to 80.0)
+//! counts as a change. Merged dictionaries keep our key order, followed by keys only
+//! theirs holds and then keys only the base holds, in their own order.
+
+use crate::nodes::node::Node;
+use crate::nodes::path::join;
+use indexmap::{IndexMap, IndexSet};
+
+/// A path changed differently on both sides of a three-way merge. A None value means the
+/// path is absent on that side.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+    /// JSON-pointer path of the conflicting value
+    pub path: String,
+    /// Value in the common base
+    pub base: Option<Node>,
+    /// Value on our side, which the merged tree keeps
+    pub ours: Option<Node>,
+    /// Value on their side
+    pub theirs: Option<Node>,
+}
+
+/// Result of a three-way merge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Merge3 {
+    /// The merged tree; conflicting paths hold our value
+    pub merged: Node,
+    /// Paths changed differently on both sides, in the order the merge reaches them
+    /// (dictionary keys in merged key order)
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Merge3 {
+    /// Returns true if the merge completed without conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Merges the changes two sides made to a common base.
+///
+/// # Arguments
+/// * `base` - The common ancestor
+/// * `ours` - Our changed tree
+/// * `theirs` - Their changed tree
+///
+/// # Returns
+/// The merged tree together with any conflicts.
+pub fn merge3(base: &Node, ours: &Node, theirs: &Node) -> Merge3 {
+    let mut conflicts = Vec::new();
+    let merged = merge_at(Some(base), Some(ours), Some(theirs), "", &mut conflicts).unwrap_or(Node::None);
+    Merge3 { merged, conflicts }
+}
+
+/// Returns true if both sides hold the same value, numbers stored in the same Numeric
+/// variant, or both lack it.
+fn same(a: Option<&Node>, b: Option<&Node>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.strict_eq(b),
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+fn merge_at(
+    base: Option<&Node>,
+    ours: Option<&Node>,
+    theirs: Option<&Node>,
+    path: &str,
+    conflicts: &mut Vec<Conflict>,
+) -> Option<Node> {
+    if same(ours, theirs) || same(theirs, base) {
+        return ours.cloned();
+    }
+    if same(ours, base) {
+        return theirs.cloned();
+    }
+    match (base, ours, theirs) {
+        (base, Some(Node::Dictionary(mine)), Some(Node::Dictionary(other)))
+            if base.is_none_or(|base| matches!(base, Node::Dictionary(_))) =>
+        {
+            let original = base.and_then(Node::as_dict);
+            let base_keys = original.into_iter().flat_map(|map| map.keys());
+            let keys: IndexSet<&String> = mine.keys().chain(other.keys()).chain(base_keys).collect();
+            let mut merged = IndexMap::new();
+            for key in keys {
+                let base_value = original.and_then(|map| map.get(key));
+                if let Some(value) = merge_at(base_value, mine.get(key), other.get(key), &join(path, key), conflicts) {
+                    merged.insert(key.clone(), value);
+                }
+            }
+            Some(Node::Dictionary(merged))
+        }
+        (Some(Node::Array(original)), Some(Node::Array(mine)), Some(Node::Array(other)))
+            if original.len() == mine.len() && mine.len() == other.len() =>
+        {
+            let items = (0..original.len())
+                .map(|index| {
+                    let item_path = join(path, &index.to_string());
+                    merge_at(original.get(index), mine.get(index), other.get(index), &item_path, conflicts)
+                        .unwrap_or(Node::None)
+                })
+                .collect();
+            Some(Node::Array(items))
+        }
+        _ => {
+            conflicts.push(Conflict {
+                path: path.to_string(),
+                base: base.cloned(),
+                ours: ours.cloned(),
+                theirs: theirs.cloned(),
+            });
+            ours.cloned()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Node {
+        Node::dict()
+            .key("name", "web")
+            .key("server", Node::dict().key("host", "a").key("port", 80))
+            .key("tags", vec!["x", "y"])
+            .build()
+    }
+
+    #[test]
+    fn merge3_combines_independent_changes() {
+        let mut ours = base();
+        ours["server"]["port"] = Node::from(8080);
+        ours["tags"][0] = Node::from("z");
+        let mut theirs = base();
+        theirs["server"]["host"] = Node::from("b");
+        theirs["extra"] = Node::from(true);
+        if let Node::Dictionary(map) = &mut theirs {
+            map.shift_remove("name");
+        }
+        let result = merge3(&base(), &ours, &theirs);
+        assert!(result.is_clean());
+        assert_eq!(result.merged["server"]["port"], Node::from(8080));
+        assert_eq!(result.merged["server"]["host"], Node::from("b"));
+        assert_eq!(result.merged["tags"], Node::from(vec!["z", "y"]));
+        assert_eq!(result.merged["extra"], Node::from(true));
+        assert!(result.merged.get("name").is_none());
+    }
+
+    #[test]
+    fn merge3_reports_conflicts() {
+        let mut ours = base();
+        ours["server"]["port"] = Node::from(1);
+        ours["tags"] = Node::from(vec!["x"]);
+        let mut theirs = base();
+        theirs["server"]["port"] = Node::from(2);
+        theirs["tags"] = Node::from(vec!["x", "y", "w"]);
+        let result = merge3(&base(), &ours, &theirs);
+        assert_eq!(result.conflicts.len(), 2);
+        assert_eq!(result.conflicts[0].path, "/server/port");
+        assert_eq!(result.conflicts[0].base, Some(Node::from(80)));
+        assert_eq!(result.conflicts[0].theirs, Some(Node::from(2)));
+        assert_eq!(result.conflicts[1].path, "/tags");
+        assert_eq!(result.merged["server"]["port"], Node::from(1));
+    }
+
+    #[test]
+    fn merge3_conflicts_on_delete_versus_change() {
+        let mut ours = base();
+        if let Node::Dictionary(map) = &mut ours {
+            map.shift_remove("name");
+        }
+        let mut theirs = base();
+        theirs["name"] = Node::from("api");
+        let result = merge3(&base(), &ours, &theirs);
+        assert_eq!(result.conflicts[0].ours, None);
+        assert!(result.merged.get("name").is_none());
+    }
+
+    #[test]
+    fn merge3_merges_keys_added_on_both_sides() {
+        let ours = Node::dict().key("a", Node::dict().key("x", 1)).build();
+        let theirs = Node::dict().key("a", Node::dict().key("y", 2)).build();
+        let result = merge3(&Node::dict().build(), &ours, &theirs);
+        assert!(result.is_clean());
+        assert_eq!(result.merged["a"]["y"], Node::from(2));
+        assert_eq!(result.merged["a"]["x"], Node::from(1));
+    }
+
+    #[test]
+    fn merge3_keeps_our_key_order() {
+        let base = Node::dict().key("z", 1).key("gone", 2).key("a", 3).build();
+        let ours = Node::dict().key("z", 1).key("m", 4).key("gone", 2).key("a", 3).build();
+        let theirs = Node::dict().key("b", 5).key("a", 6).key("z", 1).build();
+        let result = merge3(&base, &ours, &theirs);
+        assert!(result.is_clean());
+        let keys: Vec<&str> = result.merged.as_dict().unwrap().keys().map(String::as_str).collect();
+        assert_eq!(keys, ["z", "m", "a", "b"]);
+        assert_eq!(result.merged["a"], Node::from(6));
+    }
+
+    #[test]
+    fn merge3_keeps_numeric_type_changes() {
+        let base = Node::dict().key("port", 80).key("ratio", 1.0).build();
+        let ours = Node::dict().key("port", 80.0).key("ratio", 1.0).build();
+        let theirs = Node::dict().key("port", 80).key("ratio", 1).build();
+        let result = merge3(&base, &ours, &theirs);
+        assert!(result.is_clean());
+        assert!(result.merged["port"].strict_eq(&Node::from(80.0)));
+        assert!(result.merged["ratio"].strict_eq(&Node::from(1)));
+        let theirs = Node::dict().key("port", 80u16).key("ratio", 1.0).build();
+        assert_eq!(merge3(&base, &ours, &theirs).conflicts.len(), 1);
+    }
+}
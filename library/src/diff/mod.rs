@@ -0,0 +1,6 @@
+/// Patch operations describing changes between node trees
+pub mod operation;
+/// Structural diff of two node trees
+pub mod structural;
+/// Three-way merge of node trees with conflict reporting
+pub mod merge3;
@@ -0,0 +1,134 @@
+//! Patch operations describing changes to a node tree.
+//! Operations are addressed by JSON-pointer paths and convert to and from the JSON Patch
+//! (RFC 6902) document form {"op": "add", "path": "/a", "value": 1}.
+
+use crate::error::conversion::ConversionError;
+use crate::nodes::node::Node;
+use crate::nodes::traits::{from_field, FromNode, ToNode};
+use indexmap::IndexMap;
+
+/// A single change to a node tree.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operation {
+    /// Adds a value: inserts a dictionary key, or inserts into an array at an index
+    /// (shifting later items) or at the end when the last segment is "-"
+    Add { path: String, value: Node },
+    /// Removes the dictionary key or array item at a path
+    Remove { path: String },
+    /// Replaces the value at a path
+    Replace { path: String, value: Node },
+    /// Removes the value at from and adds it at path
+    Move { from: String, path: String },
+    /// Adds a copy of the value at from at path
+    Copy { from: String, path: String },
+    /// Checks that the value at path equals a value, failing the patch otherwise
+    Test { path: String, value: Node },
+}
+
+impl Operation {
+    /// Returns the RFC 6902 name of the operation ("add", "remove", "replace", "move",
+    /// "copy" or "test").
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::Add { .. } => "add",
+            Operation::Remove { .. } => "remove",
+            Operation::Replace { .. } => "replace",
+            Operation::Move { .. } => "move",
+            Operation::Copy { .. } => "copy",
+            Operation::Test { .. } => "test",
+        }
+    }
+
+    /// Returns the path the operation applies to.
+    pub fn path(&self) -> &str {
+        match self {
+            Operation::Add { path, .. }
+            | Operation::Remove { path }
+            | Operation::Replace { path, .. }
+            | Operation::Move { path, .. }
+            | Operation::Copy { path, .. }
+            | Operation::Test { path, .. } => path,
+        }
+    }
+}
+
+/// Converts an operation into its patch document form.
+impl ToNode for Operation {
+    fn to_node(&self) -> Node {
+        let mut map = IndexMap::new();
+        map.insert("op".to_string(), Node::from(self.name()));
+        map.insert("path".to_string(), Node::from(self.path()));
+        match self {
+            Operation::Add { value, .. } | Operation::Replace { value, .. } | Operation::Test { value, .. } => {
+                map.insert("value".to_string(), value.clone());
+            }
+            Operation::Move { from, .. } | Operation::Copy { from, .. } => {
+                map.insert("from".to_string(), Node::from(from.as_str()));
+            }
+            Operation::Remove { .. } => {}
+        }
+        Node::Dictionary(map)
+    }
+}
+
+/// Reads an operation from its patch document form.
+impl FromNode for Operation {
+    fn from_node(node: &Node) -> Result<Self, ConversionError> {
+        let op: String = from_field(node, "op")?;
+        let path: String = from_field(node, "path")?;
+        let value = || node.get("value").cloned().ok_or_else(|| ConversionError::new("value", &Node::None).within("value"));
+        Ok(match op.as_str() {
+            "add" => Operation::Add { path, value: value()? },
+            "remove" => Operation::Remove { path },
+            "replace" => Operation::Replace { path, value: value()? },
+            "move" => Operation::Move { from: from_field(node, "from")?, path },
+            "copy" => Operation::Copy { from: from_field(node, "from")?, path },
+            "test" => Operation::Test { path, value: value()? },
+            _ => return Err(ConversionError::new("patch operation name", &node["op"]).within("op")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_node_works() {
+        let node = Operation::Add { path: "/a".to_string(), value: Node::from(1) }.to_node();
+        assert_eq!(node["op"], Node::from("add"));
+        assert_eq!(node["path"], Node::from("/a"));
+        assert_eq!(node["value"], Node::from(1));
+        let node = Operation::Remove { path: "/a".to_string() }.to_node();
+        assert_eq!(node["op"], Node::from("remove"));
+        assert!(node.get("value").is_none());
+        let node = Operation::Move { from: "/a".to_string(), path: "/b".to_string() }.to_node();
+        assert_eq!(node["from"], Node::from("/a"));
+        let patch = vec![Operation::Replace { path: String::new(), value: Node::None }].to_node();
+        assert_eq!(patch[0]["op"], Node::from("replace"));
+    }
+
+    #[test]
+    fn from_node_round_trips() {
+        let operations = vec![
+            Operation::Add { path: "/a".to_string(), value: Node::from(1) },
+            Operation::Remove { path: "/b".to_string() },
+            Operation::Replace { path: "/c".to_string(), value: Node::None },
+            Operation::Move { from: "/d".to_string(), path: "/e".to_string() },
+            Operation::Copy { from: "/f".to_string(), path: "/g".to_string() },
+            Operation::Test { path: "/h".to_string(), value: Node::from("x") },
+        ];
+        assert_eq!(Vec::<Operation>::from_node(&operations.to_node()), Ok(operations));
+    }
+
+    #[test]
+    fn from_node_errors_work() {
+        let node = Node::dict().key("op", "delete").key("path", "/a").build();
+        assert_eq!(Operation::from_node(&node).unwrap_err().path, "/op");
+        let node = Node::dict().key("op", "add").key("path", "/a").build();
+        assert_eq!(Operation::from_node(&node).unwrap_err().path, "/value");
+        let node = Node::dict().key("op", "move").key("path", "/a").build();
+        assert_eq!(Operation::from_node(&node).unwrap_err().path, "/from");
+        assert!(Operation::from_node(&Node::from("add")).is_err());
+    }
+}
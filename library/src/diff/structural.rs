@@ -0,0 +1,121 @@
+//! Structural diff of node trees.
+//! diff compares two trees and returns the operations that turn the first into the
+//! second: dictionaries are compared key by key (in sorted key order), arrays item by
+//! item with extra items added or removed at the end, and anything else that differs is
+//! replaced. Numbers differ when their Numeric variants do (80 and 80.0 are not equal
+//! here), so a change of type is not lost.
+
+use crate::diff::operation::Operation;
+use crate::nodes::node::Node;
+use crate::nodes::path::join;
+
+/// Returns the operations that turn one tree into another.
+///
+/// # Arguments
+/// * `from` - The original tree
+/// * `to` - The changed tree
+///
+/// # Returns
+/// Operations to apply in order; empty when the trees are equal.
+pub fn diff(from: &Node, to: &Node) -> Vec<Operation> {
+    let mut operations = Vec::new();
+    diff_at(from, to, "", &mut operations);
+    operations
+}
+
+fn diff_at(from: &Node, to: &Node, path: &str, operations: &mut Vec<Operation>) {
+    if from.strict_eq(to) {
+        return;
+    }
+    match (from, to) {
+        (Node::Dictionary(old), Node::Dictionary(new)) => {
+            for (key, value) in from.entries() {
+                match new.get(key) {
+                    Some(changed) => diff_at(value, changed, &join(path, key), operations),
+                    None => operations.push(Operation::Remove { path: join(path, key) }),
+                }
+            }
+            for (key, value) in to.entries().filter(|(key, _)| !old.contains_key(*key)) {
+                operations.push(Operation::Add { path: join(path, key), value: value.clone() });
+            }
+        }
+        (Node::Array(old), Node::Array(new)) | (Node::Document(old), Node::Document(new)) => {
+            for (index, (value, changed)) in old.iter().zip(new).enumerate() {
+                diff_at(value, changed, &join(path, &index.to_string()), operations);
+            }
+            for (index, value) in new.iter().enumerate().skip(old.len()) {
+                operations.push(Operation::Add { path: join(path, &index.to_string()), value: value.clone() });
+            }
+            for index in (new.len()..old.len()).rev() {
+                operations.push(Operation::Remove { path: join(path, &index.to_string()) });
+            }
+        }
+        _ => operations.push(Operation::Replace { path: path.to_string(), value: to.clone() }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_of_equal_trees_is_empty() {
+        let node = Node::dict().key("a", vec![1, 2]).build();
+        assert!(diff(&node, &node.clone()).is_empty());
+    }
+
+    #[test]
+    fn diff_dictionaries_works() {
+        let from = Node::dict().key("a", 1).key("b", 2).key("c", Node::dict().key("d", 1)).build();
+        let to = Node::dict().key("b", 3).key("c", Node::dict().key("d", 1).key("e", 2)).key("f", true).build();
+        assert_eq!(
+            diff(&from, &to),
+            vec![
+                Operation::Remove { path: "/a".to_string() },
+                Operation::Replace { path: "/b".to_string(), value: Node::from(3) },
+                Operation::Add { path: "/c/e".to_string(), value: Node::from(2) },
+                Operation::Add { path: "/f".to_string(), value: Node::from(true) },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_arrays_works() {
+        let from = Node::from(vec![1, 2, 3, 4]);
+        let to = Node::from(vec![1, 5]);
+        assert_eq!(
+            diff(&from, &to),
+            vec![
+                Operation::Replace { path: "/1".to_string(), value: Node::from(5) },
+                Operation::Remove { path: "/3".to_string() },
+                Operation::Remove { path: "/2".to_string() },
+            ]
+        );
+        assert_eq!(
+            diff(&to, &Node::from(vec![1, 5, 6])),
+            vec![Operation::Add { path: "/2".to_string(), value: Node::from(6) }]
+        );
+    }
+
+    #[test]
+    fn diff_replaces_changed_types() {
+        assert_eq!(
+            diff(&Node::from(vec![1]), &Node::from("a")),
+            vec![Operation::Replace { path: String::new(), value: Node::from("a") }]
+        );
+        let from = Node::dict().key("a/b", 1).build();
+        let to = Node::dict().key("a/b", Node::None).build();
+        assert_eq!(diff(&from, &to)[0].path(), "/a~1b");
+    }
+
+    #[test]
+    fn diff_replaces_changed_numeric_types() {
+        let from = Node::dict().key("port", 80).build();
+        let to = Node::dict().key("port", 80.0).build();
+        assert_eq!(
+            diff(&from, &to),
+            vec![Operation::Replace { path: "/port".to_string(), value: Node::from(80.0) }]
+        );
+        assert!(diff(&from, &from.clone()).is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+//! Error type for converting Nodes into Rust values.
+
+use crate::nodes::node::{Node, Numeric};
+use crate::nodes::path::escape_segment;
+use std::convert::Infallible;
+use std::fmt;
+
+/// Error returned when a Node cannot be converted into the requested Rust type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConversionError {
+    /// Description of the type the node was being converted to (for example "u16")
+    pub expected: String,
+    /// Description of the node that was found instead (for example "string" or "number 70000")
+    pub found: String,
+    /// JSON-pointer path of the offending node within a converted collection ("" for the root)
+    pub path: String,
+}
+
+impl ConversionError {
+    /// Creates an error for a node that could not be converted.
+    ///
+    /// # Arguments
+    /// * `expected` - Description of the target type
+    /// * `node` - The node that could not be converted
+    pub fn new(expected: &str, node: &Node) -> Self {
+        Self { expected: expected.to_string(), found: describe(node), path: String::new() }
+    }
+
+    /// Prefixes the error path with the array index or dictionary key of the failing item.
+    ///
+    /// # Arguments
+    /// * `segment` - Index or key of the item within its parent collection
+    pub fn within(mut self, segment: &str) -> Self {
+        self.path = format!("/{}{}", escape_segment(segment), self.path);
+        self
+    }
+}
+
+/// Describes a node for use in conversion error messages; numbers include their value.
+fn describe(node: &Node) -> String {
+    match node {
+        Node::Boolean(_) => "boolean".to_string(),
+        Node::Number(Numeric::Float(value)) => format!("number {}", value),
+        Node::Number(number) => match number.as_wide_integer() {
+            Some(value) => format!("number {}", value),
+            None => "number".to_string(),
+        },
+        Node::Str(_) => "string".to_string(),
+        Node::Array(_) => "array".to_string(),
+        Node::Dictionary(_) => "dictionary".to_string(),
+        Node::Comment(_) => "comment".to_string(),
+        Node::Document(_) => "document".to_string(),
+        Node::None => "null".to_string(),
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Expected {} but found {}", self.expected, self.found)?;
+        if !self.path.is_empty() {
+            write!(f, " at {}", self.path)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+/// Allows infallible conversions (such as Node into Node) to be used where a
+/// ConversionError is expected
+impl From<Infallible> for ConversionError {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_conversion_error_works() {
+        assert_eq!(ConversionError::new("u16", &Node::from("x")).to_string(), "Expected u16 but found string");
+        assert_eq!(ConversionError::new("u8", &Node::from(300)).to_string(), "Expected u8 but found number 300");
+        assert_eq!(ConversionError::new("bool", &Node::None).to_string(), "Expected bool but found null");
+    }
+
+    #[test]
+    fn within_prefixes_path() {
+        let error = ConversionError::new("u16", &Node::from("x")).within("1").within("a/b");
+        assert_eq!(error.path, "/a~1b/1");
+        assert_eq!(error.to_string(), "Expected u16 but found string at /a~1b/1");
+    }
+}
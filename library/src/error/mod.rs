@@ -0,0 +1,10 @@
+/// Error type returned by the YAML parser
+/// Distinguishes syntax errors from cooperative cancellation
+pub mod parse;
+/// Error type returned when converting Nodes into Rust values
+pub mod conversion;
+/// Error type returned when a patch cannot be applied to a Node tree
+pub mod patch;
+/// Error type returned by serde serialization and deserialization to and from Nodes and YAML text
+#[cfg(feature = "serde")]
+pub mod serialization;
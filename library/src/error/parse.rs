@@ -0,0 +1,52 @@
+//! Error type for YAML parsing operations.
+
+use std::fmt;
+
+/// Errors that can be returned while parsing YAML.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+    /// The input is not valid YAML; contains a description of the problem
+    Syntax(String),
+    /// The parse was aborted via a cancellation token or because its deadline passed
+    Cancelled,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Syntax(message) => write!(f, "{}", message),
+            ParseError::Cancelled => write!(f, "Parse cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Converts a syntax error message into a ParseError
+impl From<String> for ParseError {
+    fn from(message: String) -> Self {
+        ParseError::Syntax(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_syntax_error_works() {
+        let error = ParseError::Syntax("Unexpected character: @".to_string());
+        assert_eq!(error.to_string(), "Unexpected character: @");
+    }
+
+    #[test]
+    fn display_cancelled_error_works() {
+        assert_eq!(ParseError::Cancelled.to_string(), "Parse cancelled");
+    }
+
+    #[test]
+    fn from_string_creates_syntax_error() {
+        let error = ParseError::from("bad".to_string());
+        assert_eq!(error, ParseError::Syntax("bad".to_string()));
+    }
+}
@@ -0,0 +1,40 @@
+//! Error type for applying patches to Node trees.
+
+use std::fmt;
+
+/// Error returned when a patch operation cannot be applied.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatchError {
+    /// Index of the failing operation within the patch
+    pub index: usize,
+    /// Name of the failing operation (for example "remove")
+    pub operation: String,
+    /// Path the failing operation applies to
+    pub path: String,
+    /// Description of why the operation failed
+    pub reason: String,
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Patch operation {} ({} at '{}') failed: {}", self.index, self.operation, self.path, self.reason)
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_works() {
+        let error = PatchError {
+            index: 2,
+            operation: "remove".to_string(),
+            path: "/a".to_string(),
+            reason: "path does not exist".to_string(),
+        };
+        assert_eq!(error.to_string(), "Patch operation 2 (remove at '/a') failed: path does not exist");
+    }
+}
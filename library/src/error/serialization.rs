@@ -0,0 +1,57 @@
+//! Error type for serde serialization and deserialization to and from Nodes and YAML text.
+
+use crate::error::parse::ParseError;
+use std::fmt;
+
+/// Errors returned when serializing or deserializing Rust values with serde.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SerializationError {
+    /// A value could not be mapped to or from a Node or YAML text; contains a description of
+    /// the problem
+    Message(String),
+    /// The YAML text could not be parsed
+    Parse(ParseError),
+}
+
+impl fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerializationError::Message(message) => write!(f, "{}", message),
+            SerializationError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for SerializationError {}
+
+impl serde::de::Error for SerializationError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        SerializationError::Message(message.to_string())
+    }
+}
+
+impl serde::ser::Error for SerializationError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        SerializationError::Message(message.to_string())
+    }
+}
+
+/// Wraps a parser error
+impl From<ParseError> for SerializationError {
+    fn from(error: ParseError) -> Self {
+        SerializationError::Parse(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_serialization_error_works() {
+        let error = <SerializationError as serde::de::Error>::custom("missing field `port`");
+        assert_eq!(error.to_string(), "missing field `port`");
+        let error = SerializationError::from(ParseError::Cancelled);
+        assert_eq!(error.to_string(), "Parse cancelled");
+    }
+}
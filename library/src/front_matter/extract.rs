@@ -0,0 +1,129 @@
+//! Front matter extraction for Markdown and other text content.
+//! Locates a leading "---" delimited YAML block, parses it into a Node
+//! and returns it along with the remaining body text.
+
+use crate::io::sources::buffer::Buffer;
+use crate::nodes::node::Node;
+use crate::parser::default::parse;
+
+/// Returns true if the line is a front matter delimiter ("---" with optional trailing whitespace).
+fn is_delimiter(line: &str) -> bool {
+    line.trim_end() == "---"
+}
+
+/// Returns true if the line closes a front matter block ("---" or "...").
+fn is_closing_delimiter(line: &str) -> bool {
+    is_delimiter(line) || line.trim_end() == "..."
+}
+
+/// Extracts and parses the front matter block from the start of some text.
+///
+/// # Arguments
+/// * `text` - The full text content (for example a Markdown post)
+///
+/// # Returns
+/// A tuple of the parsed front matter Node and the remaining body text. If the text has no
+/// front matter, Node::None and the unchanged text are returned. An error is returned if the
+/// block is not terminated or its YAML cannot be parsed.
+pub fn extract(text: &str) -> Result<(Node, String), String> {
+    let content = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut lines = content.split_inclusive('\n');
+    match lines.next() {
+        Some(first) if is_delimiter(first) => {}
+        _ => return Ok((Node::None, text.to_string())),
+    }
+    let mut yaml = String::new();
+    let mut consumed = content.find('\n').map_or(content.len(), |index| index + 1);
+    for line in lines {
+        consumed += line.len();
+        if is_closing_delimiter(line) {
+            let mut source = Buffer::new(yaml.as_bytes());
+            let node = parse(&mut source)?;
+            return Ok((node, content[consumed..].to_string()));
+        }
+        yaml.push_str(line);
+    }
+    Err("Unterminated front matter block".to_string())
+}
+
+/// Reads a text file and extracts its front matter.
+///
+/// # Arguments
+/// * `path` - The path of the file to read
+///
+/// # Returns
+/// A tuple of the parsed front matter Node and the remaining body text, or an error if the
+/// file cannot be read or the front matter is invalid.
+pub fn extract_from_file(path: &str) -> Result<(Node, String), String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    extract(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::node::Numeric;
+    use indexmap::IndexMap;
+    use std::fs;
+
+    #[test]
+    fn extract_front_matter_works() {
+        let (node, body) = extract("---\ntitle: Hello\nweight: 10\n---\n# Heading\nBody text\n").unwrap();
+        let mut expected = IndexMap::new();
+        expected.insert("title".to_string(), Node::Str("Hello".to_string()));
+        expected.insert("weight".to_string(), Node::Number(Numeric::Integer(10)));
+        assert_eq!(node, Node::Dictionary(expected));
+        assert_eq!(body, "# Heading\nBody text\n");
+    }
+
+    #[test]
+    fn extract_without_front_matter_returns_text() {
+        let (node, body) = extract("# Heading\nBody text\n").unwrap();
+        assert_eq!(node, Node::None);
+        assert_eq!(body, "# Heading\nBody text\n");
+    }
+
+    #[test]
+    fn extract_empty_front_matter_works() {
+        let (node, body) = extract("---\n---\nBody").unwrap();
+        assert_eq!(node, Node::None);
+        assert_eq!(body, "Body");
+    }
+
+    #[test]
+    fn extract_with_dots_terminator_works() {
+        let (node, body) = extract("---\n- a\n- b\n...\nBody").unwrap();
+        assert_eq!(node, Node::Array(vec![Node::from("a"), Node::from("b")]));
+        assert_eq!(body, "Body");
+    }
+
+    #[test]
+    fn extract_handles_crlf_and_bom() {
+        let (node, body) = extract("\u{feff}---\r\ntitle: Hello\r\n---\r\nBody").unwrap();
+        assert_eq!(node["title"], Node::Str("Hello".to_string()));
+        assert_eq!(body, "Body");
+    }
+
+    #[test]
+    fn extract_unterminated_front_matter_fails() {
+        let result = extract("---\ntitle: Hello\n");
+        assert_eq!(result.unwrap_err(), "Unterminated front matter block");
+    }
+
+    #[test]
+    fn extract_from_file_works() -> Result<(), String> {
+        let path = std::env::temp_dir().join(format!("yaml_lib_{}_front_matter.md", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        fs::write(&path, "---\ntitle: Post\n---\nContent\n").map_err(|e| e.to_string())?;
+        let (node, body) = extract_from_file(&path)?;
+        fs::remove_file(&path).map_err(|e| e.to_string())?;
+        assert_eq!(node["title"], Node::Str("Post".to_string()));
+        assert_eq!(body, "Content\n");
+        Ok(())
+    }
+
+    #[test]
+    fn extract_from_missing_file_fails() {
+        assert!(extract_from_file("missing_front_matter.md").is_err());
+    }
+}
@@ -0,0 +1,3 @@
+/// Front matter extraction for text files that start with a "---" delimited YAML block
+/// Used for blog posts and static site content (Jekyll, Hugo etc.)
+pub mod extract;
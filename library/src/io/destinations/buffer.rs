@@ -1,4 +1,5 @@
 use crate::io::traits::IDestination;
+use std::fmt;
 /// A memory buffer implementation for storing encoded JSON data as bytes.
 /// Provides functionality to write and manipulate byte content in memory.
 pub struct Buffer {
@@ -14,15 +15,19 @@ impl Buffer {
     pub fn new() -> Self {
         Self { buffer: vec![] }
     }
+}
 
-    /// Converts the buffer content to a String.
-    ///
-    /// # Returns
-    /// A String containing UTF-8 interpretation of the buffer bytes.
-    pub fn to_string(&self) -> String {
-        String::from_utf8_lossy(&self.buffer).into_owned()
+impl Default for Buffer {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
+/// Converts the buffer content to a String using a UTF-8 interpretation of the bytes.
+impl fmt::Display for Buffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", String::from_utf8_lossy(&self.buffer))
+    }
 }
 
 impl IDestination for Buffer {
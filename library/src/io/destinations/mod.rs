@@ -1,4 +1,6 @@
 /// Module providing a buffer-based destination for writing JSON data into memory
 pub mod buffer;
 /// Module providing a file-based destination for writing JSON data to disk
-pub mod file;
\ No newline at end of file
+pub mod file;
+/// Module providing destinations that write to std::io::Write and std::fmt::Write values
+pub mod writer;
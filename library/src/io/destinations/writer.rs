@@ -0,0 +1,207 @@
+use std::fmt;
+use std::io;
+use crate::io::traits::IDestination;
+
+/// A destination writing to any std::io::Write, such as a file, socket or Vec<u8>.
+/// IDestination methods cannot fail, so the first write error is kept and later writes are
+/// skipped; check it with error() or into_inner() once writing is finished.
+pub struct IoWriter<W: io::Write> {
+    /// The wrapped writer
+    writer: W,
+    /// The last byte written, if any
+    last: Option<u8>,
+    /// The first error returned by the writer
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> IoWriter<W> {
+    /// Creates a new IoWriter around a writer.
+    ///
+    /// # Arguments
+    /// * `writer` - Where the data is written; wrap unbuffered writers such as files in a
+    ///   std::io::BufWriter, as data arrives in many small pieces
+    pub fn new(writer: W) -> Self {
+        Self { writer, last: None, error: None }
+    }
+
+    /// Returns the first error the writer returned, if any.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    /// Flushes the writer and returns it, or the first error writing to it returned.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => self.writer.flush().map(|_| self.writer),
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        if self.error.is_none() && !bytes.is_empty() {
+            match self.writer.write_all(bytes) {
+                Ok(()) => self.last = bytes.last().copied(),
+                Err(error) => self.error = Some(error),
+            }
+        }
+    }
+}
+
+impl<W: io::Write> IDestination for IoWriter<W> {
+    /// Writes a single byte.
+    ///
+    /// # Arguments
+    /// * `b` - The byte to write
+    fn add_byte(&mut self, b: u8) {
+        self.write(&[b]);
+    }
+
+    /// Writes a string as bytes.
+    ///
+    /// # Arguments
+    /// * `s` - The string to write
+    fn add_bytes(&mut self, s: &str) {
+        self.write(s.as_bytes());
+    }
+
+    /// Forgets the last byte written; data already written cannot be taken back.
+    fn clear(&mut self) {
+        self.last = None;
+    }
+
+    /// Returns the last byte written, if any.
+    fn last(&self) -> Option<u8> {
+        self.last
+    }
+}
+
+/// A destination writing to any std::fmt::Write, such as a String or a Formatter.
+/// Bytes added one at a time are collected until they form a whole character. The first
+/// write error is kept and later writes are skipped; check it with error() or into_inner().
+pub struct FmtWriter<W: fmt::Write> {
+    /// The wrapped writer
+    writer: W,
+    /// Bytes of a character added with add_byte but not yet complete
+    pending: Vec<u8>,
+    /// The last byte written, if any
+    last: Option<u8>,
+    /// The first error returned by the writer
+    error: Option<fmt::Error>,
+}
+
+impl<W: fmt::Write> FmtWriter<W> {
+    /// Creates a new FmtWriter around a writer.
+    ///
+    /// # Arguments
+    /// * `writer` - Where the text is written
+    pub fn new(writer: W) -> Self {
+        Self { writer, pending: Vec::new(), last: None, error: None }
+    }
+
+    /// Returns the first error the writer returned, if any.
+    pub fn error(&self) -> Option<fmt::Error> {
+        self.error
+    }
+
+    /// Returns the writer, or the first error writing to it returned.
+    pub fn into_inner(self) -> Result<W, fmt::Error> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.writer),
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        if self.error.is_none() && !text.is_empty() {
+            match self.writer.write_str(text) {
+                Ok(()) => self.last = text.as_bytes().last().copied(),
+                Err(error) => self.error = Some(error),
+            }
+        }
+    }
+}
+
+impl<W: fmt::Write> IDestination for FmtWriter<W> {
+    /// Adds a single byte, writing it once it completes a UTF-8 character.
+    ///
+    /// # Arguments
+    /// * `b` - The byte to add
+    fn add_byte(&mut self, b: u8) {
+        self.pending.push(b);
+        match String::from_utf8(std::mem::take(&mut self.pending)) {
+            Ok(text) => self.write(&text),
+            // Not yet a whole character; bytes that can never become one are dropped
+            Err(error) if error.utf8_error().error_len().is_none() => self.pending = error.into_bytes(),
+            Err(_) => {}
+        }
+    }
+
+    /// Writes a string.
+    ///
+    /// # Arguments
+    /// * `s` - The string to write
+    fn add_bytes(&mut self, s: &str) {
+        self.pending.clear();
+        self.write(s);
+    }
+
+    /// Forgets the last byte written; text already written cannot be taken back.
+    fn clear(&mut self) {
+        self.pending.clear();
+        self.last = None;
+    }
+
+    /// Returns the last byte written, if any.
+    fn last(&self) -> Option<u8> {
+        self.last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Failing;
+
+    impl io::Write for Failing {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("closed"))
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn io_writer_works() {
+        let mut destination = IoWriter::new(Vec::new());
+        assert_eq!(destination.last(), None);
+        destination.add_bytes("key: ");
+        destination.add_byte(b'1');
+        assert_eq!(destination.last(), Some(b'1'));
+        assert!(destination.error().is_none());
+        assert_eq!(destination.into_inner().unwrap(), b"key: 1");
+    }
+
+    #[test]
+    fn io_writer_keeps_first_error() {
+        let mut destination = IoWriter::new(Failing);
+        destination.add_bytes("a");
+        destination.add_byte(b'b');
+        assert_eq!(destination.error().unwrap().to_string(), "closed");
+        assert_eq!(destination.last(), None);
+        assert!(destination.into_inner().is_err());
+    }
+
+    #[test]
+    fn fmt_writer_works() {
+        let mut destination = FmtWriter::new(String::new());
+        destination.add_bytes("café ");
+        for byte in "é".bytes() {
+            destination.add_byte(byte);
+        }
+        destination.add_byte(b'!');
+        assert_eq!(destination.last(), Some(b'!'));
+        assert_eq!(destination.into_inner().unwrap(), "café é!");
+    }
+}
@@ -0,0 +1,182 @@
+//! Character encoding detection and decoding for raw YAML bytes.
+//! Implements the YAML specification's encoding detection rules, which look at a
+//! byte order mark or, failing that, the pattern of null bytes at the start of the stream.
+
+/// The Unicode encodings a YAML stream may use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8 without a byte order mark
+    Utf8,
+    /// UTF-8 with a byte order mark
+    Utf8Bom,
+    /// UTF-16 little endian
+    Utf16le,
+    /// UTF-16 big endian
+    Utf16be,
+    /// UTF-32 little endian
+    Utf32le,
+    /// UTF-32 big endian
+    Utf32be,
+}
+
+impl Encoding {
+    /// Returns the length in bytes of the byte order mark present for this encoding in the
+    /// given bytes (0 if there is none).
+    fn bom_length(&self, bytes: &[u8]) -> usize {
+        let bom: &[u8] = match self {
+            Encoding::Utf8 => &[],
+            Encoding::Utf8Bom => &[0xEF, 0xBB, 0xBF],
+            Encoding::Utf16le => &[0xFF, 0xFE],
+            Encoding::Utf16be => &[0xFE, 0xFF],
+            Encoding::Utf32le => &[0xFF, 0xFE, 0x00, 0x00],
+            Encoding::Utf32be => &[0x00, 0x00, 0xFE, 0xFF],
+        };
+        if bytes.starts_with(bom) { bom.len() } else { 0 }
+    }
+}
+
+/// Detects the encoding of a YAML byte stream.
+///
+/// # Arguments
+/// * `bytes` - The start of the stream (at least four bytes where available)
+///
+/// # Returns
+/// The detected encoding; UTF-8 if nothing else matches.
+pub fn detect_encoding(bytes: &[u8]) -> Encoding {
+    match bytes {
+        [0x00, 0x00, 0xFE, 0xFF, ..] => Encoding::Utf32be,
+        [0x00, 0x00, 0x00, _, ..] => Encoding::Utf32be,
+        [0xFF, 0xFE, 0x00, 0x00, ..] => Encoding::Utf32le,
+        [_, 0x00, 0x00, 0x00, ..] => Encoding::Utf32le,
+        [0xFE, 0xFF, ..] => Encoding::Utf16be,
+        [0x00, _, ..] => Encoding::Utf16be,
+        [0xFF, 0xFE, ..] => Encoding::Utf16le,
+        [_, 0x00, ..] => Encoding::Utf16le,
+        [0xEF, 0xBB, 0xBF, ..] => Encoding::Utf8Bom,
+        _ => Encoding::Utf8,
+    }
+}
+
+/// Decodes bytes in the given encoding into a String, skipping any byte order mark.
+///
+/// # Arguments
+/// * `bytes` - The encoded bytes
+/// * `encoding` - The encoding of the bytes
+///
+/// # Returns
+/// The decoded text, or an error if the bytes are not valid in the encoding.
+pub fn decode(bytes: &[u8], encoding: Encoding) -> Result<String, String> {
+    let bytes = &bytes[encoding.bom_length(bytes)..];
+    match encoding {
+        Encoding::Utf8 | Encoding::Utf8Bom => {
+            String::from_utf8(bytes.to_vec()).map_err(|_| "Invalid UTF-8 sequence in input".to_string())
+        }
+        Encoding::Utf16le | Encoding::Utf16be => {
+            if !bytes.len().is_multiple_of(2) {
+                return Err("Truncated UTF-16 input".to_string());
+            }
+            let units = bytes.chunks_exact(2).map(|pair| {
+                if encoding == Encoding::Utf16le {
+                    u16::from_le_bytes([pair[0], pair[1]])
+                } else {
+                    u16::from_be_bytes([pair[0], pair[1]])
+                }
+            });
+            char::decode_utf16(units)
+                .collect::<Result<String, _>>()
+                .map_err(|_| "Invalid UTF-16 sequence in input".to_string())
+        }
+        Encoding::Utf32le | Encoding::Utf32be => {
+            if !bytes.len().is_multiple_of(4) {
+                return Err("Truncated UTF-32 input".to_string());
+            }
+            bytes
+                .chunks_exact(4)
+                .map(|quad| {
+                    let quad = [quad[0], quad[1], quad[2], quad[3]];
+                    let value = if encoding == Encoding::Utf32le {
+                        u32::from_le_bytes(quad)
+                    } else {
+                        u32::from_be_bytes(quad)
+                    };
+                    char::from_u32(value).ok_or_else(|| "Invalid UTF-32 sequence in input".to_string())
+                })
+                .collect()
+        }
+    }
+}
+
+/// Detects the encoding of some bytes and decodes them into a String.
+///
+/// # Arguments
+/// * `bytes` - The encoded bytes
+///
+/// # Returns
+/// The decoded text, or an error if the bytes are not valid in the detected encoding.
+pub fn decode_bytes(bytes: &[u8]) -> Result<String, String> {
+    decode(bytes, detect_encoding(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect()
+    }
+
+    fn utf16be(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect()
+    }
+
+    fn utf32le(text: &str) -> Vec<u8> {
+        text.chars().flat_map(|c| (c as u32).to_le_bytes()).collect()
+    }
+
+    fn utf32be(text: &str) -> Vec<u8> {
+        text.chars().flat_map(|c| (c as u32).to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn detect_encoding_from_bom_works() {
+        assert_eq!(detect_encoding(&[0xEF, 0xBB, 0xBF, b'a']), Encoding::Utf8Bom);
+        assert_eq!(detect_encoding(&[0xFE, 0xFF, 0x00, b'a']), Encoding::Utf16be);
+        assert_eq!(detect_encoding(&[0xFF, 0xFE, b'a', 0x00]), Encoding::Utf16le);
+        assert_eq!(detect_encoding(&[0x00, 0x00, 0xFE, 0xFF]), Encoding::Utf32be);
+        assert_eq!(detect_encoding(&[0xFF, 0xFE, 0x00, 0x00]), Encoding::Utf32le);
+    }
+
+    #[test]
+    fn detect_encoding_from_null_pattern_works() {
+        assert_eq!(detect_encoding(&utf16le("ab")), Encoding::Utf16le);
+        assert_eq!(detect_encoding(&utf16be("ab")), Encoding::Utf16be);
+        assert_eq!(detect_encoding(&utf32le("a")), Encoding::Utf32le);
+        assert_eq!(detect_encoding(&utf32be("a")), Encoding::Utf32be);
+        assert_eq!(detect_encoding(b"ab"), Encoding::Utf8);
+        assert_eq!(detect_encoding(b""), Encoding::Utf8);
+    }
+
+    #[test]
+    fn decode_bytes_works_for_all_encodings() {
+        let text = "key: café €";
+        assert_eq!(decode_bytes(text.as_bytes()).unwrap(), text);
+        let mut with_bom = vec![0xEF, 0xBB, 0xBF];
+        with_bom.extend_from_slice(text.as_bytes());
+        assert_eq!(decode_bytes(&with_bom).unwrap(), text);
+        assert_eq!(decode_bytes(&utf16le(text)).unwrap(), text);
+        assert_eq!(decode_bytes(&utf16be(text)).unwrap(), text);
+        assert_eq!(decode_bytes(&utf32le(text)).unwrap(), text);
+        assert_eq!(decode_bytes(&utf32be(text)).unwrap(), text);
+        let mut with_bom = vec![0xFF, 0xFE];
+        with_bom.extend(utf16le(text));
+        assert_eq!(decode_bytes(&with_bom).unwrap(), text);
+    }
+
+    #[test]
+    fn decode_invalid_bytes_fails() {
+        assert!(decode(&[0xC3], Encoding::Utf8).is_err());
+        assert!(decode(&[0x61], Encoding::Utf16le).is_err());
+        assert!(decode(&[0x00, 0xD8, 0x00, 0x00], Encoding::Utf16le).is_err());
+        assert!(decode(&[0x00, 0x00, 0x11, 0x00], Encoding::Utf32le).is_err());
+    }
+}
@@ -4,3 +4,5 @@ pub mod sources;
 pub mod destinations;
 /// Module containing trait definitions for YAML I/O operations
 pub mod traits;
+/// Module detecting and decoding the Unicode encoding of raw YAML bytes
+pub mod encoding;
@@ -1,4 +1,5 @@
 use crate::io::traits::ISource;
+use std::fmt;
 
 /// A memory buffer implementation for reading JSON data from bytes.
 /// Provides functionality to traverse and read byte content from memory.
@@ -22,12 +23,40 @@ impl Buffer {
     pub fn new(to_add: &[u8]) -> Self {
         Self { buffer: to_add.to_vec(), position: 0, last_position: 0 }
     }
-    /// Converts the buffer content to a String.
-    ///
-    /// # Returns
-    /// A String containing UTF-8 interpretation of the buffer bytes.
-    pub fn to_string(&self) -> String {
-        String::from_utf8_lossy(&self.buffer).into_owned()
+}
+
+/// Converts the buffer content to a String using a UTF-8 interpretation of the bytes.
+impl fmt::Display for Buffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", String::from_utf8_lossy(&self.buffer))
+    }
+}
+
+/// Returns the length in bytes of the UTF-8 sequence a byte starts, or 1 for a byte that
+/// cannot start a multi-byte sequence.
+pub(crate) fn sequence_length(first: u8) -> usize {
+    match first {
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => 1,
+    }
+}
+
+impl Buffer {
+    /// Decodes the UTF-8 character at the current position, returning it with its length in
+    /// bytes. Bytes that do not start a valid UTF-8 sequence are returned as single characters.
+    fn decode(&self) -> Option<(char, usize)> {
+        let first = *self.buffer.get(self.position)?;
+        let length = sequence_length(first);
+        let end = (self.position + length).min(self.buffer.len());
+        if length > 1
+            && let Ok(text) = std::str::from_utf8(&self.buffer[self.position..end])
+            && let Some(c) = text.chars().next()
+        {
+            return Some((c, length));
+        }
+        Some((first as char, 1))
     }
 }
 
@@ -35,16 +64,11 @@ impl ISource for Buffer {
     /// Moves to the next character in the buffer
     fn next(&mut self) {
         self.last_position = self.position;
-        self.last_position = self.position;
-        self.position += 1;
+        self.position += self.decode().map_or(1, |(_, length)| length);
     }
     /// Returns the current character at the buffer position
     fn current(&mut self) -> Option<char> {
-        if self.more() {
-            Some(self.buffer[self.position] as char)
-        } else {
-            None
-        }
+        self.decode().map(|(c, _)| c)
     }
     /// Checks if there are more characters to read
     fn more(&mut self) -> bool {
@@ -58,6 +82,14 @@ impl ISource for Buffer {
     fn backup(&mut self) {
         self.position = self.last_position;
     }
+    /// Returns the current position within the buffer
+    fn position(&mut self) -> usize {
+        self.position.min(self.buffer.len())
+    }
+    /// Returns the total length of the buffer
+    fn length(&mut self) -> Option<usize> {
+        Some(self.buffer.len())
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -70,26 +102,26 @@ mod tests {
     #[test]
     fn read_character_from_source_buffer_works() {
         let  mut source = Buffer::new(String::from("i32e").as_bytes());
-        match source.current() { Some('i') => assert!(true), _ => assert!(false)}
+        assert_eq!(source.current(), Some('i'));
     }
     #[test]
     fn move_to_next_character_in_source_buffer_works() {
         let  mut source = Buffer::new(String::from("i32e").as_bytes());
         source.next();
-        match source.current() { Some('3') => assert!(true), _ => assert!(false)}
+        assert_eq!(source.current(), Some('3'));
     }
     #[test]
     fn move_to_last_character_in_source_buffer_works() {
         let  mut source = Buffer::new(String::from("i32e").as_bytes());
         while source.more() { source.next()}
-        match source.current() { None => assert!(true), _ => assert!(false)}
+        assert_eq!(source.current(), None);
     }
     #[test]
     fn reset_in_source_buffer_works() {
         let  mut source = Buffer::new(String::from("i32e").as_bytes());
         while source.more() { source.next()}
         source.reset();
-        match source.current() { Some('i') => assert!(true), _ => assert!(false)}
+        assert_eq!(source.current(), Some('i'));
     }
     #[test]
     fn create_empty_buffer_works() {
@@ -113,10 +145,7 @@ mod tests {
         let mut source = Buffer::new(String::from("abc").as_bytes());
         source.next();
         source.next();
-        match source.current() {
-            Some('c') => assert!(true),
-            _ => assert!(false)
-        }
+        assert_eq!(source.current(), Some('c'));
     }
 
     #[test]
@@ -124,9 +153,41 @@ mod tests {
         let mut source = Buffer::new(String::from("abc").as_bytes());
         source.next();
         source.backup();
-        match source.current() {
-            Some('a') => assert!(true),
-            _ => assert!(false)
-        }
+        assert_eq!(source.current(), Some('a'));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn position_and_length_work() {
+        let mut source = Buffer::new(String::from("ab").as_bytes());
+        assert_eq!(source.length(), Some(2));
+        assert_eq!(source.position(), 0);
+        source.next();
+        assert_eq!(source.position(), 1);
+        source.next();
+        source.next();
+        assert_eq!(source.position(), 2);
+    }
+
+    #[test]
+    fn read_multibyte_characters_works() {
+        let mut source = Buffer::new("é€😀a".as_bytes());
+        assert_eq!(source.current(), Some('é'));
+        source.next();
+        assert_eq!(source.current(), Some('€'));
+        source.next();
+        assert_eq!(source.current(), Some('😀'));
+        source.next();
+        assert_eq!(source.current(), Some('a'));
+        assert_eq!(source.position(), 9);
+        source.backup();
+        assert_eq!(source.current(), Some('😀'));
+    }
+
+    #[test]
+    fn read_invalid_utf8_falls_back_to_bytes() {
+        let mut source = Buffer::new(&[0xC3, b'a']);
+        assert_eq!(source.current(), Some('\u{c3}'));
+        source.next();
+        assert_eq!(source.current(), Some('a'));
+    }
+}
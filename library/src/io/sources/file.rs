@@ -1,14 +1,23 @@
 use std::fs::File as StdFile;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use crate::io::sources::buffer::sequence_length;
+use crate::io::traits::ISource;
 
 
 /// A file-based implementation for reading JSON data from disk.
-/// Provides functionality to read and traverse file content byte by byte.
+/// Provides functionality to read and traverse file content character by character,
+/// decoding UTF-8 the same way as the buffer source.
 pub struct File {
-    /// Internal file handle for reading operations
-    file: StdFile,
-    /// Current byte being read from the file
-    current_byte: Option<u8>,
+    /// Buffered file handle for reading operations
+    file: BufReader<StdFile>,
+    /// Current character being read from the file and its length in bytes
+    current: Option<(char, usize)>,
+    /// Number of bytes consumed before the current character
+    position: usize,
+    /// Position of the previous character
+    last_position: usize,
+    /// Total length of the file in bytes
+    file_length: usize,
 }
 
 impl File {
@@ -20,25 +29,188 @@ impl File {
     /// # Returns
     /// A Result containing either the new File instance or an IO error
     pub fn new(path: &str) -> std::io::Result<Self> {
-        let mut file = StdFile::open(path)?;
-        let mut current_byte = [0u8; 1];
-        let has_byte = file.read(&mut current_byte)? == 1;
-
-        Ok(Self {
-            file,
-            current_byte: if has_byte { Some(current_byte[0]) } else { None },
-        })
-    }
-
-    /// Moves the reading position back one character
-    pub fn backup(&mut self) {
-        if let Ok(_) = self.file.seek(SeekFrom::Current(-2)) {
-            let mut byte = [0u8; 1];
-            self.current_byte = if self.file.read(&mut byte).unwrap_or(0) == 1 {
-                Some(byte[0])
-            } else {
-                None
-            };
+        let file = StdFile::open(path)?;
+        let file_length = file.metadata()?.len() as usize;
+        let mut source = Self { file: BufReader::new(file), current: None, position: 0, last_position: 0, file_length };
+        source.read_char();
+        Ok(source)
+    }
+
+    /// Decodes the UTF-8 character at the file handle's position into the current character.
+    /// Bytes that do not start a valid UTF-8 sequence are read as single characters.
+    fn read_char(&mut self) {
+        let mut bytes = [0u8; 4];
+        if self.file.read(&mut bytes[..1]).unwrap_or(0) != 1 {
+            self.current = None;
+            return;
+        }
+        let length = sequence_length(bytes[0]);
+        let mut read = 1;
+        while read < length {
+            match self.file.read(&mut bytes[read..length]) {
+                Ok(0) | Err(_) => break,
+                Ok(count) => read += count,
+            }
+        }
+        if length > 1
+            && let Ok(text) = std::str::from_utf8(&bytes[..read])
+            && let Some(c) = text.chars().next()
+        {
+            self.current = Some((c, length));
+            return;
+        }
+        // Not a valid sequence: keep the lead byte and leave the rest to be read next
+        let _ = self.file.seek_relative(1 - read as i64);
+        self.current = Some((bytes[0] as char, 1));
+    }
+
+    /// Moves the file handle to a byte position and reads the character there.
+    fn seek_to(&mut self, position: usize) {
+        if self.file.seek(SeekFrom::Start(position as u64)).is_ok() {
+            self.position = position;
+            self.read_char();
+        }
+    }
+}
+
+impl ISource for File {
+    /// Moves to the next character in the file
+    fn next(&mut self) {
+        if let Some((_, length)) = self.current {
+            self.last_position = self.position;
+            self.position += length;
+            self.read_char();
+        }
+    }
+    /// Returns the current character at the file position
+    fn current(&mut self) -> Option<char> {
+        self.current.map(|(c, _)| c)
+    }
+    /// Checks if there are more characters to read
+    fn more(&mut self) -> bool {
+        self.current.is_some()
+    }
+    /// Resets the file position to the start
+    fn reset(&mut self) {
+        self.last_position = 0;
+        self.seek_to(0);
+    }
+    /// Moves the reading position back to the previous character
+    fn backup(&mut self) {
+        if self.last_position != self.position {
+            self.seek_to(self.last_position);
+        }
+    }
+    /// Returns the number of bytes consumed from the file
+    fn position(&mut self) -> usize {
+        self.position
+    }
+    /// Returns the total length of the file
+    fn length(&mut self) -> Option<usize> {
+        Some(self.file_length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::sources::buffer::Buffer;
+    use std::fs;
+
+    /// Writes a fixture into the system temporary directory, returning its path.
+    fn fixture(name: &str, contents: &[u8]) -> std::io::Result<String> {
+        let path = std::env::temp_dir().join(format!("yaml_lib_{}_{}", std::process::id(), name));
+        fs::write(&path, contents)?;
+        Ok(path.to_string_lossy().into_owned())
+    }
+
+    #[test]
+    fn read_character_from_source_file_works() -> std::io::Result<()> {
+        let path = fixture("source_read.txt", b"i32e")?;
+        let mut source = File::new(&path)?;
+        assert_eq!(source.current(), Some('i'));
+        source.next();
+        assert_eq!(source.current(), Some('3'));
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn move_to_last_character_in_source_file_works() -> std::io::Result<()> {
+        let path = fixture("source_last.txt", b"i32e")?;
+        let mut source = File::new(&path)?;
+        while source.more() { source.next() }
+        assert_eq!(source.current(), None);
+        assert_eq!(source.position(), 4);
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn reset_in_source_file_works() -> std::io::Result<()> {
+        let path = fixture("source_reset.txt", b"i32e")?;
+        let mut source = File::new(&path)?;
+        while source.more() { source.next() }
+        source.reset();
+        assert_eq!(source.current(), Some('i'));
+        assert_eq!(source.position(), 0);
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn backup_in_source_file_works() -> std::io::Result<()> {
+        let path = fixture("source_backup.txt", b"abc")?;
+        let mut source = File::new(&path)?;
+        source.next();
+        source.next();
+        source.backup();
+        assert_eq!(source.current(), Some('b'));
+        while source.more() { source.next() }
+        source.backup();
+        assert_eq!(source.current(), Some('c'));
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn source_file_decodes_utf8_like_buffer() -> std::io::Result<()> {
+        let bytes = "café ✓ 😀\n".as_bytes().iter().copied().chain([0xFF, b'x', 0xC3]).collect::<Vec<u8>>();
+        let path = fixture("source_utf8.txt", &bytes)?;
+        let mut file = File::new(&path)?;
+        let mut buffer = Buffer::new(&bytes);
+        let mut read = String::new();
+        while let Some(c) = file.current() {
+            assert_eq!(buffer.current(), Some(c));
+            assert_eq!(file.position(), buffer.position());
+            read.push(c);
+            file.next();
+            buffer.next();
         }
+        assert_eq!(read, "café ✓ 😀\n\u{FF}x\u{C3}");
+        file.reset();
+        file.next();
+        file.next();
+        file.next();
+        file.next();
+        file.backup();
+        assert_eq!(file.current(), Some('é'));
+        assert_eq!(file.position(), 3);
+        fs::remove_file(path)?;
+        Ok(())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn length_of_source_file_works() -> std::io::Result<()> {
+        let path = fixture("source_length.txt", b"abcdef")?;
+        let mut source = File::new(&path)?;
+        assert_eq!(source.length(), Some(6));
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn open_missing_source_file_fails() {
+        assert!(File::new("missing_source_file.txt").is_err());
+    }
+}
@@ -11,6 +11,12 @@ pub trait ISource {
     fn reset(&mut self);
     /// Moves the reading position back one character.
     fn backup(&mut self);
+    /// Returns the number of bytes consumed from the source so far.
+    fn position(&mut self) -> usize;
+    /// Returns the total number of bytes in the source, if known.
+    fn length(&mut self) -> Option<usize> {
+        None
+    }
 
     fn is_whitespace(&self, c: char) -> bool {
         c == ' ' || c == '\t' || c == '\n' || c == '\r'
@@ -3,7 +3,7 @@
 //!
 //! This library provides a flexible YAML implementation with:
 //! - Core Node type for representing YAML structures
-//! - Parser to build Node trees from streams 
+//! - Parser to build Node trees from streams
 //! - Multiple format serializers (YAML, YAML, XML, Bencode)
 //! - File and buffer I/O abstractions
 //! - Pretty-printing utilities
@@ -11,25 +11,43 @@
 //!
 //! Minimum supported Rust version: 1.88.0
 
+// Lets code generated by the yaml_derive macros refer to this crate as ::yaml_lib
+extern crate self as yaml_lib;
+
 /// Module providing input/output operations for reading and writing YAML data
 pub mod io;
 // /// Module containing YAML data structure definitions and node types
 pub mod nodes;
 /// Module implementing YAML parsing and value extraction
 pub mod parser;
-// /// Module defining error types and handling for YAML operations.
-// pub mod error;
-// /// Module for converting YAML structures to formatted strings
-// pub mod stringify;
+/// Module extracting YAML front matter blocks from text content
+pub mod front_matter;
+/// Module defining error types and handling for YAML operations.
+pub mod error;
+/// Module querying Node trees with JSONPath expressions
+pub mod query;
+/// Module computing structural differences between Node trees
+pub mod diff;
+/// Module applying patch operations to Node trees
+pub mod patch;
+/// Module generating Rust type definitions from sample Node trees
+pub mod codegen;
+/// Module inferring JSON Schemas from sample Node trees
+pub mod schema;
+/// Module reading and writing Rust values as YAML with serde
+#[cfg(feature = "serde")]
+pub mod serialize;
+/// Module for converting YAML structures to formatted strings
+pub mod stringify;
 // /// Module handling YAML file reading and writing operations
 // pub mod file;
 // /// Module containing utility functions and helpers for YAML processing
 // pub mod misc;
-// 
-// ///
-// /// YAML_lib API
-// ///
-// 
+
+//
+// YAML_lib API
+//
+
 // /// Returns the current version of the YAML library
 // pub use misc::get_version as version;
 // /// Strip whitespace from a string.
@@ -44,28 +62,283 @@ pub mod parser;
 // pub use file::file::read_file_to_string as read_file_to_string;
 // /// This function writes a string to a file in the specified Unicode format
 // pub use file::file::write_file_from_string as write_file_from_string;
-// 
-// /// Source implementation for reading YAML data from a memory buffer
-// pub use io::sources::buffer::Buffer as BufferSource;
-// /// Destination implementation for writing YAML data to a memory buffer
-// pub use io::destinations::buffer::Buffer as BufferDestination;
-// /// Source implementation for reading YAML data from a file
-// pub use io::sources::file::File as FileSource;
-// /// Destination implementation for writing YAML data to a file
-// pub use io::destinations::file::File as FileDestination;
-// /// Core data structure representing a YAML node and numerical node in the parsed tree
-// pub use nodes::node::Node as Node;
-// /// Core data structure representing a numeric value node in the parsed tree
-// pub use nodes::node::Numeric as Numeric;
-// /// Converts a Node tree back to YAML format
-// pub use stringify::default::stringify as stringify;
-// /// Parses YAML data into a Node tree structure
-// pub use parser::default::parse as parse;
-// /// Converts a Node tree to YAML format
-// pub use stringify::bencode::stringify as to_bencode;
+
+/// Source implementation for reading YAML data from a memory buffer
+pub use io::sources::buffer::Buffer as BufferSource;
+/// Destination implementation for writing YAML data to a memory buffer
+pub use io::destinations::buffer::Buffer as BufferDestination;
+/// Source implementation for reading YAML data from a file
+pub use io::sources::file::File as FileSource;
+/// Destination implementation for writing YAML data to a file
+pub use io::destinations::file::File as FileDestination;
+/// Destination writing YAML data to any std::io::Write
+pub use io::destinations::writer::IoWriter as WriterDestination;
+/// Destination writing YAML data to any std::fmt::Write
+pub use io::destinations::writer::FmtWriter as FmtWriterDestination;
+/// Unicode encodings detected in raw YAML bytes
+pub use io::encoding::Encoding as Encoding;
+/// Detects the Unicode encoding of raw YAML bytes
+pub use io::encoding::detect_encoding as detect_encoding;
+/// Core data structure representing a YAML node and numerical node in the parsed tree
+pub use nodes::node::Node as Node;
+/// Core data structure representing a numeric value node in the parsed tree
+pub use nodes::node::Numeric as Numeric;
+/// Insertion-ordered map held by Dictionary nodes
+pub use indexmap::IndexMap as IndexMap;
+/// Node tree whose strings and collections are reference counted and shared
+pub use nodes::shared::SharedNode as SharedNode;
+/// Node counts, depth, text size and approximate memory use of a tree
+pub use nodes::stats::NodeStats as NodeStats;
+/// Cheaply cloned, read-only handle sharing a parsed Node tree between threads
+pub use nodes::handle::SharedDocument as SharedDocument;
+/// Contiguous storage for the nodes of one or more trees
+pub use nodes::arena::NodeArena as NodeArena;
+/// Index of a node within a NodeArena
+pub use nodes::arena::NodeId as NodeId;
+/// The variant of a Node without its contents, returned by Node::kind
+pub use nodes::kind::NodeKind as NodeKind;
+/// Comment metadata for a Node tree, keyed by node path
+pub use nodes::comments::Comments as Comments;
+/// Anchor metadata for a Node tree, keyed by node path
+pub use nodes::anchors::Anchors as Anchors;
+/// Fluent builder for Dictionary nodes, started with Node::dict()
+pub use nodes::builder::DictionaryBuilder as DictionaryBuilder;
+/// Fluent builder for Array nodes, started with Node::seq()
+pub use nodes::builder::SequenceBuilder as SequenceBuilder;
+/// Depth first iterator over a Node tree, created by Node::walk
+pub use nodes::walk::Walk as Walk;
+/// Breadth first iterator over a Node tree, created by Node::walk_breadth_first
+pub use nodes::walk::BreadthFirstWalk as BreadthFirstWalk;
+/// Visitor called for each node of a tree by Node::accept
+pub use nodes::visitor::NodeVisitor as NodeVisitor;
+/// Visitor that may modify each node of a tree, called by Node::accept_mut
+pub use nodes::visitor::NodeVisitorMut as NodeVisitorMut;
+/// Options controlling how Node::merge combines two trees
+pub use nodes::merge::MergeStrategy as MergeStrategy;
+/// How arrays are combined by Node::merge
+pub use nodes::merge::ArrayMerge as ArrayMerge;
+/// What overlay nulls do in Node::merge
+pub use nodes::merge::NullMerge as NullMerge;
+/// Options controlling how Node::flatten joins keys
+pub use nodes::flatten::FlattenOptions as FlattenOptions;
+/// Trait converting application types into Nodes
+pub use nodes::traits::ToNode as ToNode;
+/// Trait building application types from Nodes
+pub use nodes::traits::FromNode as FromNode;
+/// Converts a dictionary field for use in FromNode implementations
+pub use nodes::traits::from_field as from_field;
+/// Derive macro implementing ToNode for structs and enums
+#[cfg(feature = "derive")]
+pub use yaml_derive::ToNode as ToNode;
+/// Derive macro implementing FromNode for structs and enums
+#[cfg(feature = "derive")]
+pub use yaml_derive::FromNode as FromNode;
+/// Policy selecting which Numeric variants integers are stored in
+pub use nodes::node::NumericPolicy as NumericPolicy;
+/// Converts a Node tree back to YAML format
+pub use stringify::default::stringify as stringify;
+/// Converts a Node tree to YAML laid out according to the supplied emitter options
+pub use stringify::default::stringify_with_options as stringify_with_options;
+/// Converts a Node tree to YAML, writing back comments held as metadata beside it
+pub use stringify::default::stringify_with_comments as stringify_with_comments;
+/// Converts a Node tree to YAML, writing back comments, anchors and aliases held as metadata
+pub use stringify::default::stringify_with_metadata as stringify_with_metadata;
+/// Reformats YAML text with consistent indentation and spacing, keeping its comments
+pub use stringify::format::format_str as format_str;
+/// Options controlling the indentation and line width of reformatted YAML
+pub use stringify::format::FormatOptions as FormatOptions;
+/// Writes a Node tree as YAML to any std::io::Write
+pub use stringify::default::stringify_to_writer as stringify_to_writer;
+/// Options controlling the indentation, collection style and width of emitted YAML
+pub use stringify::options::EmitterOptions as EmitterOptions;
+/// Block or flow style preference for emitted collections
+pub use stringify::options::CollectionStyle as CollectionStyle;
+/// Quoting style for emitted strings
+pub use stringify::options::QuoteStyle as QuoteStyle;
+/// Representation of null values in emitted YAML
+pub use stringify::options::NullStyle as NullStyle;
+/// Words emitted booleans are written as
+pub use stringify::options::BoolStyle as BoolStyle;
+/// Case of emitted boolean and null keywords
+pub use stringify::options::KeywordCase as KeywordCase;
+/// Parses YAML data into a Node tree structure
+pub use parser::default::parse as parse;
+/// Parses raw YAML bytes into a Node tree, detecting their Unicode encoding
+pub use parser::default::parse_bytes as parse_bytes;
+/// Parses YAML data into a Node tree structure using the supplied parser options
+pub use parser::default::parse_with_options as parse_with_options;
+/// Parses JSON data into a Node tree structure
+pub use parser::json::parse as parse_json;
+/// Parses JSON data into a Node tree structure using the supplied parser options
+pub use parser::json::parse_with_options as parse_json_with_options;
+/// Parses CSV into an array of dictionaries keyed by the header row
+pub use parser::csv::parse as parse_csv;
+/// Parses CSV into an array of dictionaries using the supplied delimiter and type inference settings
+pub use parser::csv::parse_with_options as parse_csv_with_options;
+/// Options controlling the delimiter and type inference of the CSV parser
+pub use parser::csv::CsvParserOptions as CsvParserOptions;
+/// Parses a .env file into a dictionary of strings
+pub use parser::dotenv::parse as parse_dotenv;
+/// Parses bencode into a Node tree
+pub use parser::bencode::parse as parse_bencode;
+/// Parses MessagePack data into a Node tree
+#[cfg(feature = "msgpack")]
+pub use parser::msgpack::parse as parse_msgpack;
+/// Parses CBOR data into a Node tree
+#[cfg(feature = "cbor")]
+pub use parser::cbor::parse as parse_cbor;
+/// Parses YAML data into a Node tree and reports metrics about the parse
+pub use parser::default::parse_detailed as parse_detailed;
+/// Parses YAML data into a SharedNode tree whose aliases share the anchored node
+pub use parser::default::parse_shared as parse_shared;
+/// Result of a detailed parse holding the Node tree and its ParseReport
+pub use parser::report::Parsed as Parsed;
+/// Metrics describing a completed parse
+pub use parser::report::ParseReport as ParseReport;
+/// Non-fatal issue reported by a detailed parse
+pub use parser::warning::ParseWarning as ParseWarning;
+/// Kind of non-fatal issue reported by a detailed parse
+pub use parser::warning::WarningKind as WarningKind;
+/// Options controlling parser behaviour such as progress reporting
+pub use parser::options::ParserOptions as ParserOptions;
+/// Progress information passed to a parser progress callback
+pub use parser::options::Progress as Progress;
+/// Schema used by the parser to resolve plain scalar values
+pub use parser::options::Schema as Schema;
+/// Token used to cooperatively cancel a running parse
+pub use parser::options::CancellationToken as CancellationToken;
+/// Error returned by the parser, including cancellation
+pub use error::parse::ParseError as ParseError;
+/// Error returned when a Node cannot be converted into a Rust value
+pub use error::conversion::ConversionError as ConversionError;
+/// Converts a Node tree to compact JSON
+pub use stringify::json::stringify as to_json;
+/// Converts a Node tree to JSON laid out according to the supplied options
+pub use stringify::json::stringify_with_options as to_json_with_options;
+/// Converts a Node tree to JSON and reports the values it could not write exactly
+pub use stringify::json::stringify_with_report as to_json_with_report;
+/// Options controlling the layout of emitted JSON and its handling of YAML-only values
+pub use stringify::json::JsonOptions as JsonOptions;
+/// What the JSON emitter does with values that have no JSON form
+pub use stringify::json::JsonUnsupported as JsonUnsupported;
+/// Converts an array of dictionaries to CSV
+pub use stringify::csv::stringify as to_csv;
+/// Converts an array of dictionaries to CSV with the supplied delimiter, quoting and header settings
+pub use stringify::csv::stringify_with_options as to_csv_with_options;
+/// Options controlling the delimiter, quoting, header and line endings of emitted CSV
+pub use stringify::csv::CsvOptions as CsvOptions;
+/// Which fields of emitted CSV are quoted
+pub use stringify::csv::CsvQuote as CsvQuote;
+/// Converts a Node tree to Java properties
+pub use stringify::properties::stringify as to_properties;
+/// Converts a Node tree to properties or INI with the supplied separators and escaping
+pub use stringify::properties::stringify_with_options as to_properties_with_options;
+/// Options controlling the style, separators and escaping of emitted properties and INI files
+pub use stringify::properties::PropertiesOptions as PropertiesOptions;
+/// Whether the properties emitter writes Java properties or INI sections
+pub use stringify::properties::PropertiesStyle as PropertiesStyle;
+/// Converts a Node tree to a .env file
+pub use stringify::dotenv::stringify as to_dotenv;
+/// Converts a Node tree to a .env file with the supplied naming and export settings
+pub use stringify::dotenv::stringify_with_options as to_dotenv_with_options;
+/// Options controlling the variable names and export prefixes of emitted .env files
+pub use stringify::dotenv::DotenvOptions as DotenvOptions;
+/// Converts a Node tree to an Apple XML property list
+pub use stringify::plist::stringify as to_plist;
+/// Converts a Node tree to an Apple XML property list with the supplied date settings
+pub use stringify::plist::stringify_with_options as to_plist_with_options;
+/// Options controlling how emitted property lists represent dates
+pub use stringify::plist::PlistOptions as PlistOptions;
+/// Renders a Node tree as an HTML fragment
+pub use stringify::html::stringify as to_html;
+/// Renders a Node tree as an HTML fragment with the supplied class and folding settings
+pub use stringify::html::stringify_with_options as to_html_with_options;
+/// Options controlling the CSS classes and folding of rendered HTML
+pub use stringify::html::HtmlOptions as HtmlOptions;
+/// Converts a Node tree to a Graphviz DOT graph
+pub use stringify::dot::stringify as to_dot;
+/// Converts a Node tree to a Graphviz DOT graph with the supplied naming, sharing and layout settings
+pub use stringify::dot::stringify_with_options as to_dot_with_options;
+/// Options controlling the name, layout and sharing of emitted DOT graphs
+pub use stringify::dot::DotOptions as DotOptions;
+/// Converts a Node tree to bencode format
+pub use stringify::bencode::stringify as to_bencode;
+/// Converts a Node tree to bencode and reports the values it could not write exactly
+pub use stringify::bencode::stringify_with_report as to_bencode_with_report;
+/// Converts a Node tree to MessagePack
+#[cfg(feature = "msgpack")]
+pub use stringify::msgpack::stringify as to_msgpack;
+/// Converts a Node tree to CBOR
+#[cfg(feature = "cbor")]
+pub use stringify::cbor::stringify as to_cbor;
 // /// Converts a Node tree to YAML format
 // pub use stringify::yaml::stringify as to_yaml;
-// /// Converts a Node tree to XML format
-// pub use stringify::xml::stringify as to_xml;
-// /// Converts a Node tree to TOML format
-// pub use stringify::toml::stringify as to_toml;
+/// Converts a Node tree to XML format
+pub use stringify::xml::stringify as to_xml;
+/// Converts a Node tree to XML named and laid out according to the supplied options
+pub use stringify::xml::stringify_with_options as to_xml_with_options;
+/// Converts a Node tree to XML and reports the values it could not write exactly
+pub use stringify::xml::stringify_with_report as to_xml_with_report;
+/// Options controlling the element names, attributes and layout of emitted XML
+pub use stringify::xml::XmlOptions as XmlOptions;
+/// Converts a Node tree to TOML format
+pub use stringify::toml::stringify as to_toml;
+/// Converts a Node tree to TOML laid out according to the supplied options
+pub use stringify::toml::stringify_with_options as to_toml_with_options;
+/// Options controlling inline tables and mixed arrays in emitted TOML
+pub use stringify::toml::TomlOptions as TomlOptions;
+/// Converts a Node tree to TOML and reports the values it could not write exactly
+pub use stringify::toml::stringify_with_report as to_toml_with_report;
+/// The values a cross-format emitter could not write exactly, by path
+pub use stringify::loss::LossReport as LossReport;
+/// One value a cross-format emitter could not write exactly
+pub use stringify::loss::Loss as Loss;
+/// What was lost writing a value to another format
+pub use stringify::loss::LossKind as LossKind;
+/// Deserializes a Rust value from YAML text
+#[cfg(feature = "serde")]
+pub use serialize::yaml::from_str as from_str;
+/// Serializes a Rust value to YAML text
+#[cfg(feature = "serde")]
+pub use serialize::yaml::to_string as to_string;
+/// Deserializes a Rust value from a Node tree
+#[cfg(feature = "serde")]
+pub use serialize::de::from_value as from_value;
+/// Serializes a Rust value into a Node tree
+#[cfg(feature = "serde")]
+pub use serialize::ser::to_value as to_value;
+/// Error returned by serde serialization and deserialization
+#[cfg(feature = "serde")]
+pub use error::serialization::SerializationError as SerializationError;
+/// Returns the operations that turn one Node tree into another
+pub use diff::structural::diff as diff;
+/// Single change to a Node tree addressed by a JSON-pointer path
+pub use diff::operation::Operation as Operation;
+/// Merges the changes two sides made to a common base Node tree
+pub use diff::merge3::merge3 as merge3;
+/// Result of a three-way merge holding the merged tree and its conflicts
+pub use diff::merge3::Merge3 as Merge3;
+/// Path changed differently on both sides of a three-way merge
+pub use diff::merge3::Conflict as Conflict;
+/// Applies patch operations to a Node tree atomically
+pub use patch::apply::apply_patch as apply_patch;
+/// Generates Rust struct definitions that can load the sample Node trees
+pub use codegen::rust::generate_rust as generate_rust;
+/// Options controlling the root name and derives of generated Rust structs
+pub use codegen::rust::RustCodegenOptions as RustCodegenOptions;
+/// The derive macros generated Rust structs carry
+pub use codegen::rust::CodegenDerives as CodegenDerives;
+/// Infers a JSON Schema, as a Node, describing the sample Node trees
+pub use schema::infer::infer as infer_schema;
+/// Error returned when a patch operation cannot be applied
+pub use error::patch::PatchError as PatchError;
+/// Evaluates a JSONPath expression against a Node tree
+pub use query::jsonpath::query as query;
+/// Compiled JSONPath query that can be evaluated against many Node trees
+pub use query::jsonpath::Query as Query;
+/// Node matched by a JSONPath query together with its path
+pub use query::jsonpath::QueryMatch as QueryMatch;
+/// Extracts the leading YAML front matter block from text content and parses it into a Node
+pub use front_matter::extract::extract as extract_front_matter;
+/// Reads a text file and extracts its leading YAML front matter block
+pub use front_matter::extract::extract_from_file as extract_front_matter_from_file;
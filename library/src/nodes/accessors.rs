@@ -0,0 +1,387 @@
+//! Non-panicking accessors for Node values.
+//! The as_* methods borrow the value held by a node and the into_* methods take
+//! ownership of it; both return None when the node holds a different variant.
+//! The get* methods are non-panicking alternatives to indexing, and get_as/get_or
+//! look up and convert values by dotted path ("server.port", "servers.0.host").
+
+use crate::nodes::node::{lookup_key, Node, Numeric};
+use indexmap::IndexMap;
+
+impl Node {
+    /// Returns the value for a key of a Dictionary node.
+    ///
+    /// # Arguments
+    /// * `key` - Key to look up
+    ///
+    /// # Returns
+    /// The value, or None if the node is not a dictionary or has no such key.
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        match self {
+            Node::Dictionary(map) => map.get(lookup_key(map, key).as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Returns the value for a key of a Dictionary node for modification.
+    ///
+    /// # Arguments
+    /// * `key` - Key to look up
+    ///
+    /// # Returns
+    /// The value, or None if the node is not a dictionary or has no such key.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Node> {
+        match self {
+            Node::Dictionary(map) => {
+                let key = lookup_key(map, key).into_owned();
+                map.get_mut(&key)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the item at an index of an Array node.
+    ///
+    /// # Arguments
+    /// * `index` - Index of the item
+    ///
+    /// # Returns
+    /// The item, or None if the node is not an array or the index is out of bounds.
+    pub fn get_index(&self, index: usize) -> Option<&Node> {
+        self.as_array()?.get(index)
+    }
+
+    /// Returns the item at an index of an Array node for modification.
+    ///
+    /// # Arguments
+    /// * `index` - Index of the item
+    ///
+    /// # Returns
+    /// The item, or None if the node is not an array or the index is out of bounds.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut Node> {
+        self.as_array_mut()?.get_mut(index)
+    }
+
+    /// Looks up a node by dotted path ("server.port"); numeric segments index arrays.
+    /// Keys containing '.' cannot be reached this way, use pointer() for those.
+    ///
+    /// # Arguments
+    /// * `path` - Dotted path to the node ("" for this node)
+    ///
+    /// # Returns
+    /// The node, or None if the path does not resolve.
+    pub fn get_path(&self, path: &str) -> Option<&Node> {
+        if path.is_empty() {
+            return Some(self);
+        }
+        path.split('.').try_fold(self, |node, segment| match node {
+            Node::Array(items) | Node::Document(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => node.get(segment),
+        })
+    }
+
+    /// Looks up a value by dotted path and converts it to a Rust type.
+    ///
+    /// # Arguments
+    /// * `path` - Dotted path to the value, for example "server.port"
+    ///
+    /// # Returns
+    /// The converted value, or None if the path does not resolve or the value cannot be
+    /// converted (for example node.get_as::<u16>("server.port")).
+    pub fn get_as<'a, T>(&'a self, path: &str) -> Option<T>
+    where
+        T: TryFrom<&'a Node>,
+    {
+        T::try_from(self.get_path(path)?).ok()
+    }
+
+    /// Looks up a value by dotted path and converts it, falling back to a default.
+    ///
+    /// # Arguments
+    /// * `path` - Dotted path to the value
+    /// * `default` - Value returned if the path does not resolve or cannot be converted
+    pub fn get_or<'a, T>(&'a self, path: &str, default: T) -> T
+    where
+        T: TryFrom<&'a Node>,
+    {
+        self.get_as(path).unwrap_or(default)
+    }
+
+    /// Returns the value of a Boolean node.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Node::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the numeric value of a Number node.
+    pub fn as_number(&self) -> Option<&Numeric> {
+        match self {
+            Node::Number(number) => Some(number),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of an integer Number node as an i64.
+    ///
+    /// # Returns
+    /// The value, or None if the node is not an integer or the value does not fit in an i64.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number()?.as_i64()
+    }
+
+    /// Returns the value of an integer Number node as a u64.
+    ///
+    /// # Returns
+    /// The value, or None if the node is not an integer or the value is negative.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number()?.as_u64()
+    }
+
+    /// Returns the value of any Number node as an f64 (large integers may lose precision).
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(Numeric::as_f64)
+    }
+
+    /// Returns the text of a Str node.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Node::Str(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Returns the items of an Array node.
+    pub fn as_array(&self) -> Option<&Vec<Node>> {
+        match self {
+            Node::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Returns the items of an Array node for modification.
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Node>> {
+        match self {
+            Node::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Returns the entries of a Dictionary node.
+    pub fn as_dict(&self) -> Option<&IndexMap<String, Node>> {
+        match self {
+            Node::Dictionary(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Returns the entries of a Dictionary node for modification.
+    pub fn as_dict_mut(&mut self) -> Option<&mut IndexMap<String, Node>> {
+        match self {
+            Node::Dictionary(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Returns the text of a Comment node.
+    pub fn as_comment(&self) -> Option<&str> {
+        match self {
+            Node::Comment(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Returns the nodes of a Document node.
+    pub fn as_document(&self) -> Option<&Vec<Node>> {
+        match self {
+            Node::Document(nodes) => Some(nodes),
+            _ => None,
+        }
+    }
+
+    /// Returns the nodes of a Document node for modification.
+    pub fn as_document_mut(&mut self) -> Option<&mut Vec<Node>> {
+        match self {
+            Node::Document(nodes) => Some(nodes),
+            _ => None,
+        }
+    }
+
+    /// Returns Some(()) for a None (null) node, mirroring the other accessors.
+    pub fn as_null(&self) -> Option<()> {
+        match self {
+            Node::None => Some(()),
+            _ => None,
+        }
+    }
+
+    /// Takes the numeric value of a Number node.
+    pub fn into_number(self) -> Option<Numeric> {
+        match self {
+            Node::Number(number) => Some(number),
+            _ => None,
+        }
+    }
+
+    /// Takes the text of a Str node.
+    pub fn into_string(self) -> Option<String> {
+        match self {
+            Node::Str(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Takes the items of an Array node.
+    pub fn into_array(self) -> Option<Vec<Node>> {
+        match self {
+            Node::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Takes the entries of a Dictionary node.
+    pub fn into_dict(self) -> Option<IndexMap<String, Node>> {
+        match self {
+            Node::Dictionary(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Takes the text of a Comment node.
+    pub fn into_comment(self) -> Option<String> {
+        match self {
+            Node::Comment(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Takes the nodes of a Document node.
+    pub fn into_document(self) -> Option<Vec<Node>> {
+        match self {
+            Node::Document(nodes) => Some(nodes),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_works() {
+        let mut map = IndexMap::new();
+        map.insert("port".to_string(), Node::from(80));
+        let mut node = Node::Dictionary(map);
+        assert_eq!(node.get("port"), Some(&Node::from(80)));
+        assert_eq!(node.get("missing"), None);
+        *node.get_mut("port").unwrap() = Node::from(8080);
+        assert_eq!(node["port"], Node::from(8080));
+        assert!(node.get_mut("missing").is_none());
+        assert_eq!(Node::from(1).get("port"), None);
+    }
+
+    #[test]
+    fn get_index_works() {
+        let mut node = Node::from(vec![1, 2]);
+        assert_eq!(node.get_index(1), Some(&Node::from(2)));
+        assert_eq!(node.get_index(2), None);
+        *node.get_index_mut(0).unwrap() = Node::from(5);
+        assert_eq!(node[0], Node::from(5));
+        assert_eq!(Node::None.get_index(0), None);
+        assert_eq!(Node::Dictionary(IndexMap::new()).get_index(0), None);
+    }
+
+    #[test]
+    fn get_path_works() {
+        let mut server = IndexMap::new();
+        server.insert("port".to_string(), Node::from(8080));
+        server.insert("hosts".to_string(), Node::from(vec!["a", "b"]));
+        let mut map = IndexMap::new();
+        map.insert("server".to_string(), Node::Dictionary(server));
+        let node = Node::Dictionary(map);
+        assert_eq!(node.get_path("server.port"), Some(&Node::from(8080)));
+        assert_eq!(node.get_path("server.hosts.1"), Some(&Node::from("b")));
+        assert_eq!(node.get_path(""), Some(&node));
+        assert_eq!(node.get_path("server.hosts.x"), None);
+        assert_eq!(node.get_path("server.port.x"), None);
+    }
+
+    #[test]
+    fn get_as_and_get_or_work() {
+        let mut server = IndexMap::new();
+        server.insert("port".to_string(), Node::from(8080));
+        server.insert("debug".to_string(), Node::Boolean(true));
+        let mut map = IndexMap::new();
+        map.insert("server".to_string(), Node::Dictionary(server));
+        let node = Node::Dictionary(map);
+        assert_eq!(node.get_as::<u16>("server.port"), Some(8080));
+        assert_eq!(node.get_as::<u8>("server.port"), None);
+        assert_eq!(node.get_as::<bool>("server.debug"), Some(true));
+        assert_eq!(node.get_as::<String>("server.missing"), None);
+        assert_eq!(node.get_or("server.timeout", 30u32), 30);
+        assert_eq!(node.get_or("server.port", 80u16), 8080);
+        assert_eq!(node.get_or("server.port", "none".to_string()), "none");
+    }
+
+    #[test]
+    fn scalar_accessors_work() {
+        assert_eq!(Node::Boolean(true).as_bool(), Some(true));
+        assert_eq!(Node::from("text").as_str(), Some("text"));
+        assert!(Node::from(42).as_number().is_some_and(|number| number.strict_eq(&Numeric::Int32(42))));
+        assert_eq!(Node::None.as_null(), Some(()));
+        assert_eq!(Node::Comment("note".to_string()).as_comment(), Some("note"));
+    }
+
+    #[test]
+    fn scalar_accessors_reject_other_variants() {
+        let node = Node::from("text");
+        assert_eq!(node.as_bool(), None);
+        assert_eq!(node.as_i64(), None);
+        assert_eq!(node.as_f64(), None);
+        assert_eq!(node.as_null(), None);
+        assert_eq!(node.as_comment(), None);
+        assert_eq!(Node::Comment("note".to_string()).as_str(), None);
+    }
+
+    #[test]
+    fn numeric_accessors_work_across_variants() {
+        assert_eq!(Node::from(7u8).as_i64(), Some(7));
+        assert_eq!(Node::from(-3i16).as_i64(), Some(-3));
+        assert_eq!(Node::from(-3i16).as_u64(), None);
+        assert_eq!(Node::from(u64::MAX).as_i64(), None);
+        assert_eq!(Node::from(u64::MAX).as_u64(), Some(u64::MAX));
+        assert_eq!(Node::from(2.5).as_i64(), None);
+        assert_eq!(Node::from(2.5).as_f64(), Some(2.5));
+        assert_eq!(Node::from(4i32).as_f64(), Some(4.0));
+    }
+
+    #[test]
+    fn collection_accessors_work() {
+        let mut array = Node::from(vec![1, 2]);
+        assert_eq!(array.as_array().map(Vec::len), Some(2));
+        array.as_array_mut().unwrap().push(Node::from(3));
+        assert_eq!(array.as_dict(), None);
+        assert_eq!(array.into_array().map(|items| items.len()), Some(3));
+
+        let mut dict = Node::Dictionary(IndexMap::new());
+        dict.as_dict_mut().unwrap().insert("a".to_string(), Node::None);
+        assert!(dict.as_dict().unwrap().contains_key("a"));
+        assert_eq!(dict.as_array(), None);
+        assert_eq!(dict.into_dict().map(|map| map.len()), Some(1));
+
+        let mut document = Node::Document(vec![Node::None]);
+        document.as_document_mut().unwrap().push(Node::None);
+        assert_eq!(document.as_document().map(Vec::len), Some(2));
+        assert_eq!(document.into_document().map(|nodes| nodes.len()), Some(2));
+    }
+
+    #[test]
+    fn owned_accessors_work() {
+        assert_eq!(Node::from("text").into_string(), Some("text".to_string()));
+        assert_eq!(Node::from(1).into_string(), None);
+        assert!(Node::from(1).into_number().is_some_and(|number| number.strict_eq(&Numeric::Int32(1))));
+        assert_eq!(Node::Comment("c".to_string()).into_comment(), Some("c".to_string()));
+        assert_eq!(Node::None.into_array(), None);
+    }
+}
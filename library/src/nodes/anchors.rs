@@ -0,0 +1,161 @@
+//! Anchor metadata for Node trees.
+//! Records which nodes were defined with an anchor ("&name"), keyed by the
+//! JSON-pointer path of the node, in the order the anchors were defined, and where
+//! aliases ("*name") to them were read.
+
+use crate::nodes::path::join;
+
+/// Anchor definitions for a tree.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Anchors {
+    /// (node path, anchor name) pairs in definition order
+    definitions: Vec<(String, String)>,
+    /// (alias path, anchored node path) pairs in the order the aliases were read
+    aliases: Vec<(String, String)>,
+}
+
+impl Anchors {
+    /// Creates an empty anchor table.
+    pub fn new() -> Self {
+        Self { definitions: Vec::new(), aliases: Vec::new() }
+    }
+
+    /// Records that the node at a path was defined with an anchor.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the anchored node
+    /// * `name` - Anchor name without the leading "&"
+    pub fn define(&mut self, path: &str, name: &str) {
+        self.definitions.retain(|(existing, _)| existing != path);
+        self.definitions.push((path.to_string(), name.to_string()));
+    }
+
+    /// Returns the anchor a node was defined with, if any.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the node
+    pub fn anchor_at(&self, path: &str) -> Option<&str> {
+        self.definitions.iter().find(|(existing, _)| existing == path).map(|(_, name)| name.as_str())
+    }
+
+    /// Returns the path of the node an anchor name refers to. If the name was defined more
+    /// than once the most recent definition is returned, as aliases resolve to it.
+    ///
+    /// # Arguments
+    /// * `name` - Anchor name without the leading "&"
+    pub fn path_of(&self, name: &str) -> Option<&str> {
+        self.definitions.iter().rev().find(|(_, existing)| existing == name).map(|(path, _)| path.as_str())
+    }
+
+    /// Records that the node at a path was read as an alias of the anchored node at
+    /// another path.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the alias
+    /// * `target` - Path of the anchored node the alias refers to
+    pub fn alias(&mut self, path: &str, target: &str) {
+        self.aliases.push((path.to_string(), target.to_string()));
+    }
+
+    /// Iterates over (alias path, anchored node path) pairs in the order the aliases were
+    /// read, so an alias inside an anchored node comes before any alias that copies it.
+    pub fn aliases(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.aliases.iter().map(|(path, target)| (path.as_str(), target.as_str()))
+    }
+
+    /// Returns the distinct anchor names in the order they were first defined.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for (_, name) in &self.definitions {
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Iterates over (path, anchor name) pairs in definition order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.definitions.iter().map(|(path, name)| (path.as_str(), name.as_str()))
+    }
+
+    /// Returns the number of anchored nodes.
+    pub fn len(&self) -> usize {
+        self.definitions.len()
+    }
+
+    /// Returns true if no nodes are anchored.
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    /// Moves every definition under a new parent path.
+    ///
+    /// # Arguments
+    /// * `segment` - Path segment to prefix each path with
+    pub fn prefixed(self, segment: &str) -> Anchors {
+        let prefix = join("", segment);
+        let definitions = self.definitions.into_iter().map(|(path, name)| (format!("{}{}", prefix, path), name));
+        let aliases = self
+            .aliases
+            .into_iter()
+            .map(|(path, target)| (format!("{}{}", prefix, path), format!("{}{}", prefix, target)));
+        Anchors { definitions: definitions.collect(), aliases: aliases.collect() }
+    }
+
+    /// Appends all definitions from another anchor table.
+    ///
+    /// # Arguments
+    /// * `other` - Anchors to add
+    pub fn extend(&mut self, other: Anchors) {
+        for (path, name) in other.definitions {
+            self.define(&path, &name);
+        }
+        self.aliases.extend(other.aliases);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_anchors_are_empty() {
+        let anchors = Anchors::new();
+        assert!(anchors.is_empty());
+        assert_eq!(anchors.anchor_at("/a"), None);
+        assert_eq!(anchors.path_of("base"), None);
+    }
+
+    #[test]
+    fn define_and_query_works() {
+        let mut anchors = Anchors::new();
+        anchors.define("/defaults", "base");
+        anchors.define("/items/0", "first");
+        assert_eq!(anchors.anchor_at("/defaults"), Some("base"));
+        assert_eq!(anchors.path_of("first"), Some("/items/0"));
+        assert_eq!(anchors.names(), vec!["base", "first"]);
+        assert_eq!(anchors.len(), 2);
+    }
+
+    #[test]
+    fn redefined_anchor_resolves_to_latest() {
+        let mut anchors = Anchors::new();
+        anchors.define("/a", "x");
+        anchors.define("/b", "x");
+        assert_eq!(anchors.path_of("x"), Some("/b"));
+        assert_eq!(anchors.names(), vec!["x"]);
+        assert_eq!(anchors.iter().count(), 2);
+    }
+
+    #[test]
+    fn prefixed_and_extend_work() {
+        let mut first = Anchors::new();
+        first.define("/a", "x");
+        let mut all = Anchors::new();
+        first.alias("/b", "/a");
+        all.extend(first.prefixed("1"));
+        assert_eq!(all.anchor_at("/1/a"), Some("x"));
+        assert_eq!(all.aliases().collect::<Vec<_>>(), vec![("/1/b", "/1/a")]);
+    }
+}
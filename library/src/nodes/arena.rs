@@ -0,0 +1,235 @@
+//! Arena storage for node trees.
+//! A NodeArena keeps every node of one or more trees in a few contiguous vectors and
+//! refers to them by NodeId: the children of a collection occupy a contiguous run, all
+//! string values and keys share one text buffer, and dictionary entries are kept in
+//! sorted key order so lookups are binary searches. Trees are added with alloc and turned
+//! back into owned Nodes with to_node.
+
+use crate::nodes::kind::NodeKind;
+use crate::nodes::node::{Node, Numeric};
+use indexmap::IndexMap;
+use std::ops::Range;
+
+/// Index of a node within a NodeArena.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// A node stored in an arena; text and children are ranges into the arena's buffers.
+#[derive(Clone, Debug)]
+enum Slot {
+    Boolean(bool),
+    Number(Numeric),
+    Str(Range<usize>),
+    Array(Range<usize>),
+    Dictionary(Range<usize>),
+    Comment(Range<usize>),
+    Document(Range<usize>),
+    None,
+}
+
+/// Contiguous storage for the nodes of one or more trees.
+#[derive(Clone, Debug, Default)]
+pub struct NodeArena {
+    /// Every node, addressed by NodeId
+    slots: Vec<Slot>,
+    /// Children of arrays and documents, a contiguous run per collection
+    items: Vec<NodeId>,
+    /// Key text ranges and values of dictionaries, a sorted run per dictionary
+    entries: Vec<(Range<usize>, NodeId)>,
+    /// Text of all strings, comments and keys
+    text: String,
+}
+
+impl NodeArena {
+    /// Creates an empty arena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of nodes stored.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns true if no nodes are stored.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Copies a tree into the arena.
+    ///
+    /// # Arguments
+    /// * `node` - Root of the tree to copy
+    ///
+    /// # Returns
+    /// The id of the copied root.
+    pub fn alloc(&mut self, node: &Node) -> NodeId {
+        let slot = match node {
+            Node::Boolean(value) => Slot::Boolean(*value),
+            Node::Number(number) => Slot::Number(number.clone()),
+            Node::Str(text) => Slot::Str(self.alloc_text(text)),
+            Node::Comment(text) => Slot::Comment(self.alloc_text(text)),
+            Node::None => Slot::None,
+            Node::Array(items) => Slot::Array(self.alloc_items(items)),
+            Node::Document(items) => Slot::Document(self.alloc_items(items)),
+            Node::Dictionary(_) => {
+                let children: Vec<(Range<usize>, NodeId)> =
+                    node.entries().map(|(key, value)| (self.alloc_text(key), self.alloc(value))).collect();
+                let start = self.entries.len();
+                self.entries.extend(children);
+                Slot::Dictionary(start..self.entries.len())
+            }
+        };
+        self.slots.push(slot);
+        NodeId(self.slots.len() - 1)
+    }
+
+    fn alloc_text(&mut self, text: &str) -> Range<usize> {
+        let start = self.text.len();
+        self.text.push_str(text);
+        start..self.text.len()
+    }
+
+    fn alloc_items(&mut self, items: &[Node]) -> Range<usize> {
+        let children: Vec<NodeId> = items.iter().map(|item| self.alloc(item)).collect();
+        let start = self.items.len();
+        self.items.extend(children);
+        start..self.items.len()
+    }
+
+    /// Returns the kind of a node.
+    ///
+    /// # Panics
+    /// If the id does not belong to this arena.
+    pub fn kind(&self, id: NodeId) -> NodeKind {
+        match self.slots[id.0] {
+            Slot::Boolean(_) => NodeKind::Boolean,
+            Slot::Number(_) => NodeKind::Number,
+            Slot::Str(_) => NodeKind::Str,
+            Slot::Array(_) => NodeKind::Array,
+            Slot::Dictionary(_) => NodeKind::Dictionary,
+            Slot::Comment(_) => NodeKind::Comment,
+            Slot::Document(_) => NodeKind::Document,
+            Slot::None => NodeKind::Null,
+        }
+    }
+
+    /// Returns the value of a Boolean node.
+    pub fn as_bool(&self, id: NodeId) -> Option<bool> {
+        match self.slots[id.0] {
+            Slot::Boolean(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a Number node.
+    pub fn as_number(&self, id: NodeId) -> Option<&Numeric> {
+        match &self.slots[id.0] {
+            Slot::Number(number) => Some(number),
+            _ => None,
+        }
+    }
+
+    /// Returns the text of a Str node.
+    pub fn as_str(&self, id: NodeId) -> Option<&str> {
+        match &self.slots[id.0] {
+            Slot::Str(range) => Some(&self.text[range.clone()]),
+            _ => None,
+        }
+    }
+
+    /// Returns the children of an Array or Document node; other nodes have none.
+    pub fn items(&self, id: NodeId) -> &[NodeId] {
+        match &self.slots[id.0] {
+            Slot::Array(range) | Slot::Document(range) => &self.items[range.clone()],
+            _ => &[],
+        }
+    }
+
+    /// Returns the key/value pairs of a Dictionary node in sorted key order; other nodes
+    /// have none.
+    pub fn entries(&self, id: NodeId) -> impl Iterator<Item = (&str, NodeId)> {
+        let range = match &self.slots[id.0] {
+            Slot::Dictionary(range) => range.clone(),
+            _ => 0..0,
+        };
+        self.entries[range].iter().map(|(key, value)| (&self.text[key.clone()], *value))
+    }
+
+    /// Returns the value for a key of a Dictionary node.
+    ///
+    /// # Arguments
+    /// * `id` - The dictionary
+    /// * `key` - Key to look up
+    pub fn get(&self, id: NodeId, key: &str) -> Option<NodeId> {
+        let Slot::Dictionary(range) = &self.slots[id.0] else {
+            return None;
+        };
+        let entries = &self.entries[range.clone()];
+        let position = entries.binary_search_by(|(candidate, _)| self.text[candidate.clone()].cmp(key)).ok()?;
+        Some(entries[position].1)
+    }
+
+    /// Returns an owned copy of the tree rooted at a node.
+    pub fn to_node(&self, id: NodeId) -> Node {
+        match &self.slots[id.0] {
+            Slot::Boolean(value) => Node::Boolean(*value),
+            Slot::Number(number) => Node::Number(number.clone()),
+            Slot::Str(range) => Node::Str(self.text[range.clone()].to_string()),
+            Slot::Comment(range) => Node::Comment(self.text[range.clone()].to_string()),
+            Slot::None => Node::None,
+            Slot::Array(_) => Node::Array(self.items(id).iter().map(|item| self.to_node(*item)).collect()),
+            Slot::Document(_) => Node::Document(self.items(id).iter().map(|item| self.to_node(*item)).collect()),
+            Slot::Dictionary(_) => {
+                let map: IndexMap<String, Node> =
+                    self.entries(id).map(|(key, value)| (key.to_string(), self.to_node(value))).collect();
+                Node::Dictionary(map)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Node {
+        Node::dict()
+            .key("name", "web")
+            .key("ports", vec![80, 443])
+            .key("tls", Node::dict().key("enabled", true).key("cert", Node::None))
+            .build()
+    }
+
+    #[test]
+    fn alloc_and_to_node_round_trip() {
+        let node = config();
+        let mut arena = NodeArena::new();
+        let root = arena.alloc(&node);
+        assert_eq!(arena.len(), 8);
+        assert!(arena.to_node(root).strict_eq(&node));
+        let document = Node::Document(vec![Node::Comment("c".to_string()), Node::from(1.5)]);
+        let second = arena.alloc(&document);
+        assert!(arena.to_node(second).strict_eq(&document));
+        assert!(arena.to_node(root).strict_eq(&node));
+    }
+
+    #[test]
+    fn accessors_work() {
+        let mut arena = NodeArena::new();
+        let root = arena.alloc(&config());
+        assert_eq!(arena.kind(root), NodeKind::Dictionary);
+        let keys: Vec<&str> = arena.entries(root).map(|(key, _)| key).collect();
+        assert_eq!(keys, vec!["name", "ports", "tls"]);
+        assert_eq!(arena.get(root, "name").and_then(|id| arena.as_str(id)), Some("web"));
+        let ports = arena.get(root, "ports").unwrap();
+        assert_eq!(arena.items(ports).len(), 2);
+        assert_eq!(arena.as_number(arena.items(ports)[1]).and_then(Numeric::as_i64), Some(443));
+        let tls = arena.get(root, "tls").unwrap();
+        assert_eq!(arena.get(tls, "enabled").and_then(|id| arena.as_bool(id)), Some(true));
+        assert_eq!(arena.kind(arena.get(tls, "cert").unwrap()), NodeKind::Null);
+        assert_eq!(arena.get(root, "missing"), None);
+        assert_eq!(arena.get(ports, "0"), None);
+        assert!(arena.items(root).is_empty());
+    }
+}
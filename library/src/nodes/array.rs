@@ -0,0 +1,139 @@
+//! Mutation helpers for Array nodes.
+//! push, insert_at, remove_at, extend_items and pop work like their Vec counterparts
+//! (insert and remove are the Dictionary forms). A None node is turned into an empty
+//! array before adding items; any other non-array node gives a ConversionError.
+
+use crate::error::conversion::ConversionError;
+use crate::nodes::node::Node;
+
+impl Node {
+    /// Returns the items of an Array node for modification, turning a None node into an
+    /// empty array first when create is set.
+    fn array_mut(&mut self, create: bool) -> Result<&mut Vec<Node>, ConversionError> {
+        if create && let Node::None = self {
+            *self = Node::Array(Vec::new());
+        }
+        match self {
+            Node::Array(items) => Ok(items),
+            other => Err(ConversionError::new("array", other)),
+        }
+    }
+
+    /// Appends an item to an Array node.
+    ///
+    /// # Arguments
+    /// * `value` - The item to append
+    ///
+    /// # Returns
+    /// Ok, or a ConversionError if the node is neither an array nor None.
+    pub fn push<V: Into<Node>>(&mut self, value: V) -> Result<(), ConversionError> {
+        self.array_mut(true)?.push(value.into());
+        Ok(())
+    }
+
+    /// Inserts an item into an Array node, shifting later items along.
+    ///
+    /// # Arguments
+    /// * `index` - Position of the new item; may equal the length to append
+    /// * `value` - The item to insert
+    ///
+    /// # Returns
+    /// Ok, or a ConversionError if the node is neither an array nor None.
+    ///
+    /// # Panics
+    /// If index is greater than the number of items, as Vec::insert does.
+    pub fn insert_at<V: Into<Node>>(&mut self, index: usize, value: V) -> Result<(), ConversionError> {
+        self.array_mut(true)?.insert(index, value.into());
+        Ok(())
+    }
+
+    /// Removes and returns an item of an Array node, shifting later items back.
+    ///
+    /// # Arguments
+    /// * `index` - Position of the item
+    ///
+    /// # Returns
+    /// The item, or None if the node is not an array or the index is out of bounds.
+    pub fn remove_at(&mut self, index: usize) -> Option<Node> {
+        let items = self.array_mut(false).ok()?;
+        (index < items.len()).then(|| items.remove(index))
+    }
+
+    /// Appends items to an Array node.
+    ///
+    /// # Arguments
+    /// * `values` - The items to append
+    ///
+    /// # Returns
+    /// Ok, or a ConversionError if the node is neither an array nor None.
+    pub fn extend_items<I, V>(&mut self, values: I) -> Result<(), ConversionError>
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Node>,
+    {
+        self.array_mut(true)?.extend(values.into_iter().map(Into::into));
+        Ok(())
+    }
+
+    /// Removes and returns the last item of an Array node.
+    ///
+    /// # Returns
+    /// The item, or None if the node is not an array or is empty.
+    pub fn pop(&mut self) -> Option<Node> {
+        self.array_mut(false).ok()?.pop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_and_pop_work() {
+        let mut node = Node::from(vec![1]);
+        node.push(2).unwrap();
+        node.push("three").unwrap();
+        assert_eq!(node.pop(), Some(Node::from("three")));
+        assert_eq!(node, Node::from(vec![1, 2]));
+        assert_eq!(Node::Array(vec![]).pop(), None);
+    }
+
+    #[test]
+    fn push_onto_none_creates_array() {
+        let mut node = Node::None;
+        node.push(true).unwrap();
+        assert_eq!(node, Node::from(vec![true]));
+        let mut node = Node::None;
+        assert_eq!(node.pop(), None);
+        assert_eq!(node, Node::None);
+    }
+
+    #[test]
+    fn insert_at_and_remove_at_work() {
+        let mut node = Node::from(vec![1, 3]);
+        node.insert_at(1, 2).unwrap();
+        node.insert_at(3, 4).unwrap();
+        assert_eq!(node, Node::from(vec![1, 2, 3, 4]));
+        assert_eq!(node.remove_at(0), Some(Node::from(1)));
+        assert_eq!(node.remove_at(5), None);
+        assert_eq!(node, Node::from(vec![2, 3, 4]));
+    }
+
+    #[test]
+    fn extend_items_works() {
+        let mut node = Node::from(vec!["a"]);
+        node.extend_items(["b", "c"]).unwrap();
+        assert_eq!(node, Node::from(vec!["a", "b", "c"]));
+    }
+
+    #[test]
+    fn other_variants_fail() {
+        let mut node = Node::dict().key("a", 1).build();
+        assert_eq!(node.push(1).unwrap_err().to_string(), "Expected array but found dictionary");
+        assert!(node.insert_at(0, 1).is_err());
+        assert!(node.extend_items([1]).is_err());
+        assert_eq!(node.remove_at(0), None);
+        assert_eq!(node.pop(), None);
+        assert_eq!(node, Node::dict().key("a", 1).build());
+    }
+}
@@ -0,0 +1,149 @@
+//! Fluent builders for constructing Node trees programmatically, for example
+//! Node::dict().key("name", "web").key("ports", Node::seq().push(80).push(443)).build().
+
+use crate::nodes::node::Node;
+use indexmap::IndexMap;
+
+/// Builder for Dictionary nodes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DictionaryBuilder {
+    /// Entries added so far
+    entries: IndexMap<String, Node>,
+}
+
+impl DictionaryBuilder {
+    /// Creates a builder for an empty dictionary.
+    pub fn new() -> Self {
+        Self { entries: IndexMap::new() }
+    }
+
+    /// Adds an entry, replacing any earlier value for the same key.
+    ///
+    /// # Arguments
+    /// * `key` - Key of the entry
+    /// * `value` - Value of the entry; builders can be passed without calling build()
+    pub fn key<K: Into<String>, V: Into<Node>>(mut self, key: K, value: V) -> Self {
+        self.entries.insert(key.into(), value.into());
+        self
+    }
+
+    /// Adds an entry only if a value is present.
+    ///
+    /// # Arguments
+    /// * `key` - Key of the entry
+    /// * `value` - Optional value of the entry
+    pub fn key_opt<K: Into<String>, V: Into<Node>>(self, key: K, value: Option<V>) -> Self {
+        match value {
+            Some(value) => self.key(key, value),
+            None => self,
+        }
+    }
+
+    /// Returns the built Dictionary node.
+    pub fn build(self) -> Node {
+        Node::Dictionary(self.entries)
+    }
+}
+
+/// Builder for Array (sequence) nodes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SequenceBuilder {
+    /// Items added so far
+    items: Vec<Node>,
+}
+
+impl SequenceBuilder {
+    /// Creates a builder for an empty sequence.
+    pub fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    /// Appends an item.
+    ///
+    /// # Arguments
+    /// * `value` - The item; builders can be passed without calling build()
+    pub fn push<V: Into<Node>>(mut self, value: V) -> Self {
+        self.items.push(value.into());
+        self
+    }
+
+    /// Appends every item of an iterator.
+    ///
+    /// # Arguments
+    /// * `values` - The items to append
+    pub fn extend<I, V>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Node>,
+    {
+        self.items.extend(values.into_iter().map(Into::into));
+        self
+    }
+
+    /// Returns the built Array node.
+    pub fn build(self) -> Node {
+        Node::Array(self.items)
+    }
+}
+
+impl From<DictionaryBuilder> for Node {
+    fn from(builder: DictionaryBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<SequenceBuilder> for Node {
+    fn from(builder: SequenceBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl Node {
+    /// Starts building a Dictionary node.
+    pub fn dict() -> DictionaryBuilder {
+        DictionaryBuilder::new()
+    }
+
+    /// Starts building an Array (sequence) node.
+    pub fn seq() -> SequenceBuilder {
+        SequenceBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_nested_tree_works() {
+        let node = Node::dict().key("name", "web").key("ports", Node::seq().push(80).push(443)).build();
+        assert_eq!(node["name"], Node::from("web"));
+        assert_eq!(node["ports"], Node::from(vec![80, 443]));
+    }
+
+    #[test]
+    fn build_empty_collections_works() {
+        assert_eq!(Node::dict().build(), Node::Dictionary(IndexMap::new()));
+        assert_eq!(Node::seq().build(), Node::Array(Vec::new()));
+    }
+
+    #[test]
+    fn key_replaces_earlier_value() {
+        let node = Node::dict().key("a", 1).key("a".to_string(), 2).build();
+        assert_eq!(node["a"], Node::from(2));
+        assert_eq!(node.as_dict().map(IndexMap::len), Some(1));
+    }
+
+    #[test]
+    fn key_opt_and_extend_work() {
+        let node = Node::dict()
+            .key_opt("present", Some(true))
+            .key_opt::<_, bool>("absent", None)
+            .key("list", Node::seq().extend(["a", "b"]).push(Node::dict().key("c", 3)))
+            .build();
+        assert_eq!(node.get("absent"), None);
+        assert_eq!(node["present"], Node::Boolean(true));
+        assert_eq!(node["list"][1], Node::from("b"));
+        assert_eq!(node["list"][2]["c"], Node::from(3));
+    }
+}
@@ -0,0 +1,174 @@
+//! Comment metadata for Node trees.
+//! Comments are kept beside the tree rather than inside it, keyed by the
+//! JSON-pointer path of the node they belong to, so the data itself is unaffected.
+
+use crate::nodes::path::join;
+use std::collections::HashMap;
+
+/// Comments attached to a single node.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodeComments {
+    /// Comment lines appearing on their own lines before the node
+    pub leading: Vec<String>,
+    /// Comment appearing at the end of the node's line
+    pub trailing: Option<String>,
+}
+
+/// Comment metadata for a tree, keyed by node path ("" is the root, "/port", "/servers/0").
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Comments {
+    /// Comments for each node path that has any
+    entries: HashMap<String, NodeComments>,
+}
+
+impl Comments {
+    /// Creates an empty comment table.
+    pub fn new() -> Self {
+        Self { entries: HashMap::new() }
+    }
+
+    /// Returns the comments attached to a node, if any.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the node
+    pub fn get(&self, path: &str) -> Option<&NodeComments> {
+        self.entries.get(path)
+    }
+
+    /// Returns the trailing comment attached to a node, if any.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the node
+    pub fn trailing(&self, path: &str) -> Option<&str> {
+        self.entries.get(path).and_then(|comments| comments.trailing.as_deref())
+    }
+
+    /// Sets the trailing comment of a node, replacing any existing one.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the node
+    /// * `text` - Comment text without the leading "#"
+    pub fn set_trailing(&mut self, path: &str, text: &str) {
+        self.entries.entry(path.to_string()).or_default().trailing = Some(text.to_string());
+    }
+
+    /// Returns the leading comment lines attached to a node (empty if none).
+    ///
+    /// # Arguments
+    /// * `path` - Path of the node
+    pub fn leading(&self, path: &str) -> &[String] {
+        self.entries.get(path).map_or(&[], |comments| comments.leading.as_slice())
+    }
+
+    /// Appends a leading comment line to a node.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the node
+    /// * `text` - Comment text without the leading "#"
+    pub fn add_leading(&mut self, path: &str, text: &str) {
+        self.entries.entry(path.to_string()).or_default().leading.push(text.to_string());
+    }
+
+    /// Removes and returns all comments attached to a node.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the node
+    pub fn remove(&mut self, path: &str) -> Option<NodeComments> {
+        self.entries.remove(path)
+    }
+
+    /// Returns the number of nodes with comments attached.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no comments are attached to any node.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over (path, comments) pairs in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &NodeComments)> {
+        self.entries.iter().map(|(path, comments)| (path.as_str(), comments))
+    }
+
+    /// Moves every entry under a new parent path (for example when a document becomes
+    /// one of several in a Document node).
+    ///
+    /// # Arguments
+    /// * `segment` - Path segment to prefix each entry with
+    pub fn prefixed(self, segment: &str) -> Comments {
+        let prefix = join("", segment);
+        let entries = self.entries.into_iter().map(|(path, comments)| (format!("{}{}", prefix, path), comments));
+        Comments { entries: entries.collect() }
+    }
+
+    /// Adds all entries from another comment table, appending leading comments and
+    /// replacing trailing comments for paths present in both.
+    ///
+    /// # Arguments
+    /// * `other` - Comments to add
+    pub fn extend(&mut self, other: Comments) {
+        for (path, comments) in other.entries {
+            let entry = self.entries.entry(path).or_default();
+            entry.leading.extend(comments.leading);
+            if comments.trailing.is_some() {
+                entry.trailing = comments.trailing;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_comments_are_empty() {
+        let comments = Comments::new();
+        assert!(comments.is_empty());
+        assert_eq!(comments.len(), 0);
+        assert_eq!(comments.trailing("/port"), None);
+        assert!(comments.leading("/port").is_empty());
+    }
+
+    #[test]
+    fn set_trailing_works() {
+        let mut comments = Comments::new();
+        comments.set_trailing("/port", "overridden in prod");
+        assert_eq!(comments.trailing("/port"), Some("overridden in prod"));
+        comments.set_trailing("/port", "changed");
+        assert_eq!(comments.trailing("/port"), Some("changed"));
+        assert_eq!(comments.len(), 1);
+    }
+
+    #[test]
+    fn add_leading_works() {
+        let mut comments = Comments::new();
+        comments.add_leading("/name", "first");
+        comments.add_leading("/name", "second");
+        assert_eq!(comments.leading("/name"), &["first".to_string(), "second".to_string()]);
+        assert_eq!(comments.get("/name").unwrap().trailing, None);
+    }
+
+    #[test]
+    fn remove_works() {
+        let mut comments = Comments::new();
+        comments.set_trailing("/a", "x");
+        assert!(comments.remove("/a").is_some());
+        assert!(comments.is_empty());
+    }
+
+    #[test]
+    fn prefixed_and_extend_work() {
+        let mut first = Comments::new();
+        first.set_trailing("/a", "one");
+        let mut second = Comments::new();
+        second.set_trailing("", "two");
+        let mut all = first.prefixed("0");
+        all.extend(second.prefixed("1"));
+        assert_eq!(all.trailing("/0/a"), Some("one"));
+        assert_eq!(all.trailing("/1"), Some("two"));
+        assert_eq!(all.iter().count(), 2);
+    }
+}
@@ -0,0 +1,117 @@
+//! Equality and ordering for Numeric values by mathematical value.
+//! Int32(5), Integer(5) and Float(5.0) are all equal, so trees built from Rust values
+//! compare equal to parsed trees whatever variants their numbers are stored in.
+//! strict_eq is the opt-out: it also requires numbers to be stored in the same variant.
+//! NaN equals NaN so that equality is total (Numeric and Node implement Eq and Hash);
+//! ordering still leaves NaN unordered.
+
+use crate::nodes::node::{Node, Numeric};
+use std::cmp::Ordering;
+
+/// Converts a float holding a whole number into an i128, or None for fractions,
+/// infinities, NaN and values beyond the i128 range.
+pub(crate) fn whole_float(value: f64) -> Option<i128> {
+    (value.fract() == 0.0 && value.abs() < 2f64.powi(127)).then_some(value as i128)
+}
+
+/// Compares numbers by value; integers and floats compare exactly when the float is a
+/// whole number, and as f64 otherwise. All NaNs are equal.
+impl PartialEq for Numeric {
+    fn eq(&self, other: &Self) -> bool {
+        match self.partial_cmp(other) {
+            Some(ordering) => ordering == Ordering::Equal,
+            None => self.as_f64().is_nan() && other.as_f64().is_nan(),
+        }
+    }
+}
+
+impl Eq for Numeric {}
+
+/// Orders numbers by value; NaN is unordered.
+impl PartialOrd for Numeric {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.as_wide_integer(), other.as_wide_integer()) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            (Some(a), None) => compare_integer_float(a, other.as_f64()),
+            (None, Some(b)) => compare_integer_float(b, self.as_f64()).map(Ordering::reverse),
+            (None, None) => self.as_f64().partial_cmp(&other.as_f64()),
+        }
+    }
+}
+
+fn compare_integer_float(integer: i128, float: f64) -> Option<Ordering> {
+    match whole_float(float) {
+        Some(whole) => Some(integer.cmp(&whole)),
+        None => (integer as f64).partial_cmp(&float),
+    }
+}
+
+impl Numeric {
+    /// Returns true if both numbers are stored in the same variant with the same value.
+    ///
+    /// # Arguments
+    /// * `other` - The number to compare with
+    pub fn strict_eq(&self, other: &Numeric) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other) && self == other
+    }
+}
+
+impl Node {
+    /// Compares two trees like ==, except that numbers must also be stored in the same
+    /// Numeric variant.
+    ///
+    /// # Arguments
+    /// * `other` - The tree to compare with
+    pub fn strict_eq(&self, other: &Node) -> bool {
+        match (self, other) {
+            (Node::Number(a), Node::Number(b)) => a.strict_eq(b),
+            (Node::Array(a), Node::Array(b)) | (Node::Document(a), Node::Document(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.strict_eq(b))
+            }
+            (Node::Dictionary(a), Node::Dictionary(b)) => {
+                a.len() == b.len() && a.iter().all(|(key, value)| b.get(key).is_some_and(|other| value.strict_eq(other)))
+            }
+            _ => self == other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_compare_by_value() {
+        assert_eq!(Numeric::Int32(5), Numeric::Integer(5));
+        assert_eq!(Numeric::Byte(5), Numeric::Float(5.0));
+        assert_eq!(Numeric::UInteger(u64::MAX), Numeric::UInteger(u64::MAX));
+        assert_ne!(Numeric::Int8(-1), Numeric::UInteger(u64::MAX));
+        assert_ne!(Numeric::Integer(1), Numeric::Float(1.5));
+        assert_eq!(Numeric::Float(f64::NAN), Numeric::Float(-f64::NAN));
+        assert_ne!(Numeric::Float(f64::NAN), Numeric::Integer(0));
+        assert_eq!(Node::from(5), Node::Number(Numeric::Integer(5)));
+    }
+
+    #[test]
+    fn numbers_order_by_value() {
+        assert!(Numeric::Int8(-1) < Numeric::Byte(0));
+        assert!(Numeric::Integer(2) > Numeric::Float(1.5));
+        assert!(Numeric::Float(1.5) < Numeric::UInt16(2));
+        assert!(Numeric::Integer(i64::MAX) < Numeric::UInteger(u64::MAX));
+        assert!(Numeric::Integer(1) < Numeric::Float(f64::INFINITY));
+        assert_eq!(Numeric::Integer(1).partial_cmp(&Numeric::Float(f64::NAN)), None);
+        assert_eq!(Numeric::Integer(3).partial_cmp(&Numeric::Float(3.0)), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn strict_eq_requires_same_variant() {
+        assert!(Numeric::Integer(5).strict_eq(&Numeric::Integer(5)));
+        assert!(!Numeric::Int32(5).strict_eq(&Numeric::Integer(5)));
+        let a = Node::dict().key("a", vec![1]).build();
+        let b = Node::dict().key("a", vec![1i64]).build();
+        assert_eq!(a, b);
+        assert!(!a.strict_eq(&b));
+        assert!(a.strict_eq(&a.clone()));
+        assert!(Node::from("x").strict_eq(&Node::from("x")));
+    }
+}
@@ -0,0 +1,257 @@
+//! Conversions from Nodes into Rust values, the inverse of the From impls on Node.
+//! Integers convert from any integer Numeric variant whose value fits the target type,
+//! and floats from any Numeric variant. Vec and HashMap convert item by item, reporting
+//! the path of the first item that fails.
+
+use crate::error::conversion::ConversionError;
+use crate::nodes::node::{Node, Numeric};
+use std::collections::HashMap;
+
+macro_rules! integer_try_from {
+    ($($target:ty),*) => {
+        $(
+            impl TryFrom<&Node> for $target {
+                type Error = ConversionError;
+
+                fn try_from(node: &Node) -> Result<Self, Self::Error> {
+                    node.as_number()
+                        .and_then(Numeric::as_wide_integer)
+                        .and_then(|value| <$target>::try_from(value).ok())
+                        .ok_or_else(|| ConversionError::new(stringify!($target), node))
+                }
+            }
+
+            impl TryFrom<Node> for $target {
+                type Error = ConversionError;
+
+                fn try_from(node: Node) -> Result<Self, Self::Error> {
+                    <$target>::try_from(&node)
+                }
+            }
+        )*
+    };
+}
+
+integer_try_from!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+impl TryFrom<&Node> for f64 {
+    type Error = ConversionError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        node.as_f64().ok_or_else(|| ConversionError::new("f64", node))
+    }
+}
+
+impl TryFrom<&Node> for f32 {
+    type Error = ConversionError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        node.as_f64().map(|value| value as f32).ok_or_else(|| ConversionError::new("f32", node))
+    }
+}
+
+impl TryFrom<&Node> for bool {
+    type Error = ConversionError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        node.as_bool().ok_or_else(|| ConversionError::new("bool", node))
+    }
+}
+
+impl TryFrom<&Node> for String {
+    type Error = ConversionError;
+
+    fn try_from(node: &Node) -> Result<Self, Self::Error> {
+        node.as_str().map(str::to_string).ok_or_else(|| ConversionError::new("String", node))
+    }
+}
+
+impl TryFrom<Node> for f64 {
+    type Error = ConversionError;
+
+    fn try_from(node: Node) -> Result<Self, Self::Error> {
+        f64::try_from(&node)
+    }
+}
+
+impl TryFrom<Node> for f32 {
+    type Error = ConversionError;
+
+    fn try_from(node: Node) -> Result<Self, Self::Error> {
+        f32::try_from(&node)
+    }
+}
+
+impl TryFrom<Node> for bool {
+    type Error = ConversionError;
+
+    fn try_from(node: Node) -> Result<Self, Self::Error> {
+        bool::try_from(&node)
+    }
+}
+
+impl TryFrom<Node> for String {
+    type Error = ConversionError;
+
+    fn try_from(node: Node) -> Result<Self, Self::Error> {
+        match node {
+            Node::Str(text) => Ok(text),
+            other => Err(ConversionError::new("String", &other)),
+        }
+    }
+}
+
+impl<'a, T> TryFrom<&'a Node> for Vec<T>
+where
+    T: TryFrom<&'a Node>,
+    T::Error: Into<ConversionError>,
+{
+    type Error = ConversionError;
+
+    fn try_from(node: &'a Node) -> Result<Self, Self::Error> {
+        let items = node.as_array().ok_or_else(|| ConversionError::new("array", node))?;
+        items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| T::try_from(item).map_err(|e| e.into().within(&index.to_string())))
+            .collect()
+    }
+}
+
+impl<T> TryFrom<Node> for Vec<T>
+where
+    T: TryFrom<Node>,
+    T::Error: Into<ConversionError>,
+{
+    type Error = ConversionError;
+
+    fn try_from(node: Node) -> Result<Self, Self::Error> {
+        let items = match node {
+            Node::Array(items) => items,
+            other => return Err(ConversionError::new("array", &other)),
+        };
+        items
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| T::try_from(item).map_err(|e| e.into().within(&index.to_string())))
+            .collect()
+    }
+}
+
+impl<'a, T> TryFrom<&'a Node> for HashMap<String, T>
+where
+    T: TryFrom<&'a Node>,
+    T::Error: Into<ConversionError>,
+{
+    type Error = ConversionError;
+
+    fn try_from(node: &'a Node) -> Result<Self, Self::Error> {
+        let map = node.as_dict().ok_or_else(|| ConversionError::new("dictionary", node))?;
+        map.iter()
+            .map(|(key, value)| match T::try_from(value) {
+                Ok(value) => Ok((key.clone(), value)),
+                Err(e) => Err(e.into().within(key)),
+            })
+            .collect()
+    }
+}
+
+impl<T> TryFrom<Node> for HashMap<String, T>
+where
+    T: TryFrom<Node>,
+    T::Error: Into<ConversionError>,
+{
+    type Error = ConversionError;
+
+    fn try_from(node: Node) -> Result<Self, Self::Error> {
+        let map = match node {
+            Node::Dictionary(map) => map,
+            other => return Err(ConversionError::new("dictionary", &other)),
+        };
+        map.into_iter()
+            .map(|(key, value)| match T::try_from(value) {
+                Ok(value) => Ok((key, value)),
+                Err(e) => Err(e.into().within(&key)),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_conversions_work() {
+        assert_eq!(u16::try_from(&Node::from(8080i64)), Ok(8080));
+        assert_eq!(i8::try_from(&Node::from(-5i32)), Ok(-5));
+        assert_eq!(u64::try_from(&Node::from(u64::MAX)), Ok(u64::MAX));
+        assert_eq!(usize::try_from(&Node::from(3u8)), Ok(3));
+    }
+
+    #[test]
+    fn integer_conversions_check_range_and_type() {
+        assert_eq!(u8::try_from(&Node::from(300)).unwrap_err().found, "number 300");
+        assert!(u32::try_from(&Node::from(-1)).is_err());
+        assert!(i64::try_from(&Node::from(1.5)).is_err());
+        assert_eq!(i64::try_from(&Node::from("1")).unwrap_err().expected, "i64");
+    }
+
+    #[test]
+    fn float_bool_and_string_conversions_work() {
+        assert_eq!(f64::try_from(&Node::from(2.5)), Ok(2.5));
+        assert_eq!(f64::try_from(&Node::from(2)), Ok(2.0));
+        assert_eq!(f32::try_from(&Node::from(0.5)), Ok(0.5));
+        assert_eq!(bool::try_from(&Node::Boolean(true)), Ok(true));
+        assert_eq!(String::try_from(&Node::from("web")), Ok("web".to_string()));
+        assert!(bool::try_from(&Node::None).is_err());
+        assert!(String::try_from(&Node::from(1)).is_err());
+    }
+
+    #[test]
+    fn owned_scalar_conversions_work() {
+        assert_eq!(i64::try_from(Node::from(7)), Ok(7));
+        assert_eq!(u64::try_from(Node::from(7u8)), Ok(7));
+        assert_eq!(f64::try_from(Node::from(1.5)), Ok(1.5));
+        assert_eq!(bool::try_from(Node::Boolean(false)), Ok(false));
+        assert_eq!(String::try_from(Node::from("owned")), Ok("owned".to_string()));
+        assert_eq!(String::try_from(Node::None).unwrap_err().found, "null");
+    }
+
+    #[test]
+    fn vec_conversions_work() {
+        let node = Node::from(vec![80, 443]);
+        assert_eq!(Vec::<u16>::try_from(&node), Ok(vec![80, 443]));
+        assert_eq!(Vec::<i64>::try_from(node), Ok(vec![80, 443]));
+        let nested = Node::from(vec![vec!["a"], vec!["b", "c"]]);
+        assert_eq!(Vec::<Vec<String>>::try_from(&nested).map(|v| v.len()), Ok(2));
+        assert_eq!(Vec::<Node>::try_from(nested.clone()).map(|v| v.len()), Ok(2));
+    }
+
+    #[test]
+    fn vec_conversion_reports_failing_item() {
+        let error = Vec::<u8>::try_from(&Node::from(vec![1, 300])).unwrap_err();
+        assert_eq!(error.path, "/1");
+        assert_eq!(error.expected, "u8");
+        let error = Vec::<u8>::try_from(Node::from("x")).unwrap_err();
+        assert_eq!(error.expected, "array");
+    }
+
+    #[test]
+    fn hash_map_conversions_work() {
+        let node = Node::from(vec![("a", vec![1]), ("b", vec![2, 3])]);
+        let map = HashMap::<String, Vec<i32>>::try_from(&node).unwrap();
+        assert_eq!(map["b"], vec![2, 3]);
+        let map = HashMap::<String, Node>::try_from(node).unwrap();
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn hash_map_conversion_reports_failing_entry() {
+        let node = Node::from(vec![("ports", vec![Node::from(1), Node::from("x")])]);
+        let error = HashMap::<String, Vec<u16>>::try_from(node).unwrap_err();
+        assert_eq!(error.path, "/ports/1");
+        assert_eq!(error.to_string(), "Expected u16 but found string at /ports/1");
+        assert!(HashMap::<String, i32>::try_from(&Node::from(1)).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+//! Default values for Node trees.
+//! Node::default() is Node::None, and or_insert_with and set_default fill in values that
+//! are missing so configuration loading code can declare its fallbacks inline (get_or
+//! covers the read-only case).
+
+use crate::error::conversion::ConversionError;
+use crate::nodes::node::Node;
+use crate::nodes::path::join;
+
+/// The default node is None (null).
+impl Default for Node {
+    fn default() -> Self {
+        Node::None
+    }
+}
+
+impl Node {
+    /// Returns the value for a key of a Dictionary node, inserting the result of a
+    /// function first if the key is missing.
+    ///
+    /// # Arguments
+    /// * `key` - Key to look up
+    /// * `f` - Called to create the value if the key is missing
+    ///
+    /// # Returns
+    /// The value, or a ConversionError if the node is neither a dictionary nor None.
+    pub fn or_insert_with<K, F>(&mut self, key: K, f: F) -> Result<&mut Node, ConversionError>
+    where
+        K: Into<String>,
+        F: FnOnce() -> Node,
+    {
+        Ok(self.entry(key)?.or_insert_with(f))
+    }
+
+    /// Stores a value at a dotted path ("server.port") unless something is already there,
+    /// creating missing dictionaries along the way.
+    ///
+    /// Numeric segments index existing array items; a None node on the path is turned
+    /// into a dictionary.
+    ///
+    /// # Arguments
+    /// * `path` - Dotted path of the value ("" for this node, which is set only if None)
+    /// * `value` - Value to store if the path is missing
+    ///
+    /// # Returns
+    /// The value now at the path, or a ConversionError giving the path of a node that
+    /// cannot hold the next key.
+    pub fn set_default<V: Into<Node>>(&mut self, path: &str, value: V) -> Result<&mut Node, ConversionError> {
+        let mut value = Some(value.into());
+        if path.is_empty() {
+            if self.is_null() {
+                *self = value.take().unwrap_or_default();
+            }
+            return Ok(self);
+        }
+        let segments: Vec<&str> = path.split('.').collect();
+        let mut node = self;
+        let mut pointer = String::new();
+        for (position, segment) in segments.iter().enumerate() {
+            let index = segment
+                .parse::<usize>()
+                .ok()
+                .filter(|index| matches!(node, Node::Array(_) | Node::Document(_)) && *index < node.len());
+            node = match (node, index) {
+                (Node::Array(items) | Node::Document(items), Some(index)) => &mut items[index],
+                (other, _) => {
+                    let entry = other.entry(*segment).map_err(|mut error| {
+                        error.path = pointer.clone();
+                        error
+                    })?;
+                    if position + 1 == segments.len() {
+                        entry.or_insert_with(|| value.take().unwrap_or_default())
+                    } else {
+                        entry.or_insert(Node::None)
+                    }
+                }
+            };
+            pointer = join(&pointer, segment);
+        }
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_is_none() {
+        assert_eq!(Node::default(), Node::None);
+        let mut map = std::collections::HashMap::new();
+        map.entry("a".to_string()).or_insert_with(Node::default);
+        assert_eq!(map["a"], Node::None);
+    }
+
+    #[test]
+    fn or_insert_with_works() {
+        let mut node = Node::dict().key("a", 1).build();
+        assert_eq!(*node.or_insert_with("a", || Node::from(2)).unwrap(), Node::from(1));
+        node.or_insert_with("tags", || Node::Array(vec![])).unwrap().push("x").unwrap();
+        assert_eq!(node["tags"], Node::from(vec!["x"]));
+        assert!(Node::from(1).or_insert_with("a", Node::default).is_err());
+    }
+
+    #[test]
+    fn set_default_works() {
+        let mut node = Node::dict().key("server", Node::dict().key("port", 80)).key("hosts", vec!["a"]).build();
+        node.set_default("server.port", 8080).unwrap();
+        node.set_default("server.tls.enabled", false).unwrap();
+        node.set_default("hosts.0", "b").unwrap();
+        assert_eq!(node.get_or("server.port", 0), 80);
+        assert_eq!(node["server"]["tls"]["enabled"], Node::from(false));
+        assert_eq!(node["hosts"], Node::from(vec!["a"]));
+        let mut empty = Node::default();
+        assert_eq!(*empty.set_default("a.b", 1).unwrap(), Node::from(1));
+        assert_eq!(empty, Node::dict().key("a", Node::dict().key("b", 1)).build());
+    }
+
+    #[test]
+    fn set_default_reports_blocking_node() {
+        let mut node = Node::dict().key("server", "localhost").build();
+        let error = node.set_default("server.port", 80).unwrap_err();
+        assert_eq!(error.to_string(), "Expected dictionary but found string at /server");
+        assert_eq!(node["server"], Node::from("localhost"));
+    }
+}
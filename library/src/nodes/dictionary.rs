@@ -0,0 +1,206 @@
+//! Mutation helpers for Dictionary nodes.
+//! insert and entry work like their IndexMap counterparts; on a None node they first
+//! turn it into an empty dictionary (as dictionary["key"] = value does) and on any other
+//! variant they return a ConversionError. remove and contains_key treat other variants
+//! as having no keys. remove keeps the order of the remaining keys, and sort_keys and
+//! sort_keys_by reorder every dictionary in a tree, which the emitters honour when their
+//! sort_keys option is off.
+
+use crate::error::conversion::ConversionError;
+use crate::nodes::node::{lookup_key, Node};
+use indexmap::map::Entry;
+use indexmap::IndexMap;
+use std::cmp::Ordering;
+
+impl Node {
+    /// Returns the map of a Dictionary node for modification, turning a None node into an
+    /// empty dictionary first.
+    fn dictionary_mut(&mut self) -> Result<&mut IndexMap<String, Node>, ConversionError> {
+        if let Node::None = self {
+            *self = Node::Dictionary(IndexMap::new());
+        }
+        match self {
+            Node::Dictionary(map) => Ok(map),
+            other => Err(ConversionError::new("dictionary", other)),
+        }
+    }
+
+    /// Inserts a key/value pair into a Dictionary node.
+    ///
+    /// # Arguments
+    /// * `key` - Key to insert
+    /// * `value` - Value to store under the key
+    ///
+    /// # Returns
+    /// The value previously stored under the key, or a ConversionError if the node is
+    /// neither a dictionary nor None.
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Result<Option<Node>, ConversionError>
+    where
+        K: Into<String>,
+        V: Into<Node>,
+    {
+        let map = self.dictionary_mut()?;
+        let key = key.into();
+        let key = lookup_key(map, &key).into_owned();
+        Ok(map.insert(key, value.into()))
+    }
+
+    /// Removes a key from a Dictionary node.
+    ///
+    /// # Arguments
+    /// * `key` - Key to remove
+    ///
+    /// # Returns
+    /// The removed value, or None if the node is not a dictionary or has no such key.
+    pub fn remove(&mut self, key: &str) -> Option<Node> {
+        match self {
+            Node::Dictionary(map) => {
+                let key = lookup_key(map, key).into_owned();
+                map.shift_remove(&key)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if the node is a dictionary containing a key.
+    ///
+    /// # Arguments
+    /// * `key` - Key to look for
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the entry for a key of a Dictionary node for in-place manipulation.
+    ///
+    /// # Arguments
+    /// * `key` - Key of the entry
+    ///
+    /// # Returns
+    /// The IndexMap entry, or a ConversionError if the node is neither a dictionary nor None.
+    pub fn entry<K: Into<String>>(&mut self, key: K) -> Result<Entry<'_, String, Node>, ConversionError> {
+        let map = self.dictionary_mut()?;
+        let key = key.into();
+        let key = lookup_key(map, &key).into_owned();
+        Ok(map.entry(key))
+    }
+
+    /// Sorts the keys of every dictionary in the tree into ascending order.
+    pub fn sort_keys(&mut self) {
+        self.sort_keys_by(str::cmp);
+    }
+
+    /// Sorts the keys of every dictionary in the tree with a comparator. The sort is stable,
+    /// so keys the comparator treats as equal keep their current order.
+    ///
+    /// # Arguments
+    /// * `compare` - Orders two keys
+    pub fn sort_keys_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&str, &str) -> Ordering,
+    {
+        self.sort_keys_with(&mut compare);
+    }
+
+    /// Sorts dictionaries from this node down, sharing one comparator across the tree.
+    fn sort_keys_with(&mut self, compare: &mut dyn FnMut(&str, &str) -> Ordering) {
+        match self {
+            Node::Dictionary(map) => {
+                map.sort_by(|a, _, b, _| compare(a, b));
+                map.values_mut().for_each(|value| value.sort_keys_with(compare));
+            }
+            Node::Array(items) | Node::Document(items) => {
+                items.iter_mut().for_each(|item| item.sort_keys_with(compare));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::node::Numeric;
+
+    #[test]
+    fn insert_works() {
+        let mut node = Node::dict().key("a", 1).build();
+        assert_eq!(node.insert("a", 2), Ok(Some(Node::from(1))));
+        assert_eq!(node.insert("b", "x"), Ok(None));
+        assert_eq!(node["a"], Node::from(2));
+        assert_eq!(node["b"], Node::from("x"));
+    }
+
+    #[test]
+    fn insert_into_none_creates_dictionary() {
+        let mut node = Node::None;
+        node.insert("a", true).unwrap();
+        assert_eq!(node, Node::dict().key("a", true).build());
+    }
+
+    #[test]
+    fn insert_into_other_variants_fails() {
+        let mut node = Node::from(vec![1]);
+        let error = node.insert("a", 1).unwrap_err();
+        assert_eq!(error.to_string(), "Expected dictionary but found array");
+        assert!(node.entry("a").is_err());
+        assert_eq!(node, Node::from(vec![1]));
+    }
+
+    #[test]
+    fn remove_and_contains_key_work() {
+        let mut node = Node::dict().key("a", 1).build();
+        assert!(node.contains_key("a"));
+        assert_eq!(node.remove("a"), Some(Node::from(1)));
+        assert!(!node.contains_key("a"));
+        assert_eq!(node.remove("a"), None);
+        assert_eq!(Node::from("a").remove("a"), None);
+        assert!(!Node::from("a").contains_key("a"));
+    }
+
+    #[test]
+    fn entry_works() {
+        let mut node = Node::dict().key("count", 1).build();
+        if let Node::Number(number) = node.entry("count").unwrap().or_insert(Node::from(0)) {
+            *number = Numeric::Integer(2);
+        }
+        node.entry("tags").unwrap().or_insert_with(|| Node::Array(vec![]));
+        assert_eq!(node["count"], Node::from(2i64));
+        assert_eq!(node["tags"], Node::Array(vec![]));
+        let mut empty = Node::None;
+        empty.entry("a").unwrap().or_insert(Node::None);
+        assert_eq!(empty["a"], Node::None);
+    }
+
+    #[test]
+    fn remove_keeps_the_order_of_other_keys() {
+        let mut node = Node::dict().key("c", 1).key("a", 2).key("b", 3).build();
+        node.remove("a");
+        assert_eq!(node.as_dict().unwrap().keys().collect::<Vec<_>>(), ["c", "b"]);
+    }
+
+    #[test]
+    fn sort_keys_reorders_nested_dictionaries() {
+        let mut node = Node::dict()
+            .key("b", Node::dict().key("y", 1).key("x", 2).build())
+            .key("a", vec![Node::dict().key("q", 1).key("p", 2).build()])
+            .build();
+        node.sort_keys();
+        let keys = |node: &Node| node.as_dict().unwrap().keys().cloned().collect::<Vec<_>>();
+        assert_eq!(keys(&node), ["a", "b"]);
+        assert_eq!(keys(&node["b"]), ["x", "y"]);
+        assert_eq!(keys(&node["a"][0]), ["p", "q"]);
+        node.sort_keys_by(|a, b| b.cmp(a));
+        assert_eq!(keys(&node), ["b", "a"]);
+        assert_eq!(keys(&node["a"][0]), ["q", "p"]);
+    }
+
+    #[test]
+    fn sort_keys_by_is_stable() {
+        let mut node = Node::dict().key("bb", 1).key("c", 2).key("a", 3).key("dd", 4).build();
+        node.sort_keys_by(|a, b| a.len().cmp(&b.len()));
+        assert_eq!(node.as_dict().unwrap().keys().collect::<Vec<_>>(), ["c", "a", "bb", "dd"]);
+        let mut scalar = Node::from(1);
+        scalar.sort_keys();
+        assert_eq!(scalar, Node::from(1));
+    }
+}
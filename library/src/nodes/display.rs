@@ -0,0 +1,95 @@
+//! Display implementations for Node and Numeric.
+//! Nodes are written by the default emitter, so println!("{node}") and to_string() give
+//! the same block style YAML as stringify.
+
+use crate::io::destinations::writer::FmtWriter;
+use crate::nodes::node::{Node, Numeric};
+use crate::stringify::default::stringify;
+use std::fmt;
+
+/// Writes integers as is and floats so they read back as floats (".nan", ".inf",
+/// "-.inf", and "1.0" rather than "1").
+impl fmt::Display for Numeric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Numeric::Float(value) if value.is_nan() => write!(f, ".nan"),
+            Numeric::Float(value) if value.is_infinite() => {
+                write!(f, "{}", if *value > 0.0 { ".inf" } else { "-.inf" })
+            }
+            Numeric::Float(value) => {
+                let formatted = value.to_string();
+                if formatted.contains(['.', 'e', 'E']) {
+                    write!(f, "{}", formatted)
+                } else {
+                    write!(f, "{}.0", formatted)
+                }
+            }
+            Numeric::Integer(value) => write!(f, "{}", value),
+            Numeric::UInteger(value) => write!(f, "{}", value),
+            Numeric::Byte(value) => write!(f, "{}", value),
+            Numeric::Int32(value) => write!(f, "{}", value),
+            Numeric::UInt32(value) => write!(f, "{}", value),
+            Numeric::Int16(value) => write!(f, "{}", value),
+            Numeric::UInt16(value) => write!(f, "{}", value),
+            Numeric::Int8(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Writes the node as the default emitter does (stringify), so the text ends with a
+/// newline and parses back to an equal tree.
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut destination = FmtWriter::new(f);
+        stringify(self, &mut destination).map_err(|_| fmt::Error)?;
+        destination.into_inner().map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::sources::buffer::Buffer;
+    use crate::parser::default::parse;
+
+    #[test]
+    fn display_numeric_works() {
+        assert_eq!(Numeric::Integer(-5).to_string(), "-5");
+        assert_eq!(Numeric::UInt16(80).to_string(), "80");
+        assert_eq!(Numeric::Float(1.0).to_string(), "1.0");
+        assert_eq!(Numeric::Float(0.25).to_string(), "0.25");
+        assert_eq!(Numeric::Float(f64::INFINITY).to_string(), ".inf");
+        assert_eq!(Numeric::Float(f64::NAN).to_string(), ".nan");
+    }
+
+    #[test]
+    fn display_node_works() {
+        assert_eq!(Node::None.to_string(), "null\n");
+        assert_eq!(Node::from(true).to_string(), "true\n");
+        assert_eq!(Node::from("a\"b\n").to_string(), "|\n  a\"b\n");
+        assert_eq!(Node::Comment("note".to_string()).to_string(), "# note\n");
+        let comment = Node::Comment("x".to_string());
+        let node = Node::dict().key("b", vec![1, 2]).key("a", Node::None).key("c", comment).build();
+        assert_eq!(format!("{node}"), "b:\n  - 1\n  - 2\na: null\n# x\n");
+    }
+
+    #[test]
+    fn display_matches_stringify() {
+        let node = Node::dict().key("name", "web").key("tags", vec!["a", "b: c"]).build();
+        let mut destination = crate::io::destinations::buffer::Buffer::new();
+        stringify(&node, &mut destination).unwrap();
+        assert_eq!(node.to_string(), destination.to_string());
+    }
+
+    #[test]
+    fn display_round_trips_through_parser() {
+        let node = Node::dict()
+            .key("name", "web: \"one\"")
+            .key("ports", vec![80, 443])
+            .key("ratio", 2.0)
+            .key("tls", Node::dict().key("enabled", false))
+            .build();
+        let text = node.to_string();
+        assert_eq!(parse(&mut Buffer::new(text.as_bytes())).unwrap(), node);
+    }
+}
@@ -0,0 +1,98 @@
+//! Searching node trees for matching nodes.
+//! find returns the path and node of everything a predicate accepts, in the depth first
+//! order of walk; find_key and find_value_matching cover the common cases of looking for
+//! a key name or for string values matching a regular expression.
+
+use crate::nodes::node::Node;
+use crate::nodes::path::join;
+
+impl Node {
+    /// Returns every node in the tree, including this one, that a predicate accepts.
+    ///
+    /// # Arguments
+    /// * `predicate` - Called with the path and node of each node in the tree
+    ///
+    /// # Returns
+    /// The (path, node) pairs of the accepted nodes in depth first document order.
+    pub fn find<F>(&self, mut predicate: F) -> Vec<(String, &Node)>
+    where
+        F: FnMut(&str, &Node) -> bool,
+    {
+        self.walk().filter(|(path, node)| predicate(path, node)).collect()
+    }
+
+    /// Returns the values stored under a key in any dictionary of the tree.
+    ///
+    /// # Arguments
+    /// * `key` - Key to look for (array indices are not keys)
+    ///
+    /// # Returns
+    /// The (path, value) pairs in depth first document order.
+    pub fn find_key(&self, key: &str) -> Vec<(String, &Node)> {
+        let mut found = Vec::new();
+        for (path, node) in self.walk() {
+            if let Some(value) = node.get(key)
+                && node.is_dict()
+            {
+                found.push((join(&path, key), value));
+            }
+        }
+        found
+    }
+
+    /// Returns the string values in the tree that a regular expression matches.
+    ///
+    /// # Arguments
+    /// * `pattern` - Expression searched for within each string value
+    ///
+    /// # Returns
+    /// The (path, node) pairs in depth first document order.
+    #[cfg(feature = "regex")]
+    pub fn find_value_matching(&self, pattern: &regex::Regex) -> Vec<(String, &Node)> {
+        self.find(|_, node| matches!(node, Node::Str(text) if pattern.is_match(text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_works() {
+        let node = Node::dict()
+            .key("db", Node::dict().key("user", "admin"))
+            .key("services", Node::seq().push(Node::dict().key("port", 80)))
+            .build();
+        let numbers = node.find(|_, node| node.is_number());
+        assert_eq!(numbers, vec![("/services/0/port".to_string(), &Node::from(80))]);
+        let paths: Vec<String> = node.find(|path, _| path.ends_with("/db")).into_iter().map(|(path, _)| path).collect();
+        assert_eq!(paths, vec!["/db"]);
+        assert_eq!(node.find(|path, _| path.is_empty()).len(), 1);
+    }
+
+    #[test]
+    fn find_key_works() {
+        let node = Node::dict()
+            .key("db", Node::dict().key("user", "admin").key("password", "hunter2"))
+            .key("services", Node::seq().push(Node::dict().key("password", "s3cret")))
+            .key("password", Node::None)
+            .build();
+        let paths: Vec<String> = node.find_key("password").into_iter().map(|(path, _)| path).collect();
+        assert_eq!(paths, vec!["/password", "/db/password", "/services/0/password"]);
+        assert!(Node::from(vec!["a"]).find_key("0").is_empty());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn find_value_matching_works() {
+        let node = Node::dict()
+            .key("db", Node::dict().key("user", "admin").key("password", "hunter2"))
+            .key("services", Node::seq().push(Node::dict().key("password", "s3cret").key("port", 80)))
+            .build();
+        let pattern = regex::Regex::new(r"\d").unwrap();
+        let found = node.find_value_matching(&pattern);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0], ("/db/password".to_string(), &Node::from("hunter2")));
+        assert_eq!(found[1].0, "/services/0/password");
+    }
+}
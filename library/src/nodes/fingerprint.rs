@@ -0,0 +1,177 @@
+//! Stable content fingerprints for Node trees.
+//! The fingerprint is the SHA-256 digest of a canonical encoding of the tree: dictionary
+//! entries are encoded in sorted key order, Comment nodes are left out, and numbers are
+//! encoded by value (Int32(5), Integer(5) and Float(5.0) agree) as == compares them.
+//! Unlike Hash the result does not depend on the hasher or process, so it can be stored
+//! for change detection and cache keys.
+
+use crate::nodes::compare::whole_float;
+use crate::nodes::node::{Node, Numeric};
+
+impl Node {
+    /// Returns the SHA-256 digest of the canonical encoding of the tree.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let mut encoding = Vec::new();
+        encode_node(self, &mut encoding);
+        sha256(&encoding)
+    }
+
+    /// Returns the fingerprint as 64 lowercase hex digits.
+    pub fn fingerprint_hex(&self) -> String {
+        self.fingerprint().iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
+
+/// Appends a length prefixed string.
+fn encode_str(text: &str, out: &mut Vec<u8>) {
+    out.extend_from_slice(&(text.len() as u64).to_be_bytes());
+    out.extend_from_slice(text.as_bytes());
+}
+
+fn encode_number(number: &Numeric, out: &mut Vec<u8>) {
+    let value = number.as_f64();
+    match number.as_wide_integer().or_else(|| whole_float(value)) {
+        Some(integer) => {
+            out.push(b'i');
+            out.extend_from_slice(&integer.to_be_bytes());
+        }
+        None if value.is_nan() => out.push(b'n'),
+        None => {
+            out.push(b'f');
+            out.extend_from_slice(&value.to_bits().to_be_bytes());
+        }
+    }
+}
+
+/// Appends the tag and children of a collection, skipping comments.
+fn encode_items<'a>(tag: u8, items: impl Iterator<Item = &'a Node>, out: &mut Vec<u8>) {
+    let items: Vec<&Node> = items.filter(|item| !item.is_comment()).collect();
+    out.push(tag);
+    out.extend_from_slice(&(items.len() as u64).to_be_bytes());
+    for item in items {
+        encode_node(item, out);
+    }
+}
+
+fn encode_node(node: &Node, out: &mut Vec<u8>) {
+    match node {
+        Node::Boolean(value) => out.extend_from_slice(if *value { b"t" } else { b"b" }),
+        Node::Number(number) => encode_number(number, out),
+        Node::Str(text) => {
+            out.push(b's');
+            encode_str(text, out);
+        }
+        Node::Array(items) => encode_items(b'a', items.iter(), out),
+        Node::Document(items) => encode_items(b'd', items.iter(), out),
+        Node::Dictionary(_) => {
+            let entries: Vec<(&str, &Node)> = node.entries().filter(|(_, value)| !value.is_comment()).collect();
+            out.push(b'm');
+            out.extend_from_slice(&(entries.len() as u64).to_be_bytes());
+            for (key, value) in entries {
+                encode_str(key, out);
+                encode_node(value, out);
+            }
+        }
+        Node::Comment(_) => out.push(b'c'),
+        Node::None => out.push(b'z'),
+    }
+}
+
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5, 0xd807aa98,
+    0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786,
+    0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8,
+    0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13,
+    0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819,
+    0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a,
+    0x5b9cca4f, 0x682e6ff3, 0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+    0xc67178f2,
+];
+
+/// Computes the SHA-256 digest of a message (FIPS 180-4).
+fn sha256(message: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&((message.len() as u64) * 8).to_be_bytes());
+    for block in padded.chunks_exact(64) {
+        let mut schedule = [0u32; 64];
+        for (word, bytes) in schedule.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for i in 16..64 {
+            let s0 = schedule[i - 15].rotate_right(7) ^ schedule[i - 15].rotate_right(18) ^ (schedule[i - 15] >> 3);
+            let s1 = schedule[i - 2].rotate_right(17) ^ schedule[i - 2].rotate_right(19) ^ (schedule[i - 2] >> 10);
+            schedule[i] = schedule[i - 16].wrapping_add(s0).wrapping_add(schedule[i - 7]).wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let temp1 = h.wrapping_add(s1).wrapping_add(choice).wrapping_add(ROUND_CONSTANTS[i]).wrapping_add(schedule[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(majority);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+    let mut digest = [0u8; 32];
+    for (bytes, word) in digest.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn sha256_matches_known_digests() {
+        assert_eq!(hex(&sha256(b"")), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(hex(&sha256(b"abc")), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(
+            hex(&sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn fingerprint_ignores_key_order_and_comments() {
+        let a = Node::dict().key("a", 1).key("b", vec!["x", "y"]).build();
+        let mut b = Node::dict().key("b", vec!["x", "y"]).key("a", 1i64).build();
+        b.insert("__comment_2", Node::Comment("note".to_string())).unwrap();
+        b["b"].insert_at(0, Node::Comment("items".to_string())).unwrap();
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_eq!(a.fingerprint_hex().len(), 64);
+    }
+
+    #[test]
+    fn fingerprint_detects_changes() {
+        let a = Node::dict().key("a", 1).build();
+        assert_ne!(a.fingerprint(), Node::dict().key("a", 2).build().fingerprint());
+        assert_ne!(a.fingerprint(), Node::dict().key("a", "1").build().fingerprint());
+        assert_ne!(Node::from(vec!["ab", "c"]).fingerprint(), Node::from(vec!["a", "bc"]).fingerprint());
+        assert_ne!(Node::from(vec![1]).fingerprint(), Node::Document(vec![Node::from(1)]).fingerprint());
+        assert_eq!(Node::from(2).fingerprint(), Node::Number(Numeric::Float(2.0)).fingerprint());
+    }
+}
@@ -0,0 +1,338 @@
+//! Flattening of nested node trees into single level dictionaries.
+//! Node::flatten turns {"a": {"b": [1, 2]}} into {"a.b.0": 1, "a.b.1": 2}, the shape
+//! used by environment variables and properties files. Keys are joined with the
+//! separator from FlattenOptions and array indices are written either as segments or in
+//! brackets ("a.b[0]"). Empty collections are kept as values so nothing is lost, and
+//! comments are dropped (array indices count only the remaining items). A separator,
+//! "[" or "\\" inside a dictionary key is escaped with a backslash ({"a": {"c.d": 3}}
+//! flattens to {"a.c\\.d": 3}), so keys holding them still split back correctly.
+//! Node::unflatten is the inverse: it accepts both index styles, reads the escapes and
+//! turns every level whose keys are exactly 0..n into an array.
+
+use crate::error::conversion::ConversionError;
+use crate::nodes::node::Node;
+use crate::nodes::path::join;
+use indexmap::IndexMap;
+
+/// Options controlling Node::flatten and Node::unflatten.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlattenOptions {
+    /// Text placed between the keys of nested dictionaries
+    pub separator: String,
+    /// Write array indices in brackets ("a[0]") instead of as separated segments ("a.0")
+    pub bracket_indices: bool,
+}
+
+impl FlattenOptions {
+    /// Creates the default options: "." separators and indices written as segments.
+    pub fn new() -> Self {
+        Self { separator: ".".to_string(), bracket_indices: false }
+    }
+}
+
+impl Default for FlattenOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node {
+    /// Returns a single level dictionary mapping the joined key path of every leaf value
+    /// to that value.
+    ///
+    /// A scalar root is returned under the empty key. Separators, "[" and "\\" inside
+    /// dictionary keys are escaped with a backslash.
+    ///
+    /// # Arguments
+    /// * `options` - Separator and index style used to build the keys
+    pub fn flatten(&self, options: &FlattenOptions) -> Node {
+        let mut flat = IndexMap::new();
+        flatten_into(self, String::new(), options, &mut flat);
+        Node::Dictionary(flat)
+    }
+
+    /// Rebuilds a nested tree from a single level dictionary of joined key paths, as
+    /// produced by flatten.
+    ///
+    /// Levels whose keys are exactly 0, 1, .. n become arrays and all others dictionaries;
+    /// the empty key holds a scalar root. A backslash makes the separator, "[" or
+    /// character after it part of the key.
+    ///
+    /// # Arguments
+    /// * `options` - Separator used in the keys; bracketed indices are always recognised
+    ///
+    /// # Returns
+    /// The nested tree, or a ConversionError if the node is not a dictionary or a key
+    /// descends into a path that already holds a value (such as "a" and "a.b").
+    pub fn unflatten(&self, options: &FlattenOptions) -> Result<Node, ConversionError> {
+        if !self.is_dict() {
+            return Err(ConversionError::new("dictionary", self));
+        }
+        let mut root = Slot::Branch(IndexMap::new());
+        for (key, value) in self.entries() {
+            let segments = split_key(key, &options.separator);
+            if segments.is_empty() {
+                if !root.is_empty_branch() {
+                    return Err(ConversionError::new("dictionary", value));
+                }
+                root = Slot::Leaf(value.clone());
+                continue;
+            }
+            insert_slot(&mut root, &segments, value)?;
+        }
+        Ok(root.into_node())
+    }
+}
+
+/// A level of the tree being rebuilt by unflatten.
+enum Slot {
+    /// A value taken from the flattened dictionary
+    Leaf(Node),
+    /// A level whose children are keyed by segment
+    Branch(IndexMap<String, Slot>),
+}
+
+impl Slot {
+    fn is_empty_branch(&self) -> bool {
+        matches!(self, Slot::Branch(children) if children.is_empty())
+    }
+
+    /// Converts the level into a node, making arrays of levels keyed 0..n.
+    fn into_node(self) -> Node {
+        match self {
+            Slot::Leaf(node) => node,
+            Slot::Branch(mut children) => {
+                let is_array = (0..children.len()).all(|index| children.contains_key(&index.to_string()));
+                if is_array && !children.is_empty() {
+                    (0..children.len())
+                        .map(|index| children.swap_remove(&index.to_string()))
+                        .map(|slot| slot.map(Slot::into_node).unwrap_or(Node::None))
+                        .collect::<Vec<Node>>()
+                        .into()
+                } else {
+                    Node::Dictionary(children.into_iter().map(|(key, slot)| (key, slot.into_node())).collect())
+                }
+            }
+        }
+    }
+}
+
+/// Splits a flattened key into its segments, separating bracketed indices ("a[0][1]")
+/// and reading backslash escapes.
+fn split_key(key: &str, separator: &str) -> Vec<String> {
+    if key.is_empty() {
+        return Vec::new();
+    }
+    let mut segments = Vec::new();
+    let mut name = String::new();
+    let mut indices: Vec<&str> = Vec::new();
+    let mut rest = key;
+    while !rest.is_empty() {
+        if let Some(escaped) = rest.strip_prefix('\\')
+            && let Some(next) = escaped.chars().next()
+        {
+            let length = if starts_with_separator(escaped, separator) { separator.len() } else { next.len_utf8() };
+            unbracket(&mut name, &mut indices);
+            name.push_str(&escaped[..length]);
+            rest = &escaped[length..];
+        } else if starts_with_separator(rest, separator) {
+            end_part(&mut segments, std::mem::take(&mut name), std::mem::take(&mut indices));
+            rest = &rest[separator.len()..];
+        } else if let Some(index) = bracket_index(rest) {
+            indices.push(index);
+            rest = &rest[index.len() + 2..];
+        } else {
+            let length = rest.chars().next().map_or(0, char::len_utf8);
+            unbracket(&mut name, &mut indices);
+            name.push_str(&rest[..length]);
+            rest = &rest[length..];
+        }
+    }
+    end_part(&mut segments, name, indices);
+    segments
+}
+
+fn starts_with_separator(text: &str, separator: &str) -> bool {
+    !separator.is_empty() && text.starts_with(separator)
+}
+
+/// Returns the digits of a bracketed index ("[12]") at the start of text.
+fn bracket_index(text: &str) -> Option<&str> {
+    let (index, _) = text.strip_prefix('[')?.split_once(']')?;
+    (!index.is_empty() && index.bytes().all(|byte| byte.is_ascii_digit())).then_some(index)
+}
+
+/// Puts indices followed by more of the key back into its name ("a[0]b" is one name).
+fn unbracket(name: &mut String, indices: &mut Vec<&str>) {
+    for index in indices.drain(..) {
+        name.push_str(&format!("[{}]", index));
+    }
+}
+
+/// Adds a part of a key (a name followed by any bracketed indices) to the segments.
+fn end_part(segments: &mut Vec<String>, name: String, indices: Vec<&str>) {
+    if !name.is_empty() || indices.is_empty() {
+        segments.push(name);
+    }
+    segments.extend(indices.into_iter().map(str::to_string));
+}
+
+/// Escapes the separator, "[" and "\\" in a dictionary key with a backslash.
+fn escape_key(key: &str, separator: &str) -> String {
+    let mut escaped = String::new();
+    let mut rest = key;
+    while let Some(next) = rest.chars().next() {
+        let is_separator = starts_with_separator(rest, separator);
+        let length = if is_separator { separator.len() } else { next.len_utf8() };
+        if is_separator || matches!(next, '[' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push_str(&rest[..length]);
+        rest = &rest[length..];
+    }
+    escaped
+}
+
+/// Stores a value at the level addressed by segments, creating intermediate levels.
+fn insert_slot(root: &mut Slot, segments: &[String], value: &Node) -> Result<(), ConversionError> {
+    let mut slot = root;
+    let mut path = String::new();
+    for (position, segment) in segments.iter().enumerate() {
+        let children = match slot {
+            Slot::Branch(children) => children,
+            Slot::Leaf(existing) => return Err(at_path(ConversionError::new("dictionary", existing), &path)),
+        };
+        path = join(&path, segment);
+        if position + 1 == segments.len() {
+            if children.contains_key(segment) {
+                return Err(at_path(ConversionError::new("dictionary", value), &path));
+            }
+            children.insert(segment.clone(), Slot::Leaf(value.clone()));
+            return Ok(());
+        }
+        slot = children.entry(segment.clone()).or_insert_with(|| Slot::Branch(IndexMap::new()));
+    }
+    Ok(())
+}
+
+fn at_path(mut error: ConversionError, path: &str) -> ConversionError {
+    error.path = path.to_string();
+    error
+}
+
+/// Returns the key for a child of the node at prefix.
+fn child_key(prefix: &str, segment: &str, index: bool, options: &FlattenOptions) -> String {
+    if index && options.bracket_indices {
+        format!("{}[{}]", prefix, segment)
+    } else if prefix.is_empty() {
+        segment.to_string()
+    } else {
+        format!("{}{}{}", prefix, options.separator, segment)
+    }
+}
+
+fn flatten_into(node: &Node, prefix: String, options: &FlattenOptions, flat: &mut IndexMap<String, Node>) {
+    match node {
+        Node::Array(items) | Node::Document(items) if !items.is_empty() => {
+            for (index, item) in items.iter().filter(|item| !item.is_comment()).enumerate() {
+                flatten_into(item, child_key(&prefix, &index.to_string(), true, options), options, flat);
+            }
+        }
+        Node::Dictionary(map) if !map.is_empty() => {
+            for (key, value) in map.iter().filter(|(_, value)| !value.is_comment()) {
+                let key = escape_key(key, &options.separator);
+                flatten_into(value, child_key(&prefix, &key, false, options), options, flat);
+            }
+        }
+        Node::Comment(_) => {}
+        _ => {
+            flat.insert(prefix, node.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten_joins_keys() {
+        let node = Node::dict()
+            .key("a", Node::dict().key("b", vec![1, 2]))
+            .key("c", "x")
+            .key("__comment_2", Node::Comment("note".to_string()))
+            .build();
+        let flat = node.flatten(&FlattenOptions::new());
+        let expected = Node::dict().key("a.b.0", 1).key("a.b.1", 2).key("c", "x").build();
+        assert_eq!(flat, expected);
+    }
+
+    #[test]
+    fn flatten_uses_options() {
+        let options = FlattenOptions { separator: "__".to_string(), bracket_indices: true };
+        let flat = Node::dict().key("a", Node::dict().key("b", vec![1, 2])).build().flatten(&options);
+        let expected = Node::dict().key("a__b[0]", 1).key("a__b[1]", 2).build();
+        assert_eq!(flat, expected);
+        let flat = Node::from(vec![vec![1]]).flatten(&options);
+        assert_eq!(flat, Node::dict().key("[0][0]", 1).build());
+    }
+
+    #[test]
+    fn flatten_keeps_empty_collections_and_scalars() {
+        let node = Node::dict().key("a", Node::Array(vec![])).key("b", Node::dict()).build();
+        let flat = node.flatten(&FlattenOptions::default());
+        assert_eq!(flat["a"], Node::Array(vec![]));
+        assert_eq!(flat["b"], Node::Dictionary(IndexMap::new()));
+        assert_eq!(Node::from(3).flatten(&FlattenOptions::default()), Node::dict().key("", 3).build());
+    }
+
+    #[test]
+    fn unflatten_reverses_flatten() {
+        let node = Node::dict()
+            .key("a", Node::dict().key("b", vec![1, 2]).key("empty", Node::Array(vec![])).key("c.d", 3))
+            .key("c", vec![Node::dict().key("d", true).key("e_[0]\\", 4).build()])
+            .build();
+        for options in [FlattenOptions::new(), FlattenOptions { separator: "_".to_string(), bracket_indices: true }] {
+            assert_eq!(node.flatten(&options).unflatten(&options).unwrap(), node);
+        }
+        let commented = Node::from(vec![Node::Comment("c".to_string()), Node::from(1)]);
+        let flat = commented.flatten(&FlattenOptions::new());
+        assert_eq!(flat.unflatten(&FlattenOptions::new()).unwrap(), Node::from(vec![1]));
+        let scalar = Node::from("x");
+        assert_eq!(scalar.flatten(&FlattenOptions::new()).unflatten(&FlattenOptions::new()).unwrap(), scalar);
+    }
+
+    #[test]
+    fn flatten_escapes_separators_in_keys() {
+        let node = Node::dict()
+            .key("a", Node::dict().key("c.d", 3).key("e[0]", 4).key("f\\", 5).key("g__h", 6))
+            .key("a.c", vec![7])
+            .build();
+        let flat = node.flatten(&FlattenOptions::new());
+        let keys: Vec<&str> = flat.as_dict().unwrap().keys().map(String::as_str).collect();
+        assert_eq!(keys, ["a.c\\.d", "a.e\\[0]", "a.f\\\\", "a.g__h", "a\\.c.0"]);
+        let options = FlattenOptions { separator: "__".to_string(), bracket_indices: true };
+        let flat = node.flatten(&options);
+        assert_eq!(flat["a__g\\__h"], Node::from(6));
+        assert_eq!(flat.unflatten(&options).unwrap(), node);
+    }
+
+    #[test]
+    fn unflatten_only_makes_arrays_of_contiguous_indices() {
+        let flat = Node::dict().key("ports.8080", "http").key("list[1]", "b").key("list.0", "a").build();
+        let expected = Node::dict()
+            .key("ports", Node::dict().key("8080", "http"))
+            .key("list", vec!["a", "b"])
+            .build();
+        assert_eq!(flat.unflatten(&FlattenOptions::new()).unwrap(), expected);
+    }
+
+    #[test]
+    fn unflatten_reports_conflicts() {
+        let flat = Node::dict().key("a", 1).key("a.b", 2).build();
+        let error = flat.unflatten(&FlattenOptions::new()).unwrap_err();
+        assert_eq!(error.to_string(), "Expected dictionary but found number 1 at /a");
+        let error = Node::from(vec![1]).unflatten(&FlattenOptions::new()).unwrap_err();
+        assert_eq!(error.to_string(), "Expected dictionary but found array");
+    }
+}
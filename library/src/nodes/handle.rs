@@ -0,0 +1,120 @@
+//! Sharing parsed trees between threads.
+//! Node and the parse results, metadata and errors of this crate hold only owned data,
+//! so they are Send + Sync; the assertions below fail to compile if that ever changes.
+//! ParserOptions is the exception, as its progress callback need not be Send.
+//! SharedDocument wraps a tree in an Arc so a service can parse once and hand cheap
+//! clones of the handle to every thread that reads it.
+
+use crate::io::traits::ISource;
+use crate::nodes::node::Node;
+use crate::parser::default::parse;
+use std::ops::Deref;
+use std::sync::Arc;
+
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Node>();
+    assert_send_sync::<crate::nodes::node::Numeric>();
+    assert_send_sync::<crate::nodes::shared::SharedNode>();
+    assert_send_sync::<crate::nodes::arena::NodeArena>();
+    assert_send_sync::<crate::nodes::comments::Comments>();
+    assert_send_sync::<crate::nodes::anchors::Anchors>();
+    assert_send_sync::<crate::parser::report::Parsed>();
+    assert_send_sync::<crate::parser::options::CancellationToken>();
+    assert_send_sync::<crate::error::parse::ParseError>();
+    assert_send_sync::<crate::error::conversion::ConversionError>();
+    assert_send_sync::<crate::error::patch::PatchError>();
+    assert_send_sync::<crate::diff::operation::Operation>();
+    assert_send_sync::<crate::query::jsonpath::Query>();
+    assert_send_sync::<SharedDocument>();
+};
+
+/// A cheaply cloned, read-only handle to a parsed tree that can be shared between threads.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SharedDocument {
+    /// The shared tree
+    root: Arc<Node>,
+}
+
+impl SharedDocument {
+    /// Creates a handle owning a tree.
+    ///
+    /// # Arguments
+    /// * `node` - Root of the tree to share
+    pub fn new(node: Node) -> Self {
+        Self { root: Arc::new(node) }
+    }
+
+    /// Parses YAML from a source into a shared handle.
+    ///
+    /// # Arguments
+    /// * `source` - The source to read YAML from
+    ///
+    /// # Returns
+    /// The handle, or the parse error.
+    pub fn parse(source: &mut dyn ISource) -> Result<Self, String> {
+        parse(source).map(Self::new)
+    }
+
+    /// Returns the root of the shared tree.
+    pub fn root(&self) -> &Node {
+        &self.root
+    }
+
+    /// Returns the number of handles sharing the tree.
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.root)
+    }
+
+    /// Returns the tree, copying it only if other handles still share it.
+    pub fn into_node(self) -> Node {
+        Arc::try_unwrap(self.root).unwrap_or_else(|root| (*root).clone())
+    }
+}
+
+/// Gives read access to the shared tree, so a handle can be used like a &Node
+impl Deref for SharedDocument {
+    type Target = Node;
+
+    fn deref(&self) -> &Node {
+        &self.root
+    }
+}
+
+/// Wraps a tree in a shared handle
+impl From<Node> for SharedDocument {
+    fn from(node: Node) -> Self {
+        Self::new(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::sources::buffer::Buffer;
+    use std::thread;
+
+    #[test]
+    fn shared_document_is_read_from_many_threads() {
+        let document = SharedDocument::parse(&mut Buffer::new(b"name: web\nports: [80, 443]")).unwrap();
+        let workers: Vec<_> = (0..4)
+            .map(|index| {
+                let document = document.clone();
+                thread::spawn(move || document["ports"][index % 2].as_i64())
+            })
+            .collect();
+        let ports: Vec<Option<i64>> = workers.into_iter().map(|worker| worker.join().unwrap()).collect();
+        assert_eq!(ports, vec![Some(80), Some(443), Some(80), Some(443)]);
+        assert_eq!(document.handle_count(), 1);
+        assert_eq!(document.root()["name"], Node::from("web"));
+    }
+
+    #[test]
+    fn into_node_works() {
+        let document = SharedDocument::from(Node::from(vec![1]));
+        let other = document.clone();
+        assert_eq!(document.handle_count(), 2);
+        assert_eq!(document.into_node(), Node::from(vec![1]));
+        assert_eq!(other.into_node(), Node::from(vec![1]));
+    }
+}
@@ -0,0 +1,100 @@
+//! Hash implementations for Numeric and Node, consistent with their value based equality.
+//! Numbers hash by value: integers and whole floats hash as the same i128, other floats
+//! hash their bit pattern, and every NaN hashes alike (NaN equals NaN, so Eq is total).
+//! Dictionaries hash their entries in sorted key order, so insertion order is irrelevant.
+
+use crate::nodes::compare::whole_float;
+use crate::nodes::node::{Node, Numeric};
+use std::hash::{Hash, Hasher};
+
+impl Hash for Numeric {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let value = self.as_f64();
+        match self.as_wide_integer().or_else(|| whole_float(value)) {
+            Some(integer) => {
+                state.write_u8(0);
+                integer.hash(state);
+            }
+            None if value.is_nan() => state.write_u8(1),
+            None => {
+                state.write_u8(2);
+                value.to_bits().hash(state);
+            }
+        }
+    }
+}
+
+impl Hash for Node {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Node::Boolean(value) => value.hash(state),
+            Node::Number(value) => value.hash(state),
+            Node::Str(value) | Node::Comment(value) => value.hash(state),
+            Node::Array(items) | Node::Document(items) => items.hash(state),
+            Node::Dictionary(map) => {
+                state.write_usize(map.len());
+                for (key, value) in self.entries() {
+                    key.hash(state);
+                    value.hash(state);
+                }
+            }
+            Node::None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn equal_numbers_hash_equally() {
+        assert_eq!(hash_of(&Numeric::Int32(5)), hash_of(&Numeric::Integer(5)));
+        assert_eq!(hash_of(&Numeric::Byte(5)), hash_of(&Numeric::Float(5.0)));
+        assert_eq!(hash_of(&Numeric::Float(0.0)), hash_of(&Numeric::Float(-0.0)));
+        assert_eq!(hash_of(&Numeric::Float(f64::NAN)), hash_of(&Numeric::Float(-f64::NAN)));
+        assert_ne!(hash_of(&Numeric::Float(1.5)), hash_of(&Numeric::Float(2.5)));
+    }
+
+    #[test]
+    fn dictionaries_hash_independently_of_insertion_order() {
+        let a = Node::dict().key("a", 1).key("b", vec!["x"]).build();
+        let b = Node::dict().key("b", vec!["x"]).key("a", 1i64).build();
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b));
+    }
+
+    #[test]
+    fn nodes_deduplicate_in_hash_set() {
+        let nodes = vec![
+            Node::from(1),
+            Node::Number(Numeric::Float(1.0)),
+            Node::from("1"),
+            Node::from(vec![1]),
+            Node::from(vec![1i64]),
+            Node::Number(Numeric::Float(f64::NAN)),
+            Node::Number(Numeric::Float(f64::NAN)),
+            Node::None,
+        ];
+        let set: HashSet<Node> = nodes.into_iter().collect();
+        assert_eq!(set.len(), 5);
+        assert!(set.contains(&Node::from(vec![1u8])));
+    }
+
+    #[test]
+    fn nodes_work_as_map_keys() {
+        let mut counts = std::collections::HashMap::new();
+        *counts.entry(Node::from(vec!["a", "b"])).or_insert(0) += 1;
+        *counts.entry(Node::from(vec!["a", "b"])).or_insert(0) += 1;
+        assert_eq!(counts[&Node::from(vec!["a", "b"])], 2);
+    }
+}
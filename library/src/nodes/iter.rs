@@ -0,0 +1,118 @@
+//! Iteration over the children of collection nodes.
+//! entries, keys and values visit Dictionary nodes in sorted key order so iteration is
+//! deterministic; items visits the children of Array and Document nodes. Every method
+//! yields nothing for nodes of other variants.
+
+use crate::nodes::node::Node;
+
+impl Node {
+    /// Returns the key/value pairs of a Dictionary node in sorted key order.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Node)> {
+        let mut entries: Vec<(&str, &Node)> = match self {
+            Node::Dictionary(map) => map.iter().map(|(key, value)| (key.as_str(), value)).collect(),
+            _ => Vec::new(),
+        };
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        entries.into_iter()
+    }
+
+    /// Returns the key/value pairs of a Dictionary node in sorted key order, with the
+    /// values borrowed for modification.
+    pub fn entries_mut(&mut self) -> impl Iterator<Item = (&str, &mut Node)> {
+        let mut entries: Vec<(&str, &mut Node)> = match self {
+            Node::Dictionary(map) => map.iter_mut().map(|(key, value)| (key.as_str(), value)).collect(),
+            _ => Vec::new(),
+        };
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        entries.into_iter()
+    }
+
+    /// Returns the keys of a Dictionary node in sorted order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries().map(|(key, _)| key)
+    }
+
+    /// Returns the values of a Dictionary node in sorted key order.
+    pub fn values(&self) -> impl Iterator<Item = &Node> {
+        self.entries().map(|(_, value)| value)
+    }
+
+    /// Returns the values of a Dictionary node in sorted key order for modification.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Node> {
+        self.entries_mut().map(|(_, value)| value)
+    }
+
+    /// Returns the items of an Array or Document node.
+    pub fn items(&self) -> std::slice::Iter<'_, Node> {
+        match self {
+            Node::Array(items) | Node::Document(items) => items.iter(),
+            _ => Default::default(),
+        }
+    }
+
+    /// Returns the items of an Array or Document node for modification.
+    pub fn items_mut(&mut self) -> std::slice::IterMut<'_, Node> {
+        match self {
+            Node::Array(items) | Node::Document(items) => items.iter_mut(),
+            _ => Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nodes::node::Node;
+
+    fn server() -> Node {
+        Node::dict().key("port", 80).key("host", "web").key("tags", vec!["a", "b"]).build()
+    }
+
+    #[test]
+    fn entries_are_sorted_by_key() {
+        let node = server();
+        let keys: Vec<&str> = node.keys().collect();
+        assert_eq!(keys, vec!["host", "port", "tags"]);
+        let entries: Vec<(&str, &Node)> = node.entries().collect();
+        assert_eq!(entries[0], ("host", &Node::from("web")));
+        assert_eq!(node.values().nth(1), Some(&Node::from(80)));
+    }
+
+    #[test]
+    fn entries_mut_works() {
+        let mut node = server();
+        for (key, value) in node.entries_mut() {
+            if key == "host" {
+                *value = Node::from("db");
+            }
+        }
+        node.values_mut().for_each(|value| {
+            if let Node::Array(items) = value {
+                items.clear();
+            }
+        });
+        assert_eq!(node["host"], Node::from("db"));
+        assert_eq!(node["tags"], Node::Array(vec![]));
+    }
+
+    #[test]
+    fn items_works() {
+        let mut node = Node::from(vec![1, 2, 3]);
+        assert_eq!(node.items().count(), 3);
+        node.items_mut().for_each(|item| *item = Node::None);
+        assert!(node.items().all(|item| *item == Node::None));
+        let document = Node::Document(vec![Node::from(1)]);
+        assert_eq!(document.items().next(), Some(&Node::from(1)));
+    }
+
+    #[test]
+    fn non_collections_yield_nothing() {
+        let mut node = Node::from("a");
+        assert_eq!(node.entries().count(), 0);
+        assert_eq!(node.keys().count(), 0);
+        assert_eq!(node.values_mut().count(), 0);
+        assert_eq!(node.items().count(), 0);
+        assert_eq!(node.items_mut().count(), 0);
+        assert_eq!(server().items().count(), 0);
+        assert_eq!(Node::from(vec![1]).entries().count(), 0);
+    }
+}
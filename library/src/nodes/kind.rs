@@ -0,0 +1,162 @@
+//! Introspection helpers for Node values.
+//! NodeKind names the variant of a node without its contents, and the is_* predicates,
+//! len and is_empty let generic code inspect trees without matching on Node.
+
+use crate::nodes::node::Node;
+use std::fmt;
+
+/// The variant of a Node, without its contents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    /// Node::Boolean
+    Boolean,
+    /// Node::Number
+    Number,
+    /// Node::Str
+    Str,
+    /// Node::Array
+    Array,
+    /// Node::Dictionary
+    Dictionary,
+    /// Node::Comment
+    Comment,
+    /// Node::Document
+    Document,
+    /// Node::None
+    Null,
+}
+
+/// Writes the lowercase name of the kind ("boolean", "string", "null" and so on).
+impl fmt::Display for NodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NodeKind::Boolean => "boolean",
+            NodeKind::Number => "number",
+            NodeKind::Str => "string",
+            NodeKind::Array => "array",
+            NodeKind::Dictionary => "dictionary",
+            NodeKind::Comment => "comment",
+            NodeKind::Document => "document",
+            NodeKind::Null => "null",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Node {
+    /// Returns the kind of this node.
+    pub fn kind(&self) -> NodeKind {
+        match self {
+            Node::Boolean(_) => NodeKind::Boolean,
+            Node::Number(_) => NodeKind::Number,
+            Node::Str(_) => NodeKind::Str,
+            Node::Array(_) => NodeKind::Array,
+            Node::Dictionary(_) => NodeKind::Dictionary,
+            Node::Comment(_) => NodeKind::Comment,
+            Node::Document(_) => NodeKind::Document,
+            Node::None => NodeKind::Null,
+        }
+    }
+
+    /// Returns the number of children of an Array, Dictionary or Document node; other
+    /// nodes have none.
+    pub fn len(&self) -> usize {
+        match self {
+            Node::Array(items) | Node::Document(items) => items.len(),
+            Node::Dictionary(map) => map.len(),
+            _ => 0,
+        }
+    }
+
+    /// Returns true if the node has no children (always true for scalars).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true for Array, Dictionary and Document nodes.
+    pub fn is_collection(&self) -> bool {
+        matches!(self, Node::Array(_) | Node::Dictionary(_) | Node::Document(_))
+    }
+
+    /// Returns true for Boolean nodes.
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Node::Boolean(_))
+    }
+
+    /// Returns true for Number nodes.
+    pub fn is_number(&self) -> bool {
+        matches!(self, Node::Number(_))
+    }
+
+    /// Returns true for Str nodes.
+    pub fn is_str(&self) -> bool {
+        matches!(self, Node::Str(_))
+    }
+
+    /// Returns true for Array nodes.
+    pub fn is_array(&self) -> bool {
+        matches!(self, Node::Array(_))
+    }
+
+    /// Returns true for Dictionary nodes.
+    pub fn is_dict(&self) -> bool {
+        matches!(self, Node::Dictionary(_))
+    }
+
+    /// Returns true for Comment nodes.
+    pub fn is_comment(&self) -> bool {
+        matches!(self, Node::Comment(_))
+    }
+
+    /// Returns true for Document nodes.
+    pub fn is_document(&self) -> bool {
+        matches!(self, Node::Document(_))
+    }
+
+    /// Returns true for None (null) nodes.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Node::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_works() {
+        assert_eq!(Node::from(true).kind(), NodeKind::Boolean);
+        assert_eq!(Node::from(1).kind(), NodeKind::Number);
+        assert_eq!(Node::from("a").kind(), NodeKind::Str);
+        assert_eq!(Node::from(vec![1]).kind(), NodeKind::Array);
+        assert_eq!(Node::dict().build().kind(), NodeKind::Dictionary);
+        assert_eq!(Node::Comment("c".to_string()).kind(), NodeKind::Comment);
+        assert_eq!(Node::Document(vec![]).kind(), NodeKind::Document);
+        assert_eq!(Node::None.kind(), NodeKind::Null);
+        assert_eq!(NodeKind::Str.to_string(), "string");
+        assert_eq!(NodeKind::Null.to_string(), "null");
+    }
+
+    #[test]
+    fn len_and_is_empty_work() {
+        assert_eq!(Node::from(vec![1, 2]).len(), 2);
+        assert_eq!(Node::dict().key("a", 1).build().len(), 1);
+        assert_eq!(Node::Document(vec![Node::None]).len(), 1);
+        assert!(Node::Array(vec![]).is_empty());
+        assert_eq!(Node::from("abc").len(), 0);
+        assert!(Node::from("abc").is_empty());
+    }
+
+    #[test]
+    fn predicates_work() {
+        let node = Node::dict().key("a", vec![1]).key("b", Node::None).build();
+        assert!(node.is_dict() && node.is_collection());
+        assert!(node["a"].is_array() && !node["a"].is_dict());
+        assert!(node["a"][0].is_number() && !node["a"][0].is_collection());
+        assert!(node["b"].is_null());
+        assert!(Node::from("a").is_str());
+        assert!(Node::from(false).is_bool());
+        assert!(Node::Comment("c".to_string()).is_comment());
+        assert!(Node::Document(vec![]).is_document());
+    }
+}
@@ -0,0 +1,124 @@
+//! The yaml! macro for building Node trees from inline literal syntax, for example
+//! yaml!({"name": "web", "ports": [80, 443], "tls": null}). Any other expression is
+//! spliced in through its From impl, so variables can be embedded directly.
+
+/// Builds a Node from inline map, sequence and scalar syntax.
+///
+/// Maps are written `{key: value, ...}` where each key is an expression convertible to a
+/// String, sequences `[value, ...]`, `null` gives Node::None, and any other expression is
+/// converted with Node::from.
+#[macro_export]
+macro_rules! yaml {
+    (null) => {
+        $crate::nodes::node::Node::None
+    };
+    ([]) => {
+        $crate::nodes::node::Node::Array(::std::vec::Vec::new())
+    };
+    ([ $($tokens:tt)+ ]) => {
+        $crate::nodes::node::Node::Array($crate::__yaml_internal!(@array [] [] $($tokens)+))
+    };
+    ({}) => {
+        $crate::nodes::node::Node::Dictionary($crate::IndexMap::new())
+    };
+    ({ $($tokens:tt)+ }) => {
+        $crate::nodes::node::Node::Dictionary($crate::__yaml_internal!(@key [] [] $($tokens)+))
+    };
+    ($other:expr) => {
+        $crate::nodes::node::Node::from($other)
+    };
+}
+
+/// Token munching helper for yaml!; not part of the public API.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __yaml_internal {
+    // Sequence items: accumulate tokens up to each ',' and convert them with yaml!
+    (@array [$($items:expr,)*] []) => {
+        ::std::vec![$($items,)*]
+    };
+    (@array [$($items:expr,)*] [$($item:tt)+]) => {
+        ::std::vec![$($items,)* $crate::yaml!($($item)+)]
+    };
+    (@array [$($items:expr,)*] [$($item:tt)+] , $($rest:tt)*) => {
+        $crate::__yaml_internal!(@array [$($items,)* $crate::yaml!($($item)+),] [] $($rest)*)
+    };
+    (@array [$($items:expr,)*] [$($item:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::__yaml_internal!(@array [$($items,)*] [$($item)* $next] $($rest)*)
+    };
+
+    // Map keys: accumulate tokens up to the ':' then switch to the value
+    (@key [$(($keys:expr, $values:expr))*] []) => {{
+        let mut map = $crate::IndexMap::new();
+        $(map.insert(::std::string::ToString::to_string(&$keys), $values);)*
+        map
+    }};
+    (@key [$($entries:tt)*] [$($key:tt)+] : $($rest:tt)*) => {
+        $crate::__yaml_internal!(@value [$($entries)*] [$($key)+] [] $($rest)*)
+    };
+    (@key [$($entries:tt)*] [$($key:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::__yaml_internal!(@key [$($entries)*] [$($key)* $next] $($rest)*)
+    };
+
+    // Map values: accumulate tokens up to the ',' then return to the next key
+    (@value [$($entries:tt)*] [$($key:tt)+] [$($value:tt)+]) => {
+        $crate::__yaml_internal!(@key [$($entries)* (($($key)+), $crate::yaml!($($value)+))] [])
+    };
+    (@value [$($entries:tt)*] [$($key:tt)+] [$($value:tt)+] , $($rest:tt)*) => {
+        $crate::__yaml_internal!(@key [$($entries)* (($($key)+), $crate::yaml!($($value)+))] [] $($rest)*)
+    };
+    (@value [$($entries:tt)*] [$($key:tt)+] [$($value:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::__yaml_internal!(@value [$($entries)*] [$($key)+] [$($value)* $next] $($rest)*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nodes::node::{Node, Numeric};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn scalar_literals_work() {
+        assert_eq!(yaml!(null), Node::None);
+        assert_eq!(yaml!(true), Node::Boolean(true));
+        assert!(yaml!(42).strict_eq(&Node::Number(Numeric::Int32(42))));
+        assert!(yaml!(-2.5).strict_eq(&Node::Number(Numeric::Float(-2.5))));
+        assert_eq!(yaml!("text"), Node::from("text"));
+    }
+
+    #[test]
+    fn sequence_literals_work() {
+        assert_eq!(yaml!([]), Node::Array(Vec::new()));
+        assert_eq!(yaml!([1, -2, "three", null]), Node::Array(vec![
+            Node::from(1), Node::from(-2), Node::from("three"), Node::None,
+        ]));
+        assert_eq!(yaml!([[1], [2, 3],]), Node::Array(vec![Node::from(vec![1]), Node::from(vec![2, 3])]));
+    }
+
+    #[test]
+    fn map_literals_work() {
+        assert_eq!(yaml!({}), Node::Dictionary(IndexMap::new()));
+        let node = yaml!({
+            "name": "web",
+            "ports": [80, 443],
+            "tls": { "enabled": false, "cert": null },
+        });
+        assert_eq!(node["name"], Node::from("web"));
+        assert_eq!(node["ports"], Node::from(vec![80, 443]));
+        assert_eq!(node["tls"]["enabled"], Node::Boolean(false));
+        assert_eq!(node["tls"]["cert"], Node::None);
+    }
+
+    #[test]
+    fn variables_are_spliced() {
+        let name = "api";
+        let key = String::from("replicas");
+        let count = 3u8;
+        let tags = vec!["a", "b"];
+        let node = yaml!({ "name": name, key: count + 1, "tags": tags, "first": [name, null] });
+        assert_eq!(node["name"], Node::from("api"));
+        assert_eq!(node["replicas"], Node::from(4u8));
+        assert_eq!(node["tags"], Node::from(vec!["a", "b"]));
+        assert_eq!(node["first"][0], Node::from("api"));
+    }
+}
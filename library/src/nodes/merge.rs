@@ -0,0 +1,220 @@
+//! Deep merging of node trees for layered configuration.
+//! Node::merge overlays one tree onto another; a MergeStrategy controls whether
+//! dictionaries are merged recursively, how arrays are combined and what a null in the
+//! overlay means.
+
+use crate::nodes::node::Node;
+
+/// How two arrays are combined when merging.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ArrayMerge {
+    /// The overlay array replaces the base array
+    #[default]
+    Replace,
+    /// The overlay items are appended to the base items
+    Append,
+    /// Items at the same index are merged; extra overlay items are appended
+    ByIndex,
+    /// Dictionary items with the same value for the named key are merged; other overlay
+    /// items are appended
+    ByKey(String),
+}
+
+/// What a null (Node::None) value in the overlay does when merging dictionaries.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NullMerge {
+    /// The null replaces the base value
+    #[default]
+    Overwrite,
+    /// The null is ignored and the base value kept
+    Ignore,
+    /// The key is removed from the merged dictionary
+    Delete,
+}
+
+/// Options controlling Node::merge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergeStrategy {
+    /// Merge nested dictionaries recursively; when false overlay values replace base
+    /// values wholesale (a shallow merge)
+    pub deep: bool,
+    /// How arrays found at the same place in both trees are combined
+    pub arrays: ArrayMerge,
+    /// What a null value in an overlay dictionary does
+    pub nulls: NullMerge,
+}
+
+impl MergeStrategy {
+    /// Creates the default strategy: deep dictionary merges, overlay arrays replace base
+    /// arrays and overlay nulls overwrite base values.
+    pub fn new() -> Self {
+        Self { deep: true, arrays: ArrayMerge::Replace, nulls: NullMerge::Overwrite }
+    }
+}
+
+impl Default for MergeStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node {
+    /// Merges another tree into this one.
+    ///
+    /// Dictionaries are merged key by key and arrays are combined as the strategy
+    /// dictates; any other pairing of nodes replaces this node with the overlay.
+    ///
+    /// # Arguments
+    /// * `other` - The overlay tree whose values take precedence
+    /// * `strategy` - How dictionaries, arrays and nulls are merged
+    pub fn merge(&mut self, other: &Node, strategy: &MergeStrategy) {
+        merge_into(self, other, strategy, true);
+    }
+
+    /// Returns the result of merging another tree into a copy of this one.
+    ///
+    /// # Arguments
+    /// * `other` - The overlay tree whose values take precedence
+    /// * `strategy` - How dictionaries, arrays and nulls are merged
+    pub fn merged(&self, other: &Node, strategy: &MergeStrategy) -> Node {
+        let mut node = self.clone();
+        node.merge(other, strategy);
+        node
+    }
+}
+
+fn merge_into(base: &mut Node, other: &Node, strategy: &MergeStrategy, recurse: bool) {
+    match (base, other) {
+        (_, Node::None) if strategy.nulls == NullMerge::Ignore => {}
+        (Node::Dictionary(map), Node::Dictionary(overlay)) if recurse => {
+            for (key, value) in overlay {
+                match (map.get_mut(key), value) {
+                    (_, Node::None) if strategy.nulls == NullMerge::Delete => {
+                        map.shift_remove(key);
+                    }
+                    (Some(existing), _) => merge_into(existing, value, strategy, strategy.deep),
+                    (None, Node::None) if strategy.nulls == NullMerge::Ignore => {}
+                    (None, _) => {
+                        map.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (Node::Array(items), Node::Array(overlay)) if recurse => merge_arrays(items, overlay, strategy),
+        (base, _) => *base = other.clone(),
+    }
+}
+
+fn merge_arrays(items: &mut Vec<Node>, overlay: &[Node], strategy: &MergeStrategy) {
+    match &strategy.arrays {
+        ArrayMerge::Replace => *items = overlay.to_vec(),
+        ArrayMerge::Append => items.extend_from_slice(overlay),
+        ArrayMerge::ByIndex => {
+            for (index, value) in overlay.iter().enumerate() {
+                match items.get_mut(index) {
+                    Some(existing) => merge_into(existing, value, strategy, true),
+                    None => items.push(value.clone()),
+                }
+            }
+        }
+        ArrayMerge::ByKey(key) => {
+            for value in overlay {
+                let identity = value.get(key);
+                let existing = identity.and_then(|identity| items.iter_mut().find(|item| item.get(key) == Some(identity)));
+                match existing {
+                    Some(existing) => merge_into(existing, value, strategy, true),
+                    None => items.push(value.clone()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Node {
+        Node::dict()
+            .key("name", "web")
+            .key("server", Node::dict().key("host", "a").key("port", 80))
+            .key("tags", vec!["x"])
+            .build()
+    }
+
+    #[test]
+    fn merge_default_is_deep() {
+        let overlay = Node::dict().key("server", Node::dict().key("port", 8080)).key("tags", vec!["y"]).build();
+        let merged = base().merged(&overlay, &MergeStrategy::new());
+        assert_eq!(merged["server"]["host"], Node::from("a"));
+        assert_eq!(merged["server"]["port"], Node::from(8080));
+        assert_eq!(merged["tags"], Node::from(vec!["y"]));
+        assert_eq!(merged["name"], Node::from("web"));
+    }
+
+    #[test]
+    fn merge_shallow_replaces_nested_dictionaries() {
+        let overlay = Node::dict().key("server", Node::dict().key("port", 8080)).build();
+        let strategy = MergeStrategy { deep: false, ..MergeStrategy::new() };
+        let merged = base().merged(&overlay, &strategy);
+        assert!(merged["server"].get("host").is_none());
+        assert_eq!(merged["name"], Node::from("web"));
+    }
+
+    #[test]
+    fn merge_arrays_works() {
+        let overlay = Node::dict().key("tags", vec!["y"]).build();
+        let strategy = MergeStrategy { arrays: ArrayMerge::Append, ..MergeStrategy::new() };
+        assert_eq!(base().merged(&overlay, &strategy)["tags"], Node::from(vec!["x", "y"]));
+
+        let mut items = Node::from(vec![Node::dict().key("a", 1).build(), Node::from(2)]);
+        let strategy = MergeStrategy { arrays: ArrayMerge::ByIndex, ..MergeStrategy::new() };
+        items.merge(&Node::from(vec![Node::dict().key("b", 2).build(), Node::from(3), Node::from(4)]), &strategy);
+        assert_eq!(items[0]["a"], Node::from(1));
+        assert_eq!(items[0]["b"], Node::from(2));
+        assert_eq!(items[1], Node::from(3));
+        assert_eq!(items[2], Node::from(4));
+    }
+
+    #[test]
+    fn merge_arrays_by_key_works() {
+        let mut servers = Node::from(vec![
+            Node::dict().key("name", "a").key("port", 1).build(),
+            Node::dict().key("name", "b").key("port", 2).build(),
+        ]);
+        let overlay = Node::from(vec![
+            Node::dict().key("name", "b").key("port", 3).build(),
+            Node::dict().key("name", "c").key("port", 4).build(),
+        ]);
+        let strategy = MergeStrategy { arrays: ArrayMerge::ByKey("name".to_string()), ..MergeStrategy::new() };
+        servers.merge(&overlay, &strategy);
+        assert_eq!(servers.as_array().map(Vec::len), Some(3));
+        assert_eq!(servers[1]["port"], Node::from(3));
+        assert_eq!(servers[2]["name"], Node::from("c"));
+    }
+
+    #[test]
+    fn merge_nulls_works() {
+        let overlay = Node::dict().key("name", Node::None).key("extra", Node::None).build();
+        let merged = base().merged(&overlay, &MergeStrategy::new());
+        assert_eq!(merged["name"], Node::None);
+        assert_eq!(merged["extra"], Node::None);
+
+        let strategy = MergeStrategy { nulls: NullMerge::Ignore, ..MergeStrategy::new() };
+        let merged = base().merged(&overlay, &strategy);
+        assert_eq!(merged["name"], Node::from("web"));
+        assert!(merged.get("extra").is_none());
+
+        let strategy = MergeStrategy { nulls: NullMerge::Delete, ..MergeStrategy::new() };
+        let merged = base().merged(&overlay, &strategy);
+        assert!(merged.get("name").is_none());
+        assert!(merged.get("extra").is_none());
+    }
+
+    #[test]
+    fn merge_mismatched_types_replaces() {
+        let mut node = base();
+        node.merge(&Node::from(1), &MergeStrategy::new());
+        assert_eq!(node, Node::from(1));
+    }
+}
@@ -1 +1,76 @@
-pub mod node;
\ No newline at end of file
+pub mod node;
+/// Checked conversions between Numeric variants and Rust number types
+pub mod numeric;
+/// Value based equality and ordering for numbers, and strict_eq
+pub mod compare;
+/// Hash implementations consistent with value based equality, for sets and map keys
+pub mod hash;
+/// SHA-256 content fingerprints independent of key order and comments
+pub mod fingerprint;
+/// NodeKind and the len, is_empty and is_* introspection helpers
+pub mod kind;
+/// Non-panicking accessors (as_*, into_*, get*) for node values
+pub mod accessors;
+/// JSON-pointer style path helpers for addressing nodes in a tree
+pub mod path;
+/// The yaml! macro for building nodes from inline literal syntax
+#[macro_use]
+pub mod macros;
+/// Fluent builders for dictionary and sequence nodes
+pub mod builder;
+/// TryFrom conversions from nodes into Rust values
+pub mod convert;
+/// ToNode and FromNode traits for mapping application types to and from nodes
+pub mod traits;
+/// Iterators over the entries of dictionaries and the items of arrays
+pub mod iter;
+/// Depth first and breadth first traversal of node trees with paths
+pub mod walk;
+/// Visitor traits for read-only and modifying traversals of node trees
+pub mod visitor;
+/// find, find_key and find_value_matching for locating nodes anywhere in a tree
+pub mod find;
+/// rename_keys and move_path for migrating the layout of trees by path
+pub mod rename;
+/// redact and redact_paths for masking sensitive values while keeping structure
+pub mod redact;
+/// retain and retain_recursive for pruning the children of collections
+pub mod retain;
+/// map_values, map_keys and transform for bulk rewrites of node trees
+pub mod transform;
+/// union, intersection and difference of dictionary keys, shallow or deep
+pub mod setops;
+/// Deep merging of node trees with configurable strategies
+pub mod merge;
+/// Display implementations writing nodes as flow style YAML
+pub mod display;
+/// insert, remove, contains_key, entry and sort_keys for Dictionary nodes
+pub mod dictionary;
+/// Default for Node, plus or_insert_with and set_default for filling in missing values
+pub mod defaults;
+/// push, insert_at, remove_at, extend_items and pop for Array nodes
+pub mod array;
+/// Flattening of nested trees into single level dictionaries with joined keys
+pub mod flatten;
+/// SharedNode, an Arc based tree in which repeated subtrees are stored once
+pub mod shared;
+/// NodeArena, contiguous storage for node trees addressed by NodeId
+pub mod arena;
+/// SharedDocument and the Send + Sync guarantees for sharing trees between threads
+pub mod handle;
+/// Node::stats for node counts, depth, text size and approximate memory use
+pub mod stats;
+/// Node::to_tree_string for an indented tree view of a node's structure
+pub mod tree;
+/// JSON-pointer lookups (pointer/pointer_mut) on node trees
+pub mod pointer;
+/// Comment metadata attached to nodes by path
+pub mod comments;
+/// Anchor metadata recording which nodes were defined with an anchor
+pub mod anchors;
+/// serde Serialize and Deserialize implementations for nodes
+#[cfg(feature = "serde")]
+pub mod serialization;
+/// Unicode (NFC) normalization of dictionary keys
+#[cfg(feature = "unicode-normalization")]
+pub mod unicode;
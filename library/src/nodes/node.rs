@@ -1,8 +1,11 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use indexmap::IndexMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::{Index, IndexMut};
 
-/// Represents different numeric types that can be stored in a YAML node
-#[derive(Clone, Debug, PartialEq)]
+/// Represents different numeric types that can be stored in a YAML node.
+/// Numbers compare by value across variants (see nodes::compare).
+#[derive(Clone, Debug)]
 pub enum Numeric {
     Integer(i64),    // 64-bit signed integer
     Float(f64),      // 64-bit floating point
@@ -15,8 +18,76 @@ pub enum Numeric {
     Int8(i8),        // 8-bit signed integer
 }
 
+/// Controls which Numeric variants are used to store integers.
+/// Parsed YAML integers and values built from Rust integers end up in different
+/// variants unless the same policy is applied to both.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NumericPolicy {
+    /// Integers are stored as Integer(i64), or UInteger(u64) when too large for i64
+    #[default]
+    Canonical,
+    /// Integers are stored in the smallest variant that can hold them
+    /// (Int8, Byte, Int16, UInt16, Int32, UInt32, Integer, UInteger in that order)
+    SmallestFit,
+}
+
+impl Numeric {
+    /// Returns the integer value as an i128 wide enough for every integer variant, or None for floats.
+    pub(crate) fn as_wide_integer(&self) -> Option<i128> {
+        match *self {
+            Numeric::Integer(value) => Some(value as i128),
+            Numeric::UInteger(value) => Some(value as i128),
+            Numeric::Byte(value) => Some(value as i128),
+            Numeric::Int32(value) => Some(value as i128),
+            Numeric::UInt32(value) => Some(value as i128),
+            Numeric::Int16(value) => Some(value as i128),
+            Numeric::UInt16(value) => Some(value as i128),
+            Numeric::Int8(value) => Some(value as i128),
+            Numeric::Float(_) => None,
+        }
+    }
+
+    /// Converts the number to the variant dictated by a numeric policy.
+    ///
+    /// # Arguments
+    /// * `policy` - The policy to apply
+    ///
+    /// # Returns
+    /// The same mathematical value stored in the policy's variant; floats are unchanged.
+    pub fn with_policy(&self, policy: NumericPolicy) -> Numeric {
+        let Some(value) = self.as_wide_integer() else {
+            return self.clone();
+        };
+        match policy {
+            NumericPolicy::Canonical => match i64::try_from(value) {
+                Ok(value) => Numeric::Integer(value),
+                Err(_) => Numeric::UInteger(value as u64),
+            },
+            NumericPolicy::SmallestFit => {
+                if let Ok(value) = i8::try_from(value) {
+                    Numeric::Int8(value)
+                } else if let Ok(value) = u8::try_from(value) {
+                    Numeric::Byte(value)
+                } else if let Ok(value) = i16::try_from(value) {
+                    Numeric::Int16(value)
+                } else if let Ok(value) = u16::try_from(value) {
+                    Numeric::UInt16(value)
+                } else if let Ok(value) = i32::try_from(value) {
+                    Numeric::Int32(value)
+                } else if let Ok(value) = u32::try_from(value) {
+                    Numeric::UInt32(value)
+                } else if let Ok(value) = i64::try_from(value) {
+                    Numeric::Integer(value)
+                } else {
+                    Numeric::UInteger(value as u64)
+                }
+            }
+        }
+    }
+}
+
 /// A node in the YAML data structure that can represent different types of values.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Node {
     /// Represents a boolean value (true/false)
     /// Used for YAML boolean values like true/false, yes/no, on/off
@@ -31,8 +102,9 @@ pub enum Node {
     /// Used for YAML sequences/lists where order matters
     Array(Vec<Node>),
     /// Represents a dictionary/map of string keys to node values
-    /// Used for YAML mappings where keys map to values
-    Dictionary(HashMap<String, Node>),
+    /// Used for YAML mappings where keys map to values; keys keep the order they were
+    /// inserted in (source order for parsed YAML)
+    Dictionary(IndexMap<String, Node>),
     /// Represents a comment
     /// Stores documentation and descriptive text that doesn't affect the data structure
     Comment(String),
@@ -57,6 +129,17 @@ impl Index<usize> for Node {
     }
 }
 
+/// Returns the form of a key to use when looking it up in a dictionary. With the
+/// "unicode-normalization" feature a key missing as written is looked up in NFC form.
+pub(crate) fn lookup_key<'a>(map: &IndexMap<String, Node>, key: &'a str) -> Cow<'a, str> {
+    #[cfg(feature = "unicode-normalization")]
+    if !map.contains_key(key) {
+        return crate::nodes::unicode::normalize_key(key);
+    }
+    let _ = map;
+    Cow::Borrowed(key)
+}
+
 /// Implements dictionary-style indexing for Node using string keys
 impl Index<&str> for Node {
     type Output = Node;
@@ -64,7 +147,7 @@ impl Index<&str> for Node {
     /// Allows accessing dictionary properties using dictionary["key"] syntax
     fn index(&self, key: &str) -> &Self::Output {
         match self {
-            Node::Dictionary(map) => &map[key],
+            Node::Dictionary(map) => &map[lookup_key(map, key).as_ref()],
             _ => panic!("Cannot index non-dictionary node with string"),
         }
     }
@@ -83,10 +166,18 @@ impl IndexMut<usize> for Node {
 
 /// Implements mutable dictionary-style indexing for Node
 impl IndexMut<&str> for Node {
-    /// Allows modifying dictionary properties using dictionary["key"] = value syntax
+    /// Allows modifying dictionary properties using dictionary["key"] = value syntax.
+    /// A missing key is inserted with a None value, and a None node becomes an empty
+    /// dictionary first, so nested values can be assigned in one step.
     fn index_mut(&mut self, key: &str) -> &mut Self::Output {
+        if let Node::None = self {
+            *self = Node::Dictionary(IndexMap::new());
+        }
         match self {
-            Node::Dictionary(map) => map.get_mut(key).expect("No such key exists"),
+            Node::Dictionary(map) => {
+                let key = lookup_key(map, key).into_owned();
+                map.entry(key).or_insert(Node::None)
+            }
             _ => panic!("Cannot index non-dictionary node with string"),
         }
     }
@@ -99,6 +190,55 @@ impl<T: Into<Node>> From<Vec<T>> for Node {
     }
 }
 
+/// Converts a slice of values into an array node
+impl<T: Clone + Into<Node>> From<&[T]> for Node {
+    fn from(value: &[T]) -> Self {
+        Node::Array(value.iter().cloned().map(|x| x.into()).collect())
+    }
+}
+
+/// Converts a list of key/value pairs into a dictionary node; later pairs replace earlier ones
+impl<K: Into<String>, V: Into<Node>> From<Vec<(K, V)>> for Node {
+    fn from(value: Vec<(K, V)>) -> Self {
+        Node::Dictionary(value.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
+    }
+}
+
+/// Converts a slice of key/value pairs into a dictionary node; later pairs replace earlier ones
+impl<K: Clone + Into<String>, V: Clone + Into<Node>> From<&[(K, V)]> for Node {
+    fn from(value: &[(K, V)]) -> Self {
+        Node::Dictionary(value.iter().cloned().map(|(k, v)| (k.into(), v.into())).collect())
+    }
+}
+
+/// Converts an index map into a dictionary node, keeping its key order
+impl<T: Into<Node>> From<IndexMap<String, T>> for Node {
+    fn from(value: IndexMap<String, T>) -> Self {
+        Node::Dictionary(value.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+}
+
+/// Converts a hash map into a dictionary node
+impl<T: Into<Node>> From<HashMap<String, T>> for Node {
+    fn from(value: HashMap<String, T>) -> Self {
+        Node::Dictionary(value.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+}
+
+/// Converts a B-tree map into a dictionary node
+impl<T: Into<Node>> From<BTreeMap<String, T>> for Node {
+    fn from(value: BTreeMap<String, T>) -> Self {
+        Node::Dictionary(value.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+}
+
+/// Converts an optional value into a node; None becomes Node::None
+impl<T: Into<Node>> From<Option<T>> for Node {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Node::None, |x| x.into())
+    }
+}
+
 impl From<i64> for Numeric {
     fn from(value: i64) -> Self {
         Numeric::Integer(value)
@@ -225,6 +365,39 @@ impl From<String> for Node {
     }
 }
 
+impl Node {
+    /// Converts every number in the tree to the variants dictated by a numeric policy.
+    ///
+    /// # Arguments
+    /// * `policy` - The policy to apply
+    pub fn apply_numeric_policy(&mut self, policy: NumericPolicy) {
+        match self {
+            Node::Number(number) => *number = number.with_policy(policy),
+            Node::Array(items) | Node::Document(items) => {
+                for item in items {
+                    item.apply_numeric_policy(policy);
+                }
+            }
+            Node::Dictionary(map) => {
+                for value in map.values_mut() {
+                    value.apply_numeric_policy(policy);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the node with a numeric policy applied throughout, for use with the From impls
+    /// (for example Node::from(42).with_numeric_policy(NumericPolicy::Canonical)).
+    ///
+    /// # Arguments
+    /// * `policy` - The policy to apply
+    pub fn with_numeric_policy(mut self, policy: NumericPolicy) -> Node {
+        self.apply_numeric_policy(policy);
+        self
+    }
+}
+
 /// Helper functions to create a Node from any value that can be converted into a Node
 pub fn make_node<T>(value: T) -> Node
 where
@@ -237,30 +410,38 @@ where
 mod tests {
     use super::*;
 
+    /// Asserts that two values are equal and hold their numbers in the same variants.
+    macro_rules! assert_strict_eq {
+        ($left:expr, $right:expr) => {{
+            let (left, right) = (&$left, &$right);
+            assert!(left.strict_eq(right), "{:?} is not strictly equal to {:?}", left, right);
+        }};
+    }
+
     #[test]
     fn test_numeric_conversions() {
-        assert_eq!(Numeric::from(42i64), Numeric::Integer(42));
-        assert_eq!(Numeric::from(3.14f64), Numeric::Float(3.14));
-        assert_eq!(Numeric::from(42u64), Numeric::UInteger(42));
-        assert_eq!(Numeric::from(42u8), Numeric::Byte(42));
-        assert_eq!(Numeric::from(42i32), Numeric::Int32(42));
-        assert_eq!(Numeric::from(42u32), Numeric::UInt32(42));
-        assert_eq!(Numeric::from(42i16), Numeric::Int16(42));
-        assert_eq!(Numeric::from(42u16), Numeric::UInt16(42));
-        assert_eq!(Numeric::from(42i8), Numeric::Int8(42));
+        assert_strict_eq!(Numeric::from(42i64), Numeric::Integer(42));
+        assert_strict_eq!(Numeric::from(2.5f64), Numeric::Float(2.5));
+        assert_strict_eq!(Numeric::from(42u64), Numeric::UInteger(42));
+        assert_strict_eq!(Numeric::from(42u8), Numeric::Byte(42));
+        assert_strict_eq!(Numeric::from(42i32), Numeric::Int32(42));
+        assert_strict_eq!(Numeric::from(42u32), Numeric::UInt32(42));
+        assert_strict_eq!(Numeric::from(42i16), Numeric::Int16(42));
+        assert_strict_eq!(Numeric::from(42u16), Numeric::UInt16(42));
+        assert_strict_eq!(Numeric::from(42i8), Numeric::Int8(42));
     }
 
     #[test]
     fn test_node_numeric_conversions() {
-        assert_eq!(Node::from(42i64), Node::Number(Numeric::Integer(42)));
-        assert_eq!(Node::from(3.14f64), Node::Number(Numeric::Float(3.14)));
-        assert_eq!(Node::from(42u64), Node::Number(Numeric::UInteger(42)));
-        assert_eq!(Node::from(42u8), Node::Number(Numeric::Byte(42)));
-        assert_eq!(Node::from(42i32), Node::Number(Numeric::Int32(42)));
-        assert_eq!(Node::from(42u32), Node::Number(Numeric::UInt32(42)));
-        assert_eq!(Node::from(42i16), Node::Number(Numeric::Int16(42)));
-        assert_eq!(Node::from(42u16), Node::Number(Numeric::UInt16(42)));
-        assert_eq!(Node::from(42i8), Node::Number(Numeric::Int8(42)));
+        assert_strict_eq!(Node::from(42i64), Node::Number(Numeric::Integer(42)));
+        assert_strict_eq!(Node::from(2.5f64), Node::Number(Numeric::Float(2.5)));
+        assert_strict_eq!(Node::from(42u64), Node::Number(Numeric::UInteger(42)));
+        assert_strict_eq!(Node::from(42u8), Node::Number(Numeric::Byte(42)));
+        assert_strict_eq!(Node::from(42i32), Node::Number(Numeric::Int32(42)));
+        assert_strict_eq!(Node::from(42u32), Node::Number(Numeric::UInt32(42)));
+        assert_strict_eq!(Node::from(42i16), Node::Number(Numeric::Int16(42)));
+        assert_strict_eq!(Node::from(42u16), Node::Number(Numeric::UInt16(42)));
+        assert_strict_eq!(Node::from(42i8), Node::Number(Numeric::Int8(42)));
     }
 
     #[test]
@@ -290,6 +471,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_node_slice_conversion() {
+        let values = [1u8, 2u8];
+        assert_eq!(Node::from(&values[..]), Node::Array(vec![Node::from(1u8), Node::from(2u8)]));
+        let empty: &[i32] = &[];
+        assert_eq!(Node::from(empty), Node::Array(Vec::new()));
+    }
+
+    #[test]
+    fn test_node_pair_list_conversion() {
+        let node = Node::from(vec![("name", Node::from("web")), ("port", Node::from(80))]);
+        assert_eq!(node["name"], Node::from("web"));
+        assert_eq!(node["port"], Node::from(80));
+        let node = Node::from(vec![("a".to_string(), 1), ("a".to_string(), 2)]);
+        assert_eq!(node["a"], Node::from(2));
+        let pairs = [("x", true)];
+        assert_eq!(Node::from(&pairs[..])["x"], Node::Boolean(true));
+    }
+
+    #[test]
+    fn test_node_map_conversions() {
+        let mut hash = IndexMap::new();
+        hash.insert("a".to_string(), vec![1, 2]);
+        assert_eq!(Node::from(hash)["a"], Node::from(vec![1, 2]));
+        let mut tree = BTreeMap::new();
+        tree.insert("b".to_string(), "text");
+        assert_eq!(Node::from(tree)["b"], Node::from("text"));
+    }
+
+    #[test]
+    fn test_node_option_conversion() {
+        assert_eq!(Node::from(Some(5)), Node::from(5));
+        assert_eq!(Node::from(None::<i32>), Node::None);
+        assert_eq!(Node::from(vec![Some("a"), None]), Node::Array(vec![Node::from("a"), Node::None]));
+    }
+
     #[test]
     fn test_array_indexing() {
         let arr = Node::Array(vec![Node::from(1), Node::from(2)]);
@@ -306,7 +523,7 @@ mod tests {
 
     #[test]
     fn test_dictionary_indexing() {
-        let mut map = HashMap::new();
+        let mut map = IndexMap::new();
         map.insert("key".to_string(), Node::from(42));
         let obj = Node::Dictionary(map);
         assert_eq!(obj["key"], Node::Number(Numeric::Int32(42)));
@@ -335,7 +552,7 @@ mod tests {
 
     #[test]
     fn test_dictionary_mut_indexing() {
-        let mut map = HashMap::new();
+        let mut map = IndexMap::new();
         map.insert("key".to_string(), Node::from(42));
         let mut obj = Node::Dictionary(map);
         obj["key"] = Node::from(100);
@@ -350,10 +567,17 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "No such key exists")]
     fn test_dictionary_mut_indexing_nonexistent_key() {
-        let mut obj = Node::Dictionary(HashMap::new());
+        let mut obj = Node::Dictionary(IndexMap::new());
         obj["nonexistent"] = Node::from(42);
+        assert_eq!(obj["nonexistent"], Node::Number(Numeric::Int32(42)));
+    }
+
+    #[test]
+    fn test_none_mut_indexing_creates_dictionary() {
+        let mut node = Node::None;
+        node["outer"]["inner"] = Node::from(1);
+        assert_eq!(node["outer"]["inner"], Node::Number(Numeric::Int32(1)));
     }
 
     #[test]
@@ -396,4 +620,42 @@ mod tests {
         let none = make_node(Node::None);
         assert_eq!(none, Node::None);
     }
+
+    #[test]
+    fn test_canonical_numeric_policy() {
+        assert_strict_eq!(Numeric::Int32(42).with_policy(NumericPolicy::Canonical), Numeric::Integer(42));
+        assert_strict_eq!(Numeric::Byte(7).with_policy(NumericPolicy::Canonical), Numeric::Integer(7));
+        assert_strict_eq!(Numeric::UInteger(5).with_policy(NumericPolicy::Canonical), Numeric::Integer(5));
+        assert_strict_eq!(Numeric::UInteger(u64::MAX).with_policy(NumericPolicy::Canonical), Numeric::UInteger(u64::MAX));
+        assert_strict_eq!(Numeric::Float(1.5).with_policy(NumericPolicy::Canonical), Numeric::Float(1.5));
+    }
+
+    #[test]
+    fn test_smallest_fit_numeric_policy() {
+        assert_strict_eq!(Numeric::Integer(42).with_policy(NumericPolicy::SmallestFit), Numeric::Int8(42));
+        assert_strict_eq!(Numeric::Integer(200).with_policy(NumericPolicy::SmallestFit), Numeric::Byte(200));
+        assert_strict_eq!(Numeric::Integer(-300).with_policy(NumericPolicy::SmallestFit), Numeric::Int16(-300));
+        assert_strict_eq!(Numeric::Integer(40000).with_policy(NumericPolicy::SmallestFit), Numeric::UInt16(40000));
+        assert_strict_eq!(Numeric::Integer(-70000).with_policy(NumericPolicy::SmallestFit), Numeric::Int32(-70000));
+        assert_strict_eq!(Numeric::Integer(3_000_000_000).with_policy(NumericPolicy::SmallestFit), Numeric::UInt32(3_000_000_000));
+        assert_strict_eq!(Numeric::Integer(i64::MIN).with_policy(NumericPolicy::SmallestFit), Numeric::Integer(i64::MIN));
+        assert_strict_eq!(Numeric::UInteger(u64::MAX).with_policy(NumericPolicy::SmallestFit), Numeric::UInteger(u64::MAX));
+        assert_strict_eq!(Numeric::Float(1.5).with_policy(NumericPolicy::SmallestFit), Numeric::Float(1.5));
+    }
+
+    #[test]
+    fn test_apply_numeric_policy_to_tree() {
+        let mut map = IndexMap::new();
+        map.insert("list".to_string(), Node::from(vec![1u8, 2u8]));
+        map.insert("count".to_string(), Node::from(3i16));
+        let node = Node::Dictionary(map).with_numeric_policy(NumericPolicy::Canonical);
+        assert_strict_eq!(node["list"][0], Node::Number(Numeric::Integer(1)));
+        assert_strict_eq!(node["list"][1], Node::Number(Numeric::Integer(2)));
+        assert_strict_eq!(node["count"], Node::Number(Numeric::Integer(3)));
+    }
+
+    #[test]
+    fn test_default_numeric_policy_is_canonical() {
+        assert_eq!(NumericPolicy::default(), NumericPolicy::Canonical);
+    }
 }
@@ -0,0 +1,90 @@
+//! Checked conversions for Numeric values.
+//! The integer accessors accept any integer variant whose value fits the target type
+//! and return None for floats and out of range values; as_f64 accepts every variant.
+
+use crate::nodes::node::Numeric;
+
+macro_rules! checked_integer_accessors {
+    ($($name:ident => $target:ty),*) => {
+        $(
+            #[doc = concat!("Returns the value as an ", stringify!($target), ", or None for floats and values out of its range.")]
+            pub fn $name(&self) -> Option<$target> {
+                self.as_wide_integer().and_then(|value| <$target>::try_from(value).ok())
+            }
+        )*
+    };
+}
+
+impl Numeric {
+    /// Returns true for every integer variant.
+    pub fn is_integer(&self) -> bool {
+        !self.is_float()
+    }
+
+    /// Returns true for Float.
+    pub fn is_float(&self) -> bool {
+        matches!(self, Numeric::Float(_))
+    }
+
+    checked_integer_accessors!(
+        as_i64 => i64,
+        as_u64 => u64,
+        as_i32 => i32,
+        as_u32 => u32,
+        as_i16 => i16,
+        as_u16 => u16,
+        as_i8 => i8,
+        as_u8 => u8,
+        as_usize => usize
+    );
+
+    /// Returns the value as an f64; integers beyond 2^53 may lose precision.
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Numeric::Float(value) => value,
+            Numeric::Integer(value) => value as f64,
+            Numeric::UInteger(value) => value as f64,
+            Numeric::Byte(value) => value as f64,
+            Numeric::Int32(value) => value as f64,
+            Numeric::UInt32(value) => value as f64,
+            Numeric::Int16(value) => value as f64,
+            Numeric::UInt16(value) => value as f64,
+            Numeric::Int8(value) => value as f64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_accessors_check_range() {
+        assert_eq!(Numeric::Int8(-1).as_i64(), Some(-1));
+        assert_eq!(Numeric::Int8(-1).as_u64(), None);
+        assert_eq!(Numeric::UInteger(u64::MAX).as_i64(), None);
+        assert_eq!(Numeric::UInteger(u64::MAX).as_u64(), Some(u64::MAX));
+        assert_eq!(Numeric::Integer(300).as_u8(), None);
+        assert_eq!(Numeric::Integer(300).as_i16(), Some(300));
+        assert_eq!(Numeric::UInt32(70000).as_u16(), None);
+        assert_eq!(Numeric::Byte(200).as_i8(), None);
+        assert_eq!(Numeric::Int16(-5).as_i32(), Some(-5));
+        assert_eq!(Numeric::UInt16(5).as_u32(), Some(5));
+        assert_eq!(Numeric::Int32(5).as_usize(), Some(5));
+    }
+
+    #[test]
+    fn floats_are_not_integers() {
+        assert_eq!(Numeric::Float(3.0).as_i64(), None);
+        assert!(Numeric::Float(3.0).is_float());
+        assert!(!Numeric::Float(3.0).is_integer());
+        assert!(Numeric::Byte(3).is_integer());
+    }
+
+    #[test]
+    fn as_f64_works() {
+        assert_eq!(Numeric::Float(0.5).as_f64(), 0.5);
+        assert_eq!(Numeric::Int8(-2).as_f64(), -2.0);
+        assert_eq!(Numeric::UInteger(1 << 40).as_f64(), (1u64 << 40) as f64);
+    }
+}
@@ -0,0 +1,108 @@
+//! Helpers for building and splitting JSON-pointer style paths ("/servers/0/port")
+//! used to address nodes within a tree.
+
+/// Escapes a single path segment ("~" becomes "~0" and "/" becomes "~1").
+///
+/// # Arguments
+/// * `segment` - Dictionary key or array index to escape
+pub fn escape_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// Reverses escape_segment ("~1" becomes "/" and "~0" becomes "~").
+///
+/// # Arguments
+/// * `segment` - Escaped path segment
+pub fn unescape_segment(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+/// Appends an (unescaped) segment to a parent path.
+///
+/// # Arguments
+/// * `parent` - Path of the parent node ("" for the root)
+/// * `segment` - Dictionary key or array index of the child
+///
+/// # Returns
+/// The path of the child node.
+pub fn join(parent: &str, segment: &str) -> String {
+    format!("{}/{}", parent, escape_segment(segment))
+}
+
+/// Splits a path into its unescaped segments; the root path "" has no segments.
+///
+/// # Arguments
+/// * `path` - Path to split
+///
+/// # Returns
+/// The segments of the path, or None if a non-empty path does not start with '/'.
+pub fn split(path: &str) -> Option<Vec<String>> {
+    if path.is_empty() {
+        return Some(Vec::new());
+    }
+    let rest = path.strip_prefix('/')?;
+    Some(rest.split('/').map(unescape_segment).collect())
+}
+
+/// Returns true if a path matches a glob pattern written as a path whose segments may be
+/// "*" (any one segment) or "**" (any number of segments, including none).
+///
+/// # Arguments
+/// * `pattern` - Glob pattern such as "/servers/*/host" or "/**/password"
+/// * `path` - Path to test
+pub fn matches_glob(pattern: &str, path: &str) -> bool {
+    match (split(pattern), split(path)) {
+        (Some(pattern), Some(path)) => match_segments(&pattern, &path),
+        _ => false,
+    }
+}
+
+fn match_segments(pattern: &[String], path: &[String]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((first, rest)) => {
+            path.split_first().is_some_and(|(segment, tail)| (first == "*" || first == segment) && match_segments(rest, tail))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_and_unescape_segment_work() {
+        assert_eq!(escape_segment("a/b~c"), "a~1b~0c");
+        assert_eq!(unescape_segment("a~1b~0c"), "a/b~c");
+        assert_eq!(unescape_segment("~01"), "~1");
+    }
+
+    #[test]
+    fn join_works() {
+        assert_eq!(join("", "servers"), "/servers");
+        assert_eq!(join("/servers", "0"), "/servers/0");
+        assert_eq!(join("", "a/b"), "/a~1b");
+    }
+
+    #[test]
+    fn split_works() {
+        assert_eq!(split(""), Some(vec![]));
+        assert_eq!(split("/servers/0"), Some(vec!["servers".to_string(), "0".to_string()]));
+        assert_eq!(split("/a~1b"), Some(vec!["a/b".to_string()]));
+        assert_eq!(split("/"), Some(vec!["".to_string()]));
+        assert_eq!(split("servers"), None);
+    }
+
+    #[test]
+    fn matches_glob_works() {
+        assert!(matches_glob("/servers/*/host", "/servers/0/host"));
+        assert!(!matches_glob("/servers/*/host", "/servers/host"));
+        assert!(matches_glob("/**/password", "/password"));
+        assert!(matches_glob("/**/password", "/db/primary/password"));
+        assert!(!matches_glob("/**/password", "/db/password/x"));
+        assert!(matches_glob("/a~1b/*", "/a~1b/c"));
+        assert!(matches_glob("", ""));
+        assert!(!matches_glob("servers", "/servers"));
+    }
+}
@@ -0,0 +1,108 @@
+//! JSON-pointer (RFC 6901) lookups on Node trees.
+//! Array and Document items are addressed by index and dictionary values by key, so
+//! the paths used by the comment and anchor metadata resolve to their nodes.
+
+use crate::nodes::node::{lookup_key, Node};
+use crate::nodes::path::split;
+
+/// Parses an array index segment; leading zeros and signs are not allowed.
+pub(crate) fn parse_index(segment: &str) -> Option<usize> {
+    if segment.is_empty() || (segment.len() > 1 && segment.starts_with('0')) {
+        return None;
+    }
+    if !segment.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    segment.parse().ok()
+}
+
+impl Node {
+    /// Looks up a node by JSON pointer ("/servers/0/port"); "~1" and "~0" in a segment
+    /// stand for "/" and "~".
+    ///
+    /// # Arguments
+    /// * `path` - Pointer to the node ("" for this node)
+    ///
+    /// # Returns
+    /// The node, or None if the pointer is malformed or does not resolve.
+    pub fn pointer(&self, path: &str) -> Option<&Node> {
+        let mut node = self;
+        for segment in split(path)? {
+            node = match node {
+                Node::Dictionary(map) => map.get(lookup_key(map, &segment).as_ref())?,
+                Node::Array(items) | Node::Document(items) => items.get(parse_index(&segment)?)?,
+                _ => return None,
+            };
+        }
+        Some(node)
+    }
+
+    /// Looks up a node by JSON pointer for modification.
+    ///
+    /// # Arguments
+    /// * `path` - Pointer to the node ("" for this node)
+    ///
+    /// # Returns
+    /// The node, or None if the pointer is malformed or does not resolve.
+    pub fn pointer_mut(&mut self, path: &str) -> Option<&mut Node> {
+        let mut node = self;
+        for segment in split(path)? {
+            node = match node {
+                Node::Dictionary(map) => {
+                    let key = lookup_key(map, &segment).into_owned();
+                    map.get_mut(&key)?
+                }
+                Node::Array(items) | Node::Document(items) => items.get_mut(parse_index(&segment)?)?,
+                _ => return None,
+            };
+        }
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    fn config() -> Node {
+        let mut server = IndexMap::new();
+        server.insert("port".to_string(), Node::from(80));
+        let mut map = IndexMap::new();
+        map.insert("servers".to_string(), Node::Array(vec![Node::Dictionary(server)]));
+        map.insert("a/b".to_string(), Node::from("slash"));
+        map.insert("m~n".to_string(), Node::from("tilde"));
+        Node::Dictionary(map)
+    }
+
+    #[test]
+    fn pointer_works() {
+        let node = config();
+        assert_eq!(node.pointer("/servers/0/port"), Some(&Node::from(80)));
+        assert_eq!(node.pointer("/a~1b"), Some(&Node::from("slash")));
+        assert_eq!(node.pointer("/m~0n"), Some(&Node::from("tilde")));
+        assert_eq!(node.pointer(""), Some(&node));
+    }
+
+    #[test]
+    fn pointer_to_missing_node_is_none() {
+        let node = config();
+        for path in ["/missing", "/servers/1", "/servers/01", "/servers/-1", "/servers/x", "/a~1b/c", "servers"] {
+            assert_eq!(node.pointer(path), None, "{}", path);
+        }
+    }
+
+    #[test]
+    fn pointer_into_document_works() {
+        let node = Node::Document(vec![Node::Comment("c".to_string()), config()]);
+        assert_eq!(node.pointer("/1/servers/0/port"), Some(&Node::from(80)));
+    }
+
+    #[test]
+    fn pointer_mut_works() {
+        let mut node = config();
+        *node.pointer_mut("/servers/0/port").unwrap() = Node::from(8080);
+        assert_eq!(node["servers"][0]["port"], Node::from(8080));
+        assert!(node.pointer_mut("/servers/3").is_none());
+    }
+}
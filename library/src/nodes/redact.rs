@@ -0,0 +1,99 @@
+//! Redaction of sensitive values before a tree is logged or diffed.
+//! redact replaces every scalar value that a matcher accepts, or that lies beneath an
+//! accepted collection, with a replacement such as "***"; keys, array lengths and
+//! comments are kept so the structure stays visible. redact_paths does the same for
+//! glob patterns (see path::matches_glob).
+
+use crate::nodes::node::Node;
+use crate::nodes::path::{join, matches_glob};
+
+impl Node {
+    /// Replaces the matched scalar values of the tree.
+    ///
+    /// # Arguments
+    /// * `matches` - Called with the path and node of each node; when it accepts a
+    ///   collection every scalar beneath it is replaced
+    /// * `replacement` - Value written in place of each matched scalar
+    ///
+    /// # Returns
+    /// The number of scalars replaced.
+    pub fn redact<M, V>(&mut self, mut matches: M, replacement: V) -> usize
+    where
+        M: FnMut(&str, &Node) -> bool,
+        V: Into<Node>,
+    {
+        redact_at(self, "", false, &mut matches, &replacement.into())
+    }
+
+    /// Replaces the scalar values at, or beneath, paths matching any of the patterns.
+    ///
+    /// # Arguments
+    /// * `patterns` - Glob patterns such as "/**/password" or "/db/credentials"
+    /// * `replacement` - Value written in place of each matched scalar
+    ///
+    /// # Returns
+    /// The number of scalars replaced.
+    pub fn redact_paths<V: Into<Node>>(&mut self, patterns: &[&str], replacement: V) -> usize {
+        self.redact(|path, _| patterns.iter().any(|pattern| matches_glob(pattern, path)), replacement)
+    }
+}
+
+fn redact_at(
+    node: &mut Node,
+    path: &str,
+    inherited: bool,
+    matches: &mut dyn FnMut(&str, &Node) -> bool,
+    replacement: &Node,
+) -> usize {
+    if node.is_comment() {
+        return 0;
+    }
+    let matched = inherited || matches(path, node);
+    match node {
+        Node::Dictionary(map) => {
+            map.iter_mut().map(|(key, value)| redact_at(value, &join(path, key), matched, matches, replacement)).sum()
+        }
+        Node::Array(items) | Node::Document(items) => items
+            .iter_mut()
+            .enumerate()
+            .map(|(index, item)| redact_at(item, &join(path, &index.to_string()), matched, matches, replacement))
+            .sum(),
+        _ if matched => {
+            *node = replacement.clone();
+            1
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Node {
+        Node::dict()
+            .key("db", Node::dict().key("user", "admin").key("password", "hunter2"))
+            .key("api", Node::dict().key("tokens", vec!["t1", "t2"]).key("url", "https://example.com"))
+            .key("__comment_2", Node::Comment("secrets below".to_string()))
+            .build()
+    }
+
+    #[test]
+    fn redact_paths_works() {
+        let mut node = config();
+        assert_eq!(node.redact_paths(&["/**/password", "/api/tokens"], "***"), 3);
+        assert_eq!(node["db"]["password"], Node::from("***"));
+        assert_eq!(node["api"]["tokens"], Node::from(vec!["***", "***"]));
+        assert_eq!(node["db"]["user"], Node::from("admin"));
+        assert_eq!(node["__comment_2"], Node::Comment("secrets below".to_string()));
+    }
+
+    #[test]
+    fn redact_works_with_matcher() {
+        let mut node = config();
+        let count = node.redact(|_, node| node.as_str().is_some_and(|text| text.starts_with("https://")), Node::None);
+        assert_eq!(count, 1);
+        assert_eq!(node["api"]["url"], Node::None);
+        assert_eq!(node.redact(|_, _| false, "***"), 0);
+    }
+}
@@ -0,0 +1,128 @@
+//! Path based rewrites for migrating the layout of configuration trees.
+//! rename_keys renames every dictionary key whose path matches a glob pattern (see
+//! path::matches_glob) and move_path moves a value to a new path, creating any missing
+//! parent dictionaries. Both leave the rest of the tree untouched.
+
+use crate::diff::operation::Operation;
+use crate::error::patch::PatchError;
+use crate::nodes::node::Node;
+use crate::nodes::path::{join, matches_glob, split};
+use crate::patch::apply::apply_patch;
+
+impl Node {
+    /// Renames the dictionary keys whose paths match a glob pattern.
+    ///
+    /// A renamed key replaces any existing key of the same name, and children are
+    /// matched using their new paths.
+    ///
+    /// # Arguments
+    /// * `pattern` - Glob pattern of the keys to rename, such as "/servers/*/host"
+    /// * `new_name` - The new name for each matching key
+    ///
+    /// # Returns
+    /// The number of keys renamed.
+    pub fn rename_keys(&mut self, pattern: &str, new_name: &str) -> usize {
+        rename_at(self, "", pattern, new_name)
+    }
+
+    /// Moves the value at one path to another, creating missing parent dictionaries of
+    /// the destination.
+    ///
+    /// # Arguments
+    /// * `from` - Path of the value to move
+    /// * `to` - Path to move it to; "-" as the last segment appends to an array
+    ///
+    /// # Returns
+    /// Ok, or a PatchError (with the tree unchanged) if the value cannot be moved.
+    pub fn move_path(&mut self, from: &str, to: &str) -> Result<(), PatchError> {
+        let mut patch = Vec::new();
+        if let Some(segments) = split(to) {
+            let mut parent = String::new();
+            for segment in segments.iter().take(segments.len().saturating_sub(1)) {
+                parent = join(&parent, segment);
+                if self.pointer(&parent).is_none() {
+                    patch.push(Operation::Add { path: parent.clone(), value: Node::dict().build() });
+                }
+            }
+        }
+        patch.push(Operation::Move { from: from.to_string(), path: to.to_string() });
+        apply_patch(self, &patch)
+    }
+}
+
+fn rename_at(node: &mut Node, path: &str, pattern: &str, new_name: &str) -> usize {
+    let mut count = 0;
+    match node {
+        Node::Dictionary(map) => {
+            let mut matched: Vec<String> =
+                map.keys().filter(|key| *key != new_name && matches_glob(pattern, &join(path, key))).cloned().collect();
+            matched.sort();
+            for key in matched {
+                // The renamed entry keeps the position of the key it replaces
+                if let Some((index, _, value)) = map.shift_remove_full(&key) {
+                    map.shift_insert(index.min(map.len()), new_name.to_string(), value);
+                    count += 1;
+                }
+            }
+            for (key, value) in map.iter_mut() {
+                count += rename_at(value, &join(path, key), pattern, new_name);
+            }
+        }
+        Node::Array(items) | Node::Document(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                count += rename_at(item, &join(path, &index.to_string()), pattern, new_name);
+            }
+        }
+        _ => {}
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Node {
+        Node::dict()
+            .key("servers", Node::seq().push(Node::dict().key("host", "a")).push(Node::dict().key("host", "b")))
+            .key("db", Node::dict().key("host", "c").key("port", 5432))
+            .build()
+    }
+
+    #[test]
+    fn rename_keys_works() {
+        let mut node = config();
+        assert_eq!(node.rename_keys("/servers/*/host", "hostname"), 2);
+        assert_eq!(node["servers"][1]["hostname"], Node::from("b"));
+        assert_eq!(node["db"]["host"], Node::from("c"));
+        assert_eq!(node.rename_keys("/**/host", "address"), 1);
+        assert_eq!(node["db"]["address"], Node::from("c"));
+        assert_eq!(config().rename_keys("/**/host", "address"), 3);
+        assert_eq!(node.rename_keys("/missing", "x"), 0);
+    }
+
+    #[test]
+    fn renamed_children_match_new_paths() {
+        let mut node = Node::dict().key("old", Node::dict().key("old", 1)).build();
+        assert_eq!(node.rename_keys("/**/old", "new"), 2);
+        assert_eq!(node, Node::dict().key("new", Node::dict().key("new", 1)).build());
+    }
+
+    #[test]
+    fn move_path_works() {
+        let mut node = config();
+        node.move_path("/db/port", "/database/connection/port").unwrap();
+        assert_eq!(node["database"]["connection"]["port"], Node::from(5432));
+        assert!(!node["db"].contains_key("port"));
+        node.move_path("/db", "/servers/-").unwrap();
+        assert_eq!(node["servers"][2]["host"], Node::from("c"));
+    }
+
+    #[test]
+    fn failed_move_leaves_tree_unchanged() {
+        let mut node = config();
+        let error = node.move_path("/missing", "/new/place").unwrap_err();
+        assert_eq!(error.index, 1);
+        assert_eq!(node, config());
+    }
+}
@@ -0,0 +1,99 @@
+//! Pruning of collection nodes.
+//! retain keeps only the children of a Dictionary, Array or Document node that a predicate
+//! accepts; retain_recursive applies the same predicate to every collection in the tree.
+//! The predicate receives each child's key, or its index as a string, and the child.
+
+use crate::nodes::node::Node;
+
+impl Node {
+    /// Keeps only the children of this collection node accepted by a predicate.
+    ///
+    /// # Arguments
+    /// * `keep` - Called with the key (or index, as a string) of each child and the child;
+    ///   children for which it returns false are removed. Array indices are those before
+    ///   any removal.
+    pub fn retain<F: FnMut(&str, &Node) -> bool>(&mut self, mut keep: F) {
+        retain_children(self, &mut keep);
+    }
+
+    /// Keeps only the children accepted by a predicate throughout the whole tree.
+    ///
+    /// # Arguments
+    /// * `keep` - Called as for retain for the children of this node and of every nested
+    ///   collection; removed subtrees are not visited.
+    pub fn retain_recursive<F: FnMut(&str, &Node) -> bool>(&mut self, mut keep: F) {
+        retain_tree(self, &mut keep);
+    }
+}
+
+fn retain_children(node: &mut Node, keep: &mut dyn FnMut(&str, &Node) -> bool) {
+    match node {
+        Node::Dictionary(map) => map.retain(|key, value| keep(key, value)),
+        Node::Array(items) | Node::Document(items) => {
+            let mut index = 0;
+            items.retain(|item| {
+                let kept = keep(&index.to_string(), item);
+                index += 1;
+                kept
+            });
+        }
+        _ => {}
+    }
+}
+
+fn retain_tree(node: &mut Node, keep: &mut dyn FnMut(&str, &Node) -> bool) {
+    retain_children(node, keep);
+    match node {
+        Node::Dictionary(map) => map.values_mut().for_each(|value| retain_tree(value, keep)),
+        Node::Array(items) | Node::Document(items) => items.iter_mut().for_each(|item| retain_tree(item, keep)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nodes::node::Node;
+
+    #[test]
+    fn retain_dictionary_works() {
+        let mut node = Node::dict()
+            .key("debug_level", 3)
+            .key("servers", Node::seq().push(Node::dict().key("debug", true)))
+            .build();
+        node.retain(|key, _| !key.starts_with("debug"));
+        assert!(node.get("debug_level").is_none());
+        assert!(node["servers"][0].get("debug").is_some());
+    }
+
+    #[test]
+    fn retain_array_passes_original_indices() {
+        let mut node = Node::from(vec![10, 11, 12, 13]);
+        node.retain(|index, _| index != "1" && index != "2");
+        assert_eq!(node, Node::from(vec![10, 13]));
+        let mut node = Node::from(vec![1, 2, 3]);
+        node.retain(|_, item| *item != Node::from(2));
+        assert_eq!(node, Node::from(vec![1, 3]));
+    }
+
+    #[test]
+    fn retain_recursive_works() {
+        let mut node = Node::dict()
+            .key("debug_level", 3)
+            .key("name", "web")
+            .key("servers", Node::seq().push(Node::dict().key("debug", true).key("host", "a")).push(5))
+            .build();
+        node.retain_recursive(|key, _| !key.starts_with("debug"));
+        assert!(node.get("debug_level").is_none());
+        assert!(node["servers"][0].get("debug").is_none());
+        assert_eq!(node["servers"][0]["host"], Node::from("a"));
+        node.retain_recursive(|_, child| !matches!(child, Node::Number(_)));
+        assert_eq!(node["servers"].as_array().map(Vec::len), Some(1));
+    }
+
+    #[test]
+    fn retain_on_scalar_does_nothing() {
+        let mut node = Node::from("a");
+        node.retain(|_, _| false);
+        assert_eq!(node, Node::from("a"));
+    }
+}
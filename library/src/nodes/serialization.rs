@@ -0,0 +1,189 @@
+//! serde Serialize and Deserialize implementations for Node and Numeric.
+//! Numbers keep their Numeric variant when serialized. Comment nodes carry no data, so
+//! they are left out of serialized arrays, documents and dictionaries (a lone Comment
+//! serializes as unit), and Document nodes serialize as sequences. Deserialized integers
+//! follow the canonical numeric policy (Integer, or UInteger when too large for i64).
+
+use crate::nodes::node::{Node, Numeric};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use indexmap::IndexMap;
+use std::fmt;
+
+impl Serialize for Numeric {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Numeric::Integer(value) => serializer.serialize_i64(value),
+            Numeric::Float(value) => serializer.serialize_f64(value),
+            Numeric::UInteger(value) => serializer.serialize_u64(value),
+            Numeric::Byte(value) => serializer.serialize_u8(value),
+            Numeric::Int32(value) => serializer.serialize_i32(value),
+            Numeric::UInt32(value) => serializer.serialize_u32(value),
+            Numeric::Int16(value) => serializer.serialize_i16(value),
+            Numeric::UInt16(value) => serializer.serialize_u16(value),
+            Numeric::Int8(value) => serializer.serialize_i8(value),
+        }
+    }
+}
+
+/// Serializes the items of an array or document, leaving out comments.
+fn serialize_items<S: Serializer>(items: &[Node], serializer: S) -> Result<S::Ok, S::Error> {
+    let data: Vec<&Node> = items.iter().filter(|item| !matches!(item, Node::Comment(_))).collect();
+    let mut seq = serializer.serialize_seq(Some(data.len()))?;
+    for item in data {
+        seq.serialize_element(item)?;
+    }
+    seq.end()
+}
+
+impl Serialize for Node {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Node::Boolean(value) => serializer.serialize_bool(*value),
+            Node::Number(number) => number.serialize(serializer),
+            Node::Str(text) => serializer.serialize_str(text),
+            Node::Array(items) | Node::Document(items) => serialize_items(items, serializer),
+            Node::Dictionary(entries) => {
+                let data: Vec<(&String, &Node)> =
+                    entries.iter().filter(|(_, value)| !matches!(value, Node::Comment(_))).collect();
+                let mut map = serializer.serialize_map(Some(data.len()))?;
+                for (key, value) in data {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Node::Comment(_) | Node::None => serializer.serialize_unit(),
+        }
+    }
+}
+
+/// Visitor building a Node from any self-describing serde data.
+struct NodeVisitor;
+
+impl<'de> Visitor<'de> for NodeVisitor {
+    type Value = Node;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any YAML value")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Node, E> {
+        Ok(Node::Boolean(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Node, E> {
+        Ok(Node::Number(Numeric::Integer(value)))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Node, E> {
+        Ok(match i64::try_from(value) {
+            Ok(value) => Node::Number(Numeric::Integer(value)),
+            Err(_) => Node::Number(Numeric::UInteger(value)),
+        })
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Node, E> {
+        Ok(Node::Number(Numeric::Float(value)))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Node, E> {
+        Ok(Node::Str(value.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<Node, E> {
+        Ok(Node::Str(value))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Node, E> {
+        Ok(Node::None)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Node, E> {
+        Ok(Node::None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Node, D::Error> {
+        Node::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Node, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Node::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Node, A::Error> {
+        let mut entries = IndexMap::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((key, value)) = map.next_entry::<String, Node>()? {
+            entries.insert(key, value);
+        }
+        Ok(Node::Dictionary(entries))
+    }
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Node, D::Error> {
+        deserializer.deserialize_any(NodeVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Numeric {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Numeric, D::Error> {
+        match Node::deserialize(deserializer)? {
+            Node::Number(number) => Ok(number),
+            _ => Err(de::Error::custom("expected a number")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_scalars_works() {
+        assert_eq!(serde_json::to_string(&Node::Boolean(true)).unwrap(), "true");
+        assert_eq!(serde_json::to_string(&Node::from(42u8)).unwrap(), "42");
+        assert_eq!(serde_json::to_string(&Node::from(-1.5)).unwrap(), "-1.5");
+        assert_eq!(serde_json::to_string(&Node::from("a\"b")).unwrap(), "\"a\\\"b\"");
+        assert_eq!(serde_json::to_string(&Node::None).unwrap(), "null");
+    }
+
+    #[test]
+    fn serialize_collections_skips_comments() {
+        let node = Node::Array(vec![Node::Comment("c".to_string()), Node::from(1), Node::from(vec![true])]);
+        assert_eq!(serde_json::to_string(&node).unwrap(), "[1,[true]]");
+        let node = Node::dict().key("__comment_0", Node::Comment("c".to_string())).key("a", 1).build();
+        assert_eq!(serde_json::to_string(&node).unwrap(), "{\"a\":1}");
+        let node = Node::Document(vec![Node::Comment("header".to_string()), Node::from("data")]);
+        assert_eq!(serde_json::to_string(&node).unwrap(), "[\"data\"]");
+    }
+
+    #[test]
+    fn deserialize_works() {
+        let node: Node = serde_json::from_str("{\"name\": \"web\", \"ports\": [80, 443], \"tls\": null, \"ratio\": 0.5}").unwrap();
+        assert_eq!(node["name"], Node::from("web"));
+        assert_eq!(node["ports"], Node::Array(vec![Node::from(80i64), Node::from(443i64)]));
+        assert_eq!(node["tls"], Node::None);
+        assert_eq!(node["ratio"], Node::from(0.5));
+        let big: Node = serde_json::from_str("18446744073709551615").unwrap();
+        assert_eq!(big, Node::from(u64::MAX));
+    }
+
+    #[test]
+    fn numeric_round_trip_works() {
+        let number: Numeric = serde_json::from_str("7").unwrap();
+        assert!(number.strict_eq(&Numeric::Integer(7)));
+        assert_eq!(serde_json::to_string(&Numeric::Int16(-3)).unwrap(), "-3");
+        assert!(serde_json::from_str::<Numeric>("\"7\"").is_err());
+    }
+
+    #[test]
+    fn node_embeds_in_serde_types() {
+        let values: Vec<Node> = serde_json::from_str("[1, \"two\", {\"three\": [3]}]").unwrap();
+        assert_eq!(values[2]["three"][0], Node::from(3i64));
+        assert_eq!(serde_json::to_string(&values).unwrap(), "[1,\"two\",{\"three\":[3]}]");
+    }
+}
@@ -0,0 +1,137 @@
+//! Set operations on the keys of dictionary nodes.
+//! union, intersection and difference return new dictionaries built from the keys of two
+//! dictionaries; where both have a key the value is taken from self. The deep forms apply
+//! the same operation to nested dictionaries found under a shared key, so
+//! overlay.difference(&base, true) lists everything an overlay adds at any depth.
+
+use crate::error::conversion::ConversionError;
+use crate::nodes::node::Node;
+use indexmap::IndexMap;
+
+/// A set operation on dictionary keys.
+#[derive(Clone, Copy)]
+enum SetOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl Node {
+    /// Returns a dictionary with the keys of both dictionaries.
+    ///
+    /// # Arguments
+    /// * `other` - The dictionary whose keys are added; its values are used only for keys
+    ///   self does not have
+    /// * `deep` - Also take the union of nested dictionaries found under a shared key
+    ///
+    /// # Returns
+    /// The union, or a ConversionError if either node is not a dictionary.
+    pub fn union(&self, other: &Node, deep: bool) -> Result<Node, ConversionError> {
+        combine(self, other, SetOperation::Union, deep)
+    }
+
+    /// Returns a dictionary with the keys of self that other also has.
+    ///
+    /// # Arguments
+    /// * `other` - The dictionary whose keys are kept
+    /// * `deep` - Also intersect nested dictionaries found under a shared key
+    ///
+    /// # Returns
+    /// The intersection, or a ConversionError if either node is not a dictionary.
+    pub fn intersection(&self, other: &Node, deep: bool) -> Result<Node, ConversionError> {
+        combine(self, other, SetOperation::Intersection, deep)
+    }
+
+    /// Returns a dictionary with the keys of self that other does not have.
+    ///
+    /// # Arguments
+    /// * `other` - The dictionary whose keys are removed
+    /// * `deep` - Also take the difference of nested dictionaries found under a shared
+    ///   key, keeping those that are not empty
+    ///
+    /// # Returns
+    /// The difference, or a ConversionError if either node is not a dictionary.
+    pub fn difference(&self, other: &Node, deep: bool) -> Result<Node, ConversionError> {
+        combine(self, other, SetOperation::Difference, deep)
+    }
+}
+
+fn combine(a: &Node, b: &Node, operation: SetOperation, deep: bool) -> Result<Node, ConversionError> {
+    let (Node::Dictionary(first), Node::Dictionary(second)) = (a, b) else {
+        let not_dictionary = if a.is_dict() { b } else { a };
+        return Err(ConversionError::new("dictionary", not_dictionary));
+    };
+    let mut result = IndexMap::new();
+    for (key, value) in first {
+        let value = match (second.get(key), operation) {
+            (Some(other), _) if deep && value.is_dict() && other.is_dict() => {
+                let nested = combine(value, other, operation, deep)?;
+                if matches!(operation, SetOperation::Difference) && nested.is_empty() {
+                    continue;
+                }
+                nested
+            }
+            (Some(_), SetOperation::Difference) | (None, SetOperation::Intersection) => continue,
+            _ => value.clone(),
+        };
+        result.insert(key.clone(), value);
+    }
+    if let SetOperation::Union = operation {
+        for (key, value) in second {
+            result.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+    Ok(Node::Dictionary(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Node {
+        Node::dict()
+            .key("name", "web")
+            .key("server", Node::dict().key("port", 80).key("host", "a"))
+            .build()
+    }
+
+    fn overlay() -> Node {
+        Node::dict()
+            .key("server", Node::dict().key("port", 8080).key("tls", true))
+            .key("debug", true)
+            .build()
+    }
+
+    #[test]
+    fn union_works() {
+        let shallow = overlay().union(&base(), false).unwrap();
+        let expected = Node::dict().key("name", "web").key("debug", true).key("server", overlay()["server"].clone());
+        assert_eq!(shallow, expected.build());
+        let deep = overlay().union(&base(), true).unwrap();
+        assert_eq!(deep["server"], Node::dict().key("port", 8080).key("tls", true).key("host", "a").build());
+    }
+
+    #[test]
+    fn intersection_works() {
+        let shallow = overlay().intersection(&base(), false).unwrap();
+        assert_eq!(shallow, Node::dict().key("server", overlay()["server"].clone()).build());
+        let deep = overlay().intersection(&base(), true).unwrap();
+        assert_eq!(deep, Node::dict().key("server", Node::dict().key("port", 8080)).build());
+    }
+
+    #[test]
+    fn difference_works() {
+        let shallow = overlay().difference(&base(), false).unwrap();
+        assert_eq!(shallow, Node::dict().key("debug", true).build());
+        let deep = overlay().difference(&base(), true).unwrap();
+        assert_eq!(deep, Node::dict().key("debug", true).key("server", Node::dict().key("tls", true)).build());
+        assert!(base().difference(&base(), true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn non_dictionaries_fail() {
+        let error = base().union(&Node::from(vec![1]), true).unwrap_err();
+        assert_eq!(error.to_string(), "Expected dictionary but found array");
+        assert!(Node::None.difference(&base(), false).is_err());
+    }
+}
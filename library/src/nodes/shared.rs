@@ -0,0 +1,503 @@
+//! Shared-subtree representation of node trees.
+//! SharedNode mirrors Node but keeps strings and collections behind Arc, so a subtree can
+//! be referenced from many places without being copied. Node::to_shared stores each
+//! distinct collection once, so the copies the parser makes for every alias of an
+//! anchored block collapse back into a single allocation; SharedNode::to_node expands
+//! the tree into an owned Node again. parser::default::parse_shared instead shares
+//! exactly what the source does: each alias refers to the node its anchor was defined on.
+//! Cloning a SharedNode is O(1). The mutable accessors copy a collection only when it is
+//! still shared (Arc::make_mut), so an edited clone copies just the path to the change
+//! and keeps sharing everything else with the original.
+
+use crate::nodes::node::{Node, Numeric};
+use crate::nodes::path::split;
+use crate::nodes::pointer::parse_index;
+use std::collections::hash_map::DefaultHasher;
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::{Index, IndexMut};
+use std::sync::Arc;
+
+/// A node whose strings and collections are reference counted and may be shared.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SharedNode {
+    /// A boolean value
+    Boolean(bool),
+    /// A numeric value
+    Number(Numeric),
+    /// A string value
+    Str(Arc<str>),
+    /// A sequence of nodes
+    Array(Arc<Vec<SharedNode>>),
+    /// A mapping of string keys to nodes
+    Dictionary(Arc<IndexMap<String, SharedNode>>),
+    /// A comment
+    Comment(Arc<str>),
+    /// The top-level nodes of a document
+    Document(Arc<Vec<SharedNode>>),
+    /// A null value
+    None,
+}
+
+impl SharedNode {
+    /// Returns an owned copy of the tree, expanding every shared subtree.
+    pub fn to_node(&self) -> Node {
+        match self {
+            SharedNode::Boolean(value) => Node::Boolean(*value),
+            SharedNode::Number(number) => Node::Number(number.clone()),
+            SharedNode::Str(text) => Node::Str(text.to_string()),
+            SharedNode::Array(items) => Node::Array(items.iter().map(SharedNode::to_node).collect()),
+            SharedNode::Dictionary(map) => {
+                Node::Dictionary(map.iter().map(|(key, value)| (key.clone(), value.to_node())).collect())
+            }
+            SharedNode::Comment(text) => Node::Comment(text.to_string()),
+            SharedNode::Document(items) => Node::Document(items.iter().map(SharedNode::to_node).collect()),
+            SharedNode::None => Node::None,
+        }
+    }
+
+    /// Returns true if both nodes are the same shared collection or string, rather than
+    /// merely equal.
+    ///
+    /// # Arguments
+    /// * `other` - The node to compare with
+    pub fn ptr_eq(&self, other: &SharedNode) -> bool {
+        match (self, other) {
+            (SharedNode::Str(a), SharedNode::Str(b)) | (SharedNode::Comment(a), SharedNode::Comment(b)) => {
+                Arc::ptr_eq(a, b)
+            }
+            (SharedNode::Array(a), SharedNode::Array(b)) | (SharedNode::Document(a), SharedNode::Document(b)) => {
+                Arc::ptr_eq(a, b)
+            }
+            (SharedNode::Dictionary(a), SharedNode::Dictionary(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
+    /// Looks up a node by JSON pointer.
+    ///
+    /// # Arguments
+    /// * `path` - Pointer to the node ("" for this node)
+    ///
+    /// # Returns
+    /// The node, or None if the pointer is malformed or does not resolve.
+    pub fn pointer(&self, path: &str) -> Option<&SharedNode> {
+        let mut node = self;
+        for segment in split(path)? {
+            node = match node {
+                SharedNode::Dictionary(map) => map.get(&segment)?,
+                SharedNode::Array(items) | SharedNode::Document(items) => items.get(parse_index(&segment)?)?,
+                _ => return None,
+            };
+        }
+        Some(node)
+    }
+
+    /// Looks up a node by JSON pointer for modification, first copying each collection on
+    /// the way that is shared.
+    ///
+    /// # Arguments
+    /// * `path` - Pointer to the node ("" for this node)
+    ///
+    /// # Returns
+    /// The node, or None if the pointer is malformed or does not resolve.
+    pub fn pointer_mut(&mut self, path: &str) -> Option<&mut SharedNode> {
+        let mut node = self;
+        for segment in split(path)? {
+            node = match node {
+                SharedNode::Dictionary(map) if map.contains_key(&segment) => Arc::make_mut(map).get_mut(&segment)?,
+                SharedNode::Array(items) | SharedNode::Document(items) => {
+                    let index = parse_index(&segment).filter(|index| *index < items.len())?;
+                    Arc::make_mut(items).get_mut(index)?
+                }
+                _ => return None,
+            };
+        }
+        Some(node)
+    }
+
+    /// Returns the value for a key of a Dictionary node.
+    ///
+    /// # Arguments
+    /// * `key` - Key to look up
+    pub fn get(&self, key: &str) -> Option<&SharedNode> {
+        match self {
+            SharedNode::Dictionary(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    /// Returns the item at an index of an Array node.
+    ///
+    /// # Arguments
+    /// * `index` - Position of the item
+    pub fn get_index(&self, index: usize) -> Option<&SharedNode> {
+        match self {
+            SharedNode::Array(items) => items.get(index),
+            _ => None,
+        }
+    }
+
+    /// Returns the value for a key of a Dictionary node for modification, first copying
+    /// the dictionary if it is shared.
+    ///
+    /// # Arguments
+    /// * `key` - Key to look up
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut SharedNode> {
+        match self {
+            SharedNode::Dictionary(map) if map.contains_key(key) => Arc::make_mut(map).get_mut(key),
+            _ => None,
+        }
+    }
+
+    /// Returns the item at an index of an Array node for modification, first copying the
+    /// array if it is shared.
+    ///
+    /// # Arguments
+    /// * `index` - Position of the item
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut SharedNode> {
+        match self {
+            SharedNode::Array(items) if index < items.len() => Arc::make_mut(items).get_mut(index),
+            _ => None,
+        }
+    }
+
+    /// Inserts a key/value pair into a Dictionary node, first copying the dictionary if it
+    /// is shared; a None node becomes an empty dictionary.
+    ///
+    /// # Arguments
+    /// * `key` - Key to insert
+    /// * `value` - Value to store under the key
+    ///
+    /// # Returns
+    /// The value previously stored under the key.
+    ///
+    /// # Panics
+    /// If the node is neither a dictionary nor None.
+    pub fn insert<K: Into<String>>(&mut self, key: K, value: SharedNode) -> Option<SharedNode> {
+        if let SharedNode::None = self {
+            *self = SharedNode::Dictionary(Arc::new(IndexMap::new()));
+        }
+        match self {
+            SharedNode::Dictionary(map) => Arc::make_mut(map).insert(key.into(), value),
+            _ => panic!("Cannot insert into non-dictionary node"),
+        }
+    }
+
+    /// Removes a key from a Dictionary node, first copying the dictionary if it is shared.
+    ///
+    /// # Arguments
+    /// * `key` - Key to remove
+    ///
+    /// # Returns
+    /// The removed value, or None if the node is not a dictionary or has no such key.
+    pub fn remove(&mut self, key: &str) -> Option<SharedNode> {
+        match self {
+            SharedNode::Dictionary(map) if map.contains_key(key) => Arc::make_mut(map).shift_remove(key),
+            _ => None,
+        }
+    }
+
+    /// Appends an item to an Array node, first copying the array if it is shared; a None
+    /// node becomes an empty array.
+    ///
+    /// # Arguments
+    /// * `value` - The item to append
+    ///
+    /// # Panics
+    /// If the node is neither an array nor None.
+    pub fn push(&mut self, value: SharedNode) {
+        if let SharedNode::None = self {
+            *self = SharedNode::Array(Arc::new(Vec::new()));
+        }
+        match self {
+            SharedNode::Array(items) => Arc::make_mut(items).push(value),
+            _ => panic!("Cannot push onto non-array node"),
+        }
+    }
+
+    /// Returns the number of children of an Array, Dictionary or Document node; other
+    /// nodes have none.
+    pub fn len(&self) -> usize {
+        match self {
+            SharedNode::Array(items) | SharedNode::Document(items) => items.len(),
+            SharedNode::Dictionary(map) => map.len(),
+            _ => 0,
+        }
+    }
+
+    /// Returns true if the node has no children (always true for scalars).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the text of a Str node.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SharedNode::Str(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+/// Implements array-style indexing for SharedNode using integer indices
+impl Index<usize> for SharedNode {
+    type Output = SharedNode;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self {
+            SharedNode::Array(items) => &items[index],
+            _ => panic!("Cannot index non-array node with integer"),
+        }
+    }
+}
+
+/// Implements dictionary-style indexing for SharedNode using string keys
+impl Index<&str> for SharedNode {
+    type Output = SharedNode;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        match self {
+            SharedNode::Dictionary(map) => &map[key],
+            _ => panic!("Cannot index non-dictionary node with string"),
+        }
+    }
+}
+
+/// Implements mutable array-style indexing for SharedNode, copying a shared array first
+impl IndexMut<usize> for SharedNode {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match self {
+            SharedNode::Array(items) => &mut Arc::make_mut(items)[index],
+            _ => panic!("Cannot index non-array node with integer"),
+        }
+    }
+}
+
+/// Implements mutable dictionary-style indexing for SharedNode, copying a shared
+/// dictionary first. As with Node, a missing key is inserted with a None value and a None
+/// node becomes an empty dictionary.
+impl IndexMut<&str> for SharedNode {
+    fn index_mut(&mut self, key: &str) -> &mut Self::Output {
+        if let SharedNode::None = self {
+            *self = SharedNode::Dictionary(Arc::new(IndexMap::new()));
+        }
+        match self {
+            SharedNode::Dictionary(map) => Arc::make_mut(map).entry(key.to_string()).or_insert(SharedNode::None),
+            _ => panic!("Cannot index non-dictionary node with string"),
+        }
+    }
+}
+
+/// Converts an owned tree, sharing repeated collections
+impl From<&Node> for SharedNode {
+    fn from(node: &Node) -> Self {
+        node.to_shared()
+    }
+}
+
+/// Expands a shared tree into an owned one
+impl From<&SharedNode> for Node {
+    fn from(node: &SharedNode) -> Self {
+        node.to_node()
+    }
+}
+
+impl Node {
+    /// Returns the tree as a SharedNode in which every distinct collection is stored once.
+    ///
+    /// Collections are shared only when they are strictly equal (see strict_eq), so the
+    /// Numeric variants of numbers are preserved. The parser still expands aliases while
+    /// parsing, so the saving applies to the tree that is kept afterwards.
+    pub fn to_shared(&self) -> SharedNode {
+        Interner::default().share(self)
+    }
+
+    /// Returns the tree as a SharedNode without sharing anything.
+    pub(crate) fn to_unshared(&self) -> SharedNode {
+        match self {
+            Node::Boolean(value) => SharedNode::Boolean(*value),
+            Node::Number(number) => SharedNode::Number(number.clone()),
+            Node::Str(text) => SharedNode::Str(Arc::from(text.as_str())),
+            Node::Comment(text) => SharedNode::Comment(Arc::from(text.as_str())),
+            Node::None => SharedNode::None,
+            Node::Array(items) => SharedNode::Array(Arc::new(items.iter().map(Node::to_unshared).collect())),
+            Node::Document(items) => SharedNode::Document(Arc::new(items.iter().map(Node::to_unshared).collect())),
+            Node::Dictionary(map) => SharedNode::Dictionary(Arc::new(
+                map.iter().map(|(key, value)| (key.clone(), value.to_unshared())).collect(),
+            )),
+        }
+    }
+}
+
+/// Hash-consing table mapping the shallow hash of each collection built so far to the
+/// collections with that hash.
+#[derive(Default)]
+struct Interner {
+    collections: HashMap<u64, Vec<SharedNode>>,
+}
+
+impl Interner {
+    fn share(&mut self, node: &Node) -> SharedNode {
+        let shared = match node {
+            Node::Boolean(value) => return SharedNode::Boolean(*value),
+            Node::Number(number) => return SharedNode::Number(number.clone()),
+            Node::Str(text) => return SharedNode::Str(Arc::from(text.as_str())),
+            Node::Comment(text) => return SharedNode::Comment(Arc::from(text.as_str())),
+            Node::None => return SharedNode::None,
+            Node::Array(items) => SharedNode::Array(Arc::new(items.iter().map(|item| self.share(item)).collect())),
+            Node::Document(items) => {
+                SharedNode::Document(Arc::new(items.iter().map(|item| self.share(item)).collect()))
+            }
+            Node::Dictionary(map) => SharedNode::Dictionary(Arc::new(
+                map.iter().map(|(key, value)| (key.clone(), self.share(value))).collect(),
+            )),
+        };
+        let candidates = self.collections.entry(shallow_hash(&shared)).or_default();
+        match candidates.iter().find(|candidate| shallow_eq(candidate, &shared)) {
+            Some(existing) => existing.clone(),
+            None => {
+                candidates.push(shared.clone());
+                shared
+            }
+        }
+    }
+}
+
+/// Hashes a child as its address if it is a (canonical) collection, or by value.
+fn hash_child(child: &SharedNode, state: &mut DefaultHasher) {
+    std::mem::discriminant(child).hash(state);
+    match child {
+        SharedNode::Array(items) | SharedNode::Document(items) => Arc::as_ptr(items).hash(state),
+        SharedNode::Dictionary(map) => Arc::as_ptr(map).hash(state),
+        SharedNode::Boolean(value) => value.hash(state),
+        SharedNode::Number(number) => number.hash(state),
+        SharedNode::Str(text) | SharedNode::Comment(text) => text.hash(state),
+        SharedNode::None => {}
+    }
+}
+
+/// Hashes a collection whose children have already been interned.
+fn shallow_hash(node: &SharedNode) -> u64 {
+    let mut state = DefaultHasher::new();
+    std::mem::discriminant(node).hash(&mut state);
+    match node {
+        SharedNode::Array(items) | SharedNode::Document(items) => {
+            items.iter().for_each(|item| hash_child(item, &mut state))
+        }
+        SharedNode::Dictionary(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            for key in keys {
+                key.hash(&mut state);
+                hash_child(&map[key], &mut state);
+            }
+        }
+        _ => hash_child(node, &mut state),
+    }
+    state.finish()
+}
+
+/// Compares a child by address if it is a (canonical) collection, or strictly by value.
+fn child_eq(a: &SharedNode, b: &SharedNode) -> bool {
+    match (a, b) {
+        (SharedNode::Number(a), SharedNode::Number(b)) => a.strict_eq(b),
+        (SharedNode::Array(_) | SharedNode::Document(_) | SharedNode::Dictionary(_), _) => a.ptr_eq(b),
+        _ => a == b,
+    }
+}
+
+/// Compares two collections whose children have already been interned.
+fn shallow_eq(a: &SharedNode, b: &SharedNode) -> bool {
+    match (a, b) {
+        (SharedNode::Array(a), SharedNode::Array(b)) | (SharedNode::Document(a), SharedNode::Document(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| child_eq(a, b))
+        }
+        (SharedNode::Dictionary(a), SharedNode::Dictionary(b)) => {
+            a.len() == b.len() && a.iter().all(|(key, value)| b.get(key).is_some_and(|other| child_eq(value, other)))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::default::parse;
+    use crate::BufferSource;
+
+    #[test]
+    fn to_shared_round_trips() {
+        let node = Node::dict()
+            .key("a", vec![Node::from(1), Node::Number(Numeric::Float(1.0)), Node::None])
+            .key("b", Node::dict().key("c", "x").key("d", true))
+            .build();
+        let shared = node.to_shared();
+        assert_eq!(shared["b"]["c"].as_str(), Some("x"));
+        assert_eq!(shared["a"].len(), 3);
+        assert!(shared.to_node().strict_eq(&node));
+        assert!(Node::from(&shared).strict_eq(&node));
+    }
+
+    #[test]
+    fn repeated_subtrees_are_stored_once() {
+        let block = Node::dict().key("host", "db").key("ports", vec![5432, 5433]).build();
+        let node = Node::dict().key("primary", block.clone()).key("replica", block).build();
+        let shared = SharedNode::from(&node);
+        assert!(shared["primary"].ptr_eq(&shared["replica"]));
+        assert!(shared["primary"]["ports"].ptr_eq(&shared["replica"]["ports"]));
+    }
+
+    #[test]
+    fn only_strictly_equal_subtrees_are_shared() {
+        let node = Node::from(vec![Node::from(vec![1]), Node::from(vec![1i64])]);
+        let shared = node.to_shared();
+        assert!(!shared[0].ptr_eq(&shared[1]));
+        assert!(shared.to_node().strict_eq(&node));
+    }
+
+    #[test]
+    fn clones_copy_on_write() {
+        let node = Node::dict()
+            .key("server", Node::dict().key("port", 80).key("hosts", vec!["a", "b"]))
+            .key("logging", Node::dict().key("level", "info"))
+            .build();
+        let original = node.to_shared();
+        let mut edited = original.clone();
+        assert!(edited.ptr_eq(&original));
+        edited["server"]["port"] = SharedNode::Number(Numeric::Integer(8080));
+        edited["server"]["hosts"].push(SharedNode::Str(Arc::from("c")));
+        edited.insert("debug", SharedNode::Boolean(true));
+        assert!(!edited.ptr_eq(&original));
+        assert!(edited["logging"].ptr_eq(&original["logging"]));
+        assert_eq!(original.to_node(), node);
+        assert_eq!(edited["server"]["port"], SharedNode::Number(Numeric::Integer(8080)));
+        assert_eq!(edited["server"]["hosts"].len(), 3);
+        assert_eq!(edited.remove("debug"), Some(SharedNode::Boolean(true)));
+    }
+
+    #[test]
+    fn mutable_accessors_work() {
+        let mut shared = Node::dict().key("a", vec![1]).build().to_shared();
+        let copy = shared.clone();
+        *shared.get_mut("a").and_then(|items| items.get_index_mut(0)).unwrap() = SharedNode::None;
+        assert_eq!(shared["a"][0], SharedNode::None);
+        assert_eq!(copy["a"][0], SharedNode::Number(Numeric::Int32(1)));
+        assert!(shared.get_mut("missing").is_none());
+        assert!(shared.remove("missing").is_none());
+        let mut empty = SharedNode::None;
+        empty["x"].push(SharedNode::None);
+        assert_eq!(empty.to_node(), Node::dict().key("x", vec![Node::None]).build());
+    }
+
+    #[test]
+    fn aliases_share_their_anchored_block() {
+        let yaml = "base: &base {timeout: 30, retries: [1, 2]}\nfirst: *base\nsecond: *base\n";
+        let node = parse(&mut BufferSource::new(yaml.as_bytes())).unwrap();
+        let shared = node.to_shared();
+        assert!(shared["base"].ptr_eq(&shared["first"]));
+        assert!(shared["first"].ptr_eq(&shared["second"]));
+        assert_eq!(shared.to_node(), node);
+    }
+}
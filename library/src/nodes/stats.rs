@@ -0,0 +1,92 @@
+//! Size statistics for node trees.
+//! Node::stats counts the nodes of each kind (as a ParseReport does), measures the
+//! nesting depth and the bytes of text held, and estimates the heap memory the tree
+//! occupies, for capacity planning and for deciding when a document is large enough to be
+//! worth streaming.
+
+use crate::nodes::node::Node;
+use crate::parser::report::NodeCounts;
+use std::mem::size_of;
+
+/// Statistics describing a node tree, returned by Node::stats.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NodeStats {
+    /// Number of nodes of each kind
+    pub nodes: NodeCounts,
+    /// Maximum nesting depth (a lone scalar has depth 1)
+    pub max_depth: usize,
+    /// Bytes of text in string values, comments and dictionary keys
+    pub string_bytes: usize,
+    /// Approximate memory used by the tree in bytes, counting the root node, collection
+    /// buffers at their allocated capacity and string buffers
+    pub memory_bytes: usize,
+}
+
+impl Node {
+    /// Returns statistics describing this tree.
+    pub fn stats(&self) -> NodeStats {
+        let mut stats = NodeStats { memory_bytes: size_of::<Node>(), ..NodeStats::default() };
+        visit(self, 1, &mut stats);
+        stats
+    }
+}
+
+fn visit(node: &Node, depth: usize, stats: &mut NodeStats) {
+    stats.nodes.add(node);
+    stats.max_depth = stats.max_depth.max(depth);
+    match node {
+        Node::Str(text) | Node::Comment(text) => {
+            stats.string_bytes += text.len();
+            stats.memory_bytes += text.capacity();
+        }
+        Node::Array(items) | Node::Document(items) => {
+            stats.memory_bytes += items.capacity() * size_of::<Node>();
+            for item in items {
+                visit(item, depth + 1, stats);
+            }
+        }
+        Node::Dictionary(map) => {
+            // Each bucket holds a key, a value and one control byte
+            stats.memory_bytes += map.capacity() * (size_of::<String>() + size_of::<Node>() + 1);
+            for (key, value) in map {
+                stats.string_bytes += key.len();
+                stats.memory_bytes += key.capacity();
+                visit(value, depth + 1, stats);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_counts_nodes_and_depth() {
+        let node = Node::dict()
+            .key("name", "web")
+            .key("ports", vec![80, 443])
+            .key("tls", Node::dict().key("enabled", true).key("cert", Node::None))
+            .build();
+        let stats = node.stats();
+        assert_eq!(stats.nodes.dictionaries, 2);
+        assert_eq!(stats.nodes.numbers, 2);
+        assert_eq!(stats.nodes.total(), 8);
+        assert_eq!(stats.max_depth, 3);
+        let text = ["name", "web", "ports", "tls", "enabled", "cert"];
+        assert_eq!(stats.string_bytes, text.iter().map(|text| text.len()).sum::<usize>());
+    }
+
+    #[test]
+    fn memory_estimate_grows_with_content() {
+        let scalar = Node::None.stats();
+        assert_eq!(scalar.memory_bytes, size_of::<Node>());
+        assert_eq!(scalar.max_depth, 1);
+        let small = Node::from(vec!["a"]).stats();
+        let large = Node::from(vec!["a".repeat(1000); 10]).stats();
+        assert!(small.memory_bytes > scalar.memory_bytes);
+        assert!(large.memory_bytes >= 10_000 + 10 * size_of::<Node>());
+        assert_eq!(large.string_bytes, 10_000);
+    }
+}
@@ -0,0 +1,402 @@
+//! Traits mapping application types to and from Nodes without serde.
+//! Implementations are provided for the scalar std types, Option, Box, Vec, slices,
+//! HashMap, BTreeMap and IndexMap, so user implementations only need to handle their own fields.
+
+use crate::error::conversion::ConversionError;
+use crate::nodes::node::Node;
+use indexmap::IndexMap;
+use std::collections::{BTreeMap, HashMap};
+
+/// Converts a value into a Node.
+pub trait ToNode {
+    /// Returns the Node representing this value.
+    fn to_node(&self) -> Node;
+}
+
+/// Builds a value from a Node.
+pub trait FromNode: Sized {
+    /// Converts a node into a value of this type.
+    ///
+    /// # Arguments
+    /// * `node` - The node to convert
+    ///
+    /// # Returns
+    /// The value or a ConversionError describing what was expected and found.
+    fn from_node(node: &Node) -> Result<Self, ConversionError>;
+}
+
+/// Converts a field of a Dictionary node, for use in FromNode implementations of structs.
+/// A missing key is converted from Node::None, so Option fields may be omitted.
+///
+/// # Arguments
+/// * `node` - The Dictionary node holding the field
+/// * `key` - Key of the field
+///
+/// # Returns
+/// The converted field or a ConversionError whose path includes the key.
+pub fn from_field<T: FromNode>(node: &Node, key: &str) -> Result<T, ConversionError> {
+    if node.as_dict().is_none() {
+        return Err(ConversionError::new("dictionary", node));
+    }
+    T::from_node(node.get(key).unwrap_or(&Node::None)).map_err(|e| e.within(key))
+}
+
+macro_rules! scalar_node_traits {
+    ($($target:ty),*) => {
+        $(
+            impl ToNode for $target {
+                fn to_node(&self) -> Node {
+                    Node::from(self.clone())
+                }
+            }
+
+            impl FromNode for $target {
+                fn from_node(node: &Node) -> Result<Self, ConversionError> {
+                    <$target>::try_from(node)
+                }
+            }
+        )*
+    };
+}
+
+scalar_node_traits!(i8, i16, i32, i64, u8, u16, u32, u64, f64, bool, String);
+
+impl ToNode for usize {
+    fn to_node(&self) -> Node {
+        Node::from(*self as u64)
+    }
+}
+
+impl FromNode for usize {
+    fn from_node(node: &Node) -> Result<Self, ConversionError> {
+        usize::try_from(node)
+    }
+}
+
+impl ToNode for f32 {
+    fn to_node(&self) -> Node {
+        Node::from(*self as f64)
+    }
+}
+
+impl FromNode for f32 {
+    fn from_node(node: &Node) -> Result<Self, ConversionError> {
+        f32::try_from(node)
+    }
+}
+
+impl ToNode for str {
+    fn to_node(&self) -> Node {
+        Node::from(self)
+    }
+}
+
+impl ToNode for Node {
+    fn to_node(&self) -> Node {
+        self.clone()
+    }
+}
+
+impl FromNode for Node {
+    fn from_node(node: &Node) -> Result<Self, ConversionError> {
+        Ok(node.clone())
+    }
+}
+
+impl<T: ToNode + ?Sized> ToNode for &T {
+    fn to_node(&self) -> Node {
+        (**self).to_node()
+    }
+}
+
+impl<T: ToNode + ?Sized> ToNode for Box<T> {
+    fn to_node(&self) -> Node {
+        (**self).to_node()
+    }
+}
+
+impl<T: FromNode> FromNode for Box<T> {
+    fn from_node(node: &Node) -> Result<Self, ConversionError> {
+        T::from_node(node).map(Box::new)
+    }
+}
+
+impl<T: ToNode> ToNode for Option<T> {
+    fn to_node(&self) -> Node {
+        self.as_ref().map_or(Node::None, ToNode::to_node)
+    }
+}
+
+impl<T: FromNode> FromNode for Option<T> {
+    fn from_node(node: &Node) -> Result<Self, ConversionError> {
+        match node {
+            Node::None => Ok(None),
+            _ => T::from_node(node).map(Some),
+        }
+    }
+}
+
+impl<T: ToNode> ToNode for [T] {
+    fn to_node(&self) -> Node {
+        Node::Array(self.iter().map(ToNode::to_node).collect())
+    }
+}
+
+impl<T: ToNode> ToNode for Vec<T> {
+    fn to_node(&self) -> Node {
+        self.as_slice().to_node()
+    }
+}
+
+impl<T: FromNode> FromNode for Vec<T> {
+    fn from_node(node: &Node) -> Result<Self, ConversionError> {
+        let items = node.as_array().ok_or_else(|| ConversionError::new("array", node))?;
+        items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| T::from_node(item).map_err(|e| e.within(&index.to_string())))
+            .collect()
+    }
+}
+
+impl<T: ToNode> ToNode for HashMap<String, T> {
+    fn to_node(&self) -> Node {
+        Node::Dictionary(self.iter().map(|(key, value)| (key.clone(), value.to_node())).collect())
+    }
+}
+
+impl<T: FromNode> FromNode for HashMap<String, T> {
+    fn from_node(node: &Node) -> Result<Self, ConversionError> {
+        let map = node.as_dict().ok_or_else(|| ConversionError::new("dictionary", node))?;
+        map.iter()
+            .map(|(key, value)| T::from_node(value).map(|value| (key.clone(), value)).map_err(|e| e.within(key)))
+            .collect()
+    }
+}
+
+impl<T: ToNode> ToNode for BTreeMap<String, T> {
+    fn to_node(&self) -> Node {
+        Node::Dictionary(self.iter().map(|(key, value)| (key.clone(), value.to_node())).collect())
+    }
+}
+
+impl<T: FromNode> FromNode for BTreeMap<String, T> {
+    fn from_node(node: &Node) -> Result<Self, ConversionError> {
+        let map = node.as_dict().ok_or_else(|| ConversionError::new("dictionary", node))?;
+        map.iter()
+            .map(|(key, value)| T::from_node(value).map(|value| (key.clone(), value)).map_err(|e| e.within(key)))
+            .collect()
+    }
+}
+
+impl<T: ToNode> ToNode for IndexMap<String, T> {
+    fn to_node(&self) -> Node {
+        Node::Dictionary(self.iter().map(|(key, value)| (key.clone(), value.to_node())).collect())
+    }
+}
+
+impl<T: FromNode> FromNode for IndexMap<String, T> {
+    fn from_node(node: &Node) -> Result<Self, ConversionError> {
+        let map = node.as_dict().ok_or_else(|| ConversionError::new("dictionary", node))?;
+        map.iter()
+            .map(|(key, value)| T::from_node(value).map(|value| (key.clone(), value)).map_err(|e| e.within(key)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+        tags: Vec<String>,
+        timeout: Option<u32>,
+    }
+
+    impl ToNode for Server {
+        fn to_node(&self) -> Node {
+            Node::dict()
+                .key("host", self.host.to_node())
+                .key("port", self.port.to_node())
+                .key("tags", self.tags.to_node())
+                .key("timeout", self.timeout.to_node())
+                .build()
+        }
+    }
+
+    impl FromNode for Server {
+        fn from_node(node: &Node) -> Result<Self, ConversionError> {
+            Ok(Server {
+                host: from_field(node, "host")?,
+                port: from_field(node, "port")?,
+                tags: from_field(node, "tags")?,
+                timeout: from_field(node, "timeout")?,
+            })
+        }
+    }
+
+    #[test]
+    fn user_type_round_trip_works() {
+        let server = Server { host: "web".to_string(), port: 80, tags: vec!["a".to_string()], timeout: None };
+        let node = server.to_node();
+        assert_eq!(node["port"], Node::from(80u16));
+        assert_eq!(node["timeout"], Node::None);
+        assert_eq!(Server::from_node(&node), Ok(server));
+    }
+
+    #[test]
+    fn missing_optional_field_is_none() {
+        let node = Node::dict().key("host", "web").key("port", 80).key("tags", Node::seq()).build();
+        assert_eq!(Server::from_node(&node).map(|s| s.timeout), Ok(None));
+    }
+
+    #[test]
+    fn field_errors_include_path() {
+        let node = Node::dict().key("host", "web").key("port", 80).key("tags", Node::seq().push("a").push(1)).build();
+        let error = Server::from_node(&node).unwrap_err();
+        assert_eq!(error.path, "/tags/1");
+        let error = Server::from_node(&Node::dict().key("host", "web").build()).unwrap_err();
+        assert_eq!(error.path, "/port");
+        assert_eq!(error.found, "null");
+        assert_eq!(Server::from_node(&Node::from(1)).unwrap_err().expected, "dictionary");
+    }
+
+    #[test]
+    fn std_type_impls_work() {
+        assert_eq!(42i64.to_node(), Node::from(42i64));
+        assert_eq!(7usize.to_node(), Node::from(7u64));
+        assert_eq!(0.5f32.to_node(), Node::from(0.5));
+        assert_eq!("text".to_node(), Node::from("text"));
+        assert_eq!(Box::new(true).to_node(), Node::Boolean(true));
+        assert_eq!([1u8, 2u8][..].to_node(), Node::from(vec![1u8, 2u8]));
+        assert_eq!(Option::<i32>::from_node(&Node::None), Ok(None));
+        assert_eq!(Option::<i32>::from_node(&Node::from(3)), Ok(Some(3)));
+        assert_eq!(Box::<String>::from_node(&Node::from("b")), Ok(Box::new("b".to_string())));
+        assert_eq!(Node::from_node(&Node::None), Ok(Node::None));
+    }
+
+    #[test]
+    fn map_impls_work() {
+        let mut map = BTreeMap::new();
+        map.insert("a".to_string(), vec![1i64]);
+        let node = map.to_node();
+        assert_eq!(BTreeMap::<String, Vec<i64>>::from_node(&node), Ok(map));
+        let hash = HashMap::<String, Vec<i64>>::from_node(&node).unwrap();
+        assert_eq!(hash.to_node(), node);
+        assert_eq!(HashMap::<String, u8>::from_node(&Node::from(vec![("a", 300)])).unwrap_err().path, "/a");
+        let ordered = IndexMap::<String, i64>::from_node(&Node::from(vec![("z", 1), ("a", 2)])).unwrap();
+        assert_eq!(ordered.keys().collect::<Vec<_>>(), ["z", "a"]);
+        assert_eq!(ordered.to_node(), Node::from(vec![("z", 1), ("a", 2)]));
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod derive_tests {
+    use super::*;
+
+    #[derive(Debug, Default, PartialEq, yaml_lib::ToNode, yaml_lib::FromNode)]
+    struct Limits {
+        cpu: u32,
+        memory: u32,
+    }
+
+    #[derive(Debug, PartialEq, yaml_lib::ToNode, yaml_lib::FromNode)]
+    struct Container {
+        #[yaml(rename = "image-name")]
+        image: String,
+        #[yaml(default)]
+        replicas: u8,
+        #[yaml(flatten)]
+        limits: Limits,
+        ports: Vec<u16>,
+    }
+
+    #[derive(Debug, PartialEq, yaml_lib::ToNode, yaml_lib::FromNode)]
+    struct Port(u16);
+
+    #[derive(Debug, PartialEq, yaml_lib::ToNode, yaml_lib::FromNode)]
+    struct Pair(String, i64);
+
+    #[derive(Debug, PartialEq, yaml_lib::ToNode, yaml_lib::FromNode)]
+    enum Policy {
+        Always,
+        #[yaml(rename = "never")]
+        Never,
+        Retry(u8),
+        Window(u8, u8),
+        Backoff { initial: u32, #[yaml(default)] factor: u32 },
+    }
+
+    #[derive(Debug, PartialEq, yaml_lib::ToNode, yaml_lib::FromNode)]
+    struct Wrapper<T> {
+        value: T,
+    }
+
+    #[test]
+    fn derived_struct_round_trip_works() {
+        let container = Container {
+            image: "nginx".to_string(),
+            replicas: 2,
+            limits: Limits { cpu: 1, memory: 512 },
+            ports: vec![80],
+        };
+        let node = container.to_node();
+        assert_eq!(node["image-name"], Node::from("nginx"));
+        assert_eq!(node["cpu"], Node::from(1u32));
+        assert_eq!(node.get("limits"), None);
+        assert_eq!(Container::from_node(&node), Ok(container));
+    }
+
+    #[test]
+    fn derived_default_field_works() {
+        let node = Node::dict().key("image-name", "a").key("cpu", 1u32).key("memory", 2u32).key("ports", Node::seq()).build();
+        assert_eq!(Container::from_node(&node).map(|c| c.replicas), Ok(0));
+        let error = Container::from_node(&Node::dict().key("image-name", "a").build()).unwrap_err();
+        assert_eq!(error.path, "/cpu");
+    }
+
+    #[test]
+    fn derived_tuple_structs_work() {
+        assert_eq!(Port(80).to_node(), Node::from(80u16));
+        assert_eq!(Port::from_node(&Node::from(443)), Ok(Port(443)));
+        let pair = Pair("a".to_string(), 1);
+        assert_eq!(pair.to_node(), Node::Array(vec![Node::from("a"), Node::from(1i64)]));
+        assert_eq!(Pair::from_node(&pair.to_node()), Ok(pair));
+        assert_eq!(Pair::from_node(&Node::from(vec![1])).unwrap_err().expected, "array of 2 items");
+    }
+
+    #[test]
+    fn derived_enum_round_trip_works() {
+        for policy in [
+            Policy::Always,
+            Policy::Never,
+            Policy::Retry(3),
+            Policy::Window(1, 5),
+            Policy::Backoff { initial: 100, factor: 2 },
+        ] {
+            assert_eq!(Policy::from_node(&policy.to_node()), Ok(policy));
+        }
+        assert_eq!(Policy::Never.to_node(), Node::from("never"));
+        assert_eq!(Policy::Retry(3).to_node()["Retry"], Node::from(3u8));
+    }
+
+    #[test]
+    fn derived_enum_errors_work() {
+        let error = Policy::from_node(&Node::from("Sometimes")).unwrap_err();
+        assert_eq!(error.to_string(), "Expected variant of Policy but found unknown variant Sometimes");
+        let error = Policy::from_node(&Node::from(vec![("Retry", "x")])).unwrap_err();
+        assert_eq!(error.path, "/Retry");
+        let error = Policy::from_node(&Node::from(vec![("Backoff", Node::dict())])).unwrap_err();
+        assert_eq!(error.path, "/Backoff/initial");
+        assert!(Policy::from_node(&Node::from(1)).is_err());
+    }
+
+    #[test]
+    fn derived_generic_struct_works() {
+        let wrapper = Wrapper { value: vec![true] };
+        assert_eq!(Wrapper::<Vec<bool>>::from_node(&wrapper.to_node()), Ok(wrapper));
+    }
+}
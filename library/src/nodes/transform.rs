@@ -0,0 +1,166 @@
+//! Bulk rewrites of node trees.
+//! map_values and map_keys return new trees with every scalar value or dictionary key
+//! rewritten; transform rewrites in place the nodes whose path a matcher accepts.
+
+use crate::nodes::node::Node;
+use crate::nodes::path::join;
+use indexmap::IndexMap;
+
+impl Node {
+    /// Returns a copy of the tree with every scalar (non-collection) node rewritten.
+    ///
+    /// # Arguments
+    /// * `f` - Called with the path and value of each scalar; returns its replacement
+    pub fn map_values<F: FnMut(&str, &Node) -> Node>(&self, mut f: F) -> Node {
+        map_values_at(self, "", &mut f)
+    }
+
+    /// Returns a copy of the tree with every dictionary key rewritten.
+    ///
+    /// # Arguments
+    /// * `f` - Called with each key; returns its replacement. When two keys of one
+    ///   dictionary map to the same key the later one in sorted key order wins.
+    pub fn map_keys<F: FnMut(&str) -> String>(&self, mut f: F) -> Node {
+        map_keys_of(self, &mut f)
+    }
+
+    /// Rewrites in place every node whose path is accepted by a matcher.
+    ///
+    /// The tree is visited depth first and a rewritten node's new children are visited
+    /// too.
+    ///
+    /// # Arguments
+    /// * `matches` - Called with the path and value of each node
+    /// * `f` - Called with each matching node to modify it
+    ///
+    /// # Returns
+    /// The number of nodes rewritten.
+    pub fn transform<M, F>(&mut self, mut matches: M, mut f: F) -> usize
+    where
+        M: FnMut(&str, &Node) -> bool,
+        F: FnMut(&mut Node),
+    {
+        transform_at(self, "", &mut matches, &mut f)
+    }
+}
+
+fn map_values_at(node: &Node, path: &str, f: &mut dyn FnMut(&str, &Node) -> Node) -> Node {
+    match node {
+        Node::Dictionary(_) => Node::Dictionary(
+            node.entries().map(|(key, value)| (key.to_string(), map_values_at(value, &join(path, key), f))).collect(),
+        ),
+        Node::Array(items) => Node::Array(map_items(items, path, f)),
+        Node::Document(items) => Node::Document(map_items(items, path, f)),
+        _ => f(path, node),
+    }
+}
+
+fn map_items(items: &[Node], path: &str, f: &mut dyn FnMut(&str, &Node) -> Node) -> Vec<Node> {
+    items.iter().enumerate().map(|(index, item)| map_values_at(item, &join(path, &index.to_string()), f)).collect()
+}
+
+fn map_keys_of(node: &Node, f: &mut dyn FnMut(&str) -> String) -> Node {
+    match node {
+        Node::Dictionary(_) => {
+            let mut map = IndexMap::new();
+            for (key, value) in node.entries() {
+                map.insert(f(key), map_keys_of(value, f));
+            }
+            Node::Dictionary(map)
+        }
+        Node::Array(items) => Node::Array(items.iter().map(|item| map_keys_of(item, f)).collect()),
+        Node::Document(items) => Node::Document(items.iter().map(|item| map_keys_of(item, f)).collect()),
+        _ => node.clone(),
+    }
+}
+
+fn transform_at(
+    node: &mut Node,
+    path: &str,
+    matches: &mut dyn FnMut(&str, &Node) -> bool,
+    f: &mut dyn FnMut(&mut Node),
+) -> usize {
+    let mut count = 0;
+    if matches(path, node) {
+        f(node);
+        count += 1;
+    }
+    match node {
+        Node::Dictionary(map) => {
+            for (key, value) in map.iter_mut() {
+                count += transform_at(value, &join(path, key), matches, f);
+            }
+        }
+        Node::Array(items) | Node::Document(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                count += transform_at(item, &join(path, &index.to_string()), matches, f);
+            }
+        }
+        _ => {}
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nodes::node::{Node, Numeric};
+
+    #[test]
+    fn map_values_works() {
+        let node = Node::dict()
+            .key("name", "web")
+            .key("servers", Node::seq().push(Node::dict().key("port", "443")))
+            .build();
+        let node = node.map_values(|path, value| match value.as_str() {
+            Some(text) if path.ends_with("/port") => Node::from(text.parse::<i64>().unwrap()),
+            _ => value.clone(),
+        });
+        assert_eq!(node["servers"][0]["port"], Node::Number(Numeric::Integer(443)));
+        assert_eq!(node["name"], Node::from("web"));
+    }
+
+    #[test]
+    fn map_keys_works() {
+        let node = Node::dict().key("name", "web").key("servers", Node::seq().push(Node::dict().key("port", "80")));
+        let node = node.build().map_keys(|key| key.to_uppercase());
+        assert_eq!(node["SERVERS"][0]["PORT"], Node::from("80"));
+        assert!(node.get("name").is_none());
+        let collided = Node::dict().key("a", 1).key("b", 2).build().map_keys(|_| "k".to_string());
+        assert_eq!(collided["k"], Node::from(2));
+    }
+
+    #[test]
+    fn transform_works() {
+        let mut node = Node::dict()
+            .key("name", "web")
+            .key("servers", Node::seq().push(Node::dict().key("port", "80")).push(Node::dict().key("port", "443")))
+            .build();
+        let count = node.transform(
+            |path, _| path.starts_with("/servers/") && path.ends_with("/port"),
+            |value| {
+                if let Some(port) = value.as_str().and_then(|text| text.parse::<i64>().ok()) {
+                    *value = Node::from(port);
+                }
+            },
+        );
+        assert_eq!(count, 2);
+        assert_eq!(node["servers"][0]["port"], Node::Number(Numeric::Integer(80)));
+        assert_eq!(node["name"], Node::from("web"));
+    }
+
+    #[test]
+    fn transform_visits_new_children() {
+        let mut node = Node::dict().key("a", Node::None).build();
+        let count = node.transform(
+            |_, value| matches!(value, Node::None | Node::Boolean(false)),
+            |value| {
+                *value = match value {
+                    Node::None => Node::from(vec![false]),
+                    _ => Node::from(true),
+                }
+            },
+        );
+        assert_eq!(count, 2);
+        assert_eq!(node["a"], Node::from(vec![true]));
+    }
+}
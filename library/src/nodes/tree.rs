@@ -0,0 +1,101 @@
+//! Tree view of node structure for debugging.
+//! Node::to_tree_string draws a tree one node per line with box drawing branches, each
+//! labelled with its key (or [index] in arrays and streams) and its variant: collections
+//! show their number of children ("servers: Array(3)") and scalars their value as flow
+//! style YAML, cut short after MAX_TREE_VALUE_CHARS characters so long strings do not
+//! swamp the structure. Dictionary keys are listed in sorted order.
+
+use crate::nodes::node::Node;
+use crate::stringify::default::flow_scalar;
+
+/// Longest value text shown for a scalar before it is truncated with "…".
+pub const MAX_TREE_VALUE_CHARS: usize = 40;
+
+impl Node {
+    /// Returns the structure of this tree as an indented tree of node kinds and values.
+    ///
+    /// # Returns
+    /// One line per node, the root first, each ending in a newline.
+    pub fn to_tree_string(&self) -> String {
+        let mut tree = label(self);
+        tree.push('\n');
+        children(self, "", &mut tree);
+        tree
+    }
+}
+
+/// Returns a node's variant with its child count or (truncated) value.
+fn label(node: &Node) -> String {
+    match node {
+        Node::Array(_) => format!("Array({})", node.len()),
+        Node::Dictionary(_) => format!("Dictionary({})", node.len()),
+        Node::Document(_) => format!("Document({})", node.len()),
+        Node::None => "None".to_string(),
+        Node::Comment(text) => format!("Comment({})", truncate(&flow_scalar(&Node::Str(text.clone())))),
+        Node::Boolean(_) => format!("Boolean({})", flow_scalar(node)),
+        Node::Number(_) => format!("Number({})", flow_scalar(node)),
+        Node::Str(_) => format!("Str({})", truncate(&flow_scalar(node))),
+    }
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_TREE_VALUE_CHARS) {
+        Some((index, _)) => format!("{}…", &text[..index]),
+        None => text.to_string(),
+    }
+}
+
+/// Appends a line for each child of a collection, and their children below them, with
+/// prefix holding the branches of the levels above.
+fn children(node: &Node, prefix: &str, tree: &mut String) {
+    let entries: Vec<(String, &Node)> = match node {
+        Node::Array(items) | Node::Document(items) => {
+            items.iter().enumerate().map(|(index, item)| (format!("[{}]", index), item)).collect()
+        }
+        Node::Dictionary(_) => node.entries().map(|(key, value)| (key.to_string(), value)).collect(),
+        _ => return,
+    };
+    let count = entries.len();
+    for (position, (key, child)) in entries.into_iter().enumerate() {
+        let last = position + 1 == count;
+        tree.push_str(prefix);
+        tree.push_str(if last { "└─ " } else { "├─ " });
+        tree.push_str(&key);
+        tree.push_str(": ");
+        tree.push_str(&label(child));
+        tree.push('\n');
+        children(child, &format!("{}{}", prefix, if last { "   " } else { "│  " }), tree);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_tree_string_works() {
+        let node = Node::dict()
+            .key("name", "web")
+            .key("servers", vec![Node::from("a"), Node::dict().key("port", 80).build(), Node::None])
+            .key("tls", Node::dict().key("enabled", true).build())
+            .build();
+        let expected = "Dictionary(3)\n\
+                        ├─ name: Str(\"web\")\n\
+                        ├─ servers: Array(3)\n\
+                        │  ├─ [0]: Str(\"a\")\n\
+                        │  ├─ [1]: Dictionary(1)\n\
+                        │  │  └─ port: Number(80)\n\
+                        │  └─ [2]: None\n\
+                        └─ tls: Dictionary(1)\n\
+                        \x20  └─ enabled: Boolean(true)\n";
+        assert_eq!(node.to_tree_string(), expected);
+        assert_eq!(Node::from(1.5).to_tree_string(), "Number(1.5)\n");
+    }
+
+    #[test]
+    fn to_tree_string_truncates_long_values() {
+        let node = Node::Document(vec![Node::from("x".repeat(60)), Node::Comment("note".to_string())]);
+        let expected = format!("Document(2)\n├─ [0]: Str(\"{}…)\n└─ [1]: Comment(\"note\")\n", "x".repeat(39));
+        assert_eq!(node.to_tree_string(), expected);
+    }
+}
@@ -0,0 +1,83 @@
+//! Unicode normalization of dictionary keys.
+//! Keys are converted to Normalization Form C so that, for example, "café" written with a
+//! combining accent and with a precomposed "é" are treated as the same key.
+
+use crate::nodes::node::Node;
+use std::borrow::Cow;
+use indexmap::IndexMap;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+/// Returns the NFC normalized form of a key, borrowing it if already normalized.
+///
+/// # Arguments
+/// * `key` - Dictionary key to normalize
+pub fn normalize_key(key: &str) -> Cow<'_, str> {
+    if is_nfc(key) {
+        Cow::Borrowed(key)
+    } else {
+        Cow::Owned(key.nfc().collect())
+    }
+}
+
+impl Node {
+    /// NFC normalizes every dictionary key in the tree. If two keys normalize to the same
+    /// form, the value of the one visited last is kept.
+    pub fn normalize_keys(&mut self) {
+        match self {
+            Node::Dictionary(map) => {
+                let entries: IndexMap<String, Node> = std::mem::take(map)
+                    .into_iter()
+                    .map(|(key, mut value)| {
+                        value.normalize_keys();
+                        (normalize_key(&key).into_owned(), value)
+                    })
+                    .collect();
+                *map = entries;
+            }
+            Node::Array(items) | Node::Document(items) => {
+                for item in items {
+                    item.normalize_keys();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DECOMPOSED: &str = "cafe\u{301}";
+    const PRECOMPOSED: &str = "caf\u{e9}";
+
+    #[test]
+    fn normalize_key_works() {
+        assert_eq!(normalize_key(DECOMPOSED), PRECOMPOSED);
+        assert!(matches!(normalize_key(PRECOMPOSED), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn normalize_keys_in_tree_works() {
+        let mut inner = IndexMap::new();
+        inner.insert(DECOMPOSED.to_string(), Node::from(1));
+        let mut map = IndexMap::new();
+        map.insert("list".to_string(), Node::Array(vec![Node::Dictionary(inner)]));
+        let mut node = Node::Dictionary(map);
+        node.normalize_keys();
+        match &node["list"][0] {
+            Node::Dictionary(inner) => assert!(inner.contains_key(PRECOMPOSED)),
+            _ => panic!("Expected Dictionary node"),
+        }
+    }
+
+    #[test]
+    fn index_with_either_form_works() {
+        let mut map = IndexMap::new();
+        map.insert(PRECOMPOSED.to_string(), Node::from(42));
+        let mut node = Node::Dictionary(map);
+        assert_eq!(node[DECOMPOSED], Node::from(42));
+        node[DECOMPOSED] = Node::from(7);
+        assert_eq!(node[PRECOMPOSED], Node::from(7));
+    }
+}
@@ -0,0 +1,226 @@
+//! Visitor API for node trees.
+//! A NodeVisitor (or NodeVisitorMut for in place changes) is driven through a tree by
+//! Node::accept (or Node::accept_mut). Mappings and sequences call a visit hook before
+//! their children, which may skip them by returning false, and an exit hook after them;
+//! every other node is a scalar. Document nodes are visited as sequences, dictionary
+//! children in sorted key order, and every hook receives the node's path.
+
+use crate::nodes::node::Node;
+use crate::nodes::path::join;
+
+/// Read-only visitor driven by Node::accept. Every hook does nothing by default.
+pub trait NodeVisitor {
+    /// Called for Boolean, Number, Str, Comment and None nodes.
+    fn visit_scalar(&mut self, _path: &str, _node: &Node) {}
+
+    /// Called for a Dictionary node before its values; returning false skips them.
+    fn visit_mapping(&mut self, _path: &str, _node: &Node) -> bool {
+        true
+    }
+
+    /// Called for a Dictionary node after its values.
+    fn exit_mapping(&mut self, _path: &str, _node: &Node) {}
+
+    /// Called for an Array or Document node before its items; returning false skips them.
+    fn visit_sequence(&mut self, _path: &str, _node: &Node) -> bool {
+        true
+    }
+
+    /// Called for an Array or Document node after its items.
+    fn exit_sequence(&mut self, _path: &str, _node: &Node) {}
+}
+
+/// Visitor driven by Node::accept_mut that may modify nodes as it goes. Every hook does
+/// nothing by default. Children are visited as they are after visit_mapping or
+/// visit_sequence returns, so those hooks may add, remove or replace them.
+pub trait NodeVisitorMut {
+    /// Called for Boolean, Number, Str, Comment and None nodes.
+    fn visit_scalar(&mut self, _path: &str, _node: &mut Node) {}
+
+    /// Called for a Dictionary node before its values; returning false skips them.
+    fn visit_mapping(&mut self, _path: &str, _node: &mut Node) -> bool {
+        true
+    }
+
+    /// Called for a Dictionary node after its values.
+    fn exit_mapping(&mut self, _path: &str, _node: &mut Node) {}
+
+    /// Called for an Array or Document node before its items; returning false skips them.
+    fn visit_sequence(&mut self, _path: &str, _node: &mut Node) -> bool {
+        true
+    }
+
+    /// Called for an Array or Document node after its items.
+    fn exit_sequence(&mut self, _path: &str, _node: &mut Node) {}
+}
+
+fn accept_at(node: &Node, path: &str, visitor: &mut dyn NodeVisitor) {
+    match node {
+        Node::Dictionary(_) => {
+            if visitor.visit_mapping(path, node) {
+                for (key, value) in node.entries() {
+                    accept_at(value, &join(path, key), visitor);
+                }
+            }
+            visitor.exit_mapping(path, node);
+        }
+        Node::Array(items) | Node::Document(items) => {
+            if visitor.visit_sequence(path, node) {
+                for (index, item) in items.iter().enumerate() {
+                    accept_at(item, &join(path, &index.to_string()), visitor);
+                }
+            }
+            visitor.exit_sequence(path, node);
+        }
+        _ => visitor.visit_scalar(path, node),
+    }
+}
+
+fn accept_mut_at(node: &mut Node, path: &str, visitor: &mut dyn NodeVisitorMut) {
+    match node {
+        Node::Dictionary(_) => {
+            if visitor.visit_mapping(path, node) {
+                for (key, value) in node.entries_mut() {
+                    accept_mut_at(value, &join(path, key), visitor);
+                }
+            }
+            visitor.exit_mapping(path, node);
+        }
+        Node::Array(_) | Node::Document(_) => {
+            if visitor.visit_sequence(path, node) {
+                for (index, item) in node.items_mut().enumerate() {
+                    accept_mut_at(item, &join(path, &index.to_string()), visitor);
+                }
+            }
+            visitor.exit_sequence(path, node);
+        }
+        _ => visitor.visit_scalar(path, node),
+    }
+}
+
+impl Node {
+    /// Drives a visitor through this node and its descendants depth first.
+    ///
+    /// # Arguments
+    /// * `visitor` - The visitor to call; this node has the path ""
+    pub fn accept(&self, visitor: &mut dyn NodeVisitor) {
+        accept_at(self, "", visitor);
+    }
+
+    /// Drives a modifying visitor through this node and its descendants depth first.
+    ///
+    /// # Arguments
+    /// * `visitor` - The visitor to call; this node has the path ""
+    pub fn accept_mut(&mut self, visitor: &mut dyn NodeVisitorMut) {
+        accept_mut_at(self, "", visitor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+    }
+
+    impl NodeVisitor for Recorder {
+        fn visit_scalar(&mut self, path: &str, _node: &Node) {
+            self.events.push(format!("scalar {}", path));
+        }
+
+        fn visit_mapping(&mut self, path: &str, _node: &Node) -> bool {
+            self.events.push(format!("enter map {}", path));
+            path != "/meta"
+        }
+
+        fn exit_mapping(&mut self, path: &str, _node: &Node) {
+            self.events.push(format!("exit map {}", path));
+        }
+
+        fn visit_sequence(&mut self, path: &str, _node: &Node) -> bool {
+            self.events.push(format!("enter seq {}", path));
+            true
+        }
+
+        fn exit_sequence(&mut self, path: &str, _node: &Node) {
+            self.events.push(format!("exit seq {}", path));
+        }
+    }
+
+    #[test]
+    fn accept_calls_hooks_in_order() {
+        let node = Node::dict()
+            .key("tags", vec!["a", "b"])
+            .key("port", 80)
+            .key("meta", Node::dict().key("x", 1))
+            .build();
+        let mut recorder = Recorder::default();
+        node.accept(&mut recorder);
+        assert_eq!(
+            recorder.events,
+            vec![
+                "enter map ",
+                "enter map /meta",
+                "exit map /meta",
+                "scalar /port",
+                "enter seq /tags",
+                "scalar /tags/0",
+                "scalar /tags/1",
+                "exit seq /tags",
+                "exit map ",
+            ]
+        );
+    }
+
+    struct Upper;
+
+    impl NodeVisitorMut for Upper {
+        fn visit_scalar(&mut self, _path: &str, node: &mut Node) {
+            if let Node::Str(text) = node {
+                *text = text.to_uppercase();
+            }
+        }
+
+        fn visit_sequence(&mut self, _path: &str, node: &mut Node) -> bool {
+            if let Node::Array(items) = node {
+                items.push(Node::from("c"));
+            }
+            true
+        }
+
+        fn exit_mapping(&mut self, path: &str, node: &mut Node) {
+            if path.is_empty()
+                && let Node::Dictionary(map) = node
+            {
+                map.shift_remove("port");
+            }
+        }
+    }
+
+    #[test]
+    fn accept_mut_modifies_tree() {
+        let mut node = Node::dict()
+            .key("tags", vec!["a", "b"])
+            .key("port", 80)
+            .key("meta", Node::dict().key("x", 1))
+            .build();
+        node.accept_mut(&mut Upper);
+        assert_eq!(node["tags"], Node::from(vec!["A", "B", "C"]));
+        assert!(node.get("port").is_none());
+        assert_eq!(node["meta"]["x"], Node::from(1));
+    }
+
+    #[test]
+    fn default_hooks_do_nothing() {
+        struct Nothing;
+        impl NodeVisitor for Nothing {}
+        impl NodeVisitorMut for Nothing {}
+        let mut node = Node::dict().key("tags", vec!["a"]).key("meta", Node::dict().key("x", 1)).build();
+        let original = node.clone();
+        node.accept(&mut Nothing);
+        node.accept_mut(&mut Nothing);
+        assert_eq!(node, original);
+    }
+}
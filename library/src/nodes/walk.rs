@@ -0,0 +1,99 @@
+//! Deep traversal of node trees.
+//! walk visits every node depth first in document order (a parent before its children,
+//! children in order) and walk_breadth_first visits them level by level. Both yield each
+//! node together with its JSON-pointer style path; dictionary children are visited in
+//! sorted key order.
+
+use crate::nodes::node::Node;
+use crate::nodes::path::join;
+use std::collections::VecDeque;
+
+/// Returns the paths and nodes of the direct children of a node in document order.
+fn children<'a>(path: &str, node: &'a Node) -> Vec<(String, &'a Node)> {
+    match node {
+        Node::Array(items) | Node::Document(items) => {
+            items.iter().enumerate().map(|(index, item)| (join(path, &index.to_string()), item)).collect()
+        }
+        Node::Dictionary(_) => node.entries().map(|(key, value)| (join(path, key), value)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Depth first iterator over a node tree, created by Node::walk.
+pub struct Walk<'a> {
+    /// Nodes still to visit, the next one last
+    stack: Vec<(String, &'a Node)>,
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = (String, &'a Node);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, node) = self.stack.pop()?;
+        self.stack.extend(children(&path, node).into_iter().rev());
+        Some((path, node))
+    }
+}
+
+/// Breadth first iterator over a node tree, created by Node::walk_breadth_first.
+pub struct BreadthFirstWalk<'a> {
+    /// Nodes still to visit, the next one first
+    queue: VecDeque<(String, &'a Node)>,
+}
+
+impl<'a> Iterator for BreadthFirstWalk<'a> {
+    type Item = (String, &'a Node);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, node) = self.queue.pop_front()?;
+        self.queue.extend(children(&path, node));
+        Some((path, node))
+    }
+}
+
+impl Node {
+    /// Returns a depth first iterator over this node and all of its descendants.
+    ///
+    /// # Returns
+    /// An iterator of (path, node) pairs starting with ("", self).
+    pub fn walk(&self) -> Walk<'_> {
+        Walk { stack: vec![(String::new(), self)] }
+    }
+
+    /// Returns a breadth first iterator over this node and all of its descendants.
+    ///
+    /// # Returns
+    /// An iterator of (path, node) pairs starting with ("", self), shallower nodes first.
+    pub fn walk_breadth_first(&self) -> BreadthFirstWalk<'_> {
+        BreadthFirstWalk { queue: VecDeque::from([(String::new(), self)]) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nodes::node::Node;
+
+    #[test]
+    fn walk_is_depth_first_in_document_order() {
+        let node = Node::dict().key("servers", Node::seq().push(Node::dict().key("host", "a")).push("b")).build();
+        let paths: Vec<String> = node.walk().map(|(path, _)| path).collect();
+        assert_eq!(paths, vec!["", "/servers", "/servers/0", "/servers/0/host", "/servers/1"]);
+    }
+
+    #[test]
+    fn walk_breadth_first_works() {
+        let node = Node::from(vec![Node::dict().key("host", "a").build(), Node::from("b")]);
+        let paths: Vec<String> = node.walk_breadth_first().map(|(path, _)| path).collect();
+        assert_eq!(paths, vec!["", "/0", "/1", "/0/host"]);
+    }
+
+    #[test]
+    fn walk_yields_nodes_and_escaped_paths() {
+        let node = Node::dict().key("a/b", 1).build();
+        let visited: Vec<(String, &Node)> = node.walk().collect();
+        assert_eq!(visited[0].1, &node);
+        assert_eq!(visited[1], ("/a~1b".to_string(), &Node::from(1)));
+        assert_eq!(Node::from(1).walk().count(), 1);
+        assert_eq!(Node::Document(vec![Node::None]).walk_breadth_first().nth(1).unwrap().0, "/0");
+    }
+}
@@ -0,0 +1,187 @@
+//! Bencode parser.
+//! Reads bencode, the encoding of BitTorrent metainfo files, into a Node tree: integers
+//! ("i42e") become Integer or UInteger values, byte strings ("4:spam") Str values, lists
+//! Array nodes and dictionaries Dictionary nodes. Byte strings that are not UTF-8 (such as
+//! the SHA-1 piece hashes of a .torrent file) are decoded lossily, with U+FFFD in place of
+//! invalid bytes. Integers with leading zeros or "-0", and anything after the value, are
+//! errors; dictionary keys are accepted in any order, later duplicates replacing earlier
+//! ones. Lists and dictionaries nested deeper than DEFAULT_MAX_DEPTH are an error.
+
+use crate::io::traits::ISource;
+use crate::nodes::node::{Node, Numeric};
+use crate::parser::options::DEFAULT_MAX_DEPTH;
+use indexmap::IndexMap;
+
+/// Parses bencode from a source into a Node tree.
+///
+/// # Arguments
+/// * `source` - The source to read bencode from
+///
+/// # Returns
+/// The parsed Node or an error describing the first problem and its byte position.
+pub fn parse(source: &mut dyn ISource) -> Result<Node, String> {
+    let node = parse_value(source, 0)?;
+    match source.current() {
+        Some(c) => Err(error(source, &format!("Unexpected '{}' after bencode value", c))),
+        None => Ok(node),
+    }
+}
+
+/// Returns an error message noting the current byte position in the source.
+fn error(source: &mut dyn ISource, message: &str) -> String {
+    format!("{} at position {}", message, source.position())
+}
+
+/// Parses the value at the current position, nested inside depth lists and dictionaries.
+fn parse_value(source: &mut dyn ISource, depth: usize) -> Result<Node, String> {
+    if matches!(source.current(), Some('l' | 'd')) && depth >= DEFAULT_MAX_DEPTH {
+        return Err(error(source, &format!("Nesting exceeds the maximum depth of {}", DEFAULT_MAX_DEPTH)));
+    }
+    match source.current() {
+        Some('i') => {
+            source.next();
+            let number = read_integer(source, 'e')?;
+            source.next(); // Skip 'e'
+            Ok(Node::Number(number))
+        }
+        Some('l') => {
+            source.next();
+            let mut items = Vec::new();
+            while source.current() != Some('e') {
+                items.push(parse_value(source, depth + 1)?);
+            }
+            source.next();
+            Ok(Node::Array(items))
+        }
+        Some('d') => {
+            source.next();
+            let mut map = IndexMap::new();
+            while source.current() != Some('e') {
+                if !source.current().is_some_and(|c| c.is_ascii_digit()) {
+                    return Err(error(source, "Expected a byte string key in bencode dictionary"));
+                }
+                let key = read_string(source)?;
+                let value = parse_value(source, depth + 1)?;
+                map.insert(key, value);
+            }
+            source.next();
+            Ok(Node::Dictionary(map))
+        }
+        Some('0'..='9') => Ok(Node::Str(read_string(source)?)),
+        Some(c) => Err(error(source, &format!("Unexpected '{}' where a bencode value was expected", c))),
+        None => Err(error(source, "Unexpected end of input in bencode")),
+    }
+}
+
+/// Reads the digits of an integer up to (not past) its terminator.
+fn read_integer(source: &mut dyn ISource, terminator: char) -> Result<Numeric, String> {
+    let mut text = String::new();
+    while let Some(c) = source.current().filter(|&c| c != terminator) {
+        text.push(c);
+        source.next();
+    }
+    if source.current().is_none() {
+        return Err(error(source, "Unterminated bencode integer"));
+    }
+    let digits = text.strip_prefix('-').unwrap_or(&text);
+    if digits.is_empty()
+        || !digits.chars().all(|c| c.is_ascii_digit())
+        || (digits.starts_with('0') && digits.len() > 1)
+        || text == "-0"
+    {
+        return Err(error(source, &format!("Invalid bencode integer: {}", text)));
+    }
+    if let Ok(value) = text.parse::<i64>() {
+        Ok(Numeric::Integer(value))
+    } else {
+        text.parse::<u64>().map(Numeric::UInteger).map_err(|_| error(source, "Bencode integer out of range"))
+    }
+}
+
+/// Reads a length-prefixed byte string.
+fn read_string(source: &mut dyn ISource) -> Result<String, String> {
+    let length = match read_integer(source, ':')? {
+        Numeric::Integer(length) if length >= 0 => length as usize,
+        _ => return Err(error(source, "Invalid bencode string length")),
+    };
+    source.next(); // Skip ':'
+    // Checked before allocating so a corrupt length cannot reserve more than the input holds
+    if source.length().is_some_and(|total| length > total.saturating_sub(source.position())) {
+        return Err(error(source, "Bencode string length exceeds the input"));
+    }
+    let end = source.position().saturating_add(length);
+    let mut bytes = Vec::with_capacity(if source.length().is_some() { length } else { 0 });
+    while source.position() < end {
+        let Some(c) = source.current() else {
+            return Err(error(source, "Unterminated bencode string"));
+        };
+        let start = source.position();
+        source.next();
+        // A byte that is not part of a UTF-8 character is read as a character of that value
+        if source.position() - start == c.len_utf8() {
+            bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        } else {
+            bytes.push(c as u32 as u8);
+        }
+    }
+    if source.position() != end {
+        return Err(error(source, "Bencode string length ends inside a character"));
+    }
+    Ok(String::from_utf8(bytes).unwrap_or_else(|error| String::from_utf8_lossy(error.as_bytes()).into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::sources::buffer::Buffer;
+    use crate::stringify::bencode::stringify;
+
+    fn parse_bytes(bytes: &[u8]) -> Result<Node, String> {
+        parse(&mut Buffer::new(bytes))
+    }
+
+    #[test]
+    fn parse_bencode_works() {
+        let node = parse_bytes("d1:Zi1e1:ad1:ei0e2:é5:cafée1:ni18446744073709551615e4:zetali-3e4:spamee".as_bytes());
+        let expected = Node::dict()
+            .key("zeta", vec![Node::from(-3), Node::from("spam")])
+            .key("Z", 1)
+            .key("a", Node::dict().key("é", "café").key("e", 0))
+            .key("n", Node::Number(Numeric::UInteger(u64::MAX)))
+            .build();
+        assert_eq!(node.unwrap(), expected);
+        let mut destination = crate::io::destinations::buffer::Buffer::new();
+        stringify(&expected, &mut destination).unwrap();
+        assert_eq!(parse_bytes(destination.to_string().as_bytes()).unwrap(), expected);
+        assert_eq!(parse_bytes(b"0:").unwrap(), Node::from(""));
+        assert_eq!(parse_bytes(b"le").unwrap(), Node::Array(vec![]));
+    }
+
+    #[test]
+    fn parse_bencode_binary_strings_are_lossy() {
+        let node = parse_bytes(b"d6:pieces4:\xff\x00\xc3ze").unwrap();
+        assert_eq!(node, Node::dict().key("pieces", "\u{FFFD}\u{0}\u{FFFD}z").build());
+    }
+
+    #[test]
+    fn parse_bencode_rejects_invalid_input() {
+        let invalid: [&[u8]; 11] =
+            [b"", b"i01e", b"i-0e", b"ie", b"i12", b"5:abc", b"l1:a", b"di1ei2ee", b"x", b"i1ei2e", b"-1:"];
+        for bytes in invalid {
+            assert!(parse_bytes(bytes).is_err(), "{:?}", String::from_utf8_lossy(bytes));
+        }
+        assert_eq!(parse_bytes(b"i1ex").unwrap_err(), "Unexpected 'x' after bencode value at position 3");
+        let error = parse_bytes(b"99999999999999:x").unwrap_err();
+        assert_eq!(error, "Bencode string length exceeds the input at position 15");
+    }
+
+    #[test]
+    fn parse_bencode_limits_nesting_depth() {
+        let deep = format!("{}{}", "l".repeat(200_000), "e".repeat(200_000));
+        let error = parse_bytes(deep.as_bytes()).unwrap_err();
+        assert_eq!(error, "Nesting exceeds the maximum depth of 128 at position 128");
+        let (open, close) = ("l".repeat(DEFAULT_MAX_DEPTH - 1), "e".repeat(DEFAULT_MAX_DEPTH - 1));
+        assert!(parse_bytes(format!("{}d1:ai1ee{}", open, close).as_bytes()).is_ok());
+        assert!(parse_bytes(format!("{}d1:alee{}", open, close).as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,364 @@
+//! CBOR parser.
+//! Reads a single CBOR (RFC 8949) data item into a Node tree: unsigned and negative
+//! integers become Integer (or UInteger above i64::MAX), floats of any width Float, text
+//! strings Str, arrays Array, maps Dictionary, and null and undefined None. Definite and
+//! indefinite lengths are both accepted. Map keys must be text strings or integers, the
+//! latter stored as their decimal text, and byte strings are decoded as lossy UTF-8 text.
+//! Tags map where Node has an equivalent: date/time strings (0) are kept as strings, epoch
+//! times (1) become RFC 3339 UTC strings and bignums (2 and 3) integers when they fit;
+//! other tags are dropped in favour of the value they wrap. Anything after the item, or
+//! arrays, maps and tags nested deeper than DEFAULT_MAX_DEPTH, is an error.
+
+use crate::nodes::node::{Node, Numeric};
+use crate::parser::options::DEFAULT_MAX_DEPTH;
+use crate::parser::timestamp::format_timestamp;
+use indexmap::IndexMap;
+
+/// Parses a CBOR data item into a Node tree.
+///
+/// # Arguments
+/// * `bytes` - The CBOR data
+///
+/// # Returns
+/// The parsed Node or an error describing the first problem and its byte position.
+pub fn parse(bytes: &[u8]) -> Result<Node, String> {
+    let mut parser = CborParser { bytes, position: 0, depth: 0 };
+    let node = parser.value()?;
+    if parser.position < bytes.len() {
+        return Err(parser.error("Unexpected data after CBOR item"));
+    }
+    Ok(node)
+}
+
+/// The initial byte of a break, which ends an indefinite length item.
+const BREAK: u8 = 0xff;
+
+struct CborParser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    /// Number of arrays, maps and tags currently open
+    depth: usize,
+}
+
+impl<'a> CborParser<'a> {
+    fn error(&self, message: &str) -> String {
+        format!("{} at position {}", message, self.position)
+    }
+
+    /// Takes the next `count` bytes, failing if the data ends first.
+    fn take(&mut self, count: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() - self.position < count {
+            return Err(self.error("Unexpected end of CBOR data"));
+        }
+        let taken = &self.bytes[self.position..self.position + count];
+        self.position += count;
+        Ok(taken)
+    }
+
+    /// Opens an array, map or tag, failing if that exceeds the maximum depth.
+    fn enter(&mut self) -> Result<(), String> {
+        if self.depth >= DEFAULT_MAX_DEPTH {
+            return Err(self.error(&format!("Nesting exceeds the maximum depth of {}", DEFAULT_MAX_DEPTH)));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Consumes a break byte if one is next.
+    fn at_break(&mut self) -> Result<bool, String> {
+        match self.bytes.get(self.position) {
+            Some(&BREAK) => {
+                self.position += 1;
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+            None => Err(self.error("Unexpected end of CBOR data")),
+        }
+    }
+
+    /// Reads the argument that follows an initial byte; None means an indefinite length.
+    fn argument(&mut self, additional: u8) -> Result<Option<u64>, String> {
+        let size = match additional {
+            0..=23 => return Ok(Some(additional as u64)),
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            31 => return Ok(None),
+            _ => {
+                self.position -= 1;
+                return Err(self.error("Invalid CBOR additional information"));
+            }
+        };
+        Ok(Some(self.take(size)?.iter().fold(0, |value, &byte| (value << 8) | byte as u64)))
+    }
+
+    /// Reads an argument, failing for an indefinite length.
+    fn definite(&mut self, additional: u8) -> Result<u64, String> {
+        self.argument(additional)?.ok_or_else(|| self.error("Unexpected indefinite length in CBOR"))
+    }
+
+    fn length(&mut self, additional: u8) -> Result<usize, String> {
+        let length = self.definite(additional)?;
+        usize::try_from(length).map_err(|_| self.error("CBOR length out of range"))
+    }
+
+    /// Returns an integer Node for a value, failing below i64::MIN where Node has no form.
+    fn integer(&self, value: i128, start: usize) -> Result<Node, String> {
+        match i64::try_from(value) {
+            Ok(value) => Ok(Node::Number(Numeric::Integer(value))),
+            Err(_) if value > 0 => Ok(Node::Number(Numeric::UInteger(value as u64))),
+            Err(_) => Err(format!("CBOR integer out of range at position {}", start)),
+        }
+    }
+
+    fn value(&mut self) -> Result<Node, String> {
+        let start = self.position;
+        let initial = self.take(1)?[0];
+        let (major, additional) = (initial >> 5, initial & 0x1f);
+        match major {
+            0 => {
+                let value = self.definite(additional)?;
+                self.integer(value as i128, start)
+            }
+            1 => {
+                let value = self.definite(additional)?;
+                self.integer(-1 - value as i128, start)
+            }
+            2 | 3 => {
+                let bytes = self.string_bytes(major, additional)?;
+                if major == 2 {
+                    return Ok(Node::Str(String::from_utf8_lossy(&bytes).into_owned()));
+                }
+                String::from_utf8(bytes)
+                    .map(Node::Str)
+                    .map_err(|_| format!("Invalid UTF-8 in CBOR text string at position {}", start))
+            }
+            4 => {
+                self.enter()?;
+                let mut items = Vec::new();
+                match self.argument(additional)? {
+                    Some(length) => {
+                        for _ in 0..length {
+                            items.push(self.value()?);
+                        }
+                    }
+                    None => {
+                        while !self.at_break()? {
+                            items.push(self.value()?);
+                        }
+                    }
+                }
+                self.depth -= 1;
+                Ok(Node::Array(items))
+            }
+            5 => {
+                self.enter()?;
+                let mut map = IndexMap::new();
+                match self.argument(additional)? {
+                    Some(length) => {
+                        for _ in 0..length {
+                            self.entry(&mut map)?;
+                        }
+                    }
+                    None => {
+                        while !self.at_break()? {
+                            self.entry(&mut map)?;
+                        }
+                    }
+                }
+                self.depth -= 1;
+                Ok(Node::Dictionary(map))
+            }
+            6 => {
+                let tag = self.definite(additional)?;
+                if tag == 2 || tag == 3 {
+                    return self.bignum(tag, start);
+                }
+                self.enter()?;
+                let value = self.value()?;
+                self.depth -= 1;
+                tagged(tag, value).map_err(|message| format!("{} at position {}", message, start))
+            }
+            _ => self.simple(additional, start),
+        }
+    }
+
+    /// Reads the bytes of a byte or text string, joining the chunks of indefinite ones.
+    fn string_bytes(&mut self, major: u8, additional: u8) -> Result<Vec<u8>, String> {
+        if additional != 31 {
+            let length = self.length(additional)?;
+            return Ok(self.take(length)?.to_vec());
+        }
+        let mut bytes = Vec::new();
+        while !self.at_break()? {
+            let initial = self.take(1)?[0];
+            if initial >> 5 != major || initial & 0x1f == 31 {
+                self.position -= 1;
+                return Err(self.error("Invalid chunk in indefinite length CBOR string"));
+            }
+            let length = self.length(initial & 0x1f)?;
+            bytes.extend_from_slice(self.take(length)?);
+        }
+        Ok(bytes)
+    }
+
+    /// Reads the byte string of a bignum tag as an integer.
+    fn bignum(&mut self, tag: u64, start: usize) -> Result<Node, String> {
+        let initial = self.take(1)?[0];
+        if initial >> 5 != 2 {
+            return Err(format!("Invalid content for CBOR tag {} at position {}", tag, start));
+        }
+        let bytes = self.string_bytes(2, initial & 0x1f)?;
+        let digits = &bytes[bytes.iter().take_while(|&&byte| byte == 0).count()..];
+        if digits.len() > 8 {
+            return Err(format!("CBOR bignum out of range at position {}", start));
+        }
+        let value = digits.iter().fold(0, |value, &byte| (value << 8) | byte as u64) as i128;
+        self.integer(if tag == 2 { value } else { -1 - value }, start)
+    }
+
+    fn entry(&mut self, map: &mut IndexMap<String, Node>) -> Result<(), String> {
+        let start = self.position;
+        let key = match self.value()? {
+            Node::Str(key) => key,
+            Node::Number(number) if number.as_wide_integer().is_some() => {
+                number.as_wide_integer().unwrap_or_default().to_string()
+            }
+            _ => return Err(format!("CBOR map key must be a text string or integer at position {}", start)),
+        };
+        let value = self.value()?;
+        map.insert(key, value);
+        Ok(())
+    }
+
+    /// Reads a major type 7 item: a simple value or a float.
+    fn simple(&mut self, additional: u8, start: usize) -> Result<Node, String> {
+        Ok(match additional {
+            20 => Node::Boolean(false),
+            21 => Node::Boolean(true),
+            22 | 23 => Node::None,
+            25 => Node::from(half_to_f64(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))),
+            26 => Node::from(f32::from_be_bytes(self.take(4)?.try_into().unwrap()) as f64),
+            27 => Node::from(f64::from_be_bytes(self.take(8)?.try_into().unwrap())),
+            31 => return Err(format!("Unexpected CBOR break at position {}", start)),
+            _ => return Err(format!("Unsupported CBOR simple value at position {}", start)),
+        })
+    }
+}
+
+/// Maps a tagged value to a Node, keeping the value itself for tags Node has no form for.
+fn tagged(tag: u64, value: Node) -> Result<Node, String> {
+    match (tag, value) {
+        (0, value @ Node::Str(_)) => Ok(value),
+        (1, Node::Number(number)) => {
+            let (seconds, nanoseconds) = match number {
+                Numeric::Float(seconds) if seconds.is_finite() && seconds.abs() < 1e17 => {
+                    let whole = seconds.floor();
+                    (whole as i64, ((seconds - whole) * 1e9).round().min(999_999_999.0) as u32)
+                }
+                Numeric::Float(_) => return Err("CBOR epoch time out of range".to_string()),
+                number => match i64::try_from(number.as_wide_integer().unwrap_or_default()) {
+                    Ok(seconds) => (seconds, 0),
+                    Err(_) => return Err("CBOR epoch time out of range".to_string()),
+                },
+            };
+            Ok(Node::Str(format_timestamp(seconds, nanoseconds)))
+        }
+        (0 | 1, _) => Err(format!("Invalid content for CBOR tag {}", tag)),
+        (_, value) => Ok(value),
+    }
+}
+
+/// Converts an IEEE 754 half precision value to a double.
+fn half_to_f64(half: u16) -> f64 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((half >> 10) & 0x1f) as i32;
+    let fraction = (half & 0x3ff) as f64;
+    sign * match exponent {
+        0 => fraction * 2f64.powi(-24),
+        31 if fraction == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + fraction / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+    use crate::stringify::cbor::stringify;
+
+    #[test]
+    fn parse_cbor_round_trips() {
+        let node = Node::dict()
+            .key("list", vec![Node::from(-40000), Node::from(255), Node::None, Node::from(false)])
+            .key("float", 0.25)
+            .key("text", "café ".repeat(10))
+            .key("big", Node::Number(Numeric::UInteger(u64::MAX)))
+            .key("nested", Node::dict().key("min", i64::MIN).build())
+            .build();
+        let mut destination = Buffer::new();
+        stringify(&node, &mut destination).unwrap();
+        assert_eq!(parse(&destination.buffer).unwrap(), node);
+    }
+
+    #[test]
+    fn parse_cbor_decodes_rfc_examples() {
+        let cases: [(&[u8], Node); 8] = [
+            (&[0xf9, 0x3c, 0x00], Node::from(1.0)),
+            (&[0xf9, 0x00, 0x01], Node::from(5.960464477539063e-8)),
+            (&[0xfa, 0x47, 0xc3, 0x50, 0x00], Node::from(100000.0)),
+            (&[0xf7], Node::None),
+            (&[0x44, 0x01, 0x02, 0x03, 0xff], Node::from("\u{1}\u{2}\u{3}\u{FFFD}")),
+            (&[0x7f, 0x65, b's', b't', b'r', b'e', b'a', 0x64, b'm', b'i', b'n', b'g', 0xff], Node::from("streaming")),
+            (&[0x9f, 0x01, 0x82, 0x02, 0x03, 0xff], Node::from(vec![Node::from(1), Node::from(vec![2, 3])])),
+            (&[0xbf, 0x01, 0x61, b'a', 0xff], Node::dict().key("1", "a").build()),
+        ];
+        for (bytes, expected) in cases {
+            assert_eq!(parse(bytes).unwrap(), expected, "{:?}", bytes);
+        }
+        assert!(matches!(parse(&[0xf9, 0x7e, 0x00]).unwrap(), Node::Number(Numeric::Float(value)) if value.is_nan()));
+    }
+
+    #[test]
+    fn parse_cbor_maps_tags() {
+        let date = [&[0xc0, 0x74][..], b"2013-03-21T20:04:00Z"].concat();
+        assert_eq!(parse(&date).unwrap(), Node::from("2013-03-21T20:04:00Z"));
+        assert_eq!(parse(&[0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0]).unwrap(), Node::from("2013-03-21T20:04:00Z"));
+        let fractional = [0xc1, 0xfb, 0x41, 0xd4, 0x52, 0xd9, 0xec, 0x20, 0x00, 0x00];
+        assert_eq!(parse(&fractional).unwrap(), Node::from("2013-03-21T20:04:00.5Z"));
+        // Tag 55799 marks self-described CBOR and is dropped
+        assert_eq!(parse(&[0xd9, 0xd9, 0xf7, 0x01]).unwrap(), Node::from(1));
+        assert_eq!(parse(&[0xc1, 0x61, b'x']).unwrap_err(), "Invalid content for CBOR tag 1 at position 0");
+        let bignum = [0xc2, 0x49, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+        assert_eq!(parse(&bignum).unwrap(), Node::Number(Numeric::UInteger(u64::MAX)));
+        assert_eq!(parse(&[0xc3, 0x42, 0x01, 0x00]).unwrap(), Node::from(-257));
+        assert!(parse(&[0xc2, 0x49, 0x01, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+        assert!(parse(&[0x3b, 0x80, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn parse_cbor_rejects_invalid_data() {
+        assert_eq!(parse(&[0x82, 0x01]).unwrap_err(), "Unexpected end of CBOR data at position 2");
+        assert_eq!(parse(&[0x01, 0x02]).unwrap_err(), "Unexpected data after CBOR item at position 1");
+        assert_eq!(parse(&[0x61, 0xff]).unwrap_err(), "Invalid UTF-8 in CBOR text string at position 0");
+        assert_eq!(parse(&[0x1c]).unwrap_err(), "Invalid CBOR additional information at position 0");
+        assert!(parse(&[0xff]).is_err());
+        assert!(parse(&[0xa1, 0xf6, 0x01]).is_err());
+        assert!(parse(&[0x7f, 0x41, 0x00, 0xff]).is_err());
+    }
+
+    #[test]
+    fn parse_cbor_limits_nesting_depth() {
+        assert_eq!(parse(&[0x81; 200_000]).unwrap_err(), "Nesting exceeds the maximum depth of 128 at position 129");
+        assert!(parse(&[0xc6; 200_000]).is_err());
+        let mut nested = vec![0x9f; DEFAULT_MAX_DEPTH];
+        nested.extend([BREAK; DEFAULT_MAX_DEPTH]);
+        assert_eq!(parse(&nested).unwrap(), (1..DEFAULT_MAX_DEPTH).fold(Node::Array(vec![]), |node, _| {
+            Node::Array(vec![node])
+        }));
+        nested.insert(0, 0xc6);
+        assert!(parse(&nested).is_err());
+    }
+}
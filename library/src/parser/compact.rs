@@ -0,0 +1,160 @@
+//! Storage for the children of collections while they are parsed.
+//! Items gathers the items of a sequence and Entries the entries of a mapping. With the
+//! compact-collections feature both are held inline until the collection grows past
+//! INLINE_ENTRIES children, and are copied into an exactly sized Vec or IndexMap when it
+//! ends, so a typical small collection costs one allocation for a sequence (two for a
+//! mapping, its entries and their hash index) with no spare capacity instead of one per
+//! doubling. Without it children are gathered in the Vec or IndexMap itself.
+
+use crate::nodes::node::Node;
+use indexmap::IndexMap;
+
+/// Number of children a collection holds inline while it is parsed.
+#[cfg(feature = "compact-collections")]
+pub(crate) const INLINE_ENTRIES: usize = 8;
+
+/// The items of a sequence being parsed.
+#[derive(Default)]
+pub(crate) struct Items {
+    #[cfg(feature = "compact-collections")]
+    items: smallvec::SmallVec<[Node; INLINE_ENTRIES]>,
+    #[cfg(not(feature = "compact-collections"))]
+    items: Vec<Node>,
+}
+
+impl Items {
+    pub(crate) fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub(crate) fn push(&mut self, item: Node) {
+        self.items.push(item);
+    }
+
+    /// Returns the items as an array node.
+    pub(crate) fn into_node(self) -> Node {
+        #[cfg(feature = "compact-collections")]
+        let items = {
+            let mut items = Vec::with_capacity(self.items.len());
+            items.extend(self.items);
+            items
+        };
+        #[cfg(not(feature = "compact-collections"))]
+        let items = self.items;
+        Node::Array(items)
+    }
+}
+
+/// The entries of a mapping being parsed.
+#[derive(Default)]
+pub(crate) struct Entries {
+    /// Entries in the order read, while there are at most INLINE_ENTRIES of them
+    #[cfg(feature = "compact-collections")]
+    inline: smallvec::SmallVec<[(String, Node); INLINE_ENTRIES]>,
+    /// All entries, once there are more than INLINE_ENTRIES
+    map: IndexMap<String, Node>,
+}
+
+impl Entries {
+    #[cfg(feature = "compact-collections")]
+    pub(crate) fn len(&self) -> usize {
+        self.inline.len() + self.map.len()
+    }
+
+    #[cfg(not(feature = "compact-collections"))]
+    pub(crate) fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Adds an entry, replacing the value of an existing key in place as IndexMap::insert
+    /// does.
+    ///
+    /// # Returns
+    /// True if the key was already present.
+    #[cfg(feature = "compact-collections")]
+    pub(crate) fn insert(&mut self, key: String, value: Node) -> bool {
+        if self.map.is_empty() {
+            if let Some((_, existing)) = self.inline.iter_mut().find(|(existing, _)| *existing == key) {
+                *existing = value;
+                return true;
+            }
+            if self.inline.len() < INLINE_ENTRIES {
+                self.inline.push((key, value));
+                return false;
+            }
+            self.map.reserve(INLINE_ENTRIES * 2);
+            self.map.extend(self.inline.drain(..));
+        }
+        self.map.insert(key, value).is_some()
+    }
+
+    /// Adds an entry, replacing the value of an existing key in place as IndexMap::insert
+    /// does.
+    ///
+    /// # Returns
+    /// True if the key was already present.
+    #[cfg(not(feature = "compact-collections"))]
+    pub(crate) fn insert(&mut self, key: String, value: Node) -> bool {
+        self.map.insert(key, value).is_some()
+    }
+
+    /// Returns the entries as a dictionary node.
+    pub(crate) fn into_node(self) -> Node {
+        #[cfg(feature = "compact-collections")]
+        if self.map.is_empty() {
+            // Inserted one by one, as extend would grow the entries to the index's capacity
+            let mut map = IndexMap::with_capacity(self.inline.len());
+            for (key, value) in self.inline {
+                map.insert(key, value);
+            }
+            return Node::Dictionary(map);
+        }
+        Node::Dictionary(self.map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn items_keep_their_order() {
+        let mut items = Items::default();
+        for value in 0..20 {
+            items.push(Node::from(value));
+        }
+        assert_eq!(items.len(), 20);
+        assert_eq!(items.into_node(), Node::from((0..20).collect::<Vec<i64>>()));
+    }
+
+    #[test]
+    fn entries_replace_duplicate_keys_in_place() {
+        for count in [3, 20] {
+            let mut entries = Entries::default();
+            for index in 0..count {
+                assert!(!entries.insert(format!("k{}", index), Node::from(index as i64)));
+            }
+            assert!(entries.insert("k1".to_string(), Node::from("again")));
+            assert_eq!(entries.len(), count);
+            let node = entries.into_node();
+            let keys: Vec<String> = (0..count).map(|index| format!("k{}", index)).collect();
+            assert_eq!(node.as_dict().unwrap().keys().cloned().collect::<Vec<String>>(), keys);
+            assert_eq!(node["k1"], Node::from("again"));
+        }
+    }
+
+    #[cfg(feature = "compact-collections")]
+    #[test]
+    fn small_collections_are_stored_exactly_sized() {
+        for count in [1, 5, 8] {
+            let mut items = Items::default();
+            let mut entries = Entries::default();
+            for index in 0..count {
+                items.push(Node::from(index as i64));
+                entries.insert(index.to_string(), Node::None);
+            }
+            assert!(matches!(items.into_node(), Node::Array(items) if items.capacity() == count));
+            assert!(matches!(entries.into_node(), Node::Dictionary(map) if map.capacity() == count));
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! CSV parser.
+//! Reads CSV (RFC 4180) into an Array of Dictionary nodes, one per row, keyed by the names
+//! in the header row. Quoted fields may contain the delimiter, doubled quotes and line
+//! breaks; rows may end in "\n" or "\r\n", and blank lines and a leading byte order mark
+//! are skipped. Rows shorter than the header leave the remaining keys out, while longer
+//! rows, duplicate header names and unterminated quotes are errors.
+//! Fields are read as strings unless type inference is on, in which case each column is
+//! given the narrowest type all of its non-empty fields share (integer, then float, then
+//! boolean, falling back to string) and empty fields become nulls.
+
+use crate::io::traits::ISource;
+use crate::nodes::node::{Node, Numeric};
+use indexmap::IndexMap;
+
+/// Default character separating the fields of a CSV row.
+pub const DEFAULT_CSV_DELIMITER: char = ',';
+
+/// Options used to configure the CSV parser.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsvParserOptions {
+    /// Character between fields
+    pub delimiter: char,
+    /// Give each column the type all of its fields share, reading empty fields as null,
+    /// rather than reading every field as a string
+    pub infer_types: bool,
+}
+
+impl CsvParserOptions {
+    /// Creates a new CsvParserOptions instance with default settings.
+    ///
+    /// # Returns
+    /// CsvParserOptions for comma separated fields read as strings.
+    pub fn new() -> Self {
+        Self { delimiter: DEFAULT_CSV_DELIMITER, infer_types: false }
+    }
+}
+
+impl Default for CsvParserOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses comma separated CSV from a source into an Array of dictionaries of strings.
+///
+/// # Arguments
+/// * `source` - The source to read CSV from
+///
+/// # Returns
+/// The parsed Array or an error describing the first problem.
+pub fn parse(source: &mut dyn ISource) -> Result<Node, String> {
+    parse_with_options(source, &CsvParserOptions::new())
+}
+
+/// Parses CSV from a source into an Array of dictionaries using the given options.
+///
+/// # Arguments
+/// * `source` - The source to read CSV from
+/// * `options` - Delimiter and type inference settings
+///
+/// # Returns
+/// The parsed Array or an error describing the first problem.
+pub fn parse_with_options(source: &mut dyn ISource, options: &CsvParserOptions) -> Result<Node, String> {
+    if source.current() == Some('\u{feff}') {
+        source.next();
+    }
+    let mut records = Vec::new();
+    while let Some(record) = read_record(source, options.delimiter)? {
+        records.push(record);
+    }
+    let mut records = records.into_iter();
+    let Some(header) = records.next() else {
+        return Ok(Node::Array(vec![]));
+    };
+    for (index, name) in header.iter().enumerate() {
+        if header[..index].contains(name) {
+            return Err(format!("Duplicate column name '{}' in CSV header", name));
+        }
+    }
+    let rows: Vec<Vec<String>> = records.collect();
+    if let Some(index) = rows.iter().position(|row| row.len() > header.len()) {
+        return Err(format!(
+            "Row {} has {} fields but the CSV header has {}",
+            index + 1,
+            rows[index].len(),
+            header.len()
+        ));
+    }
+    let types: Vec<ColumnType> = (0..header.len())
+        .map(|column| {
+            if options.infer_types {
+                column_type(rows.iter().filter_map(|row| row.get(column)))
+            } else {
+                ColumnType::Text
+            }
+        })
+        .collect();
+    let items = rows
+        .into_iter()
+        .map(|row| {
+            let map: IndexMap<String, Node> = row
+                .into_iter()
+                .enumerate()
+                .map(|(column, field)| (header[column].clone(), convert(field, types[column], options.infer_types)))
+                .collect();
+            Node::Dictionary(map)
+        })
+        .collect();
+    Ok(Node::Array(items))
+}
+
+/// The type shared by every non-empty field of a column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum ColumnType {
+    Integer,
+    Float,
+    Boolean,
+    Text,
+}
+
+fn is_integer(text: &str) -> bool {
+    text.parse::<i64>().is_ok() || text.parse::<u64>().is_ok()
+}
+
+/// Returns true for decimal floats, leaving words such as "inf" and "nan" as strings.
+fn is_float(text: &str) -> bool {
+    text.contains(|c: char| c.is_ascii_digit())
+        && text.chars().all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | 'e' | 'E'))
+        && text.parse::<f64>().is_ok()
+}
+
+fn column_type<'a>(fields: impl Iterator<Item = &'a String>) -> ColumnType {
+    let shared = fields.filter(|field| !field.is_empty()).fold(None, |column, field| {
+        let field = match () {
+            _ if is_integer(field) => ColumnType::Integer,
+            _ if is_float(field) => ColumnType::Float,
+            _ if field == "true" || field == "false" => ColumnType::Boolean,
+            _ => ColumnType::Text,
+        };
+        Some(match (column, field) {
+            (None, field) => field,
+            (Some(column @ (ColumnType::Integer | ColumnType::Float)), ColumnType::Integer | ColumnType::Float) => {
+                column.max(field)
+            }
+            (Some(column), field) if column == field => column,
+            _ => ColumnType::Text,
+        })
+    });
+    // A column with only empty fields holds nulls whatever its type
+    shared.unwrap_or(ColumnType::Text)
+}
+
+/// Converts a field to a Node of its column's type.
+fn convert(field: String, column: ColumnType, infer_types: bool) -> Node {
+    if infer_types && field.is_empty() {
+        return Node::None;
+    }
+    match column {
+        ColumnType::Integer => match field.parse::<i64>() {
+            Ok(value) => Node::Number(Numeric::Integer(value)),
+            Err(_) => Node::Number(Numeric::UInteger(field.parse().unwrap_or_default())),
+        },
+        ColumnType::Float => Node::from(field.parse::<f64>().unwrap_or_default()),
+        ColumnType::Boolean => Node::Boolean(field == "true"),
+        ColumnType::Text => Node::Str(field),
+    }
+}
+
+/// Reads one record, skipping blank lines; None means the input has ended.
+fn read_record(source: &mut dyn ISource, delimiter: char) -> Result<Option<Vec<String>>, String> {
+    while matches!(source.current(), Some('\n' | '\r')) {
+        source.next();
+    }
+    if source.current().is_none() {
+        return Ok(None);
+    }
+    let mut fields = Vec::new();
+    loop {
+        let mut field = String::new();
+        if source.current() == Some('"') {
+            source.next();
+            loop {
+                match source.current() {
+                    Some('"') => {
+                        source.next();
+                        if source.current() != Some('"') {
+                            break;
+                        }
+                        field.push('"');
+                    }
+                    Some(c) => field.push(c),
+                    None => return Err(format!("Unterminated quoted CSV field at position {}", source.position())),
+                }
+                source.next();
+            }
+            if !matches!(source.current(), None | Some('\n' | '\r')) && source.current() != Some(delimiter) {
+                return Err(format!("Expected a delimiter after quoted CSV field at position {}", source.position()));
+            }
+        } else {
+            while let Some(c) = source.current().filter(|&c| c != delimiter && c != '\n' && c != '\r') {
+                field.push(c);
+                source.next();
+            }
+        }
+        fields.push(field);
+        match source.current() {
+            Some(c) if c == delimiter => source.next(),
+            Some('\r') => {
+                source.next();
+                if source.current() == Some('\n') {
+                    source.next();
+                }
+                return Ok(Some(fields));
+            }
+            Some(_) => {
+                source.next();
+                return Ok(Some(fields));
+            }
+            None => return Ok(Some(fields)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::sources::buffer::Buffer;
+
+    fn parse_text(text: &str, options: &CsvParserOptions) -> Result<Node, String> {
+        parse_with_options(&mut Buffer::new(text.as_bytes()), options)
+    }
+
+    #[test]
+    fn parse_csv_works() {
+        let text = "\u{feff}name,age,note\r\nAnn,31,\"Say \"\"hi\"\"\r\nthen go\"\r\n\r\nBob,,\"a, b\"\nCy\n";
+        let expected = Node::Array(vec![
+            Node::dict().key("name", "Ann").key("age", "31").key("note", "Say \"hi\"\r\nthen go").build(),
+            Node::dict().key("name", "Bob").key("age", "").key("note", "a, b").build(),
+            Node::dict().key("name", "Cy").build(),
+        ]);
+        assert_eq!(parse_text(text, &CsvParserOptions::new()).unwrap(), expected);
+        assert_eq!(parse_text("", &CsvParserOptions::new()).unwrap(), Node::Array(vec![]));
+    }
+
+    #[test]
+    fn parse_csv_infers_column_types() {
+        let options = CsvParserOptions { delimiter: ';', infer_types: true };
+        let text = "id;score;ok;code;word\n1;2;true;7;nan\n18446744073709551615;2.5;false;x;inf\n-3;;;;\n";
+        let expected = Node::Array(vec![
+            Node::dict().key("id", 1).key("score", 2.0).key("ok", true).key("code", "7").key("word", "nan").build(),
+            Node::dict()
+                .key("id", Node::Number(Numeric::UInteger(u64::MAX)))
+                .key("score", 2.5)
+                .key("ok", false)
+                .key("code", "x")
+                .key("word", "inf")
+                .build(),
+            Node::dict()
+                .key("id", -3)
+                .key("score", Node::None)
+                .key("ok", Node::None)
+                .key("code", Node::None)
+                .key("word", Node::None)
+                .build(),
+        ]);
+        assert_eq!(parse_text(text, &options).unwrap(), expected);
+    }
+
+    #[test]
+    fn parse_csv_round_trips_through_the_emitter() {
+        let node = Node::Array(vec![
+            Node::dict().key("a", 1).key("b", "x,\"y\"\nz").build(),
+            Node::dict().key("a", 2).key("b", "plain").build(),
+        ]);
+        let mut destination = crate::io::destinations::buffer::Buffer::new();
+        crate::stringify::csv::stringify(&node, &mut destination).unwrap();
+        let options = CsvParserOptions { infer_types: true, ..CsvParserOptions::new() };
+        assert_eq!(parse_text(&destination.to_string(), &options).unwrap(), node);
+    }
+
+    #[test]
+    fn parse_csv_rejects_invalid_input() {
+        let options = CsvParserOptions::new();
+        assert_eq!(parse_text("a,a\n1,2\n", &options).unwrap_err(), "Duplicate column name 'a' in CSV header");
+        assert_eq!(parse_text("a\n1\n1,2\n", &options).unwrap_err(), "Row 2 has 2 fields but the CSV header has 1");
+        assert_eq!(parse_text("a\n\"1", &options).unwrap_err(), "Unterminated quoted CSV field at position 4");
+        assert_eq!(
+            parse_text("a\n\"1\"x", &options).unwrap_err(),
+            "Expected a delimiter after quoted CSV field at position 5"
+        );
+    }
+}
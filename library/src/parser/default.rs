@@ -1,26 +1,222 @@
 //! YAML parser implementation that converts YAML text into Node structures
 //! Provides functions for parsing different YAML data types including mappings,
-//! sequences, strings, numbers, boolean and null values.
+//! sequences, strings, numbers, boolean and null values. Block mappings and sequences
+//! nest by indentation, a plain or quoted scalar may run over several lines, and an
+//! anchor alone on its line applies to the collection below it.
 
 use crate::nodes::node::Node;
 use crate::nodes::node::Numeric;
+use crate::nodes::comments::Comments;
+use crate::nodes::anchors::Anchors;
+use crate::nodes::path::join;
+use crate::nodes::shared::SharedNode;
 use std::collections::HashMap;
 use crate::io::traits::ISource;
+use crate::io::encoding::decode_bytes;
+use crate::io::sources::buffer::Buffer;
+use crate::parser::compact::{Entries, Items};
+use crate::parser::lookahead::Lookahead;
+use crate::parser::options::{ParserOptions, Progress, Schema};
+use crate::parser::report::{ParseReport, Parsed};
+use crate::parser::warning::{is_deprecated_boolean, ParseWarning, WarningKind};
+use crate::error::parse::ParseError;
 // use crate::error::messages::*;
 
-fn skip_whitespace(source: &mut dyn ISource) {
-    while let Some(c) = source.current() {
-        if !c.is_whitespace() {
-            break;
+/// Mutable state shared by the parsing functions for a single parse.
+struct Context<'a> {
+    /// Options the parse was started with
+    options: &'a mut ParserOptions,
+    /// Number of documents completed so far
+    documents: usize,
+    /// Source position at which progress was last reported
+    last_reported: usize,
+    /// Number of aliases resolved so far
+    aliases_resolved: usize,
+    /// Non-fatal issues found so far
+    warnings: Vec<ParseWarning>,
+    /// Comments attached to nodes of the document currently being parsed
+    comments: Comments,
+    /// Comments of each completed document, by document index
+    document_comments: Vec<(usize, Comments)>,
+    /// Anchors defined in the document currently being parsed
+    anchors: Anchors,
+    /// Anchors of each completed document, by document index
+    document_anchors: Vec<(usize, Anchors)>,
+    /// Nodes of the anchors defined in the current document, by anchor name
+    anchored: HashMap<String, Node>,
+    /// Number of collections currently open
+    depth: usize,
+}
+
+impl<'a> Context<'a> {
+    fn new(options: &'a mut ParserOptions) -> Self {
+        Self {
+            options,
+            documents: 0,
+            last_reported: 0,
+            aliases_resolved: 0,
+            warnings: Vec::new(),
+            comments: Comments::new(),
+            document_comments: Vec::new(),
+            anchors: Anchors::new(),
+            document_anchors: Vec::new(),
+            anchored: HashMap::new(),
+            depth: 0,
         }
-        source.next();
+    }
+
+    /// Opens a nested collection, failing if that exceeds the maximum depth.
+    fn enter(&mut self) -> Result<(), ParseError> {
+        self.depth += 1;
+        if self.depth > self.options.max_depth {
+            trace_error!(depth = self.depth, "maximum depth exceeded");
+            return Err(ParseError::Syntax(format!("Nesting exceeds the maximum depth of {}", self.options.max_depth)));
+        }
+        Ok(())
+    }
+
+    /// Closes a nested collection opened with enter.
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Sends a progress update if a callback is attached and enough bytes have been consumed
+    /// since the last update (or unconditionally if forced).
+    fn report_progress(&mut self, source: &mut dyn ISource, force: bool) {
+        if self.options.progress.is_none() {
+            return;
+        }
+        let bytes_read = source.position();
+        if !force && bytes_read - self.last_reported.min(bytes_read) < self.options.progress_interval {
+            return;
+        }
+        self.last_reported = bytes_read;
+        let progress = Progress { bytes_read, total_bytes: source.length(), documents: self.documents };
+        if let Some(callback) = self.options.progress.as_mut() {
+            callback(&progress);
+        }
+    }
+
+    /// Returns a Cancelled error if the parse has been cancelled or its deadline has passed.
+    fn check_cancelled(&self) -> Result<(), ParseError> {
+        if self.options.is_cancelled() {
+            trace_error!(documents = self.documents, "parse cancelled");
+            Err(ParseError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Resolves a scalar value using the configured schema and numeric policy.
+    fn resolve_scalar(&self, value: &str) -> Node {
+        match parse_scalar_with_schema(value, self.options.schema) {
+            Node::Number(number) => Node::Number(number.with_policy(self.options.numeric_policy)),
+            node => node,
+        }
+    }
+
+    /// Records a non-fatal warning.
+    fn warn(&mut self, kind: WarningKind, message: String, position: usize) {
+        trace_event!(kind = ?kind, position, message = message.as_str(), "warning");
+        self.warnings.push(ParseWarning { kind, message, position });
+    }
+
+    /// Checks a raw (untrimmed) scalar value for issues worth warning about.
+    fn check_value(&mut self, raw: &str, position: usize) {
+        let value = raw.trim();
+        if raw.trim_end_matches('\r').ends_with('\t') {
+            self.warn(WarningKind::TrailingTab, format!("Trailing tab after value: {}", value), position);
+        }
+        if is_deprecated_boolean(value) {
+            self.warn(
+                WarningKind::DeprecatedBoolean,
+                format!("YAML 1.1 boolean '{}' is parsed as a string", value),
+                position,
+            );
+        }
+    }
+
+    /// Moves the comments and anchors gathered for the current document under its document
+    /// index. Anchors are scoped to a document, so aliases cannot refer to earlier documents.
+    fn finish_document_metadata(&mut self, index: usize) {
+        let comments = std::mem::take(&mut self.comments);
+        if !comments.is_empty() {
+            self.document_comments.push((index, comments));
+        }
+        let anchors = std::mem::take(&mut self.anchors);
+        if !anchors.is_empty() {
+            self.document_anchors.push((index, anchors));
+        }
+        self.anchored.clear();
+    }
+
+    /// Returns all gathered comments; paths are prefixed with the document index when
+    /// the source held more than one document.
+    fn take_comments(&mut self, multiple_documents: bool) -> Comments {
+        let mut all = Comments::new();
+        for (index, comments) in self.document_comments.drain(..) {
+            if multiple_documents {
+                all.extend(comments.prefixed(&index.to_string()));
+            } else {
+                all.extend(comments);
+            }
+        }
+        all
+    }
+
+    /// Returns all defined anchors; paths are prefixed with the document index when the
+    /// source held more than one document.
+    fn take_anchors(&mut self, multiple_documents: bool) -> Anchors {
+        let mut all = Anchors::new();
+        for (index, anchors) in self.document_anchors.drain(..) {
+            if multiple_documents {
+                all.extend(anchors.prefixed(&index.to_string()));
+            } else {
+                all.extend(anchors);
+            }
+        }
+        all
+    }
+
+    /// Records the node defined with an anchor so later aliases can refer to it.
+    fn define_anchor(&mut self, name: &str, path: &str, node: &Node, position: usize) {
+        trace_event!(anchor = name, path, "anchor defined");
+        if self.anchored.insert(name.to_string(), node.clone()).is_some() {
+            self.warn(WarningKind::DuplicateAnchor, format!("Duplicate anchor: {}", name), position);
+        }
+        self.anchors.define(path, name);
+    }
+
+    /// Returns a copy of the node an alias refers to, recording the alias at its path.
+    fn resolve_alias(&mut self, name: &str, path: &str) -> Result<Node, ParseError> {
+        match self.anchored.get(name) {
+            Some(node) => {
+                trace_event!(alias = name, "alias resolved");
+                self.aliases_resolved += 1;
+                if let Some(target) = self.anchors.path_of(name).map(str::to_string) {
+                    self.anchors.alias(path, &target);
+                }
+                Ok(node.clone())
+            }
+            None => {
+                trace_error!(alias = name, "undefined alias");
+                Err(ParseError::Syntax(format!("Undefined alias: {}", name)))
+            }
+        }
+    }
+
+    /// Records a completed document and reports progress.
+    fn document_completed(&mut self, source: &mut dyn ISource) {
+        self.documents += 1;
+        trace_event!(documents = self.documents, position = source.position(), "document completed");
+        self.report_progress(source, true);
     }
 }
 
-fn parse_scalar(value: &str) -> Node {
+pub(crate) fn parse_scalar(value: &str) -> Node {
     // Check if the value is a comment (starts with #)
-    if value.starts_with('#') {
-        Node::Comment(value[1..].trim().to_string())
+    if let Some(comment) = value.strip_prefix('#') {
+        Node::Comment(comment.trim().to_string())
     } else if value == "null" || value == "~" {
         Node::None
     } else if value == "true" {
@@ -29,142 +225,1106 @@ fn parse_scalar(value: &str) -> Node {
         Node::Boolean(false)
     } else if let Ok(i) = value.parse::<i64>() {
         Node::Number(Numeric::Integer(i))
+    } else if let Ok(u) = value.parse::<u64>() {
+        Node::Number(Numeric::UInteger(u))
     } else if let Ok(f) = value.parse::<f64>() {
         Node::Number(Numeric::Float(f))
+    } else if let Some(f) = special_float(value) {
+        Node::Number(Numeric::Float(f))
     } else {
         Node::Str(value.to_string())
     }
 }
 
-fn parse_sequence(source: &mut dyn ISource) -> Result<Node, String> {
-    let mut items = Vec::new();
-    while let Some(c) = source.current() {
-        if c == '#' {
-            // Parse comment
-            source.next();
-            let mut comment = String::new();
-            while let Some(c) = source.current() {
-                if c == '\n' { break; }
-                comment.push(c);
-                source.next();
+/// Resolves the YAML forms of infinity and not-a-number (".inf", "-.inf", ".nan" in any
+/// case, with an optional '+' before ".inf").
+pub(crate) fn special_float(value: &str) -> Option<f64> {
+    match value.to_ascii_lowercase().as_str() {
+        ".inf" | "+.inf" => Some(f64::INFINITY),
+        "-.inf" => Some(f64::NEG_INFINITY),
+        ".nan" => Some(f64::NAN),
+        _ => None,
+    }
+}
+
+/// Returns true if a scalar is a JSON number (no leading '+', '.', or named values like inf/nan).
+fn is_json_number(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    digits.starts_with(|c: char| c.is_ascii_digit())
+        && !digits.ends_with('.')
+        && value.parse::<f64>().is_ok()
+}
+
+/// Decodes the character whose code point is given by the first digits hex digits of text.
+fn decode_hex(text: &str, digits: usize) -> Option<char> {
+    let hex = text.get(..digits).filter(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()))?;
+    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+}
+
+/// Folds the line break a quoted scalar's text has reached, as YAML reads them: blanks
+/// around it are dropped, and it reads as a space or, when blank lines follow it, as one
+/// line break for each of them. Blanks written as escapes (before kept) are not dropped.
+fn fold_quoted_break(result: &mut String, kept: usize, chars: &mut std::str::Chars) {
+    let end = result.trim_end_matches([' ', '\t', '\r']).len().max(kept);
+    result.truncate(end);
+    let mut blank_lines = 0;
+    loop {
+        let rest = chars.as_str().trim_start_matches([' ', '\t', '\r']);
+        match rest.strip_prefix('\n') {
+            Some(after) => {
+                blank_lines += 1;
+                *chars = after.chars();
             }
-            items.push(Node::Comment(comment.trim().to_string()));
-        } else if c == '-' {
-            source.next();
-            skip_whitespace(source);
-            let mut value = String::new();
-            while let Some(c) = source.current() {
-                if c == '\n' || c == '#' { break; }
+            None => {
+                *chars = rest.chars();
+                break;
+            }
+        }
+    }
+    match blank_lines {
+        0 => result.push(' '),
+        _ => result.extend(std::iter::repeat_n('\n', blank_lines)),
+    }
+}
+
+/// Removes the quotes from a single or double quoted scalar, processing escapes and
+/// folding the line breaks of one written across several lines.
+/// Returns None if the value is not quoted.
+pub(crate) fn unquote(value: &str) -> Option<String> {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        let mut result = String::new();
+        let mut kept = 0;
+        let mut chars = value[1..value.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            if c == '\n' {
+                fold_quoted_break(&mut result, kept, &mut chars);
+                continue;
+            }
+            if c != '\\' {
+                result.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some('r') => result.push('\r'),
+                Some('0') => result.push('\0'),
+                Some('a') => result.push('\x07'),
+                Some('b') => result.push('\x08'),
+                Some('e') => result.push('\x1B'),
+                Some('f') => result.push('\x0C'),
+                Some('v') => result.push('\x0B'),
+                Some('N') => result.push('\u{85}'),
+                Some('_') => result.push('\u{A0}'),
+                Some('L') => result.push('\u{2028}'),
+                Some('P') => result.push('\u{2029}'),
+                Some(kind @ ('x' | 'u' | 'U')) => {
+                    let digits = match kind {
+                        'x' => 2,
+                        'u' => 4,
+                        _ => 8,
+                    };
+                    match decode_hex(chars.as_str(), digits) {
+                        Some(decoded) => {
+                            result.push(decoded);
+                            chars.nth(digits - 1);
+                        }
+                        None => result.push(kind),
+                    }
+                }
+                // An escaped line break joins the lines without a space
+                Some('\r' | '\n') => chars = chars.as_str().trim_start_matches(['\n', ' ', '\t']).chars(),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+            kept = result.len();
+        }
+        Some(result)
+    } else if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        let mut result = String::new();
+        let mut chars = value[1..value.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\n' => fold_quoted_break(&mut result, 0, &mut chars),
+                '\'' if chars.as_str().starts_with('\'') => {
+                    chars.next();
+                    result.push('\'');
+                }
+                c => result.push(c),
+            }
+        }
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Resolves a scalar using the given schema; quoted scalars are always strings.
+fn parse_scalar_with_schema(value: &str, schema: Schema) -> Node {
+    if let Some(text) = unquote(value) {
+        return Node::Str(text);
+    }
+    match schema {
+        Schema::Failsafe => Node::Str(value.to_string()),
+        Schema::Json => match value {
+            "null" => Node::None,
+            "true" => Node::Boolean(true),
+            "false" => Node::Boolean(false),
+            _ if is_json_number(value) => parse_scalar(value),
+            _ => Node::Str(value.to_string()),
+        },
+        Schema::Core => parse_scalar(value),
+    }
+}
+
+/// Resolves a mapping key using the failsafe schema: keys are kept verbatim as strings
+/// (so "1.10", "on" and "null" stay as written), with quoted keys unquoted.
+pub(crate) fn resolve_key(key: &str) -> String {
+    let key = key.trim();
+    unquote(key).unwrap_or_else(|| key.to_string())
+}
+
+/// Reads a raw scalar value up to the end of the line or the start of a comment.
+/// A '#' only starts a comment outside quotes and when preceded by whitespace.
+pub(crate) fn read_value(source: &mut dyn ISource) -> String {
+    let mut value = String::new();
+    let mut quote: Option<char> = None;
+    while let Some(c) = source.current() {
+        if c == '\n' {
+            break;
+        }
+        match quote {
+            None if c == '#' && (value.is_empty() || value.ends_with(char::is_whitespace)) => break,
+            None if value.is_empty() && (c == '"' || c == '\'') => quote = Some(c),
+            Some('"') if c == '\\' => {
                 value.push(c);
                 source.next();
+                if let Some(escaped) = source.current() {
+                    value.push(escaped);
+                    source.next();
+                }
+                continue;
             }
-            items.push(parse_scalar(value.trim()));
-        } else {
+            Some(q) if c == q && !value.is_empty() => quote = None,
+            _ => {}
+        }
+        value.push(c);
+        source.next();
+    }
+    value
+}
+
+/// Reads a trailing comment if the source is positioned at '#', returning its trimmed text.
+pub(crate) fn read_trailing_comment(source: &mut dyn ISource) -> Option<String> {
+    if source.current() != Some('#') {
+        return None;
+    }
+    source.next();
+    let mut comment = String::new();
+    while let Some(c) = source.current() {
+        if c == '\n' { break; }
+        comment.push(c);
+        source.next();
+    }
+    Some(comment.trim().to_string())
+}
+
+/// Reads an anchor or alias name after its '&' or '*' indicator. The name runs up to
+/// whitespace or a flow indicator.
+pub(crate) fn read_anchor_name(source: &mut dyn ISource) -> Result<String, ParseError> {
+    let indicator = source.current().unwrap_or('&');
+    source.next();
+    let mut name = String::new();
+    while let Some(c) = source.current() {
+        if c.is_whitespace() || matches!(c, ',' | '[' | ']' | '{' | '}') {
             break;
         }
+        name.push(c);
+        source.next();
+    }
+    if name.is_empty() {
+        return Err(ParseError::Syntax(format!("Missing name after '{}'", indicator)));
+    }
+    Ok(name)
+}
+
+/// Skips spaces and tabs (but not line breaks).
+pub(crate) fn skip_blanks(source: &mut dyn ISource) {
+    while let Some(' ' | '\t') = source.current() {
         source.next();
     }
-    Ok(Node::Array(items))
 }
 
-fn parse_mapping(source: &mut dyn ISource) -> Result<Node, String> {
-    let mut map = HashMap::new();
+/// Skips whitespace, line breaks and comments inside a flow collection.
+pub(crate) fn skip_flow_whitespace(source: &mut dyn ISource) {
     while let Some(c) = source.current() {
         if c == '#' {
-            // Parse comment
+            while source.current().is_some_and(|c| c != '\n') {
+                source.next();
+            }
+        } else if c.is_whitespace() {
             source.next();
-            let mut comment = String::new();
-            while let Some(c) = source.current() {
-                if c == '\n' { break; }
-                comment.push(c);
+        } else {
+            break;
+        }
+    }
+}
+
+/// Reads a single or double quoted scalar including its quotes.
+pub(crate) fn read_quoted(source: &mut dyn ISource) -> Result<String, ParseError> {
+    let quote = source.current().unwrap_or('"');
+    let mut text = String::from(quote);
+    source.next();
+    while let Some(c) = source.current() {
+        text.push(c);
+        source.next();
+        if c == '\\' && quote == '"' {
+            if let Some(escaped) = source.current() {
+                text.push(escaped);
                 source.next();
             }
-            // Store comment with a special key
-            map.insert(format!("__comment_{}", map.len()), Node::Comment(comment.trim().to_string()));
-        } else if c.is_alphanumeric() {
-            let mut key = String::new();
-            while let Some(c) = source.current() {
-                if c == ':' { break; }
-                key.push(c);
+        } else if c == quote {
+            if quote == '\'' && source.current() == Some('\'') {
+                text.push('\'');
                 source.next();
+            } else {
+                return Ok(text);
             }
-            source.next(); // Skip ':'
-            skip_whitespace(source);
+        }
+    }
+    Err(ParseError::Syntax("Unterminated quoted scalar".to_string()))
+}
 
-            let mut value = String::new();
-            while let Some(c) = source.current() {
-                if c == '\n' || c == '#' { break; }
-                value.push(c);
+/// Reads a plain scalar inside a flow collection, stopping at a flow indicator, a line
+/// break or (for keys) a ':' followed by whitespace or a flow indicator.
+pub(crate) fn read_flow_plain(source: &mut dyn ISource, is_key: bool) -> String {
+    let mut text = String::new();
+    while let Some(c) = source.current() {
+        if matches!(c, ',' | '[' | ']' | '{' | '}' | '\n') || (c == '#' && text.ends_with(char::is_whitespace)) {
+            break;
+        }
+        if c == ':' && is_key {
+            source.next();
+            let next = source.current();
+            source.backup();
+            if next.is_none_or(|n| n.is_whitespace() || matches!(n, ',' | ']' | '}')) {
+                break;
+            }
+        }
+        text.push(c);
+        source.next();
+    }
+    text.trim().to_string()
+}
+
+/// Parses a node inside a flow collection, along with any anchor or alias.
+fn parse_flow_node(source: &mut dyn ISource, context: &mut Context, path: &str) -> Result<Node, ParseError> {
+    skip_flow_whitespace(source);
+    match source.current() {
+        Some('&') => {
+            let position = source.position();
+            let name = read_anchor_name(source)?;
+            skip_flow_whitespace(source);
+            let node = match source.current() {
+                Some(',' | ']' | '}') => Node::None,
+                Some('&') => return Err(ParseError::Syntax(format!("Node anchored as {} has a second anchor", name))),
+                _ => parse_flow_node(source, context, path)?,
+            };
+            context.define_anchor(&name, path, &node, position);
+            Ok(node)
+        }
+        Some('*') => {
+            let name = read_anchor_name(source)?;
+            context.resolve_alias(&name, path)
+        }
+        Some('[') => parse_flow_sequence(source, context, path),
+        Some('{') => parse_flow_mapping(source, context, path),
+        Some('"' | '\'') => {
+            let text = read_quoted(source)?;
+            Ok(Node::Str(unquote(&text).unwrap_or(text)))
+        }
+        Some(_) => Ok(context.resolve_scalar(&read_flow_plain(source, false))),
+        None => Err(ParseError::Syntax("Unterminated flow collection".to_string())),
+    }
+}
+
+/// Parses a flow sequence ("[a, b, c]") starting at its opening bracket.
+fn parse_flow_sequence(source: &mut dyn ISource, context: &mut Context, path: &str) -> Result<Node, ParseError> {
+    trace_event!(position = source.position(), "flow sequence start");
+    context.enter()?;
+    source.next(); // Skip '['
+    let mut items = Items::default();
+    loop {
+        context.check_cancelled()?;
+        skip_flow_whitespace(source);
+        match source.current() {
+            Some(']') => {
                 source.next();
+                break;
             }
+            Some(',') => return Err(ParseError::Syntax("Unexpected ',' in flow sequence".to_string())),
+            _ => items.push(parse_flow_node(source, context, &join(path, &items.len().to_string()))?),
+        }
+        skip_flow_whitespace(source);
+        match source.current() {
+            Some(',') => source.next(),
+            Some(']') => {}
+            Some(c) => return Err(ParseError::Syntax(format!("Expected ',' or ']' in flow sequence but found: {}", c))),
+            None => return Err(ParseError::Syntax("Unterminated flow sequence".to_string())),
+        }
+    }
+    context.leave();
+    Ok(items.into_node())
+}
 
-            map.insert(key.trim().to_string(), parse_scalar(value.trim()));
+/// Parses a flow mapping ("{a: 1, b: 2}") starting at its opening brace.
+fn parse_flow_mapping(source: &mut dyn ISource, context: &mut Context, path: &str) -> Result<Node, ParseError> {
+    trace_event!(position = source.position(), "flow mapping start");
+    context.enter()?;
+    source.next(); // Skip '{'
+    let mut map = Entries::default();
+    loop {
+        context.check_cancelled()?;
+        skip_flow_whitespace(source);
+        let key = match source.current() {
+            Some('}') => {
+                source.next();
+                break;
+            }
+            Some('"' | '\'') => resolve_key(&read_quoted(source)?),
+            Some(',') => return Err(ParseError::Syntax("Unexpected ',' in flow mapping".to_string())),
+            Some(_) => read_flow_plain(source, true),
+            None => return Err(ParseError::Syntax("Unterminated flow mapping".to_string())),
+        };
+        skip_flow_whitespace(source);
+        let value = if source.current() == Some(':') {
+            source.next();
+            skip_flow_whitespace(source);
+            match source.current() {
+                Some(',' | '}') => Node::None,
+                _ => parse_flow_node(source, context, &join(path, &key))?,
+            }
+        } else {
+            Node::None
+        };
+        if map.insert(key.clone(), value) {
+            context.warn(WarningKind::DuplicateKey, format!("Duplicate key: {}", key), source.position());
+        }
+        skip_flow_whitespace(source);
+        match source.current() {
+            Some(',') => source.next(),
+            Some('}') => {}
+            Some(c) => return Err(ParseError::Syntax(format!("Expected ',' or '}}' in flow mapping but found: {}", c))),
+            None => return Err(ParseError::Syntax("Unterminated flow mapping".to_string())),
         }
-        source.next();
     }
-    Ok(Node::Dictionary(map))
+    context.leave();
+    Ok(map.into_node())
 }
 
-pub fn parse(source: &mut dyn ISource) -> Result<Node, String> {
-    skip_whitespace(source);
+/// Returns true for the end of the input, a line break or a blank, as must follow the ':'
+/// of a mapping key, the '-' of a sequence entry and a document marker.
+fn is_break_or_blank(c: Option<char>) -> bool {
+    matches!(c, None | Some(' ' | '\t' | '\r' | '\n'))
+}
 
-    let mut documents = Vec::new();
-    let mut current_doc = None;
+/// Returns true if the source is at the end of a line, the end of the input or a comment.
+pub(crate) fn at_line_end(source: &mut dyn ISource) -> bool {
+    matches!(source.current(), None | Some('\r' | '\n' | '#'))
+}
 
+/// Moves past the rest of the current line and its line break.
+pub(crate) fn skip_line(source: &mut dyn ISource) {
     while let Some(c) = source.current() {
-        match c {
-            '-' if documents.is_empty() || current_doc.is_none() => {
-                current_doc = Some(parse_sequence(source)?);
+        source.next();
+        if c == '\n' {
+            break;
+        }
+    }
+}
+
+/// Reads the spaces indenting the line the source is at the start of, skipping blank
+/// lines, and leaves the source at the first character of the next line holding content.
+/// Returns that line's indentation (None at the end of the input) and the number of
+/// blank lines skipped.
+pub(crate) fn line_indent(source: &mut dyn ISource) -> (Option<usize>, usize) {
+    let mut blank_lines = 0;
+    loop {
+        let mut indent = 0;
+        while source.current() == Some(' ') {
+            indent += 1;
+            source.next();
+        }
+        skip_blanks(source);
+        match source.current() {
+            None => return (None, blank_lines),
+            Some('\r' | '\n') => {
+                skip_line(source);
+                blank_lines += 1;
+            }
+            Some(_) => return (Some(indent), blank_lines),
+        }
+    }
+}
+
+/// Moves to the next line holding content and returns its indentation (see line_indent).
+pub(crate) fn next_line(source: &mut dyn ISource) -> Option<usize> {
+    skip_line(source);
+    line_indent(source).0
+}
+
+/// Returns true if the source is at the '-' indicator of a block sequence entry.
+pub(crate) fn at_entry(source: &mut Lookahead) -> bool {
+    source.current() == Some('-') && is_break_or_blank(source.peek(1))
+}
+
+/// Returns true if the source (at the start of a line) is at a "---" or "..." document
+/// marker.
+pub(crate) fn at_document_marker(source: &mut Lookahead) -> bool {
+    let marker = source.current();
+    matches!(marker, Some('-' | '.'))
+        && source.peek(1) == marker
+        && source.peek(2) == marker
+        && is_break_or_blank(source.peek(3))
+}
+
+/// Returns true if the source is at a block mapping key: a plain or quoted scalar on the
+/// current line followed by ':' and a blank or the end of the line.
+pub(crate) fn at_mapping_key(source: &mut Lookahead) -> bool {
+    let mut offset = 0;
+    if let Some(quote @ ('"' | '\'')) = source.current() {
+        offset = 1;
+        loop {
+            match source.peek(offset) {
+                None | Some('\n') => return false,
+                Some('\\') if quote == '"' => offset += 2,
+                Some('\'') if quote == '\'' && source.peek(offset + 1) == Some('\'') => offset += 2,
+                Some(c) if c == quote => break,
+                Some(_) => offset += 1,
             }
-            '#' => {
+        }
+        offset += 1;
+        while let Some(' ' | '\t') = source.peek(offset) {
+            offset += 1;
+        }
+        return source.peek(offset) == Some(':') && is_break_or_blank(source.peek(offset + 1));
+    }
+    if matches!(source.current(), None | Some('[' | '{' | '&' | '*' | '!' | '|' | '>' | '#')) {
+        return false;
+    }
+    let mut previous = ' ';
+    loop {
+        match source.peek(offset) {
+            None | Some('\r' | '\n') => return false,
+            Some('#') if previous == ' ' || previous == '\t' => return false,
+            Some(':') if is_break_or_blank(source.peek(offset + 1)) => return true,
+            Some(c) => {
+                previous = c;
+                offset += 1;
+            }
+        }
+    }
+}
+
+/// Reads a block mapping key and the ':' after it; at_mapping_key must hold.
+pub(crate) fn read_block_key(source: &mut Lookahead) -> Result<String, ParseError> {
+    let raw = if let Some('"' | '\'') = source.current() {
+        read_quoted(source)?
+    } else {
+        let mut key = String::new();
+        while let Some(c) = source.current() {
+            if c == ':' && is_break_or_blank(source.peek(1)) {
+                break;
+            }
+            key.push(c);
+            source.next();
+        }
+        key
+    };
+    skip_blanks(source);
+    source.next(); // Skip ':'
+    Ok(resolve_key(&raw))
+}
+
+/// Returns an error for a line indented further than the block collection it is in allows.
+pub(crate) fn unexpected_indentation(source: &mut dyn ISource) -> ParseError {
+    let line = read_value(source);
+    trace_error!(position = source.position(), "unexpected indentation");
+    ParseError::Syntax(format!("Unexpected indentation: {}", line.trim()))
+}
+
+/// Parses a block node starting at the current character, in column indent, along with any
+/// comment lines before it. parent is the indentation of the collection holding the node
+/// (None for the root of a document); a line indented no further than it ends the node.
+/// Returns the node and the indentation of the line after it (see line_indent).
+fn parse_block_node(
+    source: &mut Lookahead,
+    context: &mut Context,
+    path: &str,
+    indent: usize,
+    parent: Option<usize>,
+) -> Result<(Node, Option<usize>), ParseError> {
+    let mut indent = indent;
+    let mut comments = Vec::new();
+    while source.current() == Some('#') {
+        comments.push(read_trailing_comment(source).unwrap_or_default());
+        match next_line(source) {
+            Some(next) if parent.is_none_or(|parent| next > parent) => indent = next,
+            next => {
+                comments.iter().for_each(|text| context.comments.add_leading(path, text));
+                return Ok((Node::None, next));
+            }
+        }
+    }
+    if at_entry(source) {
+        parse_block_sequence(source, context, path, indent, comments)
+    } else if at_mapping_key(source) {
+        parse_block_mapping(source, context, path, indent, comments)
+    } else {
+        comments.iter().for_each(|text| context.comments.add_leading(path, text));
+        parse_inline_node(source, context, path, parent)
+    }
+}
+
+/// Parses a node that starts on the line after the current one and is indented further
+/// than parent, or (for a mapping value, when compact is set) a sequence at the mapping's
+/// own indentation. Any other line leaves the node null.
+fn parse_following(
+    source: &mut Lookahead,
+    context: &mut Context,
+    path: &str,
+    parent: Option<usize>,
+    compact: bool,
+) -> Result<(Node, Option<usize>), ParseError> {
+    match next_line(source) {
+        Some(next) if parent.is_none_or(|parent| next > parent) && !(next == 0 && at_document_marker(source)) => {
+            parse_block_node(source, context, path, next, parent)
+        }
+        Some(next) if compact && Some(next) == parent && at_entry(source) => {
+            parse_block_sequence(source, context, path, next, Vec::new())
+        }
+        next => Ok((Node::None, next)),
+    }
+}
+
+/// Parses a block mapping whose first key is at the current character, in column indent.
+/// Comment lines between its entries are kept as Comment values under "__comment_N" keys,
+/// starting with any comment lines read before it.
+fn parse_block_mapping(
+    source: &mut Lookahead,
+    context: &mut Context,
+    path: &str,
+    indent: usize,
+    comments: Vec<String>,
+) -> Result<(Node, Option<usize>), ParseError> {
+    trace_event!(position = source.position(), indent, "mapping start");
+    context.enter()?;
+    let mut map = Entries::default();
+    for text in comments {
+        map.insert(format!("__comment_{}", map.len()), Node::Comment(text));
+    }
+    let next = loop {
+        context.check_cancelled()?;
+        let next = if source.current() == Some('#') {
+            let text = read_trailing_comment(source).unwrap_or_default();
+            trace_event!(token = "comment", position = source.position(), "mapping comment");
+            map.insert(format!("__comment_{}", map.len()), Node::Comment(text));
+            next_line(source)
+        } else if at_mapping_key(source) {
+            let key = read_block_key(source)?;
+            #[cfg(feature = "unicode-normalization")]
+            let key = if context.options.normalize_keys {
+                crate::nodes::unicode::normalize_key(&key).into_owned()
+            } else {
+                key
+            };
+            trace_event!(token = "key", key = key.as_str(), "mapping entry");
+            let (value, next) = parse_mapping_value(source, context, &join(path, &key), indent)?;
+            if map.insert(key.clone(), value) {
+                context.warn(WarningKind::DuplicateKey, format!("Duplicate key: {}", key), source.position());
+            }
+            next
+        } else {
+            let line = read_value(source);
+            return Err(ParseError::Syntax(format!("Expected ':' after mapping key: {}", line.trim())));
+        };
+        context.report_progress(source, false);
+        match next {
+            Some(next) if next == indent && at_entry(source) => {
+                return Err(ParseError::Syntax("Expected a mapping key but found a sequence entry".to_string()));
+            }
+            Some(next) if next == indent && !(next == 0 && at_document_marker(source)) => {}
+            Some(next) if next > indent && source.current() == Some('#') => {}
+            Some(next) if next > indent => return Err(unexpected_indentation(source)),
+            next => break next,
+        }
+    };
+    context.leave();
+    trace_event!(entries = map.len(), position = source.position(), "mapping end");
+    Ok((map.into_node(), next))
+}
+
+/// Parses the value of a block mapping entry after its ':', either on the rest of the line
+/// or, when the line ends first, on the lines below (where a sequence may start at the
+/// mapping's own indentation). A value missing altogether is null.
+fn parse_mapping_value(
+    source: &mut Lookahead,
+    context: &mut Context,
+    path: &str,
+    indent: usize,
+) -> Result<(Node, Option<usize>), ParseError> {
+    skip_blanks(source);
+    if !at_line_end(source) {
+        return parse_inline_node(source, context, path, Some(indent));
+    }
+    if let Some(comment) = read_trailing_comment(source) {
+        context.comments.set_trailing(path, &comment);
+    }
+    parse_following(source, context, path, Some(indent), true)
+}
+
+/// Parses a block sequence whose first '-' indicator is at the current character, in
+/// column indent. Comment lines between its entries are kept as Comment items, starting
+/// with any comment lines read before it.
+fn parse_block_sequence(
+    source: &mut Lookahead,
+    context: &mut Context,
+    path: &str,
+    indent: usize,
+    comments: Vec<String>,
+) -> Result<(Node, Option<usize>), ParseError> {
+    trace_event!(position = source.position(), indent, "sequence start");
+    context.enter()?;
+    let mut items = Items::default();
+    comments.into_iter().for_each(|text| items.push(Node::Comment(text)));
+    let next = loop {
+        context.check_cancelled()?;
+        let next = if source.current() == Some('#') {
+            let text = read_trailing_comment(source).unwrap_or_default();
+            trace_event!(token = "comment", position = source.position(), "sequence comment");
+            items.push(Node::Comment(text));
+            next_line(source)
+        } else {
+            source.next(); // Skip '-'
+            let mut column = indent + 1;
+            while let Some(' ' | '\t') = source.current() {
+                column += 1;
                 source.next();
-                let mut comment = String::new();
-                while let Some(c) = source.current() {
-                    if c == '\n' { break; }
-                    comment.push(c);
-                    source.next();
+            }
+            trace_event!(token = "entry", index = items.len(), "sequence entry");
+            let item_path = join(path, &items.len().to_string());
+            let (item, next) = if at_line_end(source) {
+                if let Some(comment) = read_trailing_comment(source) {
+                    context.comments.set_trailing(&item_path, &comment);
                 }
-                if let Some(doc) = current_doc {
-                    documents.push(doc);
+                parse_following(source, context, &item_path, Some(indent), false)?
+            } else {
+                parse_block_node(source, context, &item_path, column, Some(indent))?
+            };
+            items.push(item);
+            next
+        };
+        context.report_progress(source, false);
+        match next {
+            Some(next) if next == indent && (at_entry(source) || source.current() == Some('#')) => {}
+            Some(next) if next > indent && source.current() == Some('#') => {}
+            Some(next) if next > indent => return Err(unexpected_indentation(source)),
+            next => break next,
+        }
+    };
+    context.leave();
+    trace_event!(entries = items.len(), position = source.position(), "sequence end");
+    Ok((items.into_node(), next))
+}
+
+/// Parses a node that starts part way along a line: an anchored node, an alias, a flow
+/// collection, a block scalar or a scalar. A plain scalar continues onto following lines indented further
+/// than parent. Any trailing comment is recorded against the given path.
+fn parse_inline_node(
+    source: &mut Lookahead,
+    context: &mut Context,
+    path: &str,
+    parent: Option<usize>,
+) -> Result<(Node, Option<usize>), ParseError> {
+    let node = match source.current() {
+        Some('&') => {
+            let position = source.position();
+            let name = read_anchor_name(source)?;
+            skip_blanks(source);
+            // An anchor alone on its line belongs to the collection on the lines below
+            let (node, next) = if at_line_end(source) {
+                if let Some(comment) = read_trailing_comment(source) {
+                    context.comments.set_trailing(path, &comment);
                 }
-                current_doc = Some(Node::Comment(comment.trim().to_string()));
+                parse_following(source, context, path, parent, false)?
+            } else if source.current() == Some('&') {
+                return Err(ParseError::Syntax(format!("Node anchored as {} has a second anchor", name)));
+            } else {
+                parse_inline_node(source, context, path, parent)?
+            };
+            context.define_anchor(&name, path, &node, position);
+            return Ok((node, next));
+        }
+        Some('*' | '[' | '{') => {
+            let node = parse_flow_node(source, context, path)?;
+            skip_blanks(source);
+            if let Some(c) = source.current().filter(|_| !at_line_end(source)) {
+                return Err(ParseError::Syntax(format!("Unexpected character after flow collection or alias: {}", c)));
             }
-            '-' if source.current() == Some('-') && source.current() == Some('-') => {
-                if let Some(doc) = current_doc {
-                    documents.push(doc);
-                }
-                current_doc = None;
-                source.next();
-                source.next();
+            node
+        }
+        Some('"' | '\'') => {
+            let mut raw = read_quoted(source)?;
+            while let Some(c @ (' ' | '\t')) = source.current() {
+                raw.push(c);
                 source.next();
             }
-            c if c.is_alphanumeric() => {
-                current_doc = Some(parse_mapping(source)?);
+            if let Some(c) = source.current().filter(|_| !at_line_end(source)) {
+                return Err(ParseError::Syntax(format!("Unexpected character after quoted scalar: {}", c)));
             }
-            c if c.is_whitespace() => {
-                source.next();
+            trace_event!(token = "scalar", value = raw.trim(), "scalar value");
+            context.check_value(&raw, source.position());
+            context.resolve_scalar(raw.trim())
+        }
+        Some('|' | '>') => return parse_block_scalar(source, context, path, parent),
+        _ => return parse_plain(source, context, path, parent),
+    };
+    if let Some(comment) = read_trailing_comment(source) {
+        context.comments.set_trailing(path, &comment);
+    }
+    Ok((node, next_line(source)))
+}
+
+/// Parses a plain scalar running to the end of the line or a comment, joined with any
+/// continuation lines indented further than parent (see read_plain_lines).
+fn parse_plain(
+    source: &mut Lookahead,
+    context: &mut Context,
+    path: &str,
+    parent: Option<usize>,
+) -> Result<(Node, Option<usize>), ParseError> {
+    let raw = read_value(source);
+    trace_event!(token = "scalar", value = raw.trim(), "scalar value");
+    context.check_value(&raw, source.position());
+    let (text, comment, next) = read_plain_lines(source, raw.trim().to_string(), parent);
+    if let Some(comment) = comment {
+        context.comments.set_trailing(path, &comment);
+    }
+    Ok((context.resolve_scalar(&text), next))
+}
+
+/// Joins the first line of a plain scalar, already read up to a comment or the end of its
+/// line, with any continuation lines indented further than parent: the line break between
+/// two lines reads as a space, or as one line break for each blank line between them.
+/// Returns the text, the comment ending the scalar and the indentation of the line after it.
+pub(crate) fn read_plain_lines(
+    source: &mut Lookahead,
+    first: String,
+    parent: Option<usize>,
+) -> (String, Option<String>, Option<usize>) {
+    let mut text = first;
+    loop {
+        if let Some(comment) = read_trailing_comment(source) {
+            return (text, Some(comment), next_line(source));
+        }
+        skip_line(source);
+        let (next, blank_lines) = line_indent(source);
+        match next {
+            Some(indent)
+                if parent.is_none_or(|parent| indent > parent)
+                    && source.current() != Some('#')
+                    && !(indent == 0 && at_document_marker(source)) =>
+            {
+                text.push_str(&if blank_lines == 0 { " ".to_string() } else { "\n".repeat(blank_lines) });
+                text.push_str(read_value(source).trim());
             }
-            c => return Err(format!("Unexpected character: {}", c))
+            next => return (text, None, next),
         }
     }
+}
+
+/// Parses a literal ("|") or folded (">") block scalar whose header is at the current
+/// character (see read_block_scalar).
+fn parse_block_scalar(
+    source: &mut Lookahead,
+    context: &mut Context,
+    path: &str,
+    parent: Option<usize>,
+) -> Result<(Node, Option<usize>), ParseError> {
+    let (text, comment, next) = read_block_scalar(source, parent)?;
+    if let Some(comment) = comment {
+        context.comments.set_trailing(path, &comment);
+    }
+    trace_event!(token = "scalar", value = text.as_str(), "block scalar");
+    Ok((Node::Str(text), next))
+}
+
+/// Reads a literal ("|") or folded (">") block scalar whose header is at the current
+/// character. Its lines are indented by the header's indentation indicator (counted from
+/// parent) or, without one, as far as the first of them holding content, and run until
+/// a line holding content (a comment included) is indented less than that. The header's chomping
+/// indicator decides the trailing line breaks kept: "-" none, "+" all, otherwise one.
+/// Returns the text, the comment after the header and the indentation of the line after it.
+pub(crate) fn read_block_scalar(
+    source: &mut Lookahead,
+    parent: Option<usize>,
+) -> Result<(String, Option<String>, Option<usize>), ParseError> {
+    let folded = source.current() == Some('>');
+    source.next();
+    let mut chomping = None;
+    let mut indent = None;
+    loop {
+        match source.current() {
+            Some(c @ ('-' | '+')) if chomping.is_none() => chomping = Some(c),
+            Some(c @ '1'..='9') if indent.is_none() => {
+                indent = c.to_digit(10).map(|digit| parent.unwrap_or(0) + digit as usize)
+            }
+            _ => break,
+        }
+        source.next();
+    }
+    skip_blanks(source);
+    if let Some(c) = source.current().filter(|_| !at_line_end(source)) {
+        return Err(ParseError::Syntax(format!("Unexpected character in block scalar header: {}", c)));
+    }
+    let comment = read_trailing_comment(source);
+    skip_line(source);
+    let least = parent.map_or(0, |parent| parent + 1);
+    let mut lines: Vec<String> = Vec::new();
+    let next = loop {
+        let mut spaces = 0;
+        while source.current() == Some(' ') && indent.is_none_or(|indent| spaces < indent) {
+            spaces += 1;
+            source.next();
+        }
+        match source.current() {
+            None => break None,
+            Some('\r' | '\n') => {
+                lines.push(String::new());
+                skip_line(source);
+                continue;
+            }
+            Some(_) if indent.map_or(spaces < least, |indent| spaces < indent) => {
+                skip_blanks(source);
+                break Some(spaces);
+            }
+            Some(_) if spaces == 0 && at_document_marker(source) => break Some(0),
+            Some(_) => indent = indent.or(Some(spaces)),
+        }
+        let mut line = String::new();
+        while let Some(c) = source.current().filter(|&c| c != '\n') {
+            line.push(c);
+            source.next();
+        }
+        lines.push(line.strip_suffix('\r').map(str::to_string).unwrap_or(line));
+        skip_line(source);
+    };
+    let content = lines.iter().rposition(|line| !line.is_empty()).map_or(0, |last| last + 1);
+    let mut text = if folded { fold_block_lines(&lines[..content]) } else { lines[..content].join("\n") };
+    match chomping {
+        Some('-') => {}
+        Some(_) => text.push_str(&"\n".repeat(lines.len() - content + usize::from(content > 0))),
+        None if content > 0 => text.push('\n'),
+        None => {}
+    }
+    Ok((text, comment, next))
+}
+
+/// Folds the lines of a folded block scalar: the line break between two lines of text
+/// reads as a space, or as one line break for each empty line between them, while the
+/// line breaks around a more indented line are kept as they are.
+fn fold_block_lines(lines: &[String]) -> String {
+    let mut text = String::new();
+    let mut empty_lines = 0;
+    let mut previous_indented = None;
+    for line in lines {
+        if line.is_empty() {
+            empty_lines += 1;
+            continue;
+        }
+        let indented = line.starts_with([' ', '\t']);
+        match previous_indented {
+            None => text.push_str(&"\n".repeat(empty_lines)),
+            Some(false) if !indented && empty_lines == 0 => text.push(' '),
+            Some(false) if !indented => text.push_str(&"\n".repeat(empty_lines)),
+            Some(_) => text.push_str(&"\n".repeat(empty_lines + 1)),
+        }
+        text.push_str(line);
+        previous_indented = Some(indented);
+        empty_lines = 0;
+    }
+    text
+}
+
+/// Attaches any header comments read before a document's data to the document as leading
+/// comments of its root in the comment side table, leaving the data as the root node.
+fn attach_header(header: &mut Vec<Node>, doc: Node, context: &mut Context) -> Node {
+    for comment in header.drain(..) {
+        if let Node::Comment(text) = comment {
+            context.comments.add_leading("", &text);
+        }
+    }
+    doc
+}
+
+/// Parses YAML from a source into a Node tree using default options.
+///
+/// # Arguments
+/// * `source` - The source to read YAML from
+///
+/// # Returns
+/// The parsed Node (a Document node if the source contains multiple documents) or an error.
+pub fn parse(source: &mut dyn ISource) -> Result<Node, String> {
+    parse_with_options(source, &mut ParserOptions::new()).map_err(|e| e.to_string())
+}
+
+/// Parses YAML from a source into a Node tree using the given options.
+///
+/// # Arguments
+/// * `source` - The source to read YAML from
+/// * `options` - Options controlling the parse (for example a progress callback or deadline)
+///
+/// # Returns
+/// The parsed Node (a Document node if the source contains multiple documents), a syntax
+/// error, or ParseError::Cancelled if the parse was cancelled or its deadline passed.
+pub fn parse_with_options(source: &mut dyn ISource, options: &mut ParserOptions) -> Result<Node, ParseError> {
+    parse_detailed(source, options).map(|parsed| parsed.node)
+}
+
+/// Parses raw YAML bytes, detecting their encoding (UTF-8, UTF-16 or UTF-32 in either byte
+/// order, with or without a byte order mark) per the YAML specification.
+///
+/// # Arguments
+/// * `bytes` - The raw YAML bytes
+///
+/// # Returns
+/// The parsed Node or an error if the bytes cannot be decoded or parsed.
+pub fn parse_bytes(bytes: &[u8]) -> Result<Node, String> {
+    let text = decode_bytes(bytes)?;
+    parse(&mut Buffer::new(text.as_bytes()))
+}
+
+/// Parses YAML from a source into a SharedNode tree in which every alias refers to the
+/// node its anchor was defined on, so the anchored node is held once however many aliases
+/// name it. Nodes that are merely equal are not shared (see Node::to_shared for that).
+///
+/// # Arguments
+/// * `source` - The source to read YAML from
+/// * `options` - Options controlling the parse
+///
+/// # Returns
+/// The parsed SharedNode or a ParseError.
+pub fn parse_shared(source: &mut dyn ISource, options: &mut ParserOptions) -> Result<SharedNode, ParseError> {
+    let parsed = parse_detailed(source, options)?;
+    let mut shared = parsed.node.to_unshared();
+    // Aliases come in reading order, so an alias inside an anchored node is linked before
+    // any alias that refers to that node
+    for (path, target) in parsed.anchors.aliases() {
+        if let Some(anchored) = shared.pointer(target).cloned()
+            && let Some(alias) = shared.pointer_mut(path)
+        {
+            *alias = anchored;
+        }
+    }
+    Ok(shared)
+}
+
+/// Parses YAML from a source and returns the Node tree together with a report on the parse.
+///
+/// # Arguments
+/// * `source` - The source to read YAML from
+/// * `options` - Options controlling the parse
+///
+/// # Returns
+/// A Parsed value holding the root Node and a ParseReport (documents, node counts, maximum
+/// depth, aliases resolved and bytes read), or a ParseError.
+pub fn parse_detailed(source: &mut dyn ISource, options: &mut ParserOptions) -> Result<Parsed, ParseError> {
+    let mut context = Context::new(options);
+    trace_event!(total_bytes = ?source.length(), "parse start");
+    let source = &mut Lookahead::new(source);
+
+    let mut documents = Vec::new();
+    let mut header = Vec::new();
+    let mut current_doc = None;
+
+    let mut next = line_indent(source).0;
+    while let Some(indent) = next {
+        context.check_cancelled()?;
+        let c = source.current().unwrap_or_default();
+        next = if indent == 0 && at_document_marker(source) {
+            if let Some(doc) = current_doc.take() {
+                documents.push(attach_header(&mut header, doc, &mut context));
+                context.finish_document_metadata(documents.len() - 1);
+                context.document_completed(source);
+            }
+            source.next();
+            source.next();
+            source.next();
+            skip_blanks(source);
+            if c == '-' && !at_line_end(source) {
+                // A document can start on the "---" line itself
+                let (doc, next) = parse_inline_node(source, &mut context, "", None)?;
+                current_doc = Some(doc);
+                next
+            } else {
+                if let Some(comment) = read_trailing_comment(source) {
+                    header.push(Node::Comment(comment));
+                }
+                next_line(source)
+            }
+        } else if c == '#' {
+            let comment = read_trailing_comment(source).unwrap_or_default();
+            if let Some(doc) = current_doc.take() {
+                documents.push(attach_header(&mut header, doc, &mut context));
+                context.finish_document_metadata(documents.len() - 1);
+                context.document_completed(source);
+            }
+            header.push(Node::Comment(comment));
+            next_line(source)
+        } else if current_doc.is_some() || "@`,]}%!?:".contains(c) {
+            trace_error!(character = %c, position = source.position(), "unexpected character");
+            return Err(ParseError::Syntax(format!("Unexpected character: {}", c)));
+        } else {
+            let (doc, next) = parse_block_node(source, &mut context, "", indent, None)?;
+            current_doc = Some(doc);
+            next
+        };
+    }
 
     if let Some(doc) = current_doc {
-        documents.push(doc);
+        documents.push(attach_header(&mut header, doc, &mut context));
+        context.finish_document_metadata(documents.len() - 1);
+        context.documents += 1;
     }
+    // Comments with no following data are documents in their own right
+    context.documents += header.len();
+    documents.append(&mut header);
+    let comments = context.take_comments(documents.len() > 1);
+    let anchors = context.take_anchors(documents.len() > 1);
+    context.report_progress(source, true);
 
-    if documents.is_empty() {
-        Ok(Node::None)
+    let node = if documents.is_empty() {
+        Node::None
     } else if documents.len() == 1 {
-        Ok(documents.remove(0))
+        documents.remove(0)
     } else {
-        Ok(Node::Document(documents))
-    }
+        Node::Document(documents)
+    };
+    trace_event!(documents = context.documents, bytes_read = source.position(), "parse end");
+    let mut report = ParseReport {
+        documents: context.documents,
+        aliases_resolved: context.aliases_resolved,
+        bytes_read: source.position(),
+        ..ParseReport::default()
+    };
+    report.count_nodes(&node);
+    Ok(Parsed { node, report, warnings: context.warnings, comments, anchors })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::io::sources::buffer::Buffer;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_parse_scalar() {
@@ -172,10 +1332,16 @@ mod tests {
         assert_eq!(parse_scalar("~"), Node::None);
         assert_eq!(parse_scalar("true"), Node::Boolean(true));
         assert_eq!(parse_scalar("false"), Node::Boolean(false));
-        assert_eq!(parse_scalar("42"), Node::Number(Numeric::Integer(42)));
-        assert_eq!(parse_scalar("3.14"), Node::Number(Numeric::Float(3.14)));
+        assert!(parse_scalar("42").strict_eq(&Node::Number(Numeric::Integer(42))));
+        assert!(parse_scalar("2.5").strict_eq(&Node::Number(Numeric::Float(2.5))));
+        assert!(parse_scalar("18446744073709551615").strict_eq(&Node::Number(Numeric::UInteger(u64::MAX))));
         assert_eq!(parse_scalar("hello"), Node::Str("hello".to_string()));
         assert_eq!(parse_scalar("#comment"), Node::Comment("comment".to_string()));
+        assert!(parse_scalar(".inf").strict_eq(&Node::Number(Numeric::Float(f64::INFINITY))));
+        assert!(parse_scalar("+.Inf").strict_eq(&Node::Number(Numeric::Float(f64::INFINITY))));
+        assert!(parse_scalar("-.INF").strict_eq(&Node::Number(Numeric::Float(f64::NEG_INFINITY))));
+        assert!(matches!(parse_scalar(".NaN"), Node::Number(Numeric::Float(f)) if f.is_nan()));
+        assert_eq!(parse_scalar(".infinity"), Node::Str(".infinity".to_string()));
     }
 
     #[test]
@@ -205,12 +1371,25 @@ mod tests {
     fn test_parse_mapping() {
         let mut source = Buffer::new(b"key1: value1\nkey2: 42");
         let result = parse(&mut source).unwrap();
-        let mut expected = HashMap::new();
+        let mut expected = IndexMap::new();
         expected.insert("key1".to_string(), Node::Str("value1".to_string()));
         expected.insert("key2".to_string(), Node::Number(Numeric::Integer(42)));
         assert_eq!(result, Node::Dictionary(expected));
     }
 
+    #[test]
+    fn test_parse_missing_values_are_null() {
+        let mut source = Buffer::new(b"key1:\nkey2: 42\nkey3:\r\nkey4:\n  indented\nkey5:");
+        let result = parse(&mut source).unwrap();
+        assert_eq!(result["key1"], Node::None);
+        assert_eq!(result["key2"], Node::from(42));
+        assert_eq!(result["key3"], Node::None);
+        assert_eq!(result["key4"], Node::from("indented"));
+        assert_eq!(result["key5"], Node::None);
+        let mut source = Buffer::new(b"-\n- 1\n-");
+        assert_eq!(parse(&mut source).unwrap(), Node::from(vec![Node::None, Node::from(1), Node::None]));
+    }
+
     #[test]
     fn test_parse_empty() {
         let mut source = Buffer::new(b"");
@@ -233,8 +1412,629 @@ mod tests {
         assert_eq!(result, Node::Comment("Just a comment".to_string()));
     }
 
+    #[test]
+    fn test_parse_with_options_reports_progress() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        let updates = Rc::new(RefCell::new(Vec::new()));
+        let recorded = Rc::clone(&updates);
+        let mut options = ParserOptions::new();
+        options.progress_interval = 4;
+        options.on_progress(move |progress| recorded.borrow_mut().push(*progress));
+        let mut source = Buffer::new(b"key1: value1\nkey2: 42\nkey3: true");
+        parse_with_options(&mut source, &mut options).unwrap();
+        let updates = updates.borrow();
+        assert!(updates.len() > 1);
+        let last = updates.last().unwrap();
+        assert_eq!(last.bytes_read, 32);
+        assert_eq!(last.total_bytes, Some(32));
+        assert_eq!(last.documents, 1);
+        assert!(updates.windows(2).all(|pair| pair[0].bytes_read <= pair[1].bytes_read));
+    }
 
+    #[test]
+    fn test_parse_with_options_counts_documents() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        let documents = Rc::new(RefCell::new(0));
+        let recorded = Rc::clone(&documents);
+        let mut options = ParserOptions::new();
+        options.on_progress(move |progress| *recorded.borrow_mut() = progress.documents);
+        let mut source = Buffer::new(b"# one\n# two\n# three");
+        parse_with_options(&mut source, &mut options).unwrap();
+        assert_eq!(*documents.borrow(), 3);
+    }
 
-}
+    #[test]
+    fn test_parse_with_options_cancelled_by_token() {
+        use crate::parser::options::CancellationToken;
+        let token = CancellationToken::new();
+        token.cancel();
+        let mut options = ParserOptions::new();
+        options.cancellation = Some(token);
+        let mut source = Buffer::new(b"key1: value1\nkey2: 42");
+        assert_eq!(parse_with_options(&mut source, &mut options), Err(ParseError::Cancelled));
+    }
+
+    #[test]
+    fn test_parse_with_options_cancelled_from_progress_callback() {
+        use crate::parser::options::CancellationToken;
+        let token = CancellationToken::new();
+        let trigger = token.clone();
+        let mut options = ParserOptions::new();
+        options.progress_interval = 1;
+        options.cancellation = Some(token);
+        options.on_progress(move |_| trigger.cancel());
+        let mut source = Buffer::new(b"- 1\n- 2\n- 3\n- 4");
+        assert_eq!(parse_with_options(&mut source, &mut options), Err(ParseError::Cancelled));
+    }
+
+    #[test]
+    fn test_parse_with_options_deadline_expired() {
+        let mut options = ParserOptions::new();
+        options.deadline = Some(std::time::Instant::now());
+        let mut source = Buffer::new(b"- 1\n- 2");
+        assert_eq!(parse_with_options(&mut source, &mut options), Err(ParseError::Cancelled));
+    }
+
+    #[test]
+    fn test_parse_with_options_syntax_error() {
+        let mut source = Buffer::new(b"@invalid");
+        let result = parse_with_options(&mut source, &mut ParserOptions::new());
+        assert_eq!(result, Err(ParseError::Syntax("Unexpected character: @".to_string())));
+    }
+
+    #[test]
+    fn test_parse_detailed_report() {
+        let mut source = Buffer::new(b"key1: value1\nkey2: 42\nkey3: ~");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        assert_eq!(parsed.node["key2"], Node::Number(Numeric::Integer(42)));
+        assert_eq!(parsed.report.documents, 1);
+        assert_eq!(parsed.report.nodes.dictionaries, 1);
+        assert_eq!(parsed.report.nodes.strings, 1);
+        assert_eq!(parsed.report.nodes.numbers, 1);
+        assert_eq!(parsed.report.nodes.nulls, 1);
+        assert_eq!(parsed.report.max_depth, 2);
+        assert_eq!(parsed.report.aliases_resolved, 0);
+        assert_eq!(parsed.report.bytes_read, 29);
+    }
+
+    #[test]
+    fn test_parse_detailed_empty_report() {
+        let mut source = Buffer::new(b"");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        assert_eq!(parsed.node, Node::None);
+        assert_eq!(parsed.report.documents, 0);
+        assert_eq!(parsed.report.bytes_read, 0);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_parse_emits_trace_events() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        struct CountingSubscriber {
+            events: Arc<AtomicUsize>,
+        }
+
+        impl Subscriber for CountingSubscriber {
+            fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+                metadata.target() == "yaml_lib::parser"
+            }
+            fn new_span(&self, _span: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+            fn record(&self, _span: &Id, _values: &Record<'_>) {}
+            fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+            fn event(&self, _event: &Event<'_>) {
+                self.events.fetch_add(1, Ordering::SeqCst);
+            }
+            fn enter(&self, _span: &Id) {}
+            fn exit(&self, _span: &Id) {}
+        }
+
+        let events = Arc::new(AtomicUsize::new(0));
+        let subscriber = CountingSubscriber { events: Arc::clone(&events) };
+        tracing::subscriber::with_default(subscriber, || {
+            let mut source = Buffer::new(b"key1: value1\nkey2: 42");
+            parse(&mut source).unwrap();
+        });
+        assert!(events.load(Ordering::SeqCst) >= 4);
+    }
+
+    #[test]
+    fn test_parse_detailed_warns_on_deprecated_booleans() {
+        let mut source = Buffer::new(b"enabled: yes\ndisabled: off\nflag: true");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        assert_eq!(parsed.node["enabled"], Node::Str("yes".to_string()));
+        let kinds: Vec<WarningKind> = parsed.warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(kinds, vec![WarningKind::DeprecatedBoolean, WarningKind::DeprecatedBoolean]);
+    }
+
+    #[test]
+    fn test_parse_detailed_warns_on_duplicate_keys() {
+        let mut source = Buffer::new(b"key: 1\nkey: 2");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        assert_eq!(parsed.node["key"], Node::Number(Numeric::Integer(2)));
+        assert_eq!(parsed.warnings.len(), 1);
+        assert_eq!(parsed.warnings[0].kind, WarningKind::DuplicateKey);
+        assert_eq!(parsed.warnings[0].message, "Duplicate key: key");
+    }
+
+    #[test]
+    fn test_parse_detailed_warns_on_trailing_tabs() {
+        let mut source = Buffer::new(b"- one\t\n- two");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        assert_eq!(parsed.warnings.len(), 1);
+        assert_eq!(parsed.warnings[0].kind, WarningKind::TrailingTab);
+        assert_eq!(parsed.warnings[0].position, 6);
+    }
+
+    #[test]
+    fn test_parse_detailed_clean_input_has_no_warnings() {
+        let mut source = Buffer::new(b"key1: value1\nkey2: 42");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        assert!(parsed.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_with_smallest_fit_numeric_policy() {
+        use crate::nodes::node::NumericPolicy;
+        let mut options = ParserOptions::new();
+        options.numeric_policy = NumericPolicy::SmallestFit;
+        let mut source = Buffer::new(b"- 42\n- 70000\n- 1.5");
+        let result = parse_with_options(&mut source, &mut options).unwrap();
+        assert!(result.strict_eq(&Node::Array(vec![
+            Node::Number(Numeric::Int8(42)),
+            Node::Number(Numeric::Int32(70000)),
+            Node::Number(Numeric::Float(1.5))
+        ])), "{:?}", result);
+    }
+
+    #[test]
+    fn test_parsed_numbers_match_from_impls_under_policy() {
+        use crate::nodes::node::NumericPolicy;
+        let mut source = Buffer::new(b"- 42");
+        let result = parse(&mut source).unwrap();
+        assert!(result.strict_eq(&Node::from(vec![42]).with_numeric_policy(NumericPolicy::Canonical)));
+    }
+
+    #[test]
+    fn test_mapping_keys_stay_strings() {
+        let mut source = Buffer::new(b"1.10: version\non: switch\nnull: nothing\n42: answer\ntrue: yes");
+        let result = parse(&mut source).unwrap();
+        let mut expected = IndexMap::new();
+        expected.insert("1.10".to_string(), Node::Str("version".to_string()));
+        expected.insert("on".to_string(), Node::Str("switch".to_string()));
+        expected.insert("null".to_string(), Node::Str("nothing".to_string()));
+        expected.insert("42".to_string(), Node::Str("answer".to_string()));
+        expected.insert("true".to_string(), Node::Str("yes".to_string()));
+        assert_eq!(result, Node::Dictionary(expected));
+    }
+
+    #[test]
+    fn test_quoted_mapping_keys() {
+        let mut source = Buffer::new(b"\"a: b\": 1\n'it''s': 2");
+        let result = parse(&mut source).unwrap();
+        assert_eq!(result["a: b"], Node::Number(Numeric::Integer(1)));
+        assert_eq!(result["it's"], Node::Number(Numeric::Integer(2)));
+    }
+
+    #[test]
+    fn test_quoted_values_are_strings() {
+        let mut source = Buffer::new(b"a: \"true\"\nb: '42'\nc: \"x # y\"\nd: \"line\\nnext\"");
+        let result = parse(&mut source).unwrap();
+        assert_eq!(result["a"], Node::Str("true".to_string()));
+        assert_eq!(result["b"], Node::Str("42".to_string()));
+        assert_eq!(result["c"], Node::Str("x # y".to_string()));
+        assert_eq!(result["d"], Node::Str("line\nnext".to_string()));
+    }
 
+    #[test]
+    fn test_hash_inside_plain_value_is_not_a_comment() {
+        let mut source = Buffer::new(b"url: http://host/page#anchor");
+        let result = parse(&mut source).unwrap();
+        assert_eq!(result["url"], Node::Str("http://host/page#anchor".to_string()));
+    }
+
+    #[test]
+    fn test_failsafe_schema_values() {
+        let mut options = ParserOptions::new();
+        options.schema = Schema::Failsafe;
+        let mut source = Buffer::new(b"a: 42\nb: true\nc: null");
+        let result = parse_with_options(&mut source, &mut options).unwrap();
+        assert_eq!(result["a"], Node::Str("42".to_string()));
+        assert_eq!(result["b"], Node::Str("true".to_string()));
+        assert_eq!(result["c"], Node::Str("null".to_string()));
+    }
+
+    #[test]
+    fn test_json_schema_values() {
+        let mut options = ParserOptions::new();
+        options.schema = Schema::Json;
+        let mut source = Buffer::new(b"- 42\n- -1.5e3\n- true\n- null\n- ~\n- .5\n- inf");
+        let result = parse_with_options(&mut source, &mut options).unwrap();
+        assert_eq!(result, Node::Array(vec![
+            Node::Number(Numeric::Integer(42)),
+            Node::Number(Numeric::Float(-1500.0)),
+            Node::Boolean(true),
+            Node::None,
+            Node::Str("~".to_string()),
+            Node::Str(".5".to_string()),
+            Node::Str("inf".to_string())
+        ]));
+    }
+
+    #[test]
+    fn test_parse_trailing_comment_on_mapping_value() {
+        let mut source = Buffer::new(b"port: 8080  # overridden in prod\nhost: localhost");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        let mut expected = IndexMap::new();
+        expected.insert("port".to_string(), Node::Number(Numeric::Integer(8080)));
+        expected.insert("host".to_string(), Node::Str("localhost".to_string()));
+        assert_eq!(parsed.node, Node::Dictionary(expected));
+        assert_eq!(parsed.comments.trailing("/port"), Some("overridden in prod"));
+        assert_eq!(parsed.comments.trailing("/host"), None);
+    }
 
+    #[test]
+    fn test_parse_trailing_comment_on_sequence_entry() {
+        let mut source = Buffer::new(b"- 1\n- 2 # second\n- 3");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        assert_eq!(parsed.node, Node::Array(vec![
+            Node::Number(Numeric::Integer(1)),
+            Node::Number(Numeric::Integer(2)),
+            Node::Number(Numeric::Integer(3))
+        ]));
+        assert_eq!(parsed.comments.trailing("/1"), Some("second"));
+        assert_eq!(parsed.comments.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_header_comments_attached_to_document() {
+        let mut source = Buffer::new(b"# Licensed under MIT\n# Generated by tool\nkey1: value1\nkey2: 42 # answer");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        let mut expected = IndexMap::new();
+        expected.insert("key1".to_string(), Node::Str("value1".to_string()));
+        expected.insert("key2".to_string(), Node::Number(Numeric::Integer(42)));
+        assert_eq!(parsed.node, Node::Dictionary(expected));
+        assert_eq!(parsed.node.pointer("/key2"), Some(&Node::Number(Numeric::Integer(42))));
+        assert_eq!(parsed.report.documents, 1);
+        assert_eq!(parsed.comments.leading(""), &["Licensed under MIT".to_string(), "Generated by tool".to_string()]);
+        assert_eq!(parsed.comments.trailing("/key2"), Some("answer"));
+    }
+
+    #[test]
+    fn test_parse_header_comment_before_sequence() {
+        let mut source = Buffer::new(b"# Items\n- 1\n- 2");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        let expected = Node::Array(vec![Node::Number(Numeric::Integer(1)), Node::Number(Numeric::Integer(2))]);
+        assert_eq!(parsed.node, expected);
+        assert_eq!(parsed.comments.leading(""), &["Items".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_multiple_comments_only() {
+        let mut source = Buffer::new(b"# one\n# two");
+        let result = parse(&mut source).unwrap();
+        assert_eq!(result, Node::Document(vec![
+            Node::Comment("one".to_string()),
+            Node::Comment("two".to_string())
+        ]));
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn test_parse_normalizes_keys() {
+        let mut options = ParserOptions::new();
+        options.normalize_keys = true;
+        let mut source = Buffer::new("cafe\u{301}: 1".as_bytes());
+        let result = parse_with_options(&mut source, &mut options).unwrap();
+        match result {
+            Node::Dictionary(map) => assert!(map.contains_key("caf\u{e9}")),
+            _ => panic!("Expected Dictionary node"),
+        }
+    }
+
+    #[test]
+    fn test_parse_bytes_detects_encoding() {
+        let text = "name: café\ncount: 3";
+        let utf16: Vec<u8> = text.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+        let utf32: Vec<u8> = text.chars().flat_map(|c| (c as u32).to_be_bytes()).collect();
+        for bytes in [text.as_bytes().to_vec(), utf16, utf32] {
+            let result = parse_bytes(&bytes).unwrap();
+            assert_eq!(result["name"], Node::Str("café".to_string()));
+            assert_eq!(result["count"], Node::Number(Numeric::Integer(3)));
+        }
+    }
+
+    #[test]
+    fn test_parse_bytes_invalid_encoding_fails() {
+        assert_eq!(parse_bytes(&[b'a', b':', b' ', 0xC3]).unwrap_err(), "Invalid UTF-8 sequence in input");
+    }
+
+    #[test]
+    fn test_parse_flow_collections_as_mapping_values() {
+        let mut source = Buffer::new(b"ports: [80, 443]\nlabels: {app: web, tier: \"front, end\"}\nname: svc");
+        let result = parse(&mut source).unwrap();
+        assert_eq!(result["ports"], Node::Array(vec![
+            Node::Number(Numeric::Integer(80)),
+            Node::Number(Numeric::Integer(443))
+        ]));
+        let mut labels = IndexMap::new();
+        labels.insert("app".to_string(), Node::Str("web".to_string()));
+        labels.insert("tier".to_string(), Node::Str("front, end".to_string()));
+        assert_eq!(result["labels"], Node::Dictionary(labels));
+        assert_eq!(result["name"], Node::Str("svc".to_string()));
+    }
+
+    #[test]
+    fn test_parse_nested_flow_collections() {
+        let mut source = Buffer::new(b"matrix: [[1, 2], [3, {x: ~, y: [true]}], []]");
+        let result = parse(&mut source).unwrap();
+        let mut inner = IndexMap::new();
+        inner.insert("x".to_string(), Node::None);
+        inner.insert("y".to_string(), Node::Array(vec![Node::Boolean(true)]));
+        assert_eq!(result["matrix"], Node::Array(vec![
+            Node::Array(vec![Node::Number(Numeric::Integer(1)), Node::Number(Numeric::Integer(2))]),
+            Node::Array(vec![Node::Number(Numeric::Integer(3)), Node::Dictionary(inner)]),
+            Node::Array(vec![])
+        ]));
+    }
+
+    #[test]
+    fn test_parse_multiline_flow_collection_with_comment() {
+        let mut source = Buffer::new(b"ports: [\n  80,\n  443, # https\n]  # list\nname: svc");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        assert_eq!(parsed.node["ports"], Node::Array(vec![
+            Node::Number(Numeric::Integer(80)),
+            Node::Number(Numeric::Integer(443))
+        ]));
+        assert_eq!(parsed.node["name"], Node::Str("svc".to_string()));
+        assert_eq!(parsed.comments.trailing("/ports"), Some("list"));
+    }
+
+    #[test]
+    fn test_parse_flow_collection_in_sequence_and_top_level() {
+        let mut source = Buffer::new(b"- [a, b]\n- {k: v}");
+        let result = parse(&mut source).unwrap();
+        assert_eq!(result[0], Node::Array(vec![Node::from("a"), Node::from("b")]));
+        assert_eq!(result[1]["k"], Node::from("v"));
+        let mut source = Buffer::new(b"{url: http://host:80/, 'q': 'it''s'}");
+        let result = parse(&mut source).unwrap();
+        assert_eq!(result["url"], Node::from("http://host:80/"));
+        assert_eq!(result["q"], Node::from("it's"));
+    }
+
+    #[test]
+    fn test_parse_invalid_flow_collections() {
+        for input in ["a: [1, 2", "a: {x: 1", "a: [1}", "a: [1] extra", "a: [,]", "a: ['open]"] {
+            let mut source = Buffer::new(input.as_bytes());
+            assert!(parse(&mut source).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_flow_nesting_limit() {
+        let deep = format!("a: {}{}", "[".repeat(200_000), "]".repeat(200_000));
+        let expected = Err(ParseError::Syntax("Nesting exceeds the maximum depth of 128".to_string()));
+        assert_eq!(parse_with_options(&mut Buffer::new(deep.as_bytes()), &mut ParserOptions::new()), expected);
+        let deep = "{a: ".repeat(200_000);
+        assert!(parse(&mut Buffer::new(deep.as_bytes())).is_err());
+        // The block mapping holding the flow collections counts towards the depth too
+        let mut options = ParserOptions { max_depth: 3, ..ParserOptions::new() };
+        assert!(parse_with_options(&mut Buffer::new(b"a: [[1]]"), &mut options).is_ok());
+        assert!(parse_with_options(&mut Buffer::new(b"a: [[[1]]]"), &mut options).is_err());
+        let deep: String = (0..200).map(|level| format!("{}k:\n", " ".repeat(level))).collect();
+        assert_eq!(parse_with_options(&mut Buffer::new(deep.as_bytes()), &mut ParserOptions::new()), expected);
+        let deep = "- ".repeat(200_000) + "x";
+        assert!(parse(&mut Buffer::new(deep.as_bytes())).is_err());
+        assert!(parse(&mut Buffer::new(format!("a: [{}1]", "&x ".repeat(200_000)).as_bytes())).is_err());
+    }
+
+    #[test]
+    fn test_parse_anchor_and_alias() {
+        let mut source = Buffer::new(b"base: &port 8080\nadmin: *port");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        assert_eq!(parsed.node["base"], Node::Number(Numeric::Integer(8080)));
+        assert_eq!(parsed.node["admin"], Node::Number(Numeric::Integer(8080)));
+        assert_eq!(parsed.report.aliases_resolved, 1);
+        assert_eq!(parsed.anchors.anchor_at("/base"), Some("port"));
+        assert_eq!(parsed.anchors.anchor_at("/admin"), None);
+        assert_eq!(parsed.anchors.path_of("port"), Some("/base"));
+    }
+
+    #[test]
+    fn test_parse_anchors_in_sequence_and_flow_collections() {
+        let mut source = Buffer::new(b"- &list [1, &two 2]\n- *list\n- {x: *two}");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        let two = Node::Number(Numeric::Integer(2));
+        let list = Node::Array(vec![Node::Number(Numeric::Integer(1)), two.clone()]);
+        assert_eq!(parsed.node[0], list);
+        assert_eq!(parsed.node[1], list);
+        assert_eq!(parsed.node[2]["x"], two);
+        assert_eq!(parsed.anchors.names(), vec!["two", "list"]);
+        assert_eq!(parsed.anchors.anchor_at("/0/1"), Some("two"));
+        assert_eq!(parsed.report.aliases_resolved, 2);
+    }
+
+    #[test]
+    fn test_parse_shared_links_aliases_to_the_anchored_node() {
+        let text = "base: &b {host: a, ports: [80]}\nfirst: *b\nsecond: *b\nother: {host: a, ports: [80]}\n\
+            list: &l [1, *b]\ncopy: *l";
+        let shared = parse_shared(&mut Buffer::new(text.as_bytes()), &mut ParserOptions::new()).unwrap();
+        assert!(shared["first"].ptr_eq(&shared["second"]));
+        assert!(shared["first"].ptr_eq(&shared["base"]));
+        assert!(shared["list"][1].ptr_eq(&shared["base"]));
+        assert!(shared["copy"].ptr_eq(&shared["list"]));
+        assert!(!shared["other"].ptr_eq(&shared["base"]));
+        assert_eq!(shared["other"], shared["base"]);
+        assert_eq!(shared.to_node(), parse(&mut Buffer::new(text.as_bytes())).unwrap());
+        let parsed = parse_detailed(&mut Buffer::new(text.as_bytes()), &mut ParserOptions::new()).unwrap();
+        let aliases: Vec<(&str, &str)> = parsed.anchors.aliases().collect();
+        assert_eq!(aliases, vec![("/first", "/base"), ("/second", "/base"), ("/list/1", "/base"), ("/copy", "/list")]);
+    }
+
+    #[test]
+    fn test_parse_anchor_with_trailing_comment() {
+        let mut source = Buffer::new(b"# settings\nname: &n server # the host");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        assert_eq!(parsed.anchors.anchor_at("/name"), Some("n"));
+        assert_eq!(parsed.comments.trailing("/name"), Some("the host"));
+        assert_eq!(parsed.comments.leading(""), &["settings".to_string()]);
+        assert_eq!(parsed.node["name"], Node::from("server"));
+    }
+
+    #[test]
+    fn test_parse_duplicate_anchor_warns() {
+        let mut source = Buffer::new(b"- &a 1\n- &a 2\n- *a");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        assert_eq!(parsed.node[2], Node::Number(Numeric::Integer(2)));
+        assert_eq!(parsed.warnings.len(), 1);
+        assert_eq!(parsed.warnings[0].kind, WarningKind::DuplicateAnchor);
+    }
+
+    #[test]
+    fn test_parse_invalid_aliases() {
+        for input in ["a: *missing", "- & 1", "a: *", "a: &x 1\nb: *x extra"] {
+            let mut source = Buffer::new(input.as_bytes());
+            assert!(parse(&mut source).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_scalar_documents() {
+        let cases = [
+            ("42", Node::Number(Numeric::Integer(42))),
+            ("-5", Node::Number(Numeric::Integer(-5))),
+            ("\"web\"", Node::from("web")),
+            ("true", Node::Boolean(true)),
+        ];
+        for (input, expected) in cases {
+            let mut source = Buffer::new(input.as_bytes());
+            assert_eq!(parse(&mut source), Ok(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_scalar_document_with_comment() {
+        let mut source = Buffer::new(b"web # the host");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        assert_eq!(parsed.node, Node::from("web"));
+        assert_eq!(parsed.comments.trailing(""), Some("the host"));
+    }
+
+    #[test]
+    fn test_parse_mapping_key_without_colon() {
+        let mut source = Buffer::new(b"a: 1\nfoo");
+        assert!(parse(&mut source).is_err());
+    }
+
+    #[test]
+    fn test_parse_nested_block_collections() {
+        let input = concat!(
+            "server:\n  name: web\n  ports:\n    - 80\n    - 443\n",
+            "  tls:\n    enabled: true\nhosts:\n- a\n- b\n"
+        );
+        let mut source = Buffer::new(input.as_bytes());
+        let expected = Node::dict()
+            .key(
+                "server",
+                Node::dict()
+                    .key("name", "web")
+                    .key("ports", vec![80i64, 443])
+                    .key("tls", Node::dict().key("enabled", true).build())
+                    .build(),
+            )
+            .key("hosts", vec!["a", "b"])
+            .build();
+        assert_eq!(parse(&mut source), Ok(expected));
+    }
+
+    #[test]
+    fn test_parse_sequence_of_mappings() {
+        let mut source = Buffer::new(b"- name: a\n  size: 1\n- - x\n  - y\n-\n  k: v\n");
+        let expected = Node::Array(vec![
+            Node::dict().key("name", "a").key("size", 1i64).build(),
+            Node::from(vec!["x", "y"]),
+            Node::dict().key("k", "v").build(),
+        ]);
+        assert_eq!(parse(&mut source), Ok(expected));
+    }
+
+    #[test]
+    fn test_parse_multiple_block_documents() {
+        let mut source = Buffer::new(b"---\na:\n  b: 1\n...\n--- text\n---\n- 1\n");
+        let expected = Node::Document(vec![
+            Node::dict().key("a", Node::dict().key("b", 1i64).build()).build(),
+            Node::from("text"),
+            Node::from(vec![1i64]),
+        ]);
+        assert_eq!(parse(&mut source), Ok(expected));
+    }
+
+    #[test]
+    fn test_parse_multi_line_scalars() {
+        let input = "plain: one\n  two\n\n  three\ndouble: \"a  \n  b\\\n  c\"\nsingle: 'it''s\n\n\n  here'\n";
+        let mut source = Buffer::new(input.as_bytes());
+        let expected = Node::dict()
+            .key("plain", "one two\nthree")
+            .key("double", "a bc")
+            .key("single", "it's\n\nhere")
+            .build();
+        assert_eq!(parse(&mut source), Ok(expected));
+    }
+
+    #[test]
+    fn test_parse_anchored_block_collections() {
+        let mut source = Buffer::new(b"base: &base\n  size: 1\nitems:\n  - &x\n    a: 1\n  - *x\ncopy: *base\n");
+        let item = Node::dict().key("a", 1i64).build();
+        let base = Node::dict().key("size", 1i64).build();
+        let expected = Node::dict()
+            .key("base", base.clone())
+            .key("items", vec![item.clone(), item])
+            .key("copy", base)
+            .build();
+        assert_eq!(parse(&mut source), Ok(expected));
+    }
+
+    #[test]
+    fn test_parse_bad_indentation() {
+        let mut source = Buffer::new(b"a:\n    b: 1\n  c: 2\n");
+        let error = parse(&mut source).unwrap_err();
+        assert!(error.to_string().contains("Unexpected indentation"), "{}", error);
+    }
+
+    #[test]
+    fn test_parse_block_scalars() {
+        let input = concat!(
+            "literal: |\n  one\n\n  two\nfolded: >-\n  fold\n  this\n\n  para\n    kept\n  end\n",
+            "kept: |+ # all breaks\n  k\n\n\nexplicit:\n  - |2\n      x\n    y\n"
+        );
+        let mut source = Buffer::new(input.as_bytes());
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        let expected = Node::dict()
+            .key("literal", "one\n\ntwo\n")
+            .key("folded", "fold this\npara\n  kept\nend")
+            .key("kept", "k\n\n\n")
+            .key("explicit", vec!["  x\ny\n"])
+            .build();
+        assert_eq!(parsed.node, expected);
+        assert_eq!(parsed.comments.trailing("/kept"), Some("all breaks"));
+        let mut source = Buffer::new(b"|\na\nb\n--- >\n a\n b\n");
+        assert_eq!(parse(&mut source), Ok(Node::Document(vec![Node::from("a\nb\n"), Node::from("a b\n")])));
+        let mut source = Buffer::new(b"a: |x\n  b\n");
+        assert!(parse(&mut source).is_err());
+        let mut source = Buffer::new(b"|\n  a\n# c\n");
+        assert_eq!(parse(&mut source), Ok(Node::Document(vec![Node::from("a\n"), Node::Comment("c".to_string())])));
+        let mut source = Buffer::new(b"a: |\n    x\n  # c\nb: 1\n");
+        let parsed = parse_detailed(&mut source, &mut ParserOptions::new()).unwrap();
+        let comment = Node::Comment("c".to_string());
+        assert_eq!(parsed.node, Node::dict().key("a", "x\n").key("__comment_1", comment).key("b", 1i64).build());
+        let mut source = Buffer::new(b"a: |\n    x\n  y\n");
+        assert!(parse(&mut source).is_err());
+    }
+}
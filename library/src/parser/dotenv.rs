@@ -0,0 +1,189 @@
+//! Dotenv parser.
+//! Reads a .env file into a Dictionary of Str values, one per KEY=value line. Blank lines
+//! and "#" comment lines are skipped and an "export " prefix is allowed. Unquoted values
+//! run to the end of the line or a " #" comment and are trimmed; single quoted values are
+//! taken literally, and double quoted values have their \n, \r, \t, \", \\ and \$ escapes
+//! replaced; both kinds of quoted value may span lines. References such as ${HOME} are
+//! kept as written rather than expanded. Later duplicate keys replace earlier ones.
+
+use crate::io::traits::ISource;
+use crate::nodes::node::Node;
+use indexmap::IndexMap;
+
+/// Parses a .env file from a source into a Dictionary of strings.
+///
+/// # Arguments
+/// * `source` - The source to read the .env file from
+///
+/// # Returns
+/// The parsed Dictionary or an error describing the first problem and its byte position.
+pub fn parse(source: &mut dyn ISource) -> Result<Node, String> {
+    let mut map = IndexMap::new();
+    loop {
+        skip_spaces(source);
+        match source.current() {
+            None => break,
+            Some('\n' | '\r') => source.next(),
+            Some('#') => skip_line(source),
+            Some(_) => {
+                let (key, value) = read_entry(source)?;
+                map.insert(key, Node::Str(value));
+            }
+        }
+    }
+    Ok(Node::Dictionary(map))
+}
+
+fn error(source: &mut dyn ISource, message: &str) -> String {
+    format!("{} at position {}", message, source.position())
+}
+
+fn skip_spaces(source: &mut dyn ISource) {
+    while matches!(source.current(), Some(' ' | '\t')) {
+        source.next();
+    }
+}
+
+fn skip_line(source: &mut dyn ISource) {
+    while source.current().is_some_and(|c| c != '\n') {
+        source.next();
+    }
+}
+
+fn read_name(source: &mut dyn ISource) -> String {
+    let mut name = String::new();
+    while let Some(c) = source.current().filter(|&c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+        name.push(c);
+        source.next();
+    }
+    name
+}
+
+/// Reads a KEY=value line, leaving the source at the end of the line.
+fn read_entry(source: &mut dyn ISource) -> Result<(String, String), String> {
+    let mut key = read_name(source);
+    if key == "export" && matches!(source.current(), Some(' ' | '\t')) {
+        skip_spaces(source);
+        key = read_name(source);
+    }
+    if key.is_empty() || key.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(error(source, "Expected a variable name in .env file"));
+    }
+    skip_spaces(source);
+    if source.current() != Some('=') {
+        return Err(error(source, &format!("Expected '=' after {} in .env file", key)));
+    }
+    source.next();
+    skip_spaces(source);
+    let value = match source.current() {
+        Some(quote @ ('\'' | '"')) => {
+            source.next();
+            let value = read_quoted(source, quote)?;
+            skip_spaces(source);
+            match source.current() {
+                None | Some('\n' | '\r') => {}
+                Some('#') => skip_line(source),
+                Some(c) => return Err(error(source, &format!("Unexpected '{}' after quoted value in .env file", c))),
+            }
+            value
+        }
+        _ => {
+            let mut value = String::new();
+            while let Some(c) = source.current().filter(|&c| c != '\n' && c != '\r') {
+                // A comment starts with a "#" after whitespace
+                if c == '#' && (value.is_empty() || value.ends_with([' ', '\t'])) {
+                    skip_line(source);
+                    break;
+                }
+                value.push(c);
+                source.next();
+            }
+            value.trim_end().to_string()
+        }
+    };
+    Ok((key, value))
+}
+
+/// Reads the rest of a quoted value after its opening quote.
+fn read_quoted(source: &mut dyn ISource, quote: char) -> Result<String, String> {
+    let mut value = String::new();
+    loop {
+        match source.current() {
+            None => return Err(error(source, "Unterminated quoted value in .env file")),
+            Some(c) if c == quote => {
+                source.next();
+                return Ok(value);
+            }
+            Some('\\') if quote == '"' => {
+                source.next();
+                match source.current() {
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some(c @ ('"' | '\\' | '$')) => value.push(c),
+                    Some(c) => {
+                        value.push('\\');
+                        value.push(c);
+                    }
+                    None => continue,
+                }
+            }
+            Some(c) => value.push(c),
+        }
+        source.next();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::sources::buffer::Buffer;
+    use crate::stringify::dotenv::stringify;
+
+    fn parse_text(text: &str) -> Result<Node, String> {
+        parse(&mut Buffer::new(text.as_bytes()))
+    }
+
+    #[test]
+    fn parse_dotenv_works() {
+        let text = "# settings\n\nexport NAME = web app # the name\r\nURL=http://h/#anchor\n\
+                    SINGLE='it\"s \\n ${HOME}' # quoted\nDOUBLE=\"line\\none\\t\\\"q\\\" \\$x \\d\"\n\
+                    MULTI='a\nb'\nEMPTY=\n  INDENTED=1\nNAME=again\n";
+        let expected = Node::dict()
+            .key("NAME", "again")
+            .key("URL", "http://h/#anchor")
+            .key("SINGLE", "it\"s \\n ${HOME}")
+            .key("DOUBLE", "line\none\t\"q\" $x \\d")
+            .key("MULTI", "a\nb")
+            .key("EMPTY", "")
+            .key("INDENTED", "1")
+            .build();
+        assert_eq!(parse_text(text).unwrap(), expected);
+        assert_eq!(parse_text("").unwrap(), Node::dict().build());
+    }
+
+    #[test]
+    fn parse_dotenv_round_trips_through_the_emitter() {
+        let node = Node::dict()
+            .key("A", "plain")
+            .key("B", "two words # not a comment")
+            .key("C", "it's \"$HOME\"\\\n")
+            .key("D", "")
+            .build();
+        let mut destination = crate::io::destinations::buffer::Buffer::new();
+        stringify(&node, &mut destination).unwrap();
+        assert_eq!(parse_text(&destination.to_string()).unwrap(), node);
+    }
+
+    #[test]
+    fn parse_dotenv_rejects_invalid_lines() {
+        assert_eq!(parse_text("A=1\n=2\n").unwrap_err(), "Expected a variable name in .env file at position 4");
+        assert_eq!(parse_text("A 1\n").unwrap_err(), "Expected '=' after A in .env file at position 2");
+        assert_eq!(parse_text("A='1\n").unwrap_err(), "Unterminated quoted value in .env file at position 5");
+        assert_eq!(
+            parse_text("A='1'x\n").unwrap_err(),
+            "Unexpected 'x' after quoted value in .env file at position 5"
+        );
+        assert!(parse_text("1A=x").is_err());
+    }
+}
@@ -0,0 +1,394 @@
+//! JSON parser.
+//! Reads a single JSON value (RFC 8259) into the same Node tree the YAML parser builds:
+//! objects become Dictionary nodes, arrays Array nodes, numbers Integer, UInteger or Float
+//! values and null None. The grammar is applied strictly, so comments, trailing commas,
+//! single quotes and unquoted keys are errors, as is anything after the value other than
+//! whitespace. Later duplicate keys replace earlier ones.
+
+use crate::error::parse::ParseError;
+use crate::io::traits::ISource;
+use crate::nodes::node::{Node, Numeric};
+use crate::parser::options::ParserOptions;
+use indexmap::IndexMap;
+
+/// Parses a JSON document from a source into a Node tree.
+///
+/// # Arguments
+/// * `source` - The source to read JSON from
+///
+/// # Returns
+/// The parsed Node or an error describing the first syntax problem.
+pub fn parse(source: &mut dyn ISource) -> Result<Node, String> {
+    parse_with_options(source, &mut ParserOptions::new()).map_err(|e| e.to_string())
+}
+
+/// Parses a JSON document from a source into a Node tree using the given options.
+/// The numeric policy, key normalization and cancellation settings apply; the YAML
+/// schema does not, as JSON scalars have only one reading.
+///
+/// # Arguments
+/// * `source` - The source to read JSON from
+/// * `options` - Options controlling the parse
+///
+/// # Returns
+/// The parsed Node, a syntax error, or ParseError::Cancelled if the parse was cancelled or
+/// its deadline passed.
+pub fn parse_with_options(source: &mut dyn ISource, options: &mut ParserOptions) -> Result<Node, ParseError> {
+    let mut parser = JsonParser { source, options, depth: 0 };
+    parser.skip_whitespace();
+    let node = parser.value()?;
+    parser.skip_whitespace();
+    match parser.source.current() {
+        Some(c) => Err(parser.error(&format!("Unexpected '{}' after JSON value", c))),
+        None => Ok(node),
+    }
+}
+
+/// JSON reader for a single source.
+struct JsonParser<'a> {
+    source: &'a mut dyn ISource,
+    options: &'a ParserOptions,
+    /// Number of objects and arrays currently open
+    depth: usize,
+}
+
+impl JsonParser<'_> {
+    /// Returns a syntax error noting the current position in the source.
+    fn error(&mut self, message: &str) -> ParseError {
+        ParseError::Syntax(format!("{} at position {}", message, self.source.position()))
+    }
+
+    /// Opens an object or array, failing if that exceeds the maximum depth.
+    fn enter(&mut self) -> Result<(), ParseError> {
+        self.depth += 1;
+        if self.depth > self.options.max_depth {
+            return Err(self.error(&format!("Nesting exceeds the maximum depth of {}", self.options.max_depth)));
+        }
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.source.current().is_some_and(|c| matches!(c, ' ' | '\t' | '\n' | '\r')) {
+            self.source.next();
+        }
+    }
+
+    /// Consumes an expected character.
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.source.current() {
+            Some(c) if c == expected => {
+                self.source.next();
+                Ok(())
+            }
+            Some(c) => Err(self.error(&format!("Expected '{}' but found '{}'", expected, c))),
+            None => Err(self.error(&format!("Expected '{}' but found end of input", expected))),
+        }
+    }
+
+    /// Parses a value starting at the current character.
+    fn value(&mut self) -> Result<Node, ParseError> {
+        match self.source.current() {
+            Some('{') => {
+                self.enter()?;
+                let object = self.object();
+                self.depth -= 1;
+                object
+            }
+            Some('[') => {
+                self.enter()?;
+                let array = self.array();
+                self.depth -= 1;
+                array
+            }
+            Some('"') => Ok(Node::Str(self.string()?)),
+            Some('-' | '0'..='9') => self.number(),
+            Some('t') => self.literal("true", Node::Boolean(true)),
+            Some('f') => self.literal("false", Node::Boolean(false)),
+            Some('n') => self.literal("null", Node::None),
+            Some(c) => Err(self.error(&format!("Unexpected '{}' where a JSON value was expected", c))),
+            None => Err(self.error("Unexpected end of input where a JSON value was expected")),
+        }
+    }
+
+    fn literal(&mut self, word: &str, node: Node) -> Result<Node, ParseError> {
+        for expected in word.chars() {
+            if self.source.current() != Some(expected) {
+                return Err(self.error(&format!("Invalid literal, expected {}", word)));
+            }
+            self.source.next();
+        }
+        Ok(node)
+    }
+
+    fn object(&mut self) -> Result<Node, ParseError> {
+        self.source.next(); // Skip '{'
+        let mut map = IndexMap::new();
+        self.skip_whitespace();
+        if self.source.current() == Some('}') {
+            self.source.next();
+            return Ok(Node::Dictionary(map));
+        }
+        loop {
+            if self.options.is_cancelled() {
+                return Err(ParseError::Cancelled);
+            }
+            self.skip_whitespace();
+            if self.source.current() != Some('"') {
+                return Err(self.error("Expected a string key in JSON object"));
+            }
+            let key = self.string()?;
+            #[cfg(feature = "unicode-normalization")]
+            let key = if self.options.normalize_keys {
+                crate::nodes::unicode::normalize_key(&key).into_owned()
+            } else {
+                key
+            };
+            self.skip_whitespace();
+            self.expect(':')?;
+            self.skip_whitespace();
+            let value = self.value()?;
+            map.insert(key, value);
+            self.skip_whitespace();
+            match self.source.current() {
+                Some(',') => self.source.next(),
+                Some('}') => {
+                    self.source.next();
+                    return Ok(Node::Dictionary(map));
+                }
+                _ => return Err(self.error("Expected ',' or '}' in JSON object")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Node, ParseError> {
+        self.source.next(); // Skip '['
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.source.current() == Some(']') {
+            self.source.next();
+            return Ok(Node::Array(items));
+        }
+        loop {
+            if self.options.is_cancelled() {
+                return Err(ParseError::Cancelled);
+            }
+            self.skip_whitespace();
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.source.current() {
+                Some(',') => self.source.next(),
+                Some(']') => {
+                    self.source.next();
+                    return Ok(Node::Array(items));
+                }
+                _ => return Err(self.error("Expected ',' or ']' in JSON array")),
+            }
+        }
+    }
+
+    /// Reads a string starting at its opening quote, processing escapes.
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.source.next(); // Skip '"'
+        let mut text = String::new();
+        loop {
+            match self.source.current() {
+                Some('"') => {
+                    self.source.next();
+                    return Ok(text);
+                }
+                Some('\\') => {
+                    self.source.next();
+                    let escaped = match self.source.current() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.unicode_escape()?,
+                        _ => return Err(self.error("Invalid escape in JSON string")),
+                    };
+                    text.push(escaped);
+                    self.source.next();
+                }
+                Some(c) if c < ' ' => return Err(self.error("Unescaped control character in JSON string")),
+                Some(c) => {
+                    text.push(c);
+                    self.source.next();
+                }
+                None => return Err(self.error("Unterminated JSON string")),
+            }
+        }
+    }
+
+    /// Reads the four hex digits of a \u escape (the 'u' is current), and the low half of a
+    /// surrogate pair when they give the high half, leaving the last digit current.
+    fn unicode_escape(&mut self) -> Result<char, ParseError> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| self.error("Unpaired surrogate in JSON string"));
+        }
+        self.source.next();
+        if self.source.current() != Some('\\') {
+            return Err(self.error("Unpaired surrogate in JSON string"));
+        }
+        self.source.next();
+        if self.source.current() != Some('u') {
+            return Err(self.error("Unpaired surrogate in JSON string"));
+        }
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err(self.error("Unpaired surrogate in JSON string"));
+        }
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+            .ok_or_else(|| self.error("Invalid surrogate pair in JSON string"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, ParseError> {
+        let mut value = 0;
+        for _ in 0..4 {
+            self.source.next();
+            let digit = self.source.current().and_then(|c| c.to_digit(16));
+            value = value * 16 + digit.ok_or_else(|| self.error("Invalid \\u escape in JSON string"))?;
+        }
+        Ok(value)
+    }
+
+    fn digits(&mut self, text: &mut String) -> usize {
+        let start = text.len();
+        while let Some(c) = self.source.current().filter(char::is_ascii_digit) {
+            text.push(c);
+            self.source.next();
+        }
+        text.len() - start
+    }
+
+    fn number(&mut self) -> Result<Node, ParseError> {
+        let mut text = String::new();
+        if self.source.current() == Some('-') {
+            text.push('-');
+            self.source.next();
+        }
+        let integer_start = text.len();
+        if self.digits(&mut text) == 0 {
+            return Err(self.error("Expected a digit in JSON number"));
+        }
+        if text[integer_start..].starts_with('0') && text.len() - integer_start > 1 {
+            return Err(self.error("Leading zero in JSON number"));
+        }
+        let mut float = false;
+        if self.source.current() == Some('.') {
+            text.push('.');
+            self.source.next();
+            if self.digits(&mut text) == 0 {
+                return Err(self.error("Expected a digit after '.' in JSON number"));
+            }
+            float = true;
+        }
+        if let Some(exponent @ ('e' | 'E')) = self.source.current() {
+            text.push(exponent);
+            self.source.next();
+            if let Some(sign @ ('+' | '-')) = self.source.current() {
+                text.push(sign);
+                self.source.next();
+            }
+            if self.digits(&mut text) == 0 {
+                return Err(self.error("Expected a digit in JSON number exponent"));
+            }
+            float = true;
+        }
+        let number = if float {
+            None
+        } else if let Ok(value) = text.parse::<i64>() {
+            Some(Numeric::Integer(value))
+        } else {
+            text.parse::<u64>().ok().map(Numeric::UInteger)
+        };
+        // Integers too large for 64 bits are kept as their nearest float
+        let number = number.unwrap_or_else(|| Numeric::Float(text.parse().unwrap_or(f64::NAN)));
+        Ok(Node::Number(number.with_policy(self.options.numeric_policy)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::sources::buffer::Buffer;
+    use crate::stringify::json::{stringify_with_options, JsonOptions};
+
+    fn parse_text(text: &str) -> Result<Node, String> {
+        parse(&mut Buffer::new(text.as_bytes()))
+    }
+
+    #[test]
+    fn parse_json_works() {
+        let text = r#" {"name": "web", "ports": [80, 443], "tls": {"enabled": true, "ratio": 0.5e1},
+            "none": null, "empty": [], "big": 18446744073709551615, "neg": -1.5E-3, "off": false} "#;
+        let expected = Node::dict()
+            .key("name", "web")
+            .key("ports", vec![80, 443])
+            .key("tls", Node::dict().key("enabled", true).key("ratio", 5.0))
+            .key("none", Node::None)
+            .key("empty", Node::from(Vec::<Node>::new()))
+            .key("big", Node::Number(Numeric::UInteger(u64::MAX)))
+            .key("neg", -0.0015)
+            .key("off", false)
+            .build();
+        assert_eq!(parse_text(text).unwrap(), expected);
+        for options in [JsonOptions::new(), JsonOptions::pretty()] {
+            let mut destination = crate::io::destinations::buffer::Buffer::new();
+            stringify_with_options(&expected, &mut destination, &options).unwrap();
+            assert_eq!(parse_text(&destination.to_string()).unwrap(), expected);
+        }
+        assert_eq!(parse_text("42").unwrap(), Node::from(42));
+        assert_eq!(parse_text("[1e400]").unwrap(), Node::from(vec![f64::INFINITY]));
+    }
+
+    #[test]
+    fn parse_json_strings_works() {
+        let node = parse_text(r#""a\"b\\c\/d\n\t\u00e9\ud83d\ude00""#).unwrap();
+        assert_eq!(node, Node::from("a\"b\\c/d\n\té😀"));
+        assert_eq!(parse_text("\"café\"").unwrap(), Node::from("café"));
+    }
+
+    #[test]
+    fn parse_json_rejects_invalid_documents() {
+        let invalid = [
+            "", "[1,]", "{\"a\":1,}", "{a:1}", "['a']", "01", "1.", "-", ".5", "+1", "[1 2]", "tru", "nul",
+            "\"open", "\"\\x\"", "\"\\ud83d\"", "\"tab\there\"", "[1] x", "// c\n1", "{\"a\" 1}", "NaN",
+        ];
+        for text in invalid {
+            assert!(parse_text(text).is_err(), "{:?}", text);
+        }
+        assert_eq!(parse_text("[1, 2").unwrap_err(), "Expected ',' or ']' in JSON array at position 5");
+    }
+
+    #[test]
+    fn parse_json_limits_nesting_depth() {
+        let deep = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert_eq!(parse_text(&deep).unwrap_err(), "Nesting exceeds the maximum depth of 128 at position 128");
+        let mut options = ParserOptions { max_depth: 2, ..ParserOptions::new() };
+        assert!(parse_with_options(&mut Buffer::new(b"{\"a\": [1]}"), &mut options).is_ok());
+        assert!(parse_with_options(&mut Buffer::new(b"{\"a\": [{}]}"), &mut options).is_err());
+        assert!(parse_with_options(&mut Buffer::new(b"[[1], [2], [3]]"), &mut options).is_ok());
+    }
+
+    #[test]
+    fn parse_json_applies_options() {
+        use crate::nodes::node::NumericPolicy;
+        let mut options = ParserOptions::new();
+        options.numeric_policy = NumericPolicy::SmallestFit;
+        let node = parse_with_options(&mut Buffer::new(b"[42, 70000]"), &mut options).unwrap();
+        assert!(node.strict_eq(&Node::Array(vec![
+            Node::Number(Numeric::Int8(42)),
+            Node::Number(Numeric::Int32(70000))
+        ])));
+        let token = crate::parser::options::CancellationToken::new();
+        token.cancel();
+        options.cancellation = Some(token);
+        let result = parse_with_options(&mut Buffer::new(b"[1]"), &mut options);
+        assert_eq!(result, Err(ParseError::Cancelled));
+    }
+}
@@ -0,0 +1,108 @@
+//! Lookahead source.
+//! Wraps a source so the YAML parser can look several characters ahead before deciding
+//! how to read them, for example to tell a "---" document marker from a "--flag" key or
+//! a "key: value" line from a plain scalar. Characters looked at are held in a queue and
+//! handed out again in order, and position counts only the characters handed out.
+
+use crate::io::traits::ISource;
+use std::collections::VecDeque;
+
+/// A source that can be peeked any number of characters ahead.
+pub(crate) struct Lookahead<'a> {
+    source: &'a mut dyn ISource,
+    /// Characters read ahead of the current position, with their lengths in bytes
+    queue: VecDeque<(char, usize)>,
+    /// The character the last call to next moved past, so backup can return to it
+    last: Option<(char, usize)>,
+}
+
+impl<'a> Lookahead<'a> {
+    /// Creates a Lookahead reading from a source.
+    pub(crate) fn new(source: &'a mut dyn ISource) -> Self {
+        Self { source, queue: VecDeque::new(), last: None }
+    }
+
+    /// Returns the character offset places after the current one (0 for the current one)
+    /// without moving past anything.
+    pub(crate) fn peek(&mut self, offset: usize) -> Option<char> {
+        while self.queue.len() <= offset {
+            let c = self.source.current()?;
+            let start = self.source.position();
+            self.source.next();
+            self.queue.push_back((c, self.source.position() - start));
+        }
+        self.queue.get(offset).map(|(c, _)| *c)
+    }
+}
+
+impl ISource for Lookahead<'_> {
+    fn next(&mut self) {
+        self.last = match self.queue.pop_front() {
+            Some(entry) => Some(entry),
+            None => {
+                let c = self.source.current();
+                let start = self.source.position();
+                self.source.next();
+                c.map(|c| (c, self.source.position() - start))
+            }
+        };
+    }
+
+    fn current(&mut self) -> Option<char> {
+        match self.queue.front() {
+            Some((c, _)) => Some(*c),
+            None => self.source.current(),
+        }
+    }
+
+    fn more(&mut self) -> bool {
+        !self.queue.is_empty() || self.source.more()
+    }
+
+    fn reset(&mut self) {
+        self.queue.clear();
+        self.last = None;
+        self.source.reset();
+    }
+
+    fn backup(&mut self) {
+        if let Some(entry) = self.last.take() {
+            self.queue.push_front(entry);
+        }
+    }
+
+    fn position(&mut self) -> usize {
+        self.source.position() - self.queue.iter().map(|(_, length)| length).sum::<usize>()
+    }
+
+    fn length(&mut self) -> Option<usize> {
+        self.source.length()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::sources::buffer::Buffer;
+
+    #[test]
+    fn peek_does_not_move_the_position() {
+        let mut buffer = Buffer::new("a\u{e9}cd".as_bytes());
+        let mut source = Lookahead::new(&mut buffer);
+        assert_eq!(source.peek(2), Some('c'));
+        assert_eq!(source.position(), 0);
+        assert_eq!(source.current(), Some('a'));
+        source.next();
+        assert_eq!(source.position(), 1);
+        source.next();
+        assert_eq!(source.position(), 3);
+        assert_eq!(source.peek(5), None);
+        assert_eq!(source.current(), Some('c'));
+        source.backup();
+        assert_eq!(source.current(), Some('\u{e9}'));
+        source.next();
+        source.next();
+        source.next();
+        assert_eq!((source.current(), source.position(), source.more()), (None, 5, false));
+    }
+}
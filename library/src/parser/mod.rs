@@ -2,6 +2,49 @@
 //! Implements parsing of YAML text into internal data structures
 //! Supports standard YAML types and syntax validation
 
+/// Optional tracing instrumentation macros
+#[macro_use]
+mod trace;
+
+/// Bencode parser
+/// Reads bencode data such as .torrent files into Node trees
+pub mod bencode;
+/// CBOR parser
+/// Reads CBOR data items into Node trees
+#[cfg(feature = "cbor")]
+pub mod cbor;
+/// Collection storage
+/// Gathers the children of collections being parsed, inline with compact-collections
+mod compact;
+/// CSV parser
+/// Reads CSV rows into an array of dictionaries keyed by the header
+pub mod csv;
+/// Dotenv parser
+/// Reads KEY=value lines of .env files into dictionaries of strings
+pub mod dotenv;
 /// Default parser implementation
 /// Handles YAML parsing and error reporting functionality
-pub mod default;
\ No newline at end of file
+pub mod default;
+/// JSON parser
+/// Reads strict JSON documents into Node trees
+pub mod json;
+/// Lookahead source
+/// Lets the YAML parser look several characters ahead of its position
+pub(crate) mod lookahead;
+/// MessagePack parser
+/// Reads MessagePack values into Node trees
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+/// Parser configuration options
+/// Controls optional behaviour such as progress reporting
+pub mod options;
+/// Parse reporting
+/// Metrics gathered about a completed parse
+pub mod report;
+/// Timestamp formatting
+/// Renders epoch times read by the binary format parsers as RFC 3339 text
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+mod timestamp;
+/// Parse warnings
+/// Non-fatal issues reported alongside a successful parse
+pub mod warning;
@@ -0,0 +1,253 @@
+//! MessagePack parser.
+//! Reads a single MessagePack value into a Node tree: nil becomes None, integers Integer
+//! (or UInteger above i64::MAX), floats Float, strings Str, arrays Array and maps
+//! Dictionary. Map keys must be strings or integers, the latter stored as their decimal
+//! text. Node has no binary or timestamp variants, so bin data is decoded as lossy UTF-8
+//! text and the timestamp extension (type -1) as an RFC 3339 UTC string; other extension
+//! types are errors, as is anything after the value or arrays and maps nested deeper than
+//! DEFAULT_MAX_DEPTH.
+
+use crate::nodes::node::{Node, Numeric};
+use crate::parser::options::DEFAULT_MAX_DEPTH;
+use crate::parser::timestamp::format_timestamp;
+use indexmap::IndexMap;
+
+/// Parses a MessagePack value into a Node tree.
+///
+/// # Arguments
+/// * `bytes` - The MessagePack data
+///
+/// # Returns
+/// The parsed Node or an error describing the first problem and its byte position.
+pub fn parse(bytes: &[u8]) -> Result<Node, String> {
+    let mut parser = MsgpackParser { bytes, position: 0, depth: 0 };
+    let node = parser.value()?;
+    if parser.position < bytes.len() {
+        return Err(parser.error("Unexpected data after MessagePack value"));
+    }
+    Ok(node)
+}
+
+struct MsgpackParser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    /// Number of arrays and maps currently open
+    depth: usize,
+}
+
+impl<'a> MsgpackParser<'a> {
+    fn error(&self, message: &str) -> String {
+        format!("{} at position {}", message, self.position)
+    }
+
+    /// Takes the next `count` bytes, failing if the data ends first.
+    fn take(&mut self, count: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() - self.position < count {
+            return Err(self.error("Unexpected end of MessagePack data"));
+        }
+        let taken = &self.bytes[self.position..self.position + count];
+        self.position += count;
+        Ok(taken)
+    }
+
+    /// Opens an array or map, failing if that exceeds the maximum depth.
+    fn enter(&mut self) -> Result<(), String> {
+        if self.depth >= DEFAULT_MAX_DEPTH {
+            return Err(self.error(&format!("Nesting exceeds the maximum depth of {}", DEFAULT_MAX_DEPTH)));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    /// Reads a big endian length of 1, 2 or 4 bytes.
+    fn length(&mut self, size: usize) -> Result<usize, String> {
+        Ok(match size {
+            1 => self.take_array::<1>()?[0] as usize,
+            2 => u16::from_be_bytes(self.take_array()?) as usize,
+            _ => u32::from_be_bytes(self.take_array()?) as usize,
+        })
+    }
+
+    fn value(&mut self) -> Result<Node, String> {
+        let marker = self.take_array::<1>()?[0];
+        let integer = |value: i128| match i64::try_from(value) {
+            Ok(value) => Node::Number(Numeric::Integer(value)),
+            Err(_) => Node::Number(Numeric::UInteger(value as u64)),
+        };
+        Ok(match marker {
+            0x00..=0x7f => integer(marker as i128),
+            0x80..=0x8f => self.map(marker as usize & 0x0f)?,
+            0x90..=0x9f => self.array(marker as usize & 0x0f)?,
+            0xa0..=0xbf => Node::Str(self.string(marker as usize & 0x1f)?),
+            0xc0 => Node::None,
+            0xc2 => Node::Boolean(false),
+            0xc3 => Node::Boolean(true),
+            0xc4..=0xc6 => {
+                let length = self.length(1 << (marker - 0xc4))?;
+                Node::Str(String::from_utf8_lossy(self.take(length)?).into_owned())
+            }
+            0xc7..=0xc9 => {
+                let length = self.length(1 << (marker - 0xc7))?;
+                self.extension(length)?
+            }
+            0xca => Node::from(f32::from_be_bytes(self.take_array()?) as f64),
+            0xcb => Node::from(f64::from_be_bytes(self.take_array()?)),
+            0xcc => integer(self.take_array::<1>()?[0] as i128),
+            0xcd => integer(u16::from_be_bytes(self.take_array()?) as i128),
+            0xce => integer(u32::from_be_bytes(self.take_array()?) as i128),
+            0xcf => integer(u64::from_be_bytes(self.take_array()?) as i128),
+            0xd0 => integer(self.take_array::<1>()?[0] as i8 as i128),
+            0xd1 => integer(i16::from_be_bytes(self.take_array()?) as i128),
+            0xd2 => integer(i32::from_be_bytes(self.take_array()?) as i128),
+            0xd3 => integer(i64::from_be_bytes(self.take_array()?) as i128),
+            0xd4..=0xd8 => self.extension(1 << (marker - 0xd4))?,
+            0xd9..=0xdb => {
+                let length = self.length(1 << (marker - 0xd9))?;
+                Node::Str(self.string(length)?)
+            }
+            0xdc | 0xdd => {
+                let length = self.length(if marker == 0xdc { 2 } else { 4 })?;
+                self.array(length)?
+            }
+            0xde | 0xdf => {
+                let length = self.length(if marker == 0xde { 2 } else { 4 })?;
+                self.map(length)?
+            }
+            0xe0..=0xff => integer(marker as i8 as i128),
+            0xc1 => {
+                self.position -= 1;
+                return Err(self.error("Invalid MessagePack marker 0xc1"));
+            }
+        })
+    }
+
+    fn string(&mut self, length: usize) -> Result<String, String> {
+        let start = self.position;
+        let bytes = self.take(length)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| {
+            format!("Invalid UTF-8 in MessagePack string at position {}", start)
+        })
+    }
+
+    fn array(&mut self, length: usize) -> Result<Node, String> {
+        self.enter()?;
+        // Every element takes at least a byte, which bounds the allocation for bogus lengths
+        let mut items = Vec::with_capacity(length.min(self.bytes.len() - self.position));
+        for _ in 0..length {
+            items.push(self.value()?);
+        }
+        self.depth -= 1;
+        Ok(Node::Array(items))
+    }
+
+    fn map(&mut self, length: usize) -> Result<Node, String> {
+        self.enter()?;
+        let mut map = IndexMap::new();
+        for _ in 0..length {
+            let start = self.position;
+            let key = match self.value()? {
+                Node::Str(key) => key,
+                Node::Number(number) if number.as_wide_integer().is_some() => {
+                    number.as_wide_integer().unwrap_or_default().to_string()
+                }
+                _ => return Err(format!("MessagePack map key must be a string or integer at position {}", start)),
+            };
+            let value = self.value()?;
+            map.insert(key, value);
+        }
+        self.depth -= 1;
+        Ok(Node::Dictionary(map))
+    }
+
+    /// Reads an extension value of a given data length; only timestamps are supported.
+    fn extension(&mut self, length: usize) -> Result<Node, String> {
+        let start = self.position;
+        let kind = self.take_array::<1>()?[0] as i8;
+        let data = self.take(length)?;
+        let (seconds, nanoseconds) = match (kind, length) {
+            (-1, 4) => (u32::from_be_bytes(data.try_into().unwrap()) as i64, 0),
+            (-1, 8) => {
+                let value = u64::from_be_bytes(data.try_into().unwrap());
+                ((value & 0x3_ffff_ffff) as i64, (value >> 34) as u32)
+            }
+            (-1, 12) => {
+                let nanoseconds = u32::from_be_bytes(data[..4].try_into().unwrap());
+                (i64::from_be_bytes(data[4..].try_into().unwrap()), nanoseconds)
+            }
+            (-1, _) => return Err(format!("Invalid MessagePack timestamp length {} at position {}", length, start)),
+            _ => return Err(format!("Unsupported MessagePack extension type {} at position {}", kind, start)),
+        };
+        if nanoseconds >= 1_000_000_000 {
+            return Err(format!("Invalid MessagePack timestamp at position {}", start));
+        }
+        Ok(Node::Str(format_timestamp(seconds, nanoseconds)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+    use crate::stringify::msgpack::stringify;
+
+    #[test]
+    fn parse_msgpack_round_trips() {
+        let node = Node::dict()
+            .key("list", vec![Node::from(-40000), Node::from(255), Node::None, Node::from(false)])
+            .key("float", 0.25)
+            .key("text", "café ".repeat(10))
+            .key("big", Node::Number(Numeric::UInteger(u64::MAX)))
+            .key("nested", Node::dict().key("min", i64::MIN).build())
+            .build();
+        let mut destination = Buffer::new();
+        stringify(&node, &mut destination).unwrap();
+        assert_eq!(parse(&destination.buffer).unwrap(), node);
+    }
+
+    #[test]
+    fn parse_msgpack_maps_other_types() {
+        // float 32, bin 8 holding invalid UTF-8, and an integer map key
+        let bytes = [0x93, 0xca, 0x3f, 0xc0, 0x00, 0x00, 0xc4, 0x02, 0xff, b'a', 0x81, 0x07, 0xa1, b'x'];
+        let expected = Node::Array(vec![Node::from(1.5), Node::from("\u{FFFD}a"), Node::dict().key("7", "x").build()]);
+        assert_eq!(parse(&bytes).unwrap(), expected);
+    }
+
+    #[test]
+    fn parse_msgpack_decodes_timestamps() {
+        assert_eq!(parse(&[0xd6, 0xff, 0, 0, 0, 0]).unwrap(), Node::from("1970-01-01T00:00:00Z"));
+        let mut bytes = vec![0xd7, 0xff];
+        bytes.extend_from_slice(&((500_000_000u64 << 34) | 1_709_210_096).to_be_bytes());
+        assert_eq!(parse(&bytes).unwrap(), Node::from("2024-02-29T12:34:56.5Z"));
+        let mut bytes = vec![0xc7, 12, 0xff, 0, 0, 0, 1];
+        bytes.extend_from_slice(&(-86_401i64).to_be_bytes());
+        assert_eq!(parse(&bytes).unwrap(), Node::from("1969-12-30T23:59:59.000000001Z"));
+    }
+
+    #[test]
+    fn parse_msgpack_rejects_invalid_data() {
+        assert_eq!(parse(&[0xc1]).unwrap_err(), "Invalid MessagePack marker 0xc1 at position 0");
+        assert_eq!(parse(&[0x92, 0x01]).unwrap_err(), "Unexpected end of MessagePack data at position 2");
+        assert_eq!(parse(&[0x01, 0x02]).unwrap_err(), "Unexpected data after MessagePack value at position 1");
+        assert_eq!(parse(&[0xa1, 0xff]).unwrap_err(), "Invalid UTF-8 in MessagePack string at position 1");
+        assert!(parse(&[0x81, 0xc0, 0x01]).is_err());
+        assert!(parse(&[0xd4, 0x05, 0x00]).is_err());
+        assert!(parse(&[0xdd, 0xff, 0xff, 0xff, 0xff]).is_err());
+    }
+
+    #[test]
+    fn parse_msgpack_limits_nesting_depth() {
+        let deep = vec![0x91; 200_000];
+        assert_eq!(parse(&deep).unwrap_err(), "Nesting exceeds the maximum depth of 128 at position 129");
+        let mut nested = vec![0x91; DEFAULT_MAX_DEPTH];
+        nested.push(0xc0);
+        assert!(parse(&nested).is_ok());
+        nested.insert(0, 0x81);
+        nested.insert(1, 0xa1);
+        nested.insert(2, b'k');
+        assert!(parse(&nested).is_err());
+    }
+}
@@ -0,0 +1,207 @@
+//! Options controlling the behaviour of the YAML parser.
+//! Allows callers to hook into parsing progress for long running parses
+//! and to abort them cooperatively.
+
+use crate::nodes::node::NumericPolicy;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Progress information passed to the progress callback while parsing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Progress {
+    /// Number of bytes consumed from the source so far
+    pub bytes_read: usize,
+    /// Total number of bytes in the source, if the source knows its length
+    pub total_bytes: Option<usize>,
+    /// Number of documents that have been completely parsed
+    pub documents: usize,
+}
+
+impl Progress {
+    /// Returns the fraction of the source consumed (0.0 to 1.0), if the total length is known.
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total_bytes {
+            Some(0) => Some(1.0),
+            Some(total) => Some(self.bytes_read as f64 / total as f64),
+            None => None,
+        }
+    }
+}
+
+/// A cloneable token used to cancel a parse from another thread or callback.
+/// All clones share the same cancellation flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    /// Shared flag set when cancellation is requested
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a new token that has not been cancelled.
+    pub fn new() -> Self {
+        Self { cancelled: Arc::new(AtomicBool::new(false)) }
+    }
+    /// Requests cancellation of any parse using this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+    /// Returns true if cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// The YAML schema used to resolve plain scalar values.
+/// Mapping keys are always resolved with the failsafe schema and so remain strings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Schema {
+    /// Every scalar is a string
+    Failsafe,
+    /// Only JSON compatible null, boolean and number forms are recognised
+    Json,
+    /// YAML 1.2 core schema (null/~, true/false, integers and floats)
+    #[default]
+    Core,
+}
+
+/// Callback type invoked with progress information during parsing.
+pub type ProgressCallback = Box<dyn FnMut(&Progress)>;
+
+/// Default number of bytes consumed between progress callbacks.
+pub const DEFAULT_PROGRESS_INTERVAL: usize = 64 * 1024;
+
+/// Default maximum number of collections that may be nested inside one another.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Options used to configure a parse.
+pub struct ParserOptions {
+    /// Optional callback receiving progress updates
+    pub progress: Option<ProgressCallback>,
+    /// Minimum number of bytes consumed between progress updates; updates are
+    /// also sent whenever a document completes and when parsing finishes
+    pub progress_interval: usize,
+    /// Optional token that aborts the parse when cancelled
+    pub cancellation: Option<CancellationToken>,
+    /// Optional point in time after which the parse is aborted
+    pub deadline: Option<Instant>,
+    /// Numeric variants used for parsed integers
+    pub numeric_policy: NumericPolicy,
+    /// Schema used to resolve plain scalar values
+    pub schema: Schema,
+    /// Maximum number of collections that may be nested inside one another; deeper input
+    /// is a syntax error rather than exhausting the stack (the JSON parser honours it too)
+    pub max_depth: usize,
+    /// NFC normalize mapping keys as they are inserted
+    #[cfg(feature = "unicode-normalization")]
+    pub normalize_keys: bool,
+}
+
+impl ParserOptions {
+    /// Creates a new ParserOptions instance with default settings.
+    ///
+    /// # Returns
+    /// ParserOptions with no progress callback attached.
+    pub fn new() -> Self {
+        Self {
+            progress: None,
+            progress_interval: DEFAULT_PROGRESS_INTERVAL,
+            cancellation: None,
+            deadline: None,
+            numeric_policy: NumericPolicy::Canonical,
+            schema: Schema::Core,
+            max_depth: DEFAULT_MAX_DEPTH,
+            #[cfg(feature = "unicode-normalization")]
+            normalize_keys: false,
+        }
+    }
+
+    /// Attaches a progress callback to the options.
+    ///
+    /// # Arguments
+    /// * `callback` - Function called with the current parse progress
+    pub fn on_progress<F>(&mut self, callback: F)
+    where
+        F: FnMut(&Progress) + 'static,
+    {
+        self.progress = Some(Box::new(callback));
+    }
+
+    /// Sets a deadline relative to now after which the parse is aborted.
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum time the parse may take
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.deadline = Some(Instant::now() + timeout);
+    }
+
+    /// Returns true if the parse should be aborted because of cancellation or an expired deadline.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.as_ref().is_some_and(|token| token.is_cancelled())
+            || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_options_have_no_progress_callback() {
+        let options = ParserOptions::new();
+        assert!(options.progress.is_none());
+        assert_eq!(options.progress_interval, DEFAULT_PROGRESS_INTERVAL);
+    }
+
+    #[test]
+    fn on_progress_attaches_callback() {
+        let mut options = ParserOptions::new();
+        options.on_progress(|_| {});
+        assert!(options.progress.is_some());
+    }
+
+    #[test]
+    fn progress_fraction_works() {
+        let progress = Progress { bytes_read: 25, total_bytes: Some(100), documents: 0 };
+        assert_eq!(progress.fraction(), Some(0.25));
+        let progress = Progress { bytes_read: 0, total_bytes: Some(0), documents: 0 };
+        assert_eq!(progress.fraction(), Some(1.0));
+        let progress = Progress { bytes_read: 10, total_bytes: None, documents: 0 };
+        assert_eq!(progress.fraction(), None);
+    }
+
+    #[test]
+    fn cancellation_token_works() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!token.is_cancelled());
+        clone.cancel();
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn options_cancelled_by_token() {
+        let mut options = ParserOptions::new();
+        assert!(!options.is_cancelled());
+        let token = CancellationToken::new();
+        options.cancellation = Some(token.clone());
+        assert!(!options.is_cancelled());
+        token.cancel();
+        assert!(options.is_cancelled());
+    }
+
+    #[test]
+    fn options_cancelled_by_deadline() {
+        let mut options = ParserOptions::new();
+        options.set_timeout(Duration::from_secs(3600));
+        assert!(!options.is_cancelled());
+        options.deadline = Some(Instant::now());
+        assert!(options.is_cancelled());
+    }
+}
@@ -0,0 +1,154 @@
+//! Parse reporting for the YAML parser.
+//! Collects metrics about a completed parse such as document and node counts,
+//! nesting depth and the number of bytes read.
+
+use crate::nodes::anchors::Anchors;
+use crate::nodes::comments::Comments;
+use crate::nodes::node::Node;
+use crate::parser::warning::ParseWarning;
+
+/// Number of nodes of each type found in a tree.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NodeCounts {
+    /// Number of Boolean nodes
+    pub booleans: usize,
+    /// Number of Number nodes
+    pub numbers: usize,
+    /// Number of Str nodes
+    pub strings: usize,
+    /// Number of Array nodes
+    pub arrays: usize,
+    /// Number of Dictionary nodes
+    pub dictionaries: usize,
+    /// Number of Comment nodes
+    pub comments: usize,
+    /// Number of Document nodes
+    pub documents: usize,
+    /// Number of None (null) nodes
+    pub nulls: usize,
+}
+
+impl NodeCounts {
+    /// Returns the total number of nodes counted.
+    pub fn total(&self) -> usize {
+        self.booleans + self.numbers + self.strings + self.arrays + self.dictionaries
+            + self.comments + self.documents + self.nulls
+    }
+
+    /// Adds a single node (not its children) to the counts.
+    pub fn add(&mut self, node: &Node) {
+        match node {
+            Node::Boolean(_) => self.booleans += 1,
+            Node::Number(_) => self.numbers += 1,
+            Node::Str(_) => self.strings += 1,
+            Node::Array(_) => self.arrays += 1,
+            Node::Dictionary(_) => self.dictionaries += 1,
+            Node::Comment(_) => self.comments += 1,
+            Node::Document(_) => self.documents += 1,
+            Node::None => self.nulls += 1,
+        }
+    }
+}
+
+/// Metrics describing a completed parse.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParseReport {
+    /// Number of YAML documents parsed
+    pub documents: usize,
+    /// Number of nodes of each type in the parsed tree
+    pub nodes: NodeCounts,
+    /// Maximum nesting depth of the parsed tree (a lone scalar has depth 1)
+    pub max_depth: usize,
+    /// Number of aliases resolved while parsing
+    pub aliases_resolved: usize,
+    /// Number of bytes read from the source
+    pub bytes_read: usize,
+}
+
+impl ParseReport {
+    /// Fills in the node counts and maximum depth from a parsed tree.
+    ///
+    /// # Arguments
+    /// * `node` - Root of the parsed tree
+    pub fn count_nodes(&mut self, node: &Node) {
+        self.nodes = NodeCounts::default();
+        self.max_depth = 0;
+        self.visit(node, 1);
+    }
+
+    fn visit(&mut self, node: &Node, depth: usize) {
+        self.nodes.add(node);
+        self.max_depth = self.max_depth.max(depth);
+        match node {
+            Node::Array(items) | Node::Document(items) => {
+                for item in items {
+                    self.visit(item, depth + 1);
+                }
+            }
+            Node::Dictionary(map) => {
+                for value in map.values() {
+                    self.visit(value, depth + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The full result of a detailed parse: the parsed tree plus a report describing it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Parsed {
+    /// Root node of the parsed tree
+    pub node: Node,
+    /// Metrics gathered during the parse
+    pub report: ParseReport,
+    /// Non-fatal issues found during the parse
+    pub warnings: Vec<ParseWarning>,
+    /// Comments attached to nodes, keyed by node path
+    pub comments: Comments,
+    /// Anchors nodes were defined with, keyed by node path
+    pub anchors: Anchors,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn node_counts_add_works() {
+        let mut counts = NodeCounts::default();
+        counts.add(&Node::from(1));
+        counts.add(&Node::from("a"));
+        counts.add(&Node::None);
+        assert_eq!(counts.numbers, 1);
+        assert_eq!(counts.strings, 1);
+        assert_eq!(counts.nulls, 1);
+        assert_eq!(counts.total(), 3);
+    }
+
+    #[test]
+    fn count_nodes_of_scalar_works() {
+        let mut report = ParseReport::default();
+        report.count_nodes(&Node::Boolean(true));
+        assert_eq!(report.nodes.booleans, 1);
+        assert_eq!(report.max_depth, 1);
+    }
+
+    #[test]
+    fn count_nodes_of_nested_tree_works() {
+        let mut map = IndexMap::new();
+        map.insert("list".to_string(), Node::from(vec![1, 2]));
+        map.insert("name".to_string(), Node::from("x"));
+        let mut report = ParseReport::default();
+        report.count_nodes(&Node::Document(vec![Node::Comment("c".to_string()), Node::Dictionary(map)]));
+        assert_eq!(report.nodes.documents, 1);
+        assert_eq!(report.nodes.comments, 1);
+        assert_eq!(report.nodes.dictionaries, 1);
+        assert_eq!(report.nodes.arrays, 1);
+        assert_eq!(report.nodes.numbers, 2);
+        assert_eq!(report.nodes.strings, 1);
+        assert_eq!(report.nodes.total(), 7);
+        assert_eq!(report.max_depth, 4);
+    }
+}
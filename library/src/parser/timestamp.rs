@@ -0,0 +1,34 @@
+//! Timestamp formatting for the binary format parsers.
+//! MessagePack and CBOR carry timestamps as seconds since the Unix epoch; Node has no
+//! timestamp variant, so they are decoded as RFC 3339 UTC strings, which YAML reads as
+//! timestamps.
+
+/// Formats seconds and nanoseconds since the Unix epoch as an RFC 3339 UTC timestamp.
+pub(crate) fn format_timestamp(seconds: i64, nanoseconds: u32) -> String {
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+    // Converts days since the epoch to a proleptic Gregorian date (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    let mut text = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    );
+    if nanoseconds > 0 {
+        text.push_str(format!(".{:09}", nanoseconds).trim_end_matches('0'));
+    }
+    text.push('Z');
+    text
+}
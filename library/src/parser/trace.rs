@@ -0,0 +1,19 @@
+//! Optional tracing instrumentation for the parser.
+//! With the "tracing" feature enabled these macros forward to the tracing crate,
+//! otherwise they expand to nothing so there is no runtime cost.
+
+/// Emits a trace level event describing parser activity.
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(target: "yaml_lib::parser", $($arg)*);
+    };
+}
+
+/// Emits a debug level event describing a parse failure.
+macro_rules! trace_error {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(target: "yaml_lib::parser", $($arg)*);
+    };
+}
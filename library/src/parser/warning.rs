@@ -0,0 +1,82 @@
+//! Non-fatal warnings produced while parsing YAML.
+//! Warnings describe questionable input that does not prevent a successful parse.
+
+use std::fmt;
+
+/// The kind of issue a warning describes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+    /// A YAML 1.1 boolean form (yes/no/on/off/y/n) that YAML 1.2 treats as a string
+    DeprecatedBoolean,
+    /// A mapping key that appears more than once; the last value wins
+    DuplicateKey,
+    /// A line ending in tab characters
+    TrailingTab,
+    /// An anchor name defined more than once; aliases refer to the latest definition
+    DuplicateAnchor,
+}
+
+/// A non-fatal issue found while parsing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseWarning {
+    /// The kind of issue found
+    pub kind: WarningKind,
+    /// Human readable description of the issue
+    pub message: String,
+    /// Byte offset in the source at which the issue was detected
+    pub position: usize,
+}
+
+impl ParseWarning {
+    /// Creates a new warning.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of issue
+    /// * `message` - Description of the issue
+    /// * `position` - Byte offset in the source
+    pub fn new(kind: WarningKind, message: &str, position: usize) -> Self {
+        Self { kind, message: message.to_string(), position }
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at byte {})", self.message, self.position)
+    }
+}
+
+/// Returns true if the value is a YAML 1.1 only boolean form.
+pub fn is_deprecated_boolean(value: &str) -> bool {
+    matches!(
+        value,
+        "y" | "Y" | "yes" | "Yes" | "YES" | "n" | "N" | "no" | "No" | "NO"
+            | "on" | "On" | "ON" | "off" | "Off" | "OFF"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_warning_works() {
+        let warning = ParseWarning::new(WarningKind::DuplicateKey, "Duplicate key: a", 10);
+        assert_eq!(warning.kind, WarningKind::DuplicateKey);
+        assert_eq!(warning.message, "Duplicate key: a");
+        assert_eq!(warning.position, 10);
+    }
+
+    #[test]
+    fn display_warning_works() {
+        let warning = ParseWarning::new(WarningKind::TrailingTab, "Trailing tab", 3);
+        assert_eq!(warning.to_string(), "Trailing tab (at byte 3)");
+    }
+
+    #[test]
+    fn is_deprecated_boolean_works() {
+        assert!(is_deprecated_boolean("yes"));
+        assert!(is_deprecated_boolean("OFF"));
+        assert!(!is_deprecated_boolean("true"));
+        assert!(!is_deprecated_boolean("yesterday"));
+    }
+}
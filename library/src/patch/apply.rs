@@ -0,0 +1,201 @@
+//! Application of JSON Patch (RFC 6902) style operations to node trees.
+//! Patches are atomic: the operations are applied to a copy of the tree, which only
+//! replaces the original once every operation has succeeded.
+
+use crate::diff::operation::Operation;
+use crate::error::patch::PatchError;
+use crate::nodes::node::{lookup_key, Node};
+use crate::nodes::path::unescape_segment;
+use crate::nodes::pointer::parse_index;
+
+/// Applies a patch to a node tree.
+///
+/// # Arguments
+/// * `node` - The tree to patch
+/// * `patch` - Operations to apply in order
+///
+/// # Returns
+/// Ok if every operation succeeded, otherwise a PatchError for the first failing
+/// operation, in which case the tree is left unchanged.
+pub fn apply_patch(node: &mut Node, patch: &[Operation]) -> Result<(), PatchError> {
+    let mut patched = node.clone();
+    for (index, operation) in patch.iter().enumerate() {
+        apply_operation(&mut patched, operation).map_err(|reason| PatchError {
+            index,
+            operation: operation.name().to_string(),
+            path: operation.path().to_string(),
+            reason,
+        })?;
+    }
+    *node = patched;
+    Ok(())
+}
+
+fn apply_operation(node: &mut Node, operation: &Operation) -> Result<(), String> {
+    match operation {
+        Operation::Add { path, value } => add(node, path, value.clone()),
+        Operation::Remove { path } => remove(node, path).map(|_| ()),
+        Operation::Replace { path, value } => {
+            *node.pointer_mut(path).ok_or("path does not exist")? = value.clone();
+            Ok(())
+        }
+        Operation::Move { from, path } => {
+            if path.starts_with(&format!("{}/", from)) {
+                return Err("cannot move a value into one of its own children".to_string());
+            }
+            let value = remove(node, from)?;
+            add(node, path, value)
+        }
+        Operation::Copy { from, path } => {
+            let value = node.pointer(from).ok_or("from path does not exist")?.clone();
+            add(node, path, value)
+        }
+        Operation::Test { path, value } => match node.pointer(path) {
+            Some(found) if found == value => Ok(()),
+            Some(_) => Err("value does not match".to_string()),
+            None => Err("path does not exist".to_string()),
+        },
+    }
+}
+
+/// Splits a non-root path into the path of its parent and its final unescaped segment.
+fn split_last(path: &str) -> Result<(&str, String), String> {
+    match path.rfind('/') {
+        Some(position) => Ok((&path[..position], unescape_segment(&path[position + 1..]))),
+        None => Err("invalid path".to_string()),
+    }
+}
+
+fn add(node: &mut Node, path: &str, value: Node) -> Result<(), String> {
+    if path.is_empty() {
+        *node = value;
+        return Ok(());
+    }
+    let (parent, last) = split_last(path)?;
+    match node.pointer_mut(parent).ok_or("parent path does not exist")? {
+        Node::Dictionary(map) => {
+            let key = lookup_key(map, &last).into_owned();
+            map.insert(key, value);
+        }
+        Node::Array(items) | Node::Document(items) => {
+            let index = if last == "-" { items.len() } else { parse_index(&last).ok_or("invalid array index")? };
+            if index > items.len() {
+                return Err("array index out of bounds".to_string());
+            }
+            items.insert(index, value);
+        }
+        _ => return Err("parent is not a collection".to_string()),
+    }
+    Ok(())
+}
+
+fn remove(node: &mut Node, path: &str) -> Result<Node, String> {
+    if path.is_empty() {
+        return Err("cannot remove the root".to_string());
+    }
+    let (parent, last) = split_last(path)?;
+    match node.pointer_mut(parent).ok_or("parent path does not exist")? {
+        Node::Dictionary(map) => {
+            let key = lookup_key(map, &last).into_owned();
+            map.shift_remove(&key).ok_or_else(|| "path does not exist".to_string())
+        }
+        Node::Array(items) | Node::Document(items) => match parse_index(&last) {
+            Some(index) if index < items.len() => Ok(items.remove(index)),
+            _ => Err("array index out of bounds".to_string()),
+        },
+        _ => Err("parent is not a collection".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::structural::diff;
+    use crate::nodes::traits::FromNode;
+
+    fn config() -> Node {
+        Node::dict().key("name", "web").key("tags", vec!["a", "b"]).key("server", Node::dict().key("port", 80)).build()
+    }
+
+    #[test]
+    fn add_remove_replace_work() {
+        let mut node = config();
+        let patch = vec![
+            Operation::Add { path: "/tags/1".to_string(), value: Node::from("x") },
+            Operation::Add { path: "/tags/-".to_string(), value: Node::from("z") },
+            Operation::Add { path: "/server/host".to_string(), value: Node::from("h") },
+            Operation::Remove { path: "/name".to_string() },
+            Operation::Replace { path: "/server/port".to_string(), value: Node::from(8080) },
+        ];
+        apply_patch(&mut node, &patch).unwrap();
+        assert_eq!(node["tags"], Node::from(vec!["a", "x", "b", "z"]));
+        assert_eq!(node["server"]["host"], Node::from("h"));
+        assert_eq!(node["server"]["port"], Node::from(8080));
+        assert!(node.get("name").is_none());
+    }
+
+    #[test]
+    fn move_copy_test_work() {
+        let mut node = config();
+        let patch = vec![
+            Operation::Test { path: "/name".to_string(), value: Node::from("web") },
+            Operation::Copy { from: "/server".to_string(), path: "/backup".to_string() },
+            Operation::Move { from: "/tags/0".to_string(), path: "/first".to_string() },
+        ];
+        apply_patch(&mut node, &patch).unwrap();
+        assert_eq!(node["backup"]["port"], Node::from(80));
+        assert_eq!(node["first"], Node::from("a"));
+        assert_eq!(node["tags"], Node::from(vec!["b"]));
+    }
+
+    #[test]
+    fn failed_patch_leaves_tree_unchanged() {
+        let mut node = config();
+        let patch = vec![
+            Operation::Remove { path: "/name".to_string() },
+            Operation::Test { path: "/server/port".to_string(), value: Node::from(81) },
+        ];
+        let error = apply_patch(&mut node, &patch).unwrap_err();
+        assert_eq!(error.index, 1);
+        assert_eq!(error.operation, "test");
+        assert_eq!(node, config());
+    }
+
+    #[test]
+    fn invalid_operations_fail() {
+        let failures = [
+            Operation::Remove { path: "/missing".to_string() },
+            Operation::Remove { path: String::new() },
+            Operation::Remove { path: "/tags/5".to_string() },
+            Operation::Add { path: "/tags/3".to_string(), value: Node::None },
+            Operation::Add { path: "/tags/01".to_string(), value: Node::None },
+            Operation::Add { path: "/missing/a".to_string(), value: Node::None },
+            Operation::Add { path: "/name/a".to_string(), value: Node::None },
+            Operation::Replace { path: "/missing".to_string(), value: Node::None },
+            Operation::Move { from: "/server".to_string(), path: "/server/inner".to_string() },
+            Operation::Copy { from: "/missing".to_string(), path: "/a".to_string() },
+            Operation::Test { path: "/missing".to_string(), value: Node::None },
+        ];
+        for operation in failures {
+            assert!(apply_patch(&mut config(), std::slice::from_ref(&operation)).is_err(), "{:?}", operation);
+        }
+    }
+
+    #[test]
+    fn diff_then_patch_round_trips() {
+        let from = config();
+        let to = Node::dict().key("tags", vec!["b"]).key("server", Node::dict().key("port", 81).key("tls", true)).build();
+        let mut node = from.clone();
+        apply_patch(&mut node, &diff(&from, &to)).unwrap();
+        assert_eq!(node, to);
+    }
+
+    #[test]
+    fn patch_document_applies() {
+        let document = Node::from(vec![Node::dict().key("op", "replace").key("path", "").key("value", 1).build()]);
+        let patch = Vec::<Operation>::from_node(&document).unwrap();
+        let mut node = config();
+        apply_patch(&mut node, &patch).unwrap();
+        assert_eq!(node, Node::from(1));
+    }
+}
@@ -0,0 +1,2 @@
+/// Application of patch operations to node trees
+pub mod apply;
@@ -0,0 +1,717 @@
+//! JSONPath query engine for Node trees.
+//! Supports the root "$", child names (".name", "['name']"), indices and slices
+//! ("[0]", "[-1]", "[1:3]", "[::2]"), wildcards ("*"), recursive descent ("..") and
+//! filters ("[?(@.name == 'PATH')]" with ==, !=, <, <=, >, >=, &&, || and !).
+//! Matches are returned with their JSON-pointer paths; dictionary entries are visited
+//! in sorted key order so results are deterministic.
+
+use crate::nodes::node::Node;
+use crate::nodes::path::join;
+
+/// A node matched by a query together with its JSON-pointer path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryMatch<'a> {
+    /// JSON-pointer path of the matched node relative to the queried node
+    pub path: String,
+    /// The matched node
+    pub node: &'a Node,
+}
+
+/// A single selector inside a path segment.
+#[derive(Clone, Debug, PartialEq)]
+enum Selector {
+    Name(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, i64),
+    Wildcard,
+    Filter(Filter),
+}
+
+/// A path segment: one or more selectors applied to the children (or, for recursive
+/// segments, all descendants) of each input node.
+#[derive(Clone, Debug, PartialEq)]
+struct Segment {
+    recursive: bool,
+    selectors: Vec<Selector>,
+}
+
+/// Comparison operators usable in filters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+/// A value in a filter expression.
+#[derive(Clone, Debug, PartialEq)]
+enum Operand {
+    /// Path relative to the current node ("@") or to the query root ("$")
+    Path { relative: bool, segments: Vec<Segment> },
+    Literal(Node),
+}
+
+/// A filter expression.
+#[derive(Clone, Debug, PartialEq)]
+enum Filter {
+    Exists(Operand),
+    Compare(Operand, Comparison, Operand),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+}
+
+/// A compiled JSONPath query that can be evaluated against any number of nodes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    segments: Vec<Segment>,
+}
+
+/// Recursive descent parser for query expressions.
+struct ExpressionParser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl ExpressionParser {
+    fn new(expression: &str) -> Self {
+        Self { chars: expression.chars().collect(), position: 0 }
+    }
+
+    fn current(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("Invalid query at position {}: {}", self.position, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.current().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    /// Consumes the given text if the expression continues with it.
+    fn consume(&mut self, text: &str) -> bool {
+        let matches = text.chars().enumerate().all(|(offset, c)| self.peek(offset) == Some(c));
+        if matches {
+            self.position += text.chars().count();
+        }
+        matches
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.consume(&c.to_string()) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+
+    fn parse_query(&mut self) -> Result<Vec<Segment>, String> {
+        self.skip_whitespace();
+        if !self.consume("$") {
+            return Err(self.error("query must start with '$'"));
+        }
+        let segments = self.parse_segments(true)?;
+        self.skip_whitespace();
+        match self.current() {
+            None => Ok(segments),
+            Some(c) => Err(self.error(&format!("unexpected character '{}'", c))),
+        }
+    }
+
+    /// Parses the segments following "$" or "@"; recursive descent is only allowed
+    /// in full queries, not in filter paths.
+    fn parse_segments(&mut self, allow_recursive: bool) -> Result<Vec<Segment>, String> {
+        let mut segments = Vec::new();
+        loop {
+            match self.current() {
+                Some('.') if self.peek(1) == Some('.') => {
+                    if !allow_recursive {
+                        return Err(self.error("recursive descent is not allowed in filters"));
+                    }
+                    self.position += 2;
+                    let selectors = if self.current() == Some('[') {
+                        self.parse_bracket()?
+                    } else {
+                        vec![self.parse_dot_selector()?]
+                    };
+                    segments.push(Segment { recursive: true, selectors });
+                }
+                Some('.') => {
+                    self.position += 1;
+                    segments.push(Segment { recursive: false, selectors: vec![self.parse_dot_selector()?] });
+                }
+                Some('[') => segments.push(Segment { recursive: false, selectors: self.parse_bracket()? }),
+                _ => return Ok(segments),
+            }
+        }
+    }
+
+    /// Parses the selector after "." or "..": a wildcard or a member name.
+    fn parse_dot_selector(&mut self) -> Result<Selector, String> {
+        if self.consume("*") {
+            return Ok(Selector::Wildcard);
+        }
+        let mut name = String::new();
+        while let Some(c) = self.current() {
+            if c.is_whitespace() || "$@.[]()=!<>&|,'\"*?:".contains(c) {
+                break;
+            }
+            name.push(c);
+            self.position += 1;
+        }
+        if name.is_empty() {
+            Err(self.error("expected a member name or '*'"))
+        } else {
+            Ok(Selector::Name(name))
+        }
+    }
+
+    /// Parses a bracketed, comma separated selector list.
+    fn parse_bracket(&mut self) -> Result<Vec<Selector>, String> {
+        self.position += 1; // Skip '['
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_whitespace();
+            selectors.push(self.parse_bracket_selector()?);
+            self.skip_whitespace();
+            match self.current() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(selectors);
+                }
+                Some(c) => return Err(self.error(&format!("unexpected character '{}' in brackets", c))),
+                None => return Err(self.error("unterminated '['")),
+            }
+        }
+    }
+
+    fn parse_bracket_selector(&mut self) -> Result<Selector, String> {
+        match self.current() {
+            Some('*') => {
+                self.position += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('\'' | '"') => Ok(Selector::Name(self.parse_string()?)),
+            Some('?') => {
+                self.position += 1;
+                Ok(Selector::Filter(self.parse_or()?))
+            }
+            _ => self.parse_index_or_slice(),
+        }
+    }
+
+    fn parse_index_or_slice(&mut self) -> Result<Selector, String> {
+        let start = self.parse_optional_integer()?;
+        self.skip_whitespace();
+        if self.current() != Some(':') {
+            return start.map(Selector::Index).ok_or_else(|| self.error("expected a selector"));
+        }
+        self.position += 1;
+        self.skip_whitespace();
+        let end = self.parse_optional_integer()?;
+        self.skip_whitespace();
+        let mut step = 1;
+        if self.current() == Some(':') {
+            self.position += 1;
+            self.skip_whitespace();
+            step = self.parse_optional_integer()?.unwrap_or(1);
+        }
+        Ok(Selector::Slice(start, end, step))
+    }
+
+    fn parse_optional_integer(&mut self) -> Result<Option<i64>, String> {
+        let begin = self.position;
+        if self.current() == Some('-') {
+            self.position += 1;
+        }
+        while self.current().is_some_and(|c| c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        if self.position == begin {
+            return Ok(None);
+        }
+        let text: String = self.chars[begin..self.position].iter().collect();
+        text.parse().map(Some).map_err(|_| self.error(&format!("invalid integer '{}'", text)))
+    }
+
+    /// Parses a single or double quoted string, processing backslash escapes.
+    fn parse_string(&mut self) -> Result<String, String> {
+        let quote = self.current().unwrap_or('\'');
+        self.position += 1;
+        let mut text = String::new();
+        while let Some(c) = self.current() {
+            self.position += 1;
+            if c == quote {
+                return Ok(text);
+            }
+            if c == '\\' {
+                match self.current() {
+                    Some(escaped) => {
+                        text.push(match escaped {
+                            'n' => '\n',
+                            't' => '\t',
+                            other => other,
+                        });
+                        self.position += 1;
+                    }
+                    None => break,
+                }
+            } else {
+                text.push(c);
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn parse_or(&mut self) -> Result<Filter, String> {
+        let mut filter = self.parse_and()?;
+        loop {
+            self.skip_whitespace();
+            if !self.consume("||") {
+                return Ok(filter);
+            }
+            filter = Filter::Or(Box::new(filter), Box::new(self.parse_and()?));
+        }
+    }
+
+    fn parse_and(&mut self) -> Result<Filter, String> {
+        let mut filter = self.parse_unary()?;
+        loop {
+            self.skip_whitespace();
+            if !self.consume("&&") {
+                return Ok(filter);
+            }
+            filter = Filter::And(Box::new(filter), Box::new(self.parse_unary()?));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Filter, String> {
+        self.skip_whitespace();
+        if self.current() == Some('!') && self.peek(1) != Some('=') {
+            self.position += 1;
+            return Ok(Filter::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.consume("(") {
+            let filter = self.parse_or()?;
+            self.expect(')')?;
+            return Ok(filter);
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Filter, String> {
+        let left = self.parse_operand()?;
+        self.skip_whitespace();
+        let comparison = if self.consume("==") {
+            Comparison::Equal
+        } else if self.consume("!=") {
+            Comparison::NotEqual
+        } else if self.consume("<=") {
+            Comparison::LessEqual
+        } else if self.consume(">=") {
+            Comparison::GreaterEqual
+        } else if self.consume("<") {
+            Comparison::Less
+        } else if self.consume(">") {
+            Comparison::Greater
+        } else {
+            return match left {
+                Operand::Path { .. } => Ok(Filter::Exists(left)),
+                Operand::Literal(_) => Err(self.error("expected a comparison operator")),
+            };
+        };
+        let right = self.parse_operand()?;
+        Ok(Filter::Compare(left, comparison, right))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, String> {
+        self.skip_whitespace();
+        match self.current() {
+            Some(c @ ('@' | '$')) => {
+                self.position += 1;
+                Ok(Operand::Path { relative: c == '@', segments: self.parse_segments(false)? })
+            }
+            Some('\'' | '"') => Ok(Operand::Literal(Node::Str(self.parse_string()?))),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            _ if self.consume("true") => Ok(Operand::Literal(Node::Boolean(true))),
+            _ if self.consume("false") => Ok(Operand::Literal(Node::Boolean(false))),
+            _ if self.consume("null") => Ok(Operand::Literal(Node::None)),
+            _ => Err(self.error("expected a path or literal in filter")),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Operand, String> {
+        let begin = self.position;
+        while self.current().is_some_and(|c| c.is_ascii_digit() || "-+.eE".contains(c)) {
+            self.position += 1;
+        }
+        let text: String = self.chars[begin..self.position].iter().collect();
+        if let Ok(value) = text.parse::<i64>() {
+            Ok(Operand::Literal(Node::from(value)))
+        } else if let Ok(value) = text.parse::<f64>() {
+            Ok(Operand::Literal(Node::from(value)))
+        } else {
+            Err(self.error(&format!("invalid number '{}'", text)))
+        }
+    }
+}
+
+/// Returns the children of a node with their path segments; dictionary entries are
+/// returned in sorted key order.
+fn children(node: &Node) -> Vec<(String, &Node)> {
+    match node {
+        Node::Array(items) | Node::Document(items) => {
+            items.iter().enumerate().map(|(index, item)| (index.to_string(), item)).collect()
+        }
+        Node::Dictionary(map) => {
+            let mut entries: Vec<(String, &Node)> = map.iter().map(|(key, value)| (key.clone(), value)).collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            entries
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Resolves a possibly negative index against a length.
+fn normalize_index(index: i64, length: usize) -> Option<usize> {
+    let length = length as i64;
+    let index = if index < 0 { length + index } else { index };
+    (0..length).contains(&index).then_some(index as usize)
+}
+
+/// Returns the indices selected by a slice, following Python slice semantics.
+fn slice_indices(start: Option<i64>, end: Option<i64>, step: i64, length: usize) -> Vec<usize> {
+    let length = length as i64;
+    let bound = |value: i64| if value < 0 { (length + value).max(-1) } else { value.min(length) };
+    let mut indices = Vec::new();
+    if step > 0 {
+        let mut index = start.map_or(0, bound).max(0);
+        let end = end.map_or(length, bound);
+        while index < end {
+            indices.push(index as usize);
+            match index.checked_add(step) {
+                Some(next) => index = next,
+                None => break,
+            }
+        }
+    } else if step < 0 {
+        let mut index = start.map_or(length - 1, bound).min(length - 1);
+        let end = end.map_or(-1, bound);
+        while index > end {
+            indices.push(index as usize);
+            match index.checked_add(step) {
+                Some(next) => index = next,
+                None => break,
+            }
+        }
+    }
+    indices
+}
+
+/// Compares two nodes; numbers compare by value regardless of their Numeric variant.
+fn compare(left: &Node, comparison: Comparison, right: &Node) -> bool {
+    let ordering = match (left, right) {
+        (Node::Number(_), Node::Number(_)) => left.as_f64().zip(right.as_f64()).and_then(|(a, b)| a.partial_cmp(&b)),
+        (Node::Str(a), Node::Str(b)) => Some(a.cmp(b)),
+        _ => {
+            return match comparison {
+                Comparison::Equal => left == right,
+                Comparison::NotEqual => left != right,
+                _ => false,
+            };
+        }
+    };
+    match comparison {
+        Comparison::Equal => ordering == Some(std::cmp::Ordering::Equal),
+        Comparison::NotEqual => ordering != Some(std::cmp::Ordering::Equal),
+        Comparison::Less => ordering == Some(std::cmp::Ordering::Less),
+        Comparison::LessEqual => matches!(ordering, Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal)),
+        Comparison::Greater => ordering == Some(std::cmp::Ordering::Greater),
+        Comparison::GreaterEqual => {
+            matches!(ordering, Some(std::cmp::Ordering::Greater | std::cmp::Ordering::Equal))
+        }
+    }
+}
+
+impl Query {
+    /// Compiles a JSONPath expression.
+    ///
+    /// # Arguments
+    /// * `expression` - The expression, for example "$.spec.containers[*].image"
+    ///
+    /// # Returns
+    /// The compiled query or a description of the syntax error.
+    pub fn compile(expression: &str) -> Result<Query, String> {
+        Ok(Query { segments: ExpressionParser::new(expression).parse_query()? })
+    }
+
+    /// Evaluates the query against a node.
+    ///
+    /// # Arguments
+    /// * `node` - Root node the query's "$" refers to
+    ///
+    /// # Returns
+    /// The matched nodes with their paths, in document order.
+    pub fn select<'a>(&self, node: &'a Node) -> Vec<QueryMatch<'a>> {
+        let matches = Self::select_segments(&self.segments, node, String::new(), node);
+        matches.into_iter().map(|(path, node)| QueryMatch { path, node }).collect()
+    }
+
+    fn select_segments<'a>(segments: &[Segment], node: &'a Node, path: String, root: &'a Node) -> Vec<(String, &'a Node)> {
+        let mut current = vec![(path, node)];
+        for segment in segments {
+            let mut next = Vec::new();
+            for (path, node) in current {
+                if segment.recursive {
+                    Self::select_descendants(segment, node, path, root, &mut next);
+                } else {
+                    Self::select_children(&segment.selectors, node, &path, root, &mut next);
+                }
+            }
+            current = next;
+        }
+        current
+    }
+
+    /// Applies a recursive segment's selectors to a node and each of its descendants.
+    fn select_descendants<'a>(
+        segment: &Segment,
+        node: &'a Node,
+        path: String,
+        root: &'a Node,
+        output: &mut Vec<(String, &'a Node)>,
+    ) {
+        Self::select_children(&segment.selectors, node, &path, root, output);
+        for (key, child) in children(node) {
+            Self::select_descendants(segment, child, join(&path, &key), root, output);
+        }
+    }
+
+    fn select_children<'a>(
+        selectors: &[Selector],
+        node: &'a Node,
+        path: &str,
+        root: &'a Node,
+        output: &mut Vec<(String, &'a Node)>,
+    ) {
+        for selector in selectors {
+            match selector {
+                Selector::Name(name) => {
+                    if let Some(child) = node.get(name) {
+                        output.push((join(path, name), child));
+                    }
+                }
+                Selector::Index(index) => {
+                    if let Node::Array(items) | Node::Document(items) = node
+                        && let Some(index) = normalize_index(*index, items.len())
+                    {
+                        output.push((join(path, &index.to_string()), &items[index]));
+                    }
+                }
+                Selector::Slice(start, end, step) => {
+                    if let Node::Array(items) | Node::Document(items) = node {
+                        for index in slice_indices(*start, *end, *step, items.len()) {
+                            output.push((join(path, &index.to_string()), &items[index]));
+                        }
+                    }
+                }
+                Selector::Wildcard => {
+                    for (key, child) in children(node) {
+                        output.push((join(path, &key), child));
+                    }
+                }
+                Selector::Filter(filter) => {
+                    for (key, child) in children(node) {
+                        if Self::test(filter, child, root) {
+                            output.push((join(path, &key), child));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns true if a node passes a filter.
+    fn test(filter: &Filter, node: &Node, root: &Node) -> bool {
+        match filter {
+            Filter::Exists(operand) => Self::value(operand, node, root).is_some(),
+            Filter::Compare(left, comparison, right) => {
+                match (Self::value(left, node, root), Self::value(right, node, root)) {
+                    (Some(left), Some(right)) => compare(left, *comparison, right),
+                    // A missing value only equals another missing value
+                    (None, None) => *comparison == Comparison::Equal,
+                    _ => *comparison == Comparison::NotEqual,
+                }
+            }
+            Filter::And(left, right) => Self::test(left, node, root) && Self::test(right, node, root),
+            Filter::Or(left, right) => Self::test(left, node, root) || Self::test(right, node, root),
+            Filter::Not(inner) => !Self::test(inner, node, root),
+        }
+    }
+
+    /// Returns the value of a filter operand; paths yield their first match.
+    fn value<'a>(operand: &'a Operand, node: &'a Node, root: &'a Node) -> Option<&'a Node> {
+        match operand {
+            Operand::Literal(value) => Some(value),
+            Operand::Path { relative, segments } => {
+                let start = if *relative { node } else { root };
+                Self::select_segments(segments, start, String::new(), root).into_iter().next().map(|(_, node)| node)
+            }
+        }
+    }
+}
+
+/// Evaluates a JSONPath expression against a node.
+///
+/// # Arguments
+/// * `node` - Root node the expression's "$" refers to
+/// * `expression` - The expression, for example "$..env[?(@.name=='PATH')]"
+///
+/// # Returns
+/// The matched nodes with their JSON-pointer paths, or a description of the syntax error.
+pub fn query<'a>(node: &'a Node, expression: &str) -> Result<Vec<QueryMatch<'a>>, String> {
+    Ok(Query::compile(expression)?.select(node))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::sources::buffer::Buffer;
+    use crate::parser::default::parse;
+    use indexmap::IndexMap;
+
+    fn dict(entries: Vec<(&str, Node)>) -> Node {
+        Node::Dictionary(entries.into_iter().map(|(key, value)| (key.to_string(), value)).collect::<IndexMap<_, _>>())
+    }
+
+    fn pod() -> Node {
+        let env = |name: &str, value: &str| dict(vec![("name", Node::from(name)), ("value", Node::from(value))]);
+        let web = dict(vec![
+            ("image", Node::from("nginx")),
+            ("port", Node::from(80)),
+            ("env", Node::Array(vec![env("PATH", "/bin"), env("MODE", "prod")])),
+        ]);
+        let sidecar = dict(vec![
+            ("image", Node::from("envoy")),
+            ("port", Node::from(9901)),
+            ("env", Node::Array(vec![env("PATH", "/usr/bin")])),
+        ]);
+        dict(vec![("spec", dict(vec![("containers", Node::Array(vec![web, sidecar]))]))])
+    }
+
+    fn paths(matches: &[QueryMatch]) -> Vec<String> {
+        matches.iter().map(|m| m.path.clone()).collect()
+    }
+
+    #[test]
+    fn root_query_works() {
+        let node = pod();
+        let matches = query(&node, "$").unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].path, "");
+        assert_eq!(matches[0].node, &node);
+    }
+
+    #[test]
+    fn wildcard_query_works() {
+        let node = pod();
+        let matches = query(&node, "$.spec.containers[*].image").unwrap();
+        assert_eq!(paths(&matches), vec!["/spec/containers/0/image", "/spec/containers/1/image"]);
+        assert_eq!(matches[0].node, &Node::from("nginx"));
+        assert_eq!(matches[1].node, &Node::from("envoy"));
+    }
+
+    #[test]
+    fn recursive_descent_with_filter_works() {
+        let node = pod();
+        let matches = query(&node, "$..env[?(@.name=='PATH')]").unwrap();
+        assert_eq!(paths(&matches), vec!["/spec/containers/0/env/0", "/spec/containers/1/env/0"]);
+        let matches = query(&node, "$..env[?(@.name=='PATH')].value").unwrap();
+        assert_eq!(matches[1].node, &Node::from("/usr/bin"));
+    }
+
+    #[test]
+    fn recursive_descent_finds_all_members() {
+        let node = pod();
+        assert_eq!(query(&node, "$..image").unwrap().len(), 2);
+        assert_eq!(query(&node, "$..name").unwrap().len(), 3);
+        assert_eq!(query(&node, "$..[0].image").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn index_and_slice_queries_work() {
+        let node = Node::from(vec![0, 1, 2, 3, 4]);
+        assert_eq!(paths(&query(&node, "$[1]").unwrap()), vec!["/1"]);
+        assert_eq!(paths(&query(&node, "$[-1]").unwrap()), vec!["/4"]);
+        assert_eq!(paths(&query(&node, "$[1:3]").unwrap()), vec!["/1", "/2"]);
+        assert_eq!(paths(&query(&node, "$[::2]").unwrap()), vec!["/0", "/2", "/4"]);
+        assert_eq!(paths(&query(&node, "$[::-2]").unwrap()), vec!["/4", "/2", "/0"]);
+        assert_eq!(paths(&query(&node, "$[-2:]").unwrap()), vec!["/3", "/4"]);
+        assert_eq!(paths(&query(&node, "$[0,3]").unwrap()), vec!["/0", "/3"]);
+        assert!(query(&node, "$[7]").unwrap().is_empty());
+        assert_eq!(paths(&query(&node, "$[1::9223372036854775807]").unwrap()), vec!["/1"]);
+        assert_eq!(paths(&query(&node, "$[3::-9223372036854775808]").unwrap()), vec!["/3"]);
+        let everything = "$[-9223372036854775808:9223372036854775807]";
+        assert_eq!(paths(&query(&node, everything).unwrap()), vec!["/0", "/1", "/2", "/3", "/4"]);
+    }
+
+    #[test]
+    fn bracket_names_and_escaped_paths_work() {
+        let node = dict(vec![("a/b", Node::from(1)), ("c d", Node::from(2))]);
+        assert_eq!(paths(&query(&node, "$['a/b']").unwrap()), vec!["/a~1b"]);
+        assert_eq!(query(&node, "$[\"c d\"]").unwrap()[0].node, &Node::from(2));
+    }
+
+    #[test]
+    fn filter_comparisons_work() {
+        let node = pod();
+        let images = |expression: &str| -> Vec<Node> {
+            query(&node, expression).unwrap().into_iter().map(|m| m.node.clone()).collect()
+        };
+        assert_eq!(images("$.spec.containers[?(@.port > 100)].image"), vec![Node::from("envoy")]);
+        assert_eq!(images("$.spec.containers[?(@.port <= 80)].image"), vec![Node::from("nginx")]);
+        assert_eq!(images("$.spec.containers[?(@.image != 'nginx')].image"), vec![Node::from("envoy")]);
+        assert_eq!(images("$.spec.containers[?(@.port > 10 && @.image == 'nginx')].image"), vec![Node::from("nginx")]);
+        assert_eq!(images("$.spec.containers[?(@.port == 1 || @.image == 'envoy')].image"), vec![Node::from("envoy")]);
+        assert_eq!(images("$.spec.containers[?(!(@.image == 'envoy'))].image"), vec![Node::from("nginx")]);
+        assert_eq!(images("$.spec.containers[?(@.missing)].image"), Vec::<Node>::new());
+        assert_eq!(images("$.spec.containers[?(@.port == $.spec.containers[0].port)].image"), vec![Node::from("nginx")]);
+    }
+
+    #[test]
+    fn query_parsed_yaml_works() {
+        let mut source = Buffer::new(b"servers: [{host: a, port: 1}, {host: b, port: 2}]");
+        let node = parse(&mut source).unwrap();
+        let matches = query(&node, "$.servers[?(@.port == 2)].host").unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].node, &Node::from("b"));
+        assert_eq!(matches[0].path, "/servers/1/host");
+    }
+
+    #[test]
+    fn compiled_query_can_be_reused() {
+        let compiled = Query::compile("$.a").unwrap();
+        assert_eq!(compiled.select(&dict(vec![("a", Node::from(1))])).len(), 1);
+        assert!(compiled.select(&Node::from(1)).is_empty());
+    }
+
+    #[test]
+    fn invalid_queries_fail() {
+        for expression in ["", "a.b", "$.", "$[", "$[1", "$['a", "$[?(@.a ==)]", "$[?(1)]", "$[?(@..a)]", "$.a b"] {
+            assert!(Query::compile(expression).is_err(), "{}", expression);
+        }
+    }
+}
@@ -0,0 +1,2 @@
+/// JSONPath query expressions evaluated against node trees
+pub mod jsonpath;
@@ -0,0 +1,211 @@
+//! JSON Schema inference from sample documents.
+//! infer merges the structure of one or more sample trees into a JSON Schema (draft
+//! 2020-12) held as a Node, ready to be written with to_json or to_yaml. Each value is
+//! described by the types seen for it ("integer" is folded into "number" where both
+//! occur), a dictionary by its properties with the keys present in every sample listed
+//! as required, and an array by a schema covering all of its items. Comments are
+//! ignored and a stream contributes each of its documents as a separate sample.
+
+use crate::nodes::node::{Node, Numeric};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Dialect URI written as the "$schema" of inferred schemas.
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// JSON Schema type names, in the order they are listed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum JsonType {
+    Null,
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonType {
+    fn name(self) -> &'static str {
+        match self {
+            JsonType::Null => "null",
+            JsonType::Boolean => "boolean",
+            JsonType::Integer => "integer",
+            JsonType::Number => "number",
+            JsonType::String => "string",
+            JsonType::Array => "array",
+            JsonType::Object => "object",
+        }
+    }
+}
+
+/// What has been seen at one place in the samples.
+#[derive(Default)]
+struct Observed {
+    types: BTreeSet<JsonType>,
+    /// Number of dictionaries seen
+    objects: usize,
+    /// Each key seen in those dictionaries, with the number holding it
+    properties: BTreeMap<String, (Observed, usize)>,
+    /// Every item of the arrays seen
+    items: Option<Box<Observed>>,
+}
+
+impl Observed {
+    /// Adds a value to what has been seen.
+    fn add(&mut self, node: &Node) {
+        match node {
+            Node::None => {
+                self.types.insert(JsonType::Null);
+            }
+            Node::Boolean(_) => {
+                self.types.insert(JsonType::Boolean);
+            }
+            Node::Number(Numeric::Float(_)) => {
+                self.types.insert(JsonType::Number);
+            }
+            Node::Number(_) => {
+                self.types.insert(JsonType::Integer);
+            }
+            Node::Str(_) => {
+                self.types.insert(JsonType::String);
+            }
+            Node::Array(items) | Node::Document(items) => {
+                self.types.insert(JsonType::Array);
+                let observed = self.items.get_or_insert_with(Box::default);
+                for item in items.iter().filter(|item| !item.is_comment()) {
+                    observed.add(item);
+                }
+            }
+            Node::Dictionary(_) => {
+                self.types.insert(JsonType::Object);
+                self.objects += 1;
+                for (key, value) in node.entries().filter(|(_, value)| !value.is_comment()) {
+                    let (observed, count) = self.properties.entry(key.to_string()).or_default();
+                    observed.add(value);
+                    *count += 1;
+                }
+            }
+            Node::Comment(_) => {}
+        }
+    }
+
+    /// Returns the schema describing what has been seen.
+    fn to_schema(&self) -> Node {
+        let mut schema = Node::dict();
+        let mut types: Vec<&str> = self.types.iter().map(|json_type| json_type.name()).collect();
+        if self.types.contains(&JsonType::Number) {
+            types.retain(|name| *name != "integer");
+        }
+        match types.as_slice() {
+            [] => {}
+            [name] => schema = schema.key("type", *name),
+            _ => schema = schema.key("type", types),
+        }
+        if let Some(items) = &self.items
+            && !items.types.is_empty()
+        {
+            schema = schema.key("items", items.to_schema());
+        }
+        if self.types.contains(&JsonType::Object) {
+            let properties = self
+                .properties
+                .iter()
+                .fold(Node::dict(), |properties, (key, (observed, _))| properties.key(key, observed.to_schema()));
+            let required: Vec<&str> = self
+                .properties
+                .iter()
+                .filter(|(_, (_, count))| *count == self.objects)
+                .map(|(key, _)| key.as_str())
+                .collect();
+            schema = schema.key("properties", properties);
+            if !required.is_empty() {
+                schema = schema.key("required", required);
+            }
+        }
+        schema.build()
+    }
+}
+
+/// Infers a JSON Schema describing every sample.
+///
+/// # Arguments
+/// * `samples` - Sample trees, a stream counting as one sample per document
+///
+/// # Returns
+/// The schema as a Dictionary node, accepting any value if there are no samples.
+pub fn infer(samples: &[Node]) -> Node {
+    let mut observed = Observed::default();
+    for sample in samples {
+        match sample {
+            Node::Document(documents) => {
+                for document in documents.iter().filter(|document| !document.is_comment()) {
+                    observed.add(document);
+                }
+            }
+            _ => observed.add(sample),
+        }
+    }
+    let mut schema = observed.to_schema();
+    if let Node::Dictionary(entries) = &mut schema {
+        entries.insert("$schema".to_string(), Node::from(JSON_SCHEMA_DIALECT));
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infer_works() {
+        let first = Node::dict()
+            .key("name", "web")
+            .key("port", 80)
+            .key("servers", vec![Node::dict().key("host", "a").key("weight", 1).build()])
+            .key("tags", Vec::<Node>::new())
+            .build();
+        let second = Node::dict()
+            .key("name", Node::None)
+            .key("port", 8080)
+            .key("servers", vec![Node::dict().key("host", "b").key("weight", 0.5).build()])
+            .key("tls", true)
+            .build();
+        let expected = Node::dict()
+            .key("$schema", JSON_SCHEMA_DIALECT)
+            .key("type", "object")
+            .key(
+                "properties",
+                Node::dict()
+                    .key("name", Node::dict().key("type", vec!["null", "string"]))
+                    .key("port", Node::dict().key("type", "integer"))
+                    .key(
+                        "servers",
+                        Node::dict().key("type", "array").key(
+                            "items",
+                            Node::dict()
+                                .key("type", "object")
+                                .key(
+                                    "properties",
+                                    Node::dict()
+                                        .key("host", Node::dict().key("type", "string"))
+                                        .key("weight", Node::dict().key("type", "number")),
+                                )
+                                .key("required", vec!["host", "weight"]),
+                        ),
+                    )
+                    .key("tags", Node::dict().key("type", "array"))
+                    .key("tls", Node::dict().key("type", "boolean")),
+            )
+            .key("required", vec!["name", "port", "servers"])
+            .build();
+        assert_eq!(infer(&[first, second]), expected);
+    }
+
+    #[test]
+    fn infer_streams_and_empty() {
+        let stream = Node::Document(vec![Node::from(1), Node::Comment("x".to_string()), Node::from("a")]);
+        let expected = Node::dict().key("$schema", JSON_SCHEMA_DIALECT).key("type", vec!["integer", "string"]).build();
+        assert_eq!(infer(&[stream]), expected);
+        assert_eq!(infer(&[]), Node::dict().key("$schema", JSON_SCHEMA_DIALECT).build());
+    }
+}
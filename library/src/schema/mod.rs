@@ -0,0 +1,2 @@
+/// JSON Schema inference from sample node trees
+pub mod infer;
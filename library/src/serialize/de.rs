@@ -0,0 +1,248 @@
+//! serde Deserializer that reads Rust values out of a Node tree.
+//! Comment nodes are skipped inside collections, Document nodes read as sequences, and
+//! enums are read from a string (unit variants) or a single entry dictionary.
+
+use crate::error::serialization::SerializationError;
+use crate::nodes::node::{Node, Numeric};
+use serde::de::value::{MapDeserializer, SeqDeserializer, StringDeserializer};
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, Unexpected, VariantAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+/// Deserializer over an owned Node.
+pub struct NodeDeserializer {
+    /// The node being deserialized
+    node: Node,
+}
+
+impl NodeDeserializer {
+    /// Creates a deserializer reading from a node.
+    ///
+    /// # Arguments
+    /// * `node` - The node to read values from
+    pub fn new(node: Node) -> Self {
+        Self { node }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, SerializationError> for Node {
+    type Deserializer = NodeDeserializer;
+
+    fn into_deserializer(self) -> NodeDeserializer {
+        NodeDeserializer::new(self)
+    }
+}
+
+/// Returns a serde description of a node for error messages.
+fn unexpected(node: &Node) -> Unexpected<'_> {
+    match node {
+        Node::Boolean(value) => Unexpected::Bool(*value),
+        Node::Number(Numeric::Float(value)) => Unexpected::Float(*value),
+        Node::Number(Numeric::UInteger(value)) => Unexpected::Unsigned(*value),
+        Node::Number(number) => match number.as_wide_integer().and_then(|value| i64::try_from(value).ok()) {
+            Some(value) => Unexpected::Signed(value),
+            None => Unexpected::Other("number"),
+        },
+        Node::Str(text) => Unexpected::Str(text),
+        Node::Array(_) | Node::Document(_) => Unexpected::Seq,
+        Node::Dictionary(_) => Unexpected::Map,
+        Node::Comment(_) => Unexpected::Other("comment"),
+        Node::None => Unexpected::Unit,
+    }
+}
+
+impl<'de> de::Deserializer<'de> for NodeDeserializer {
+    type Error = SerializationError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerializationError> {
+        match self.node {
+            Node::Boolean(value) => visitor.visit_bool(value),
+            Node::Number(number) => match number {
+                Numeric::Integer(value) => visitor.visit_i64(value),
+                Numeric::Float(value) => visitor.visit_f64(value),
+                Numeric::UInteger(value) => visitor.visit_u64(value),
+                Numeric::Byte(value) => visitor.visit_u8(value),
+                Numeric::Int32(value) => visitor.visit_i32(value),
+                Numeric::UInt32(value) => visitor.visit_u32(value),
+                Numeric::Int16(value) => visitor.visit_i16(value),
+                Numeric::UInt16(value) => visitor.visit_u16(value),
+                Numeric::Int8(value) => visitor.visit_i8(value),
+            },
+            Node::Str(text) => visitor.visit_string(text),
+            Node::Array(items) | Node::Document(items) => {
+                let mut seq = SeqDeserializer::new(items.into_iter().filter(|item| !matches!(item, Node::Comment(_))));
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Node::Dictionary(entries) => {
+                let mut map =
+                    MapDeserializer::new(entries.into_iter().filter(|(_, value)| !matches!(value, Node::Comment(_))));
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            Node::Comment(_) | Node::None => visitor.visit_unit(),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerializationError> {
+        match self.node {
+            Node::None => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerializationError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerializationError> {
+        match self.node {
+            Node::Str(variant) => visitor.visit_enum(EnumDeserializer { variant, value: None }),
+            Node::Dictionary(entries) if entries.len() == 1 => {
+                let (variant, value) = entries.into_iter().next().expect("single entry");
+                visitor.visit_enum(EnumDeserializer { variant, value: Some(value) })
+            }
+            other => Err(de::Error::invalid_type(unexpected(&other), &"a string or single entry dictionary")),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Enum access over a variant name and its optional value.
+struct EnumDeserializer {
+    variant: String,
+    value: Option<Node>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = SerializationError;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, VariantDeserializer), SerializationError> {
+        let variant = seed.deserialize(StringDeserializer::<SerializationError>::new(self.variant))?;
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+/// Variant access over the optional value of an enum variant.
+struct VariantDeserializer {
+    value: Option<Node>,
+}
+
+impl VariantDeserializer {
+    fn into_value(self, expected: &str) -> Result<Node, SerializationError> {
+        self.value.ok_or_else(|| de::Error::invalid_type(Unexpected::UnitVariant, &expected))
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer {
+    type Error = SerializationError;
+
+    fn unit_variant(self) -> Result<(), SerializationError> {
+        match self.value {
+            None | Some(Node::None) => Ok(()),
+            Some(other) => Err(de::Error::invalid_type(unexpected(&other), &"unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, SerializationError> {
+        seed.deserialize(NodeDeserializer::new(self.into_value("newtype variant")?))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, SerializationError> {
+        de::Deserializer::deserialize_seq(NodeDeserializer::new(self.into_value("tuple variant")?), visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerializationError> {
+        de::Deserializer::deserialize_map(NodeDeserializer::new(self.into_value("struct variant")?), visitor)
+    }
+}
+
+/// Deserializes a Rust value from a Node.
+///
+/// # Arguments
+/// * `node` - The node to read
+///
+/// # Returns
+/// The value or a SerializationError describing why the node does not match the type.
+pub fn from_value<T: DeserializeOwned>(node: Node) -> Result<T, SerializationError> {
+    T::deserialize(NodeDeserializer::new(node))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+        tags: Vec<String>,
+        timeout: Option<u32>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Policy {
+        Always,
+        Retry(u8),
+        Window(u8, u8),
+        Backoff { initial: u32 },
+    }
+
+    #[test]
+    fn from_value_struct_works() {
+        let node = Node::dict().key("host", "web").key("port", 80).key("tags", Node::seq().push("a")).build();
+        let server: Server = from_value(node).unwrap();
+        assert_eq!(server, Server { host: "web".to_string(), port: 80, tags: vec!["a".to_string()], timeout: None });
+    }
+
+    #[test]
+    fn from_value_skips_comments() {
+        let node = Node::Array(vec![Node::Comment("c".to_string()), Node::from(1), Node::from(2)]);
+        assert_eq!(from_value::<Vec<i64>>(node), Ok(vec![1, 2]));
+        let node = Node::dict().key("__comment_0", Node::Comment("c".to_string())).key("a", 1).build();
+        assert_eq!(from_value::<HashMap<String, u8>>(node).map(|map| map.len()), Ok(1));
+    }
+
+    #[test]
+    fn from_value_enum_works() {
+        assert_eq!(from_value::<Policy>(Node::from("Always")), Ok(Policy::Always));
+        assert_eq!(from_value::<Policy>(Node::from(vec![("Retry", 3)])), Ok(Policy::Retry(3)));
+        assert_eq!(from_value::<Policy>(Node::from(vec![("Window", vec![1, 2])])), Ok(Policy::Window(1, 2)));
+        let node = Node::from(vec![("Backoff", Node::dict().key("initial", 10))]);
+        assert_eq!(from_value::<Policy>(node), Ok(Policy::Backoff { initial: 10 }));
+        assert!(from_value::<Policy>(Node::from("Sometimes")).is_err());
+        assert!(from_value::<Policy>(Node::from("Retry")).is_err());
+    }
+
+    #[test]
+    fn from_value_type_errors_work() {
+        let error = from_value::<u8>(Node::from(300)).unwrap_err();
+        assert!(error.to_string().contains("300"), "{}", error);
+        assert!(from_value::<String>(Node::from(1)).is_err());
+        let node = Node::dict().key("host", "web").build();
+        assert!(from_value::<Server>(node).unwrap_err().to_string().contains("port"));
+    }
+}
@@ -0,0 +1,10 @@
+/// serde Deserializer reading Rust values out of a Node
+pub mod de;
+/// serde Serializer building a Node from Rust values
+pub mod ser;
+/// serde Deserializer reading Rust values straight from YAML text
+pub(crate) mod text_de;
+/// serde Serializer writing Rust values straight out as YAML text
+pub(crate) mod text_ser;
+/// Conversion of Rust values to and from YAML text
+pub mod yaml;
@@ -0,0 +1,392 @@
+//! serde Serializer that builds a Node tree from Rust values.
+//! Integers keep their width (for example a u16 becomes Numeric::UInt16), unit values
+//! and None become Node::None, and enums use a string for unit variants and a single
+//! entry dictionary keyed by the variant name otherwise.
+
+use crate::error::serialization::SerializationError;
+use crate::nodes::node::Node;
+use serde::ser::{self, Serialize};
+use indexmap::IndexMap;
+
+/// Serializer producing a Node.
+pub struct NodeSerializer;
+
+/// Wraps a value in a single entry dictionary keyed by an enum variant name.
+fn variant_node(variant: &str, value: Node) -> Node {
+    let mut map = IndexMap::new();
+    map.insert(variant.to_string(), value);
+    Node::Dictionary(map)
+}
+
+impl ser::Serializer for NodeSerializer {
+    type Ok = Node;
+    type Error = SerializationError;
+    type SerializeSeq = SequenceSerializer;
+    type SerializeTuple = SequenceSerializer;
+    type SerializeTupleStruct = SequenceSerializer;
+    type SerializeTupleVariant = SequenceSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = MapSerializer;
+
+    fn serialize_bool(self, value: bool) -> Result<Node, SerializationError> {
+        Ok(Node::from(value))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<Node, SerializationError> {
+        Ok(Node::from(value))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<Node, SerializationError> {
+        Ok(Node::from(value))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<Node, SerializationError> {
+        Ok(Node::from(value))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<Node, SerializationError> {
+        Ok(Node::from(value))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<Node, SerializationError> {
+        Ok(Node::from(value))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<Node, SerializationError> {
+        Ok(Node::from(value))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<Node, SerializationError> {
+        Ok(Node::from(value))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Node, SerializationError> {
+        Ok(Node::from(value))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<Node, SerializationError> {
+        Ok(Node::from(value as f64))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Node, SerializationError> {
+        Ok(Node::from(value))
+    }
+
+    fn serialize_char(self, value: char) -> Result<Node, SerializationError> {
+        Ok(Node::Str(value.to_string()))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Node, SerializationError> {
+        Ok(Node::from(value))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Node, SerializationError> {
+        Ok(Node::from(value))
+    }
+
+    fn serialize_none(self) -> Result<Node, SerializationError> {
+        Ok(Node::None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Node, SerializationError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Node, SerializationError> {
+        Ok(Node::None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Node, SerializationError> {
+        Ok(Node::None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Node, SerializationError> {
+        Ok(Node::from(variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Node, SerializationError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Node, SerializationError> {
+        Ok(variant_node(variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SequenceSerializer, SerializationError> {
+        Ok(SequenceSerializer { items: Vec::with_capacity(len.unwrap_or(0)), variant: None })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SequenceSerializer, SerializationError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SequenceSerializer, SerializationError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SequenceSerializer, SerializationError> {
+        Ok(SequenceSerializer { items: Vec::with_capacity(len), variant: Some(variant) })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer, SerializationError> {
+        Ok(MapSerializer { entries: IndexMap::with_capacity(len.unwrap_or(0)), key: None, variant: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<MapSerializer, SerializationError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<MapSerializer, SerializationError> {
+        Ok(MapSerializer { entries: IndexMap::with_capacity(len), key: None, variant: Some(variant) })
+    }
+}
+
+/// Collects the items of sequences, tuples and tuple variants.
+pub struct SequenceSerializer {
+    items: Vec<Node>,
+    /// Variant name when serializing a tuple variant
+    variant: Option<&'static str>,
+}
+
+impl SequenceSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializationError> {
+        self.items.push(value.serialize(NodeSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Node, SerializationError> {
+        let node = Node::Array(self.items);
+        Ok(match self.variant {
+            Some(variant) => variant_node(variant, node),
+            None => node,
+        })
+    }
+}
+
+impl ser::SerializeSeq for SequenceSerializer {
+    type Ok = Node;
+    type Error = SerializationError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializationError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Node, SerializationError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SequenceSerializer {
+    type Ok = Node;
+    type Error = SerializationError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializationError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Node, SerializationError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SequenceSerializer {
+    type Ok = Node;
+    type Error = SerializationError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializationError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Node, SerializationError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SequenceSerializer {
+    type Ok = Node;
+    type Error = SerializationError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializationError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Node, SerializationError> {
+        self.finish()
+    }
+}
+
+/// Collects the entries of maps, structs and struct variants.
+pub struct MapSerializer {
+    entries: IndexMap<String, Node>,
+    /// Key waiting for its value when serializing a map
+    key: Option<String>,
+    /// Variant name when serializing a struct variant
+    variant: Option<&'static str>,
+}
+
+impl MapSerializer {
+    fn finish(self) -> Result<Node, SerializationError> {
+        let node = Node::Dictionary(self.entries);
+        Ok(match self.variant {
+            Some(variant) => variant_node(variant, node),
+            None => node,
+        })
+    }
+}
+
+/// Converts a serialized map key into a dictionary key; strings, numbers, booleans and
+/// unit enum variants are accepted.
+fn key_string(key: Node) -> Result<String, SerializationError> {
+    match key {
+        Node::Str(text) => Ok(text),
+        Node::Boolean(value) => Ok(value.to_string()),
+        Node::Number(number) => match number.as_wide_integer() {
+            Some(value) => Ok(value.to_string()),
+            None => Err(ser::Error::custom("floating point map keys are not supported")),
+        },
+        _ => Err(ser::Error::custom("map keys must be strings, integers or booleans")),
+    }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Node;
+    type Error = SerializationError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerializationError> {
+        self.key = Some(key_string(key.serialize(NodeSerializer)?)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializationError> {
+        let key = self.key.take().ok_or_else(|| SerializationError::Message("map value serialized before its key".to_string()))?;
+        self.entries.insert(key, value.serialize(NodeSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Node, SerializationError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer {
+    type Ok = Node;
+    type Error = SerializationError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), SerializationError> {
+        self.entries.insert(key.to_string(), value.serialize(NodeSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Node, SerializationError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for MapSerializer {
+    type Ok = Node;
+    type Error = SerializationError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), SerializationError> {
+        self.entries.insert(key.to_string(), value.serialize(NodeSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Node, SerializationError> {
+        self.finish()
+    }
+}
+
+/// Serializes a Rust value into a Node.
+///
+/// # Arguments
+/// * `value` - The value to serialize
+///
+/// # Returns
+/// The Node or a SerializationError (for example for unsupported map key types).
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Node, SerializationError> {
+    value.serialize(NodeSerializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::node::Numeric;
+    use serde::Serialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    struct Server {
+        host: String,
+        port: u16,
+        timeout: Option<u32>,
+    }
+
+    #[derive(Serialize)]
+    enum Policy {
+        Always,
+        Retry(u8),
+        Window(u8, u8),
+        Backoff { initial: u32 },
+    }
+
+    #[test]
+    fn to_value_struct_works() {
+        let node = to_value(&Server { host: "web".to_string(), port: 80, timeout: None }).unwrap();
+        assert_eq!(node["host"], Node::from("web"));
+        assert!(node["port"].strict_eq(&Node::Number(Numeric::UInt16(80))));
+        assert_eq!(node["timeout"], Node::None);
+    }
+
+    #[test]
+    fn to_value_enum_works() {
+        assert_eq!(to_value(&Policy::Always), Ok(Node::from("Always")));
+        assert_eq!(to_value(&Policy::Retry(3)), Ok(Node::from(vec![("Retry", 3u8)])));
+        assert_eq!(to_value(&Policy::Window(1, 2)), Ok(Node::from(vec![("Window", vec![1u8, 2u8])])));
+        let node = to_value(&Policy::Backoff { initial: 5 }).unwrap();
+        assert_eq!(node["Backoff"]["initial"], Node::from(5u32));
+    }
+
+    #[test]
+    fn to_value_collections_work() {
+        assert_eq!(to_value(&vec![Some(1i64), None]), Ok(Node::Array(vec![Node::from(1i64), Node::None])));
+        assert_eq!(to_value(&(1u8, "a")), Ok(Node::Array(vec![Node::from(1u8), Node::from("a")])));
+        let mut map = BTreeMap::new();
+        map.insert(7, 'x');
+        assert_eq!(to_value(&map).unwrap()["7"], Node::from("x"));
+    }
+
+    #[test]
+    fn to_value_rejects_unsupported_keys() {
+        let mut map = BTreeMap::new();
+        map.insert(vec![1], 1);
+        assert!(to_value(&map).is_err());
+    }
+}
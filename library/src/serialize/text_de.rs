@@ -0,0 +1,865 @@
+//! serde Deserializer that reads Rust values straight from YAML text.
+//! The text is walked with the parser's own readers, but no Node is built: each block or
+//! flow collection is handed to the visitor entry by entry as it is read, and each scalar
+//! is resolved as the type reading it asks (a plain "42" is an integer where any value
+//! will do and the string "42" where a string is wanted). An alias is read by going back
+//! over the text of the node its anchor names. Comments are skipped, and enums are read
+//! from a scalar (unit variants) or a single entry mapping.
+
+use crate::error::parse::ParseError;
+use crate::error::serialization::SerializationError;
+use crate::io::sources::buffer::Buffer;
+use crate::io::traits::ISource;
+use crate::parser::default::{
+    at_document_marker, at_entry, at_line_end, at_mapping_key, line_indent, next_line, read_anchor_name,
+    read_block_key, read_block_scalar, read_flow_plain, read_plain_lines, read_quoted, read_trailing_comment,
+    read_value, resolve_key, skip_blanks, skip_flow_whitespace, special_float, unexpected_indentation,
+    unquote,
+};
+use crate::parser::lookahead::Lookahead;
+use crate::parser::options::DEFAULT_MAX_DEPTH;
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, Unexpected,
+    VariantAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+use std::collections::HashMap;
+
+/// Where the next node to be read starts.
+#[derive(Clone, Copy)]
+enum Start {
+    /// At the current character, in the given column, where a block collection may begin
+    Block(usize),
+    /// On the rest of the current line or, when that holds nothing, on the lines below;
+    /// compact lets a mapping value's sequence sit at the mapping's own indentation
+    Inline { compact: bool },
+    /// Inside a flow collection
+    Flow,
+}
+
+/// What the type being read asks its node for.
+#[derive(Clone, Copy, PartialEq)]
+enum Hint {
+    /// Whatever the node holds
+    Any,
+    /// An optional value, None for a null node
+    Option,
+    /// A string, so scalars are not resolved
+    Str,
+    /// An enum variant
+    Enum,
+}
+
+/// Where an anchored node starts, so an alias can read it again.
+#[derive(Clone, Copy)]
+struct Mark {
+    /// Byte offset in the text just after the anchor's name
+    offset: usize,
+    /// How the node starts from there
+    start: Start,
+    /// Indentation of the collection holding the node
+    parent: Option<usize>,
+}
+
+/// Deserializer over YAML text.
+pub(crate) struct TextDeserializer<'a> {
+    /// The whole text, read again from an anchor for each alias
+    text: &'a str,
+    /// Byte offset in text at which source starts
+    offset: usize,
+    source: Lookahead<'a>,
+    /// Anchors defined so far, by name
+    anchors: HashMap<String, Mark>,
+    /// Where the next node starts
+    start: Start,
+    /// Indentation of the collection holding the next node (None at the root)
+    parent: Option<usize>,
+    /// Indentation of the line the source is at after the last node read (None at the end
+    /// of the input), as the parser's line_indent returns it
+    next: Option<usize>,
+    /// Number of collections and aliases currently open
+    depth: usize,
+}
+
+impl<'a> TextDeserializer<'a> {
+    /// Creates a deserializer reading a document from text, held by source.
+    fn new(text: &'a str, source: &'a mut dyn ISource) -> Self {
+        Self {
+            text,
+            offset: 0,
+            source: Lookahead::new(source),
+            anchors: HashMap::new(),
+            start: Start::Block(0),
+            parent: None,
+            next: None,
+            depth: 0,
+        }
+    }
+
+    /// Opens a nested collection or alias, failing if that exceeds the maximum depth.
+    fn enter(&mut self) -> Result<(), SerializationError> {
+        self.depth += 1;
+        if self.depth > DEFAULT_MAX_DEPTH {
+            let message = format!("Nesting exceeds the maximum depth of {}", DEFAULT_MAX_DEPTH);
+            return Err(ParseError::Syntax(message).into());
+        }
+        Ok(())
+    }
+
+    /// Closes a nested collection or alias opened with enter.
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Moves past any comment lines and "---" marker before the document's root node.
+    fn begin(&mut self) -> Result<(), SerializationError> {
+        let source = &mut self.source;
+        let mut next = line_indent(source).0;
+        while let Some(indent) = next {
+            let c = source.current().unwrap_or_default();
+            next = if indent == 0 && at_document_marker(source) {
+                for _ in 0..3 {
+                    source.next();
+                }
+                skip_blanks(source);
+                if c == '-' && !at_line_end(source) {
+                    // The document starts on the "---" line itself
+                    self.start = Start::Inline { compact: false };
+                    return Ok(());
+                }
+                next_line(source)
+            } else if c == '#' {
+                next_line(source)
+            } else if "@`,]}%!?:".contains(c) {
+                return Err(ParseError::Syntax(format!("Unexpected character: {}", c)).into());
+            } else {
+                self.start = Start::Block(indent);
+                return Ok(());
+            };
+        }
+        Ok(())
+    }
+
+    /// Checks that nothing but comment lines and a "..." marker follow the root node.
+    fn end(&mut self) -> Result<(), SerializationError> {
+        let source = &mut self.source;
+        while let Some(indent) = self.next {
+            let end_marker = indent == 0 && at_document_marker(source) && source.current() == Some('.');
+            if source.current() != Some('#') && !end_marker {
+                let line = read_value(source);
+                let message = format!("Expected a single document but found: {}", line.trim());
+                return Err(ParseError::Syntax(message).into());
+            }
+            self.next = next_line(source);
+        }
+        Ok(())
+    }
+
+    /// Reads an anchor's name, recording where its node starts in anchors.
+    fn anchor(&mut self, anchors: &mut Vec<(String, Mark)>, start: Start) -> Result<(), SerializationError> {
+        let name = read_anchor_name(&mut self.source)?;
+        let mark = Mark { offset: self.offset + self.source.position(), start, parent: self.parent };
+        match start {
+            Start::Flow => skip_flow_whitespace(&mut self.source),
+            _ => skip_blanks(&mut self.source),
+        }
+        if self.source.current() == Some('&') {
+            return Err(ParseError::Syntax(format!("Node anchored as {} has a second anchor", name)).into());
+        }
+        anchors.push((name, mark));
+        self.start = start;
+        Ok(())
+    }
+
+    /// Moves to the content of the next node past any comment lines and anchors before it,
+    /// adding the anchors to anchors. Returns where the content starts, or None if the node
+    /// is empty (null).
+    fn reach(&mut self, anchors: &mut Vec<(String, Mark)>) -> Result<Option<Start>, SerializationError> {
+        loop {
+            match self.start {
+                Start::Flow => {
+                    skip_flow_whitespace(&mut self.source);
+                    match self.source.current() {
+                        Some('&') => self.anchor(anchors, Start::Flow)?,
+                        Some(',' | ']' | '}') => return Ok(None),
+                        Some(_) => return Ok(Some(Start::Flow)),
+                        None => return Err(ParseError::Syntax("Unterminated flow collection".to_string()).into()),
+                    }
+                }
+                Start::Inline { compact } => {
+                    skip_blanks(&mut self.source);
+                    if self.source.current() == Some('&') {
+                        self.anchor(anchors, Start::Inline { compact: false })?;
+                        continue;
+                    }
+                    if !at_line_end(&mut self.source) {
+                        return Ok(Some(Start::Inline { compact: false }));
+                    }
+                    read_trailing_comment(&mut self.source);
+                    let parent = self.parent;
+                    match next_line(&mut self.source) {
+                        Some(next)
+                            if parent.is_none_or(|parent| next > parent)
+                                && !(next == 0 && at_document_marker(&mut self.source)) =>
+                        {
+                            self.start = Start::Block(next)
+                        }
+                        Some(next) if compact && Some(next) == parent && at_entry(&mut self.source) => {
+                            return Ok(Some(Start::Block(next)));
+                        }
+                        next => {
+                            self.next = next;
+                            return Ok(None);
+                        }
+                    }
+                }
+                Start::Block(column) => {
+                    let mut column = column;
+                    while self.source.current() == Some('#') {
+                        read_trailing_comment(&mut self.source);
+                        match next_line(&mut self.source) {
+                            Some(next) if self.parent.is_none_or(|parent| next > parent) => column = next,
+                            next => {
+                                self.next = next;
+                                return Ok(None);
+                            }
+                        }
+                    }
+                    match self.source.current() {
+                        Some('&') => self.anchor(anchors, Start::Inline { compact: false })?,
+                        Some(_) => return Ok(Some(Start::Block(column))),
+                        None => {
+                            self.next = None;
+                            return Ok(None);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reads the next node for a visitor. Anchors on it are defined once it has been read,
+    /// so an alias inside it cannot refer to it.
+    fn node<'de, V: Visitor<'de>>(&mut self, hint: Hint, visitor: V) -> Result<V::Value, SerializationError> {
+        let mut anchors = Vec::new();
+        let value = match self.reach(&mut anchors)? {
+            None if hint == Hint::Option => visitor.visit_none::<SerializationError>()?,
+            None => visitor.visit_unit::<SerializationError>()?,
+            Some(start) => {
+                self.start = start;
+                self.content(hint, visitor)?
+            }
+        };
+        self.anchors.extend(anchors);
+        Ok(value)
+    }
+
+    /// Reads a node whose content is at the current character.
+    fn content<'de, V: Visitor<'de>>(&mut self, hint: Hint, visitor: V) -> Result<V::Value, SerializationError> {
+        let source = &mut self.source;
+        let block = match self.start {
+            Start::Block(column) if at_entry(source) => Some((column, false)),
+            Start::Block(column) if at_mapping_key(source) => Some((column, true)),
+            _ => None,
+        };
+        if hint == Hint::Option && (block.is_some() || matches!(source.current(), Some('[' | '{'))) {
+            // Read again, from the same place, as the value inside the option
+            return visitor.visit_some(self);
+        }
+        match (block, self.start) {
+            (Some((column, false)), _) => self.block_sequence(column, hint, visitor),
+            (Some((column, true)), _) => self.block_mapping(column, hint, visitor),
+            (None, Start::Flow) => self.flow_node(hint, visitor),
+            (None, _) => self.inline_node(hint, visitor),
+        }
+    }
+
+    /// Reads a node that starts part way along a line: a flow collection, an alias or a
+    /// scalar, with any comment after it.
+    fn inline_node<'de, V: Visitor<'de>>(&mut self, hint: Hint, visitor: V) -> Result<V::Value, SerializationError> {
+        let source = &mut self.source;
+        let scalar = match source.current() {
+            Some('*' | '[' | '{') => {
+                let value = self.flow_node(hint, visitor)?;
+                let source = &mut self.source;
+                skip_blanks(source);
+                if let Some(c) = source.current().filter(|_| !at_line_end(source)) {
+                    let message = format!("Unexpected character after flow collection or alias: {}", c);
+                    return Err(ParseError::Syntax(message).into());
+                }
+                read_trailing_comment(source);
+                self.next = next_line(source);
+                return Ok(value);
+            }
+            Some('"' | '\'') => {
+                let raw = read_quoted(source)?;
+                skip_blanks(source);
+                if let Some(c) = source.current().filter(|_| !at_line_end(source)) {
+                    return Err(ParseError::Syntax(format!("Unexpected character after quoted scalar: {}", c)).into());
+                }
+                read_trailing_comment(source);
+                self.next = next_line(source);
+                Scalar { text: unquote(&raw).unwrap_or(raw), plain: false }
+            }
+            Some('|' | '>') => {
+                let (text, _, next) = read_block_scalar(source, self.parent)?;
+                self.next = next;
+                Scalar { text, plain: false }
+            }
+            _ => {
+                let first = read_value(source).trim().to_string();
+                let (text, _, next) = read_plain_lines(source, first, self.parent);
+                self.next = next;
+                Scalar { text, plain: true }
+            }
+        };
+        scalar.visit(hint, visitor)
+    }
+
+    /// Reads a node inside a flow collection, or an alias or flow collection in block context.
+    fn flow_node<'de, V: Visitor<'de>>(&mut self, hint: Hint, visitor: V) -> Result<V::Value, SerializationError> {
+        let source = &mut self.source;
+        match source.current() {
+            Some('*') => self.alias(hint, visitor),
+            Some('[') => {
+                if hint == Hint::Enum {
+                    return Err(de::Error::invalid_type(Unexpected::Seq, &"a string or single entry mapping"));
+                }
+                self.enter()?;
+                self.source.next(); // Skip '['
+                let mut items = FlowSequence { de: self, done: false };
+                let value = visitor.visit_seq(&mut items)?;
+                items.end()?;
+                self.leave();
+                Ok(value)
+            }
+            Some('{') => {
+                self.enter()?;
+                self.source.next(); // Skip '{'
+                let mut entries = FlowMapping { de: self, done: false };
+                let value = if hint == Hint::Enum {
+                    visitor.visit_enum(Variant { entries })?
+                } else {
+                    let value = visitor.visit_map(&mut entries)?;
+                    entries.end()?;
+                    value
+                };
+                self.leave();
+                Ok(value)
+            }
+            Some('"' | '\'') => {
+                let raw = read_quoted(source)?;
+                Scalar { text: unquote(&raw).unwrap_or(raw), plain: false }.visit(hint, visitor)
+            }
+            _ => Scalar { text: read_flow_plain(source, false), plain: true }.visit(hint, visitor),
+        }
+    }
+
+    /// Reads an alias by reading the text of the node its anchor names again.
+    fn alias<'de, V: Visitor<'de>>(&mut self, hint: Hint, visitor: V) -> Result<V::Value, SerializationError> {
+        let name = read_anchor_name(&mut self.source)?;
+        let Some(mark) = self.anchors.get(&name).copied() else {
+            return Err(ParseError::Syntax(format!("Undefined alias: {}", name)).into());
+        };
+        let mut buffer = Buffer::new(&self.text.as_bytes()[mark.offset..]);
+        let mut anchored = TextDeserializer::new(self.text, &mut buffer);
+        anchored.offset = mark.offset;
+        anchored.anchors = self.anchors.clone();
+        anchored.start = mark.start;
+        anchored.parent = mark.parent;
+        anchored.depth = self.depth;
+        anchored.enter()?;
+        anchored.node(hint, visitor)
+    }
+
+    /// Reads a block sequence whose first '-' indicator is at the current character, in
+    /// column indent.
+    fn block_sequence<'de, V: Visitor<'de>>(
+        &mut self,
+        indent: usize,
+        hint: Hint,
+        visitor: V,
+    ) -> Result<V::Value, SerializationError> {
+        if hint == Hint::Enum {
+            return Err(de::Error::invalid_type(Unexpected::Seq, &"a string or single entry mapping"));
+        }
+        self.enter()?;
+        let mut items = BlockSequence { de: self, indent, done: false };
+        let value = visitor.visit_seq(&mut items)?;
+        items.end()?;
+        self.leave();
+        Ok(value)
+    }
+
+    /// Reads a block mapping whose first key is at the current character, in column indent.
+    fn block_mapping<'de, V: Visitor<'de>>(
+        &mut self,
+        indent: usize,
+        hint: Hint,
+        visitor: V,
+    ) -> Result<V::Value, SerializationError> {
+        self.enter()?;
+        let mut entries = BlockMapping { de: self, indent, done: false };
+        let value = if hint == Hint::Enum {
+            visitor.visit_enum(Variant { entries })?
+        } else {
+            let value = visitor.visit_map(&mut entries)?;
+            entries.end()?;
+            value
+        };
+        self.leave();
+        Ok(value)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut TextDeserializer<'_> {
+    type Error = SerializationError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerializationError> {
+        self.node(Hint::Any, visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerializationError> {
+        self.node(Hint::Option, visitor)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerializationError> {
+        self.node(Hint::Str, visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerializationError> {
+        self.node(Hint::Str, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerializationError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerializationError> {
+        self.node(Hint::Enum, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// A scalar read from the text, resolved once the type reading it says what it wants.
+struct Scalar {
+    text: String,
+    /// The scalar was written plain, so it may stand for null, a boolean or a number
+    plain: bool,
+}
+
+impl Scalar {
+    /// Hands the scalar to a visitor as the hint asks: plain scalars are resolved as the
+    /// parser's core schema resolves them unless a string is wanted.
+    fn visit<'de, V: Visitor<'de>>(self, hint: Hint, visitor: V) -> Result<V::Value, SerializationError> {
+        let text = self.text.as_str();
+        match hint {
+            Hint::Str => visitor.visit_string(self.text),
+            Hint::Enum => visitor.visit_enum(self.text.into_deserializer()),
+            Hint::Option if self.plain && (text == "null" || text == "~") => visitor.visit_none(),
+            Hint::Option => visitor.visit_some(self),
+            Hint::Any if !self.plain => visitor.visit_string(self.text),
+            Hint::Any => match text {
+                "null" | "~" => visitor.visit_unit(),
+                "true" => visitor.visit_bool(true),
+                "false" => visitor.visit_bool(false),
+                _ => {
+                    if let Ok(value) = text.parse::<i64>() {
+                        visitor.visit_i64(value)
+                    } else if let Ok(value) = text.parse::<u64>() {
+                        visitor.visit_u64(value)
+                    } else if let Some(value) = text.parse::<f64>().ok().or_else(|| special_float(text)) {
+                        visitor.visit_f64(value)
+                    } else {
+                        visitor.visit_string(self.text)
+                    }
+                }
+            },
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Scalar {
+    type Error = SerializationError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerializationError> {
+        self.visit(Hint::Any, visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerializationError> {
+        self.visit(Hint::Option, visitor)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerializationError> {
+        self.visit(Hint::Str, visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerializationError> {
+        self.visit(Hint::Str, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerializationError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerializationError> {
+        self.visit(Hint::Enum, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// A collection being read, which must be read to its end.
+trait Entries {
+    /// Fails if the visitor stopped before the collection's last entry.
+    fn end(&mut self) -> Result<(), SerializationError>;
+}
+
+/// Items of a block sequence, read as the visitor asks for them.
+struct BlockSequence<'d, 'a> {
+    de: &'d mut TextDeserializer<'a>,
+    /// Column of the sequence's '-' indicators
+    indent: usize,
+    /// The line after the last item has been reached
+    done: bool,
+}
+
+impl BlockSequence<'_, '_> {
+    /// Decides from the line after an item or comment whether the sequence goes on.
+    fn advance(&mut self) -> Result<(), SerializationError> {
+        let source = &mut self.de.source;
+        match self.de.next {
+            Some(next) if next == self.indent && (at_entry(source) || source.current() == Some('#')) => {}
+            Some(next) if next > self.indent && source.current() == Some('#') => {}
+            Some(next) if next > self.indent => return Err(unexpected_indentation(source).into()),
+            _ => self.done = true,
+        }
+        Ok(())
+    }
+}
+
+impl<'de> SeqAccess<'de> for BlockSequence<'_, '_> {
+    type Error = SerializationError;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>, SerializationError> {
+        while !self.done && self.de.source.current() == Some('#') {
+            read_trailing_comment(&mut self.de.source);
+            self.de.next = next_line(&mut self.de.source);
+            self.advance()?;
+        }
+        if self.done {
+            return Ok(None);
+        }
+        let source = &mut self.de.source;
+        source.next(); // Skip '-'
+        let mut column = self.indent + 1;
+        while let Some(' ' | '\t') = source.current() {
+            column += 1;
+            source.next();
+        }
+        self.de.start = if at_line_end(source) { Start::Inline { compact: false } } else { Start::Block(column) };
+        self.de.parent = Some(self.indent);
+        let value = seed.deserialize(&mut *self.de)?;
+        self.advance()?;
+        Ok(Some(value))
+    }
+}
+
+impl Entries for BlockSequence<'_, '_> {
+    fn end(&mut self) -> Result<(), SerializationError> {
+        if self.done { Ok(()) } else { Err(de::Error::custom("Sequence has more items than expected")) }
+    }
+}
+
+/// Entries of a block mapping, read as the visitor asks for them.
+struct BlockMapping<'d, 'a> {
+    de: &'d mut TextDeserializer<'a>,
+    /// Column of the mapping's keys
+    indent: usize,
+    /// The line after the last entry has been reached
+    done: bool,
+}
+
+impl BlockMapping<'_, '_> {
+    /// Decides from the line after an entry or comment whether the mapping goes on.
+    fn advance(&mut self) -> Result<(), SerializationError> {
+        let source = &mut self.de.source;
+        match self.de.next {
+            Some(next) if next == self.indent && at_entry(source) => {
+                let message = "Expected a mapping key but found a sequence entry".to_string();
+                return Err(ParseError::Syntax(message).into());
+            }
+            Some(next) if next == self.indent && !(next == 0 && at_document_marker(source)) => {}
+            Some(next) if next > self.indent && source.current() == Some('#') => {}
+            Some(next) if next > self.indent => return Err(unexpected_indentation(source).into()),
+            _ => self.done = true,
+        }
+        Ok(())
+    }
+}
+
+impl<'de> MapAccess<'de> for BlockMapping<'_, '_> {
+    type Error = SerializationError;
+
+    fn next_key_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>, SerializationError> {
+        while !self.done && self.de.source.current() == Some('#') {
+            read_trailing_comment(&mut self.de.source);
+            self.de.next = next_line(&mut self.de.source);
+            self.advance()?;
+        }
+        if self.done {
+            return Ok(None);
+        }
+        let source = &mut self.de.source;
+        if !at_mapping_key(source) {
+            let line = read_value(source);
+            return Err(ParseError::Syntax(format!("Expected ':' after mapping key: {}", line.trim())).into());
+        }
+        let key = read_block_key(source)?;
+        seed.deserialize(key.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, SerializationError> {
+        self.de.start = Start::Inline { compact: true };
+        self.de.parent = Some(self.indent);
+        let value = seed.deserialize(&mut *self.de)?;
+        self.advance()?;
+        Ok(value)
+    }
+}
+
+impl Entries for BlockMapping<'_, '_> {
+    fn end(&mut self) -> Result<(), SerializationError> {
+        if self.done { Ok(()) } else { Err(de::Error::custom("Mapping has more entries than expected")) }
+    }
+}
+
+/// Items of a flow sequence after its '[', read as the visitor asks for them.
+struct FlowSequence<'d, 'a> {
+    de: &'d mut TextDeserializer<'a>,
+    /// The closing ']' has been read
+    done: bool,
+}
+
+impl<'de> SeqAccess<'de> for FlowSequence<'_, '_> {
+    type Error = SerializationError;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>, SerializationError> {
+        let source = &mut self.de.source;
+        skip_flow_whitespace(source);
+        match source.current() {
+            _ if self.done => return Ok(None),
+            Some(']') => {
+                source.next();
+                self.done = true;
+                return Ok(None);
+            }
+            Some(',') => return Err(ParseError::Syntax("Unexpected ',' in flow sequence".to_string()).into()),
+            _ => {}
+        }
+        self.de.start = Start::Flow;
+        let value = seed.deserialize(&mut *self.de)?;
+        let source = &mut self.de.source;
+        skip_flow_whitespace(source);
+        match source.current() {
+            Some(',') => source.next(),
+            Some(']') => {}
+            Some(c) => {
+                return Err(ParseError::Syntax(format!("Expected ',' or ']' in flow sequence but found: {}", c)).into());
+            }
+            None => return Err(ParseError::Syntax("Unterminated flow sequence".to_string()).into()),
+        }
+        Ok(Some(value))
+    }
+}
+
+impl Entries for FlowSequence<'_, '_> {
+    fn end(&mut self) -> Result<(), SerializationError> {
+        let source = &mut self.de.source;
+        if !self.done {
+            skip_flow_whitespace(source);
+            if source.current() == Some(']') {
+                source.next();
+                self.done = true;
+            }
+        }
+        if self.done { Ok(()) } else { Err(de::Error::custom("Sequence has more items than expected")) }
+    }
+}
+
+/// Entries of a flow mapping after its '{', read as the visitor asks for them.
+struct FlowMapping<'d, 'a> {
+    de: &'d mut TextDeserializer<'a>,
+    /// The closing '}' has been read
+    done: bool,
+}
+
+impl<'de> MapAccess<'de> for FlowMapping<'_, '_> {
+    type Error = SerializationError;
+
+    fn next_key_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>, SerializationError> {
+        let source = &mut self.de.source;
+        skip_flow_whitespace(source);
+        let key = match source.current() {
+            _ if self.done => return Ok(None),
+            Some('}') => {
+                source.next();
+                self.done = true;
+                return Ok(None);
+            }
+            Some('"' | '\'') => resolve_key(&read_quoted(source)?),
+            Some(',') => return Err(ParseError::Syntax("Unexpected ',' in flow mapping".to_string()).into()),
+            Some(_) => read_flow_plain(source, true),
+            None => return Err(ParseError::Syntax("Unterminated flow mapping".to_string()).into()),
+        };
+        skip_flow_whitespace(source);
+        // A key without a ':' leaves the source at the ',' or '}' after it, so its value reads as null
+        match source.current() {
+            Some(':') => source.next(),
+            Some(',' | '}') => {}
+            Some(c) => {
+                return Err(ParseError::Syntax(format!("Expected ',' or '}}' in flow mapping but found: {}", c)).into());
+            }
+            None => return Err(ParseError::Syntax("Unterminated flow mapping".to_string()).into()),
+        }
+        seed.deserialize(key.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, SerializationError> {
+        self.de.start = Start::Flow;
+        let value = seed.deserialize(&mut *self.de)?;
+        let source = &mut self.de.source;
+        skip_flow_whitespace(source);
+        match source.current() {
+            Some(',') => source.next(),
+            Some('}') => {}
+            Some(c) => {
+                return Err(ParseError::Syntax(format!("Expected ',' or '}}' in flow mapping but found: {}", c)).into());
+            }
+            None => return Err(ParseError::Syntax("Unterminated flow mapping".to_string()).into()),
+        }
+        Ok(value)
+    }
+}
+
+impl Entries for FlowMapping<'_, '_> {
+    fn end(&mut self) -> Result<(), SerializationError> {
+        let source = &mut self.de.source;
+        if !self.done {
+            skip_flow_whitespace(source);
+            if source.current() == Some('}') {
+                source.next();
+                self.done = true;
+            }
+        }
+        if self.done { Ok(()) } else { Err(de::Error::custom("Mapping has more entries than expected")) }
+    }
+}
+
+/// An enum variant read from a single entry mapping: the key names the variant and the
+/// value holds its data.
+struct Variant<M> {
+    entries: M,
+}
+
+impl<'de, M: MapAccess<'de, Error = SerializationError> + Entries> EnumAccess<'de> for Variant<M> {
+    type Error = SerializationError;
+    type Variant = Self;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(mut self, seed: S) -> Result<(S::Value, Self), SerializationError> {
+        match self.entries.next_key_seed(seed)? {
+            Some(variant) => Ok((variant, self)),
+            None => Err(de::Error::invalid_length(0, &"a single entry mapping")),
+        }
+    }
+}
+
+impl<'de, M: MapAccess<'de, Error = SerializationError> + Entries> VariantAccess<'de> for Variant<M> {
+    type Error = SerializationError;
+
+    fn unit_variant(mut self) -> Result<(), SerializationError> {
+        self.entries.next_value::<()>()?;
+        self.entries.end()
+    }
+
+    fn newtype_variant_seed<S: DeserializeSeed<'de>>(mut self, seed: S) -> Result<S::Value, SerializationError> {
+        let value = self.entries.next_value_seed(seed)?;
+        self.entries.end()?;
+        Ok(value)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(mut self, _len: usize, visitor: V) -> Result<V::Value, SerializationError> {
+        let value = self.entries.next_value_seed(Shaped { visitor, mapping: false })?;
+        self.entries.end()?;
+        Ok(value)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        mut self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerializationError> {
+        let value = self.entries.next_value_seed(Shaped { visitor, mapping: true })?;
+        self.entries.end()?;
+        Ok(value)
+    }
+}
+
+/// Reads a value as a sequence (for a tuple variant) or a mapping (for a struct variant).
+struct Shaped<V> {
+    visitor: V,
+    mapping: bool,
+}
+
+impl<'de, V: Visitor<'de>> DeserializeSeed<'de> for Shaped<V> {
+    type Value = V::Value;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        if self.mapping {
+            deserializer.deserialize_map(self.visitor)
+        } else {
+            deserializer.deserialize_seq(self.visitor)
+        }
+    }
+}
+
+/// Deserializes a Rust value from the single YAML document held in text.
+///
+/// # Arguments
+/// * `text` - The YAML text to read
+///
+/// # Returns
+/// The value, or a SerializationError if the text is not valid YAML or does not match the type.
+pub(crate) fn from_text<T: DeserializeOwned>(text: &str) -> Result<T, SerializationError> {
+    let mut buffer = Buffer::new(text.as_bytes());
+    let mut deserializer = TextDeserializer::new(text, &mut buffer);
+    deserializer.begin()?;
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
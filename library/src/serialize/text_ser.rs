@@ -0,0 +1,584 @@
+//! serde Serializer that writes Rust values straight out as YAML text.
+//! Values are written as they are serialized, with no Node built first, in the block
+//! style layout the default emitter gives: "key: value" and "- item" lines indented by
+//! two spaces, a collection inside a sequence starting on the item's own line, empty
+//! collections as "[]" and "{}", and multi-line strings as literal block scalars. Strings
+//! are quoted and numbers formatted as the default emitter does, and enums use a string
+//! for unit variants and a single entry mapping keyed by the variant name otherwise.
+
+use crate::error::serialization::SerializationError;
+use crate::nodes::node::Numeric;
+use crate::stringify::default::{literal_header, write_literal_lines, write_quoted};
+use crate::stringify::number::format_number;
+use crate::stringify::options::{DEFAULT_INDENT, EmitterOptions, QuoteStyle};
+use serde::ser::{self, Impossible, Serialize};
+
+/// Where a value is written relative to what is already on its line.
+#[derive(Clone, Copy, PartialEq)]
+enum Place {
+    /// At the start of the output
+    Root,
+    /// After a mapping key's ':'
+    Value,
+    /// After a sequence item's "- "
+    Item,
+}
+
+/// Serializer writing one value to a string.
+pub(crate) struct TextSerializer<'o> {
+    output: &'o mut String,
+    place: Place,
+    /// Indentation of the lines the value's entries (or block scalar lines) are written on
+    column: usize,
+}
+
+impl<'o> TextSerializer<'o> {
+    /// Writes a scalar's text and ends its line.
+    fn scalar(self, text: &str) -> Result<(), SerializationError> {
+        if self.place == Place::Value {
+            self.output.push(' ');
+        }
+        self.output.push_str(text);
+        self.output.push('\n');
+        Ok(())
+    }
+
+    /// Writes a number as the default emitter formats it.
+    fn number(self, number: Numeric) -> Result<(), SerializationError> {
+        self.scalar(&format_number(&number, &EmitterOptions::new()))
+    }
+
+    /// Writes a string as a literal block scalar if it spans lines and can be, otherwise
+    /// plain or double quoted as its text needs.
+    fn string(self, text: &str) -> Result<(), SerializationError> {
+        // A value's lines are indented one step further than its key or '-' indicator
+        let Some(header) = literal_header(text, DEFAULT_INDENT) else {
+            let mut quoted = String::with_capacity(text.len() + 2);
+            write_quoted(text, QuoteStyle::Minimal, false, &mut |part| quoted.push_str(part));
+            return self.scalar(&quoted);
+        };
+        if self.place == Place::Value {
+            self.output.push(' ');
+        }
+        self.output.push_str(&header);
+        self.output.push('\n');
+        write_literal_lines(text, self.column.max(DEFAULT_INDENT), &mut |part| self.output.push_str(part));
+        Ok(())
+    }
+
+    /// Starts a sequence or mapping whose entries are written as they are serialized.
+    fn collection(self, mapping: bool) -> Collection<'o> {
+        Collection { output: self.output, place: self.place, column: self.column, entries: 0, mapping }
+    }
+
+    /// Starts the single entry mapping an enum variant holding data is written as,
+    /// returning it with the variant name written as its key.
+    fn variant(self, variant: &str) -> Collection<'o> {
+        let mut entries = self.collection(true);
+        entries.key(variant);
+        entries
+    }
+}
+
+/// Writes the entries of a sequence, tuple, mapping or struct as they are serialized.
+pub(crate) struct Collection<'o> {
+    output: &'o mut String,
+    /// Where the collection itself is written
+    place: Place,
+    /// Indentation of the collection's entries
+    column: usize,
+    /// Number of entries written so far
+    entries: usize,
+    mapping: bool,
+}
+
+impl Collection<'_> {
+    /// Moves to where the next entry is written: the first entry of a root collection or of
+    /// one inside a sequence goes on the current line, every other entry on a new one.
+    fn next_entry(&mut self) {
+        if self.entries == 0 && self.place == Place::Value {
+            self.output.push('\n');
+        }
+        if self.entries > 0 || self.place == Place::Value {
+            self.output.extend(std::iter::repeat_n(' ', self.column));
+        }
+        self.entries += 1;
+    }
+
+    /// Writes a sequence item.
+    fn item<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializationError> {
+        self.next_entry();
+        self.output.push_str("- ");
+        value.serialize(TextSerializer { output: self.output, place: Place::Item, column: self.column + 2 })
+    }
+
+    /// Writes a mapping key and its ':'. A plain key ends at its first ':', so keys
+    /// containing one are double quoted.
+    fn key(&mut self, key: &str) {
+        self.next_entry();
+        let style = if key.contains(':') { QuoteStyle::Double } else { QuoteStyle::Minimal };
+        write_quoted(key, style, false, &mut |part| self.output.push_str(part));
+        self.output.push(':');
+    }
+
+    /// Writes the value of the mapping entry whose key was written last.
+    fn value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializationError> {
+        let column = self.column + DEFAULT_INDENT;
+        value.serialize(TextSerializer { output: self.output, place: Place::Value, column })
+    }
+
+    /// Writes the value of a tuple or struct variant's entry: its sequence or mapping.
+    fn nested(self, mapping: bool) -> Self {
+        let column = self.column + DEFAULT_INDENT;
+        Collection { output: self.output, place: Place::Value, column, entries: 0, mapping }
+    }
+
+    /// Ends the collection, writing it as "[]" or "{}" if it had no entries.
+    fn end(self) -> Result<(), SerializationError> {
+        if self.entries > 0 {
+            return Ok(());
+        }
+        let empty = if self.mapping { "{}" } else { "[]" };
+        TextSerializer { output: self.output, place: self.place, column: self.column }.scalar(empty)
+    }
+}
+
+impl<'o> ser::Serializer for TextSerializer<'o> {
+    type Ok = ();
+    type Error = SerializationError;
+    type SerializeSeq = Collection<'o>;
+    type SerializeTuple = Collection<'o>;
+    type SerializeTupleStruct = Collection<'o>;
+    type SerializeTupleVariant = Collection<'o>;
+    type SerializeMap = Collection<'o>;
+    type SerializeStruct = Collection<'o>;
+    type SerializeStructVariant = Collection<'o>;
+
+    fn serialize_bool(self, value: bool) -> Result<(), SerializationError> {
+        self.scalar(if value { "true" } else { "false" })
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<(), SerializationError> {
+        self.number(Numeric::Int8(value))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<(), SerializationError> {
+        self.number(Numeric::Int16(value))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<(), SerializationError> {
+        self.number(Numeric::Int32(value))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<(), SerializationError> {
+        self.number(Numeric::Integer(value))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<(), SerializationError> {
+        self.number(Numeric::Byte(value))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<(), SerializationError> {
+        self.number(Numeric::UInt16(value))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<(), SerializationError> {
+        self.number(Numeric::UInt32(value))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<(), SerializationError> {
+        self.number(Numeric::UInteger(value))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<(), SerializationError> {
+        self.number(Numeric::Float(value as f64))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<(), SerializationError> {
+        self.number(Numeric::Float(value))
+    }
+
+    fn serialize_char(self, value: char) -> Result<(), SerializationError> {
+        self.string(value.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<(), SerializationError> {
+        self.string(value)
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<(), SerializationError> {
+        let mut items = self.collection(false);
+        for byte in value {
+            items.item(byte)?;
+        }
+        items.end()
+    }
+
+    fn serialize_none(self) -> Result<(), SerializationError> {
+        self.scalar("null")
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), SerializationError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), SerializationError> {
+        self.scalar("null")
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), SerializationError> {
+        self.scalar("null")
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<(), SerializationError> {
+        self.string(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), SerializationError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), SerializationError> {
+        self.variant(variant).value(value)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Collection<'o>, SerializationError> {
+        Ok(self.collection(false))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Collection<'o>, SerializationError> {
+        Ok(self.collection(false))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Collection<'o>, SerializationError> {
+        Ok(self.collection(false))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Collection<'o>, SerializationError> {
+        Ok(self.variant(variant).nested(false))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Collection<'o>, SerializationError> {
+        Ok(self.collection(true))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Collection<'o>, SerializationError> {
+        Ok(self.collection(true))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Collection<'o>, SerializationError> {
+        Ok(self.variant(variant).nested(true))
+    }
+}
+
+impl ser::SerializeSeq for Collection<'_> {
+    type Ok = ();
+    type Error = SerializationError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializationError> {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), SerializationError> {
+        Collection::end(self)
+    }
+}
+
+impl ser::SerializeTuple for Collection<'_> {
+    type Ok = ();
+    type Error = SerializationError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializationError> {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), SerializationError> {
+        Collection::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for Collection<'_> {
+    type Ok = ();
+    type Error = SerializationError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializationError> {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), SerializationError> {
+        Collection::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for Collection<'_> {
+    type Ok = ();
+    type Error = SerializationError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializationError> {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), SerializationError> {
+        Collection::end(self)
+    }
+}
+
+impl ser::SerializeMap for Collection<'_> {
+    type Ok = ();
+    type Error = SerializationError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerializationError> {
+        let key = key.serialize(KeySerializer)?;
+        self.key(&key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializationError> {
+        self.value(value)
+    }
+
+    fn end(self) -> Result<(), SerializationError> {
+        Collection::end(self)
+    }
+}
+
+impl ser::SerializeStruct for Collection<'_> {
+    type Ok = ();
+    type Error = SerializationError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerializationError> {
+        self.key(key);
+        self.value(value)
+    }
+
+    fn end(self) -> Result<(), SerializationError> {
+        Collection::end(self)
+    }
+}
+
+impl ser::SerializeStructVariant for Collection<'_> {
+    type Ok = ();
+    type Error = SerializationError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerializationError> {
+        self.key(key);
+        self.value(value)
+    }
+
+    fn end(self) -> Result<(), SerializationError> {
+        Collection::end(self)
+    }
+}
+
+/// Serializer turning a map key into the text of a mapping key; strings, integers,
+/// booleans and unit enum variants are accepted.
+struct KeySerializer;
+
+/// Returns the error for a map key that cannot be written as a mapping key.
+fn unsupported_key() -> SerializationError {
+    ser::Error::custom("map keys must be strings, integers or booleans")
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = SerializationError;
+    type SerializeSeq = Impossible<String, SerializationError>;
+    type SerializeTuple = Impossible<String, SerializationError>;
+    type SerializeTupleStruct = Impossible<String, SerializationError>;
+    type SerializeTupleVariant = Impossible<String, SerializationError>;
+    type SerializeMap = Impossible<String, SerializationError>;
+    type SerializeStruct = Impossible<String, SerializationError>;
+    type SerializeStructVariant = Impossible<String, SerializationError>;
+
+    fn serialize_bool(self, value: bool) -> Result<String, SerializationError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<String, SerializationError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<String, SerializationError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<String, SerializationError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<String, SerializationError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<String, SerializationError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<String, SerializationError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<String, SerializationError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<String, SerializationError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_f32(self, _value: f32) -> Result<String, SerializationError> {
+        Err(ser::Error::custom("floating point map keys are not supported"))
+    }
+
+    fn serialize_f64(self, _value: f64) -> Result<String, SerializationError> {
+        Err(ser::Error::custom("floating point map keys are not supported"))
+    }
+
+    fn serialize_char(self, value: char) -> Result<String, SerializationError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_str(self, value: &str) -> Result<String, SerializationError> {
+        Ok(value.to_string())
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<String, SerializationError> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_none(self) -> Result<String, SerializationError> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<String, SerializationError> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_unit(self) -> Result<String, SerializationError> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, SerializationError> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<String, SerializationError> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, SerializationError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, SerializationError> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, SerializationError> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, SerializationError> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, SerializationError> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, SerializationError> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, SerializationError> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, SerializationError> {
+        Err(unsupported_key())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, SerializationError> {
+        Err(unsupported_key())
+    }
+}
+
+/// Serializes a Rust value to block style YAML text.
+///
+/// # Arguments
+/// * `value` - The value to serialize
+///
+/// # Returns
+/// The YAML text, or a SerializationError for a value YAML cannot hold (for example a map
+/// with sequence keys).
+pub(crate) fn to_text<T: Serialize + ?Sized>(value: &T) -> Result<String, SerializationError> {
+    let mut output = String::new();
+    value.serialize(TextSerializer { output: &mut output, place: Place::Root, column: 0 })?;
+    Ok(output)
+}
@@ -0,0 +1,191 @@
+//! Reading and writing Rust values as YAML text with serde.
+//! from_str reads values straight from the text and to_string writes them straight out,
+//! with no Node tree in between. The text is read as a single document, comments skipped
+//! and aliases read as the node their anchor names, and values are written as block style
+//! YAML laid out as the default emitter lays out a tree, with struct fields and map
+//! entries in the order serde gives them. from_value and to_value convert between values
+//! and Node trees instead.
+
+use crate::error::serialization::SerializationError;
+use crate::serialize::text_de::from_text;
+use crate::serialize::text_ser::to_text;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Deserializes a Rust value from YAML text holding a single document.
+///
+/// # Arguments
+/// * `text` - The YAML text to read
+///
+/// # Returns
+/// The value, or a SerializationError if the text is not valid YAML, holds more than one
+/// document or does not match the type.
+pub fn from_str<T: DeserializeOwned>(text: &str) -> Result<T, SerializationError> {
+    from_text(text)
+}
+
+/// Serializes a Rust value to block style YAML text.
+///
+/// The text is the same as the default emitter writes for the value's Node tree, keeping
+/// the order of its fields and map entries.
+///
+/// # Arguments
+/// * `value` - The value to serialize
+///
+/// # Returns
+/// The YAML text or a SerializationError if the value cannot be written as YAML (for
+/// example a map with sequence keys).
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, SerializationError> {
+    to_text(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Server {
+        host: String,
+        port: u16,
+        ratio: f64,
+        enabled: bool,
+        tags: Vec<String>,
+        timeout: Option<u32>,
+        policy: Policy,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    enum Policy {
+        Always,
+        Retry(u8),
+        Backoff { initial: u32, factor: f64 },
+    }
+
+    fn server(policy: Policy) -> Server {
+        Server {
+            host: "web \"one\"\n".to_string(),
+            port: 8080,
+            ratio: 2.0,
+            enabled: true,
+            tags: vec!["a".to_string(), "b: c".to_string()],
+            timeout: None,
+            policy,
+        }
+    }
+
+    #[test]
+    fn from_str_block_mapping_works() {
+        let text = "host: web\nport: 80\nratio: 0.5\nenabled: false\ntags: [x, y]\ntimeout: 30\npolicy: Always\n";
+        let parsed: Server = from_str(text).unwrap();
+        assert_eq!(parsed.host, "web");
+        assert_eq!(parsed.port, 80);
+        assert_eq!(parsed.tags, vec!["x".to_string(), "y".to_string()]);
+        assert_eq!(parsed.timeout, Some(30));
+        assert_eq!(parsed.policy, Policy::Always);
+    }
+
+    #[test]
+    fn from_str_scalars_work() {
+        assert_eq!(from_str::<i64>("42"), Ok(42));
+        assert_eq!(from_str::<i64>("-5"), Ok(-5));
+        assert_eq!(from_str::<String>("\"web\""), Ok("web".to_string()));
+        assert_eq!(from_str::<Vec<u8>>("[1, 2]"), Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn from_str_errors_work() {
+        assert!(matches!(from_str::<Server>("a: [1"), Err(SerializationError::Parse(_))));
+        assert!(matches!(from_str::<Server>("host: web\n"), Err(SerializationError::Message(_))));
+        assert!(matches!(from_str::<Vec<u8>>("[*a]"), Err(SerializationError::Parse(_))));
+        assert!(matches!(from_str::<u8>("---\n1\n---\n2\n"), Err(SerializationError::Parse(_))));
+        assert!(matches!(from_str::<(u8,)>("[1, 2]"), Err(SerializationError::Message(_))));
+    }
+
+    #[test]
+    fn from_str_reads_aliases_and_block_scalars() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Endpoint {
+            host: String,
+            port: u16,
+        }
+        #[derive(Debug, Deserialize)]
+        struct Config {
+            defaults: Endpoint,
+            servers: Vec<Endpoint>,
+            note: String,
+        }
+        let text = "defaults: &d\n  host: web\n  port: 80\nservers:\n- *d\n- {host: db, port: 5432}\n\
+            note: |\n  two\n  lines\n...\n";
+        let config: Config = from_str(text).unwrap();
+        assert_eq!(config.servers[0], config.defaults);
+        assert_eq!(config.servers[1], Endpoint { host: "db".to_string(), port: 5432 });
+        assert_eq!(config.note, "two\nlines\n");
+    }
+
+    #[test]
+    fn from_str_reads_scalars_as_the_type_asks() {
+        let strings = vec!["1.10".to_string(), "true".to_string(), "null".to_string()];
+        assert_eq!(from_str::<Vec<String>>("[1.10, true, null]"), Ok(strings));
+        assert_eq!(from_str::<Option<u8>>("~"), Ok(None));
+        let map = from_str::<BTreeMap<String, Option<u8>>>("a:\nb: 2 # two\n").unwrap();
+        assert_eq!(map, BTreeMap::from([("a".to_string(), None), ("b".to_string(), Some(2))]));
+        assert_eq!(from_str::<Policy>("{Retry: 4}"), Ok(Policy::Retry(4)));
+    }
+
+    #[test]
+    fn to_string_scalars_work() {
+        assert_eq!(to_string(&1.0).unwrap(), "1.0\n");
+        assert_eq!(to_string(&f64::NEG_INFINITY).unwrap(), "-.inf\n");
+        assert_eq!(to_string(&"a\"b").unwrap(), "a\"b\n");
+        assert_eq!(to_string(&"yes").unwrap(), "\"yes\"\n");
+        assert_eq!(to_string(&None::<u8>).unwrap(), "null\n");
+        let mut map = BTreeMap::new();
+        map.insert("b", vec![1, 2]);
+        map.insert("a", vec![]);
+        assert_eq!(to_string(&map).unwrap(), "a: []\nb:\n  - 1\n  - 2\n");
+    }
+
+    #[test]
+    fn to_string_matches_the_default_emitter() {
+        use crate::serialize::ser::to_value;
+        use crate::stringify::default::stringify;
+        let mut map = BTreeMap::new();
+        map.insert("k: v", vec![vec![], vec![Some(" x\ny"), None]]);
+        map.insert("n", vec![vec![Some("a")]]);
+        let value = (map, vec![Policy::Always, Policy::Backoff { initial: 1, factor: 0.5 }], "");
+        let mut destination = crate::io::destinations::buffer::Buffer::new();
+        stringify(&to_value(&value).unwrap(), &mut destination).unwrap();
+        assert_eq!(to_string(&value).unwrap(), destination.to_string());
+    }
+
+    #[test]
+    fn round_trip_works() {
+        for policy in [Policy::Always, Policy::Retry(3), Policy::Backoff { initial: 10, factor: 1.5 }] {
+            let value = server(policy);
+            let text = to_string(&value).unwrap();
+            assert_eq!(from_str::<Server>(&text), Ok(value), "{}", text);
+        }
+    }
+
+    #[test]
+    fn to_string_writes_block_yaml_in_field_order() {
+        let text = to_string(&server(Policy::Retry(3))).unwrap();
+        let expected = concat!(
+            "host: |\n  web \"one\"\nport: 8080\nratio: 2.0\nenabled: true\ntags:\n  - a\n  - \"b: c\"\n",
+            "timeout: null\npolicy:\n  Retry: 3\n"
+        );
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn from_str_skips_comments() {
+        let text = "# server settings\nhost: web # the host\nport: 80\nratio: 1.5\n# flags\nenabled: true\n\
+            tags:\n  - a\n  # more\n  - b\npolicy:\n  Backoff:\n    initial: 5\n    factor: 2.0\n";
+        let parsed: Server = from_str(text).unwrap();
+        assert_eq!(parsed.host, "web");
+        assert_eq!(parsed.tags, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(parsed.policy, Policy::Backoff { initial: 5, factor: 2.0 });
+    }
+}
@@ -0,0 +1,142 @@
+//! Bencode emitter.
+//! Writes a Node tree as bencode, the encoding of BitTorrent metainfo files: integers as
+//! "i42e", strings as their UTF-8 byte length, a ':' and the bytes ("4:spam"), arrays as
+//! "l...e" lists and dictionaries as "d...e" with keys in sorted byte order, as the
+//! format requires. Booleans are written as the integers 1 and 0. Bencode has no floats
+//! or null, so those are errors, as are streams of documents; comments are left out.
+//! Errors are reported before anything is written.
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::{Node, Numeric};
+use crate::nodes::kind::NodeKind;
+use crate::nodes::path::join;
+use crate::stringify::loss::{LossKind, LossReport};
+use crate::stringify::number::format_number;
+use crate::stringify::options::EmitterOptions;
+
+/// Writes a Node tree to a destination as bencode.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the bencode is written
+///
+/// # Returns
+/// Ok, or an error naming the path of a value bencode cannot represent; nothing is
+/// written when there is an error.
+pub fn stringify(node: &Node, destination: &mut dyn IDestination) -> Result<(), String> {
+    check(node, "")?;
+    write_node(node, destination);
+    Ok(())
+}
+
+/// Writes a Node tree to a destination as bencode and reports what the bencode does not
+/// hold.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the bencode is written
+///
+/// # Returns
+/// A report of the comments left out and the booleans written as integers, or the error
+/// stringify returns.
+pub fn stringify_with_report(node: &Node, destination: &mut dyn IDestination) -> Result<LossReport, String> {
+    stringify(node, destination)?;
+    let mut report = LossReport::new();
+    for (path, node) in node.walk() {
+        match node {
+            Node::Comment(_) => report.add(&path, LossKind::CommentDropped),
+            Node::Boolean(_) => report.add(&path, LossKind::TypeLost(NodeKind::Boolean)),
+            _ => {}
+        }
+    }
+    Ok(report)
+}
+
+/// Returns an error naming the first value below a node that bencode cannot represent.
+fn check(node: &Node, path: &str) -> Result<(), String> {
+    let at = if path.is_empty() { "/" } else { path };
+    match node {
+        Node::Number(Numeric::Float(_)) => Err(format!("Float at {} cannot be written as bencode", at)),
+        Node::None => Err(format!("Null at {} cannot be written as bencode", at)),
+        Node::Document(_) => Err(format!("A stream of documents at {} cannot be written as bencode", at)),
+        Node::Array(items) => {
+            items.iter().enumerate().try_for_each(|(index, item)| check(item, &join(path, &index.to_string())))
+        }
+        Node::Dictionary(_) => node.entries().try_for_each(|(key, value)| check(value, &join(path, key))),
+        _ => Ok(()),
+    }
+}
+
+fn write_string(text: &str, destination: &mut dyn IDestination) {
+    destination.add_bytes(&text.len().to_string());
+    destination.add_byte(b':');
+    destination.add_bytes(text);
+}
+
+fn write_node(node: &Node, destination: &mut dyn IDestination) {
+    match node {
+        Node::Boolean(value) => destination.add_bytes(if *value { "i1e" } else { "i0e" }),
+        Node::Number(number) => {
+            destination.add_byte(b'i');
+            destination.add_bytes(&format_number(number, &EmitterOptions::new()));
+            destination.add_byte(b'e');
+        }
+        Node::Str(text) => write_string(text, destination),
+        Node::Array(items) => {
+            destination.add_byte(b'l');
+            for item in items.iter().filter(|item| !item.is_comment()) {
+                write_node(item, destination);
+            }
+            destination.add_byte(b'e');
+        }
+        Node::Dictionary(_) => {
+            destination.add_byte(b'd');
+            // str ordering is byte ordering, which bencode requires for keys
+            for (key, value) in node.entries().filter(|(_, value)| !value.is_comment()) {
+                write_string(key, destination);
+                write_node(value, destination);
+            }
+            destination.add_byte(b'e');
+        }
+        Node::None | Node::Comment(_) | Node::Document(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+
+    fn emit(node: &Node) -> Result<String, String> {
+        let mut destination = Buffer::new();
+        stringify(node, &mut destination).map(|_| destination.to_string())
+    }
+
+    #[test]
+    fn stringify_bencode_works() {
+        let node = Node::dict()
+            .key("zeta", vec![Node::from(-3), Node::from("spam"), Node::Comment("skipped".to_string())])
+            .key("Z", true)
+            .key("a", Node::dict().key("é", "café").key("e", false))
+            .key("n", Node::Number(Numeric::UInteger(u64::MAX)))
+            .build();
+        let expected = "d1:Zi1e1:ad1:ei0e2:é5:cafée1:ni18446744073709551615e4:zetali-3e4:spamee";
+        assert_eq!(emit(&node).unwrap(), expected);
+        assert_eq!(emit(&Node::from("")).unwrap(), "0:");
+        let report = stringify_with_report(&node, &mut Buffer::new()).unwrap();
+        let messages: Vec<String> = report.iter().map(|loss| loss.to_string()).collect();
+        let expected = vec![
+            "Value at /Z lost its boolean type",
+            "Value at /a/e lost its boolean type",
+            "Comment at /zeta/2 was dropped",
+        ];
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn stringify_bencode_rejects_floats_and_nulls() {
+        let node = Node::dict().key("a", vec![Node::from(1), Node::from(1.5)]).build();
+        assert_eq!(emit(&node).unwrap_err(), "Float at /a/1 cannot be written as bencode");
+        assert_eq!(emit(&Node::None).unwrap_err(), "Null at / cannot be written as bencode");
+    }
+}
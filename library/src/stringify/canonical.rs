@@ -0,0 +1,96 @@
+//! Canonical YAML emitter.
+//! Writes every document after an explicit "---" marker, every scalar double quoted with
+//! an explicit tag (!!null, !!bool, !!int, !!float or !!str) and every collection as a
+//! tagged flow collection with one entry per line, "? key" / ": value" pairs and trailing
+//! commas, in the style of "yaml --canonical". Keys are sorted and comments dropped, so
+//! equal trees always give byte identical output.
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::{Node, Numeric};
+use crate::stringify::default::write_double_quoted;
+use crate::stringify::number::format_number;
+use crate::stringify::options::EmitterOptions;
+
+/// Writes a Node tree to a destination as canonical YAML.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the YAML text is written
+pub(crate) fn write_canonical(node: &Node, destination: &mut dyn IDestination) {
+    let documents = match node {
+        Node::Document(documents) => documents.iter().filter(|document| !document.is_comment()).collect(),
+        _ => vec![node],
+    };
+    for document in documents {
+        destination.add_bytes("---\n");
+        write_node(document, 0, destination);
+        destination.add_byte(b'\n');
+    }
+}
+
+/// Returns the tag of a scalar.
+fn scalar_tag(node: &Node) -> &'static str {
+    match node {
+        Node::Boolean(_) => "!!bool",
+        Node::Number(Numeric::Float(_)) => "!!float",
+        Node::Number(_) => "!!int",
+        Node::None => "!!null",
+        _ => "!!str",
+    }
+}
+
+fn indent(level: usize, destination: &mut dyn IDestination) {
+    for _ in 0..level {
+        destination.add_bytes("  ");
+    }
+}
+
+/// Writes a node whose first line is already indented and whose closing line is at level.
+fn write_node(node: &Node, level: usize, destination: &mut dyn IDestination) {
+    match node {
+        Node::Array(items) | Node::Document(items) => {
+            destination.add_bytes("!!seq [");
+            let items: Vec<&Node> = items.iter().filter(|item| !item.is_comment()).collect();
+            if !items.is_empty() {
+                destination.add_byte(b'\n');
+                for item in items {
+                    indent(level + 1, destination);
+                    write_node(item, level + 1, destination);
+                    destination.add_bytes(",\n");
+                }
+                indent(level, destination);
+            }
+            destination.add_byte(b']');
+        }
+        Node::Dictionary(_) => {
+            destination.add_bytes("!!map {");
+            let entries: Vec<(&str, &Node)> = node.entries().filter(|(_, value)| !value.is_comment()).collect();
+            if !entries.is_empty() {
+                destination.add_byte(b'\n');
+                for (key, value) in entries {
+                    indent(level + 1, destination);
+                    destination.add_bytes("? !!str ");
+                    write_double_quoted(key, &mut |part| destination.add_bytes(part));
+                    destination.add_byte(b'\n');
+                    indent(level + 1, destination);
+                    destination.add_bytes(": ");
+                    write_node(value, level + 1, destination);
+                    destination.add_bytes(",\n");
+                }
+                indent(level, destination);
+            }
+            destination.add_byte(b'}');
+        }
+        _ => {
+            destination.add_bytes(scalar_tag(node));
+            destination.add_byte(b' ');
+            let mut out = |part: &str| destination.add_bytes(part);
+            match node {
+                Node::Boolean(value) => write_double_quoted(if *value { "true" } else { "false" }, &mut out),
+                Node::Number(number) => write_double_quoted(&format_number(number, &EmitterOptions::new()), &mut out),
+                Node::Str(text) | Node::Comment(text) => write_double_quoted(text, &mut out),
+                _ => write_double_quoted("", &mut out),
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! CBOR emitter.
+//! Writes a Node tree as CBOR (RFC 8949) using definite lengths and the shortest argument
+//! for each integer and length: integers as major types 0 and 1, floats as double
+//! precision, strings as text strings, arrays and dictionaries as arrays and maps with
+//! keys in sorted order, and booleans and null as simple values. Node carries no tags of
+//! its own, so nothing is tagged; comments are left out and a stream of documents is an
+//! error, reported before anything is written.
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::{Node, Numeric};
+use crate::nodes::path::join;
+
+/// Writes a Node tree to a destination as CBOR.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the CBOR bytes are written
+///
+/// # Returns
+/// Ok, or an error naming the path of a value CBOR cannot represent; nothing is written
+/// when there is an error.
+pub fn stringify(node: &Node, destination: &mut dyn IDestination) -> Result<(), String> {
+    check(node, "")?;
+    write_node(node, destination);
+    Ok(())
+}
+
+/// Returns an error naming the first value below a node that CBOR cannot represent.
+fn check(node: &Node, path: &str) -> Result<(), String> {
+    let at = if path.is_empty() { "/" } else { path };
+    match node {
+        Node::Document(_) => Err(format!("A stream of documents at {} cannot be written as CBOR", at)),
+        Node::Array(items) => {
+            items.iter().enumerate().try_for_each(|(index, item)| check(item, &join(path, &index.to_string())))
+        }
+        Node::Dictionary(_) => node.entries().try_for_each(|(key, value)| check(value, &join(path, key))),
+        _ => Ok(()),
+    }
+}
+
+/// Writes an initial byte for a major type with the shortest encoding of its argument.
+fn write_head(major: u8, argument: u64, destination: &mut dyn IDestination) {
+    let major = major << 5;
+    let bytes = argument.to_be_bytes();
+    let (additional, length) = match argument {
+        0..=23 => (argument as u8, 0),
+        24..=0xff => (24, 1),
+        0x100..=0xffff => (25, 2),
+        0x1_0000..=0xffff_ffff => (26, 4),
+        _ => (27, 8),
+    };
+    destination.add_byte(major | additional);
+    for &byte in &bytes[8 - length..] {
+        destination.add_byte(byte);
+    }
+}
+
+fn write_string(text: &str, destination: &mut dyn IDestination) {
+    write_head(3, text.len() as u64, destination);
+    destination.add_bytes(text);
+}
+
+fn write_node(node: &Node, destination: &mut dyn IDestination) {
+    match node {
+        Node::None => destination.add_byte(0xf6),
+        Node::Boolean(value) => destination.add_byte(if *value { 0xf5 } else { 0xf4 }),
+        Node::Number(Numeric::Float(value)) => {
+            destination.add_byte(0xfb);
+            for byte in value.to_be_bytes() {
+                destination.add_byte(byte);
+            }
+        }
+        Node::Number(number) => {
+            let value = number.as_wide_integer().unwrap_or_default();
+            if value < 0 {
+                write_head(1, (-1 - value) as u64, destination);
+            } else {
+                write_head(0, value as u64, destination);
+            }
+        }
+        Node::Str(text) => write_string(text, destination),
+        Node::Array(items) => {
+            let items: Vec<&Node> = items.iter().filter(|item| !item.is_comment()).collect();
+            write_head(4, items.len() as u64, destination);
+            for item in items {
+                write_node(item, destination);
+            }
+        }
+        Node::Dictionary(_) => {
+            let entries: Vec<(&str, &Node)> = node.entries().filter(|(_, value)| !value.is_comment()).collect();
+            write_head(5, entries.len() as u64, destination);
+            for (key, value) in entries {
+                write_string(key, destination);
+                write_node(value, destination);
+            }
+        }
+        Node::Comment(_) | Node::Document(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+
+    fn emit(node: &Node) -> Result<Vec<u8>, String> {
+        let mut destination = Buffer::new();
+        stringify(node, &mut destination).map(|_| destination.buffer)
+    }
+
+    #[test]
+    fn stringify_cbor_works() {
+        // The examples from appendix A of RFC 8949
+        let cases: [(Node, &[u8]); 9] = [
+            (Node::from(0), &[0x00]),
+            (Node::from(1000), &[0x19, 0x03, 0xe8]),
+            (Node::from(-1000), &[0x39, 0x03, 0xe7]),
+            (Node::Number(Numeric::UInteger(u64::MAX)), &[0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+            (Node::from(1.1), &[0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a]),
+            (Node::from("\u{fc}"), &[0x62, 0xc3, 0xbc]),
+            (Node::None, &[0xf6]),
+            (Node::from(vec![1, 2, 3]), &[0x83, 0x01, 0x02, 0x03]),
+            (Node::dict().key("b", vec![Node::from(true)]).key("a", false).build(), &[
+                0xa2, 0x61, b'a', 0xf4, 0x61, b'b', 0x81, 0xf5,
+            ]),
+        ];
+        for (node, expected) in cases {
+            assert_eq!(emit(&node).unwrap(), expected, "{:?}", node);
+        }
+        let commented = Node::Array(vec![Node::Comment("skipped".to_string()), Node::from(i64::MIN)]);
+        assert_eq!(emit(&commented).unwrap(), [0x81, 0x3b, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+    }
+
+    #[test]
+    fn stringify_cbor_rejects_documents() {
+        let node = Node::dict().key("a", Node::Document(vec![])).build();
+        assert_eq!(emit(&node).unwrap_err(), "A stream of documents at /a cannot be written as CBOR");
+    }
+}
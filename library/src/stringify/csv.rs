@@ -0,0 +1,216 @@
+//! CSV emitter.
+//! Writes an Array of flat Dictionary nodes as CSV, one row per dictionary. The header row
+//! is the union of the dictionaries' keys: the first row's keys in sorted order, followed
+//! by keys first seen in later rows. Missing keys and nulls are written as empty fields,
+//! booleans as true and false, and comments are left out. Fields are quoted according to
+//! the quoting option, with quotes inside a field doubled. A root that is not an array,
+//! items that are not dictionaries and nested arrays or dictionaries are errors, reported
+//! before anything is written.
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::Node;
+use crate::nodes::path::join;
+use crate::stringify::number::format_number;
+use crate::stringify::options::EmitterOptions;
+
+/// Which CSV fields are quoted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CsvQuote {
+    /// Quote only fields containing the delimiter, a quote or a line break
+    #[default]
+    Minimal,
+    /// Quote every field
+    All,
+    /// Quote header names and string values, leaving numbers, booleans and empty fields
+    /// bare
+    NonNumeric,
+}
+
+/// Default character separating the fields of a CSV row.
+pub const DEFAULT_CSV_DELIMITER: char = ',';
+
+/// Options used to configure the CSV emitter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Character written between fields
+    pub delimiter: char,
+    /// Which fields are quoted
+    pub quoting: CsvQuote,
+    /// Write a header row of key names before the data rows
+    pub header: bool,
+    /// End rows with "\r\n", as RFC 4180 specifies, rather than "\n"
+    pub crlf: bool,
+}
+
+impl CsvOptions {
+    /// Creates a new CsvOptions instance with default settings.
+    ///
+    /// # Returns
+    /// CsvOptions for comma separated, minimally quoted rows under a header, ended by "\n".
+    pub fn new() -> Self {
+        Self { delimiter: DEFAULT_CSV_DELIMITER, quoting: CsvQuote::Minimal, header: true, crlf: false }
+    }
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes an Array of dictionaries to a destination as comma separated CSV.
+///
+/// # Arguments
+/// * `node` - The Array of dictionaries to write
+/// * `destination` - Where the CSV text is written
+///
+/// # Returns
+/// Ok, or an error naming the path of a value that cannot be written as a CSV field.
+pub fn stringify(node: &Node, destination: &mut dyn IDestination) -> Result<(), String> {
+    stringify_with_options(node, destination, &CsvOptions::new())
+}
+
+/// Writes an Array of dictionaries to a destination as CSV laid out according to the
+/// supplied options.
+///
+/// # Arguments
+/// * `node` - The Array of dictionaries to write
+/// * `destination` - Where the CSV text is written
+/// * `options` - Delimiter, quoting, header and line ending settings
+///
+/// # Returns
+/// Ok, or an error naming the path of a value that cannot be written as a CSV field;
+/// nothing is written when there is an error.
+pub fn stringify_with_options(
+    node: &Node,
+    destination: &mut dyn IDestination,
+    options: &CsvOptions,
+) -> Result<(), String> {
+    let Node::Array(items) = node else {
+        return Err("CSV can only be written from an array of dictionaries".to_string());
+    };
+    let mut columns: Vec<&str> = Vec::new();
+    let mut rows = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let path = join("", &index.to_string());
+        match item {
+            Node::Comment(_) => continue,
+            Node::Dictionary(_) => {}
+            _ => return Err(format!("Row at {} is not a dictionary", path)),
+        }
+        for (key, value) in item.entries().filter(|(_, value)| !value.is_comment()) {
+            if matches!(value, Node::Array(_) | Node::Dictionary(_) | Node::Document(_)) {
+                return Err(format!("Nested value at {} cannot be written as a CSV field", join(&path, key)));
+            }
+            if !columns.contains(&key) {
+                columns.push(key);
+            }
+        }
+        rows.push(item);
+    }
+    let ending = if options.crlf { "\r\n" } else { "\n" };
+    let mut delimiter = [0; 4];
+    let delimiter = &*options.delimiter.encode_utf8(&mut delimiter);
+    let mut out = |part: &str| destination.add_bytes(part);
+    if options.header && !columns.is_empty() {
+        for (index, column) in columns.iter().enumerate() {
+            if index > 0 {
+                out(delimiter);
+            }
+            write_field(column, options.quoting != CsvQuote::Minimal, options.delimiter, &mut out);
+        }
+        out(ending);
+    }
+    let number_options = EmitterOptions::new();
+    let all = options.quoting == CsvQuote::All;
+    for row in rows {
+        for (index, column) in columns.iter().enumerate() {
+            if index > 0 {
+                out(delimiter);
+            }
+            match row.get(column) {
+                Some(Node::Str(text)) => {
+                    write_field(text, options.quoting != CsvQuote::Minimal, options.delimiter, &mut out)
+                }
+                Some(Node::Number(number)) => {
+                    write_field(&format_number(number, &number_options), all, options.delimiter, &mut out)
+                }
+                Some(Node::Boolean(value)) => {
+                    write_field(if *value { "true" } else { "false" }, all, options.delimiter, &mut out)
+                }
+                _ => write_field("", all, options.delimiter, &mut out),
+            }
+        }
+        out(ending);
+    }
+    Ok(())
+}
+
+/// Passes a field to out, quoting it when asked to or when it contains the delimiter, a
+/// quote or a line break, and doubling any quotes inside it.
+fn write_field(text: &str, quote: bool, delimiter: char, out: &mut dyn FnMut(&str)) {
+    if !quote && !text.contains([delimiter, '"', '\n', '\r']) {
+        out(text);
+        return;
+    }
+    out("\"");
+    for (index, part) in text.split('"').enumerate() {
+        if index > 0 {
+            out("\"\"");
+        }
+        out(part);
+    }
+    out("\"");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+
+    fn emit_with(node: &Node, options: &CsvOptions) -> Result<String, String> {
+        let mut destination = Buffer::new();
+        stringify_with_options(node, &mut destination, options).map(|_| destination.to_string())
+    }
+
+    fn people() -> Node {
+        Node::Array(vec![
+            Node::dict().key("name", "Ann").key("age", 31).key("__comment_0", Node::Comment("c".to_string())).build(),
+            Node::Comment("skipped".to_string()),
+            Node::dict().key("name", "Bob, Jr.").key("admin", true).key("age", Node::None).build(),
+            Node::dict().key("name", "Say \"hi\"\nthen go").key("age", 1.5).build(),
+        ])
+    }
+
+    #[test]
+    fn stringify_csv_works() {
+        let expected = "age,name,admin\n31,Ann,\n,\"Bob, Jr.\",true\n1.5,\"Say \"\"hi\"\"\nthen go\",\n";
+        assert_eq!(emit_with(&people(), &CsvOptions::new()).unwrap(), expected);
+        assert_eq!(emit_with(&Node::Array(vec![]), &CsvOptions::new()).unwrap(), "");
+    }
+
+    #[test]
+    fn stringify_csv_options_work() {
+        let options = CsvOptions { delimiter: ';', quoting: CsvQuote::NonNumeric, header: false, crlf: true };
+        let expected = "31;\"Ann\";\r\n;\"Bob, Jr.\";true\r\n1.5;\"Say \"\"hi\"\"\nthen go\";\r\n";
+        assert_eq!(emit_with(&people(), &options).unwrap(), expected);
+        let options = CsvOptions { quoting: CsvQuote::All, ..CsvOptions::new() };
+        let node = Node::Array(vec![Node::dict().key("a", 1).key("b", Node::None).build()]);
+        assert_eq!(emit_with(&node, &options).unwrap(), "\"a\",\"b\"\n\"1\",\"\"\n");
+        let options = CsvOptions { delimiter: '\t', ..CsvOptions::new() };
+        let node = Node::Array(vec![Node::dict().key("a", "x\ty").key("b", "x,y").build()]);
+        assert_eq!(emit_with(&node, &options).unwrap(), "a\tb\n\"x\ty\"\tx,y\n");
+    }
+
+    #[test]
+    fn stringify_csv_rejects_non_tabular_data() {
+        let options = CsvOptions::new();
+        assert_eq!(
+            emit_with(&Node::dict().key("a", 1).build(), &options).unwrap_err(),
+            "CSV can only be written from an array of dictionaries"
+        );
+        assert_eq!(emit_with(&Node::from(vec![1]), &options).unwrap_err(), "Row at /0 is not a dictionary");
+        let node = Node::Array(vec![Node::dict().key("a", vec![1]).build()]);
+        assert_eq!(emit_with(&node, &options).unwrap_err(), "Nested value at /0/a cannot be written as a CSV field");
+    }
+}
@@ -0,0 +1,1510 @@
+//! Default YAML emitter.
+//! Writes a Node tree as block style YAML: dictionaries as "key: value" lines in the
+//! order the dictionary holds them (sorted when sort_keys is on), arrays as "- item"
+//! lines, and nested collections indented by two spaces (a collection inside an array
+//! starts on the item's own line). Empty collections are
+//! written in flow style ("[]" and "{}"). Strings are written plain unless that would
+//! change their meaning, in which case they are double quoted. Comment nodes become
+//! "# text" lines, and a Document node is written as one "---" document per item.
+//! EmitterOptions change the indentation, prefer flow style, limit the line width or
+//! write small collections of scalars inline (auto flow), and choose how strings are
+//! quoted, for every string or for those at paths matching a glob pattern. Multi-line
+//! strings are written as literal block scalars unless they have to be quoted, with the
+//! chomping and indentation indicators the parser needs to read them back exactly. With a
+//! width limit, long scalars are folded at spaces and long flow collections wrapped.
+//! stringify_with_comments also writes back comments held as metadata beside the tree,
+//! and stringify_with_metadata anchors and aliases as well: an anchored node is written
+//! with its "&name" and a node read as an alias as "*name" again, a "<<" merge key
+//! holding aliases staying a plain key.
+//! With anchor_repeats, a collection that appears more than once in a document is written
+//! in full once with an "&id001" anchor and replaced by "*id001" aliases elsewhere.
+//! The canonical and minify options hand the tree to the canonical or minified emitter
+//! instead. A banner comment from the options is written first in every case, ahead of
+//! any "---" marker and the root's own leading comments.
+//! Output is written to the destination as it is produced, strings as slices of the tree's
+//! own text; only values measured against a width limit or for auto flow are built up
+//! as strings first.
+
+use crate::io::destinations::writer::{FmtWriter, IoWriter};
+use crate::io::traits::IDestination;
+use crate::nodes::anchors::Anchors;
+use crate::nodes::comments::Comments;
+use crate::nodes::node::Node;
+use crate::parser::default::parse_scalar;
+use crate::stringify::canonical::write_canonical;
+use crate::stringify::minify::write_minified;
+use crate::stringify::number::format_number;
+use crate::nodes::path::{join, matches_glob};
+use crate::stringify::options::{BoolStyle, CollectionStyle, EmitterOptions, KeywordCase, NullStyle, QuoteStyle};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Writes a Node tree to a destination as block style YAML.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the YAML text is written
+///
+/// # Returns
+/// Ok, or an error describing why the tree could not be written.
+pub fn stringify(node: &Node, destination: &mut dyn IDestination) -> Result<(), String> {
+    stringify_with_options(node, destination, &EmitterOptions::new())
+}
+
+/// Writes a Node tree to a destination as YAML laid out according to the supplied options.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the YAML text is written
+/// * `options` - Indentation, collection style and width settings
+///
+/// # Returns
+/// Ok, or an error describing why the tree could not be written.
+pub fn stringify_with_options(
+    node: &Node,
+    destination: &mut dyn IDestination,
+    options: &EmitterOptions,
+) -> Result<(), String> {
+    write_yaml(node, None, None, destination, options)
+}
+
+/// Writes a Node tree to a destination as YAML, putting back the comments held as
+/// metadata (such as those gathered by parse_detailed): leading comments on their own
+/// lines before a node and trailing comments at the end of its line.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `comments` - Comments keyed by the path of the node they belong to
+/// * `destination` - Where the YAML text is written
+/// * `options` - Indentation, collection style and width settings
+///
+/// # Returns
+/// Ok, or an error describing why the tree could not be written.
+pub fn stringify_with_comments(
+    node: &Node,
+    comments: &Comments,
+    destination: &mut dyn IDestination,
+    options: &EmitterOptions,
+) -> Result<(), String> {
+    write_yaml(node, Some(comments), None, destination, options)
+}
+
+/// Writes a Node tree to a destination as YAML, putting back the comments, anchors and
+/// aliases held as metadata (such as those gathered by parse_detailed). Nodes the anchor
+/// table records as aliases are written as aliases of their anchored node rather than in
+/// full; the anchor_repeats option is not applied.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `comments` - Comments keyed by the path of the node they belong to
+/// * `anchors` - Anchors and aliases keyed by the path of their node
+/// * `destination` - Where the YAML text is written
+/// * `options` - Indentation, collection style and width settings
+///
+/// # Returns
+/// Ok, or an error describing why the tree could not be written.
+pub fn stringify_with_metadata(
+    node: &Node,
+    comments: &Comments,
+    anchors: &Anchors,
+    destination: &mut dyn IDestination,
+    options: &EmitterOptions,
+) -> Result<(), String> {
+    write_yaml(node, Some(comments), Some(anchors), destination, options)
+}
+
+/// Returns a scalar as one line of flow style YAML with strings double quoted, the form
+/// the tree view and DOT labels show values in.
+pub(crate) fn flow_scalar(node: &Node) -> String {
+    let options =
+        EmitterOptions { style: CollectionStyle::Flow, quote_style: QuoteStyle::Double, ..EmitterOptions::new() };
+    let mut destination = FmtWriter::new(String::new());
+    let _ = stringify_with_options(node, &mut destination, &options);
+    let text = destination.into_inner().unwrap_or_default();
+    text.strip_suffix('\n').unwrap_or(&text).to_string()
+}
+
+/// Writes a Node tree as block style YAML to any std::io::Write, such as a file or socket.
+/// Output is buffered and flushed before returning.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `writer` - Where the YAML text is written
+///
+/// # Returns
+/// Ok, or the first error writing to the writer returned.
+pub fn stringify_to_writer(node: &Node, writer: impl std::io::Write) -> std::io::Result<()> {
+    let mut destination = IoWriter::new(std::io::BufWriter::new(writer));
+    stringify(node, &mut destination).map_err(std::io::Error::other)?;
+    destination.into_inner()?.into_inner().map_err(|error| error.into_error())?;
+    Ok(())
+}
+
+fn write_yaml(
+    node: &Node,
+    comments: Option<&Comments>,
+    anchors: Option<&Anchors>,
+    destination: &mut dyn IDestination,
+    options: &EmitterOptions,
+) -> Result<(), String> {
+    for line in options.banner.iter().flat_map(|banner| banner.lines()) {
+        destination.add_bytes(&comment_text(line, options));
+        destination.add_byte(b'\n');
+    }
+    if options.canonical {
+        write_canonical(node, destination);
+        return Ok(());
+    }
+    if options.minify {
+        write_minified(node, destination, options);
+        return Ok(());
+    }
+    let mut emitter = Emitter { destination, options, comments, repeats: HashMap::new(), anchors: 0 };
+    let plan_repeats = options.anchor_repeats && anchors.is_none();
+    if let Some(anchors) = anchors {
+        emitter.restore_anchors(node, anchors);
+    }
+    match node {
+        Node::Document(documents) => {
+            for (index, document) in documents.iter().enumerate() {
+                if let Node::Comment(text) = document {
+                    emitter.comment(text);
+                } else {
+                    if plan_repeats {
+                        emitter.plan_repeats(document);
+                    }
+                    let path = join("", &index.to_string());
+                    emitter.destination.add_bytes("---\n");
+                    emitter.leading(&path, 0);
+                    emitter.node(document, &path, 0, 0, 0);
+                }
+            }
+        }
+        _ => {
+            if plan_repeats {
+                emitter.plan_repeats(node);
+            }
+            if options.explicit_start {
+                emitter.destination.add_bytes("---\n");
+            }
+            emitter.leading("", 0);
+            emitter.node(node, "", 0, 0, 0);
+        }
+    }
+    Ok(())
+}
+
+/// Returns a comment as it is written ("# text"), without trailing whitespace if the
+/// options ask for trimmed lines.
+fn comment_text(text: &str, options: &EmitterOptions) -> String {
+    if !options.trim_trailing_whitespace {
+        return format!("# {}", text);
+    }
+    match text.trim_end() {
+        "" => "#".to_string(),
+        text => format!("# {}", text),
+    }
+}
+
+/// Characters that cannot start a plain scalar.
+const INDICATORS: &str = "-?:,[]{}#&*!|>'\"%@`";
+
+/// Returns true for strings that can be written as plain scalars without changing their
+/// text, although they may then read back as another type ("true" or "42").
+fn is_plain_syntax(text: &str, flow: bool) -> bool {
+    let Some(first) = text.chars().next() else {
+        return false;
+    };
+    !(INDICATORS.contains(first)
+        || text.starts_with(char::is_whitespace)
+        || text.ends_with(char::is_whitespace)
+        || text.ends_with(':')
+        || text.contains(": ")
+        || text.contains(" #")
+        || text.contains(|c: char| c.is_control())
+        || (flow && text.contains([',', '[', ']', '{', '}'])))
+}
+
+/// Returns true for strings that can be written without quotes and read back unchanged.
+pub(crate) fn is_plain_safe(text: &str) -> bool {
+    is_plain_syntax(text, false) && !is_ambiguous(text)
+}
+
+/// Returns true if a plain scalar would not read back as the same string, either through
+/// this crate's scalar resolver or through the wider YAML 1.1 rules other readers apply
+/// (yes/no booleans, case variants of null and true, 0x1F and 1_000 integers, 1:30
+/// sexagesimals, dates, the "<<" merge key and document markers).
+fn is_ambiguous(text: &str) -> bool {
+    const RESERVED: [&str; 12] = ["null", "~", "true", "false", "yes", "no", "on", "off", "y", "n", "<<", "="];
+    let lowered = text.to_ascii_lowercase();
+    let unsigned = lowered.trim_start_matches(['+', '-']);
+    !matches!(parse_scalar(text), Node::Str(resolved) if resolved == text)
+        || RESERVED.contains(&lowered.as_str())
+        || [".inf", ".nan"].contains(&unsigned)
+        || (unsigned.starts_with(|c: char| c.is_ascii_digit())
+            && unsigned.chars().all(|c| c.is_ascii_hexdigit() || "_:.+-xotz".contains(c)))
+        || text.starts_with("---")
+        || text.starts_with("...")
+}
+
+/// Passes a string written in a quoting style to out piece by piece, falling back to double
+/// quotes where the style cannot represent the text.
+///
+/// # Arguments
+/// * `text` - The string to write
+/// * `style` - The preferred quoting style
+/// * `flow` - The string is inside a flow collection, where the flow indicators also
+///   force quoting
+/// * `out` - Receives the written text as a series of slices
+pub(crate) fn write_quoted(text: &str, style: QuoteStyle, flow: bool, out: &mut dyn FnMut(&str)) {
+    match style {
+        QuoteStyle::Minimal if is_plain_safe(text) && is_plain_syntax(text, flow) => out(text),
+        QuoteStyle::Plain if is_plain_syntax(text, flow) => out(text),
+        QuoteStyle::Single if !text.contains(|c: char| c.is_control()) => {
+            out("'");
+            for (index, part) in text.split('\'').enumerate() {
+                if index > 0 {
+                    out("''");
+                }
+                out(part);
+            }
+            out("'");
+        }
+        _ => write_double_quoted(text, out),
+    }
+}
+
+/// Returns a string written in a quoting style, falling back to double quotes where the
+/// style cannot represent the text.
+fn quoted(text: &str, style: QuoteStyle, flow: bool) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    write_quoted(text, style, flow, &mut |part| quoted.push_str(part));
+    quoted
+}
+
+/// Passes a string double quoted to out, escaping quotes, backslashes and control
+/// characters; runs of characters that need no escape are passed as single slices.
+pub(crate) fn write_double_quoted(text: &str, out: &mut dyn FnMut(&str)) {
+    out("\"");
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        let escape = match c {
+            '"' => "\\\"",
+            '\\' => "\\\\",
+            '\n' => "\\n",
+            '\t' => "\\t",
+            '\r' => "\\r",
+            '\0' => "\\0",
+            c if c.is_control() => "",
+            _ => continue,
+        };
+        out(&text[start..index]);
+        if escape.is_empty() {
+            out(&format!("\\x{:02X}", c as u32));
+        } else {
+            out(escape);
+        }
+        start = index + c.len_utf8();
+    }
+    out(&text[start..]);
+    out("\"");
+}
+
+/// Returns the header of the literal block scalar a multi-line string is written as, or
+/// None if it has to be quoted. The chomping indicator keeps the trailing line breaks
+/// exactly: "|-" for none, "|" for one and "|+" for more. indicator is how much further than
+/// its parent entry the scalar's lines are indented, which the header gives when the first
+/// line starts with a blank.
+pub(crate) fn literal_header(text: &str, indicator: usize) -> Option<String> {
+    if !text.contains('\n')
+        || text.trim().is_empty()
+        || text.contains(|c: char| c.is_control() && c != '\n' && c != '\t')
+    {
+        return None;
+    }
+    let body = text.trim_end_matches('\n');
+    let mut header = String::from("|");
+    // Leading spaces on the first line would be taken as indentation, so give it explicitly
+    if body.trim_start_matches('\n').starts_with([' ', '\t']) {
+        if !(1..=9).contains(&indicator) {
+            return None;
+        }
+        header.push_str(&indicator.to_string());
+    }
+    match text.len() - body.len() {
+        0 => header.push('-'),
+        1 => {}
+        _ => header.push('+'),
+    }
+    Some(header)
+}
+
+/// Passes the lines of a literal block scalar to out, each indented by indent and ended
+/// by a line break, followed by any further trailing line breaks the text holds.
+pub(crate) fn write_literal_lines(text: &str, indent: usize, out: &mut dyn FnMut(&str)) {
+    let body = text.trim_end_matches('\n');
+    let margin = " ".repeat(indent);
+    for line in body.split('\n') {
+        if !line.is_empty() {
+            out(&margin);
+            out(line);
+        }
+        out("\n");
+    }
+    for _ in 1..text.len() - body.len() {
+        out("\n");
+    }
+}
+
+/// Folds scalar text starting at the given column into lines that fit the width where it
+/// can, breaking only at single spaces (which a reader folds back into a space) that are
+/// not followed by an indicator. Later lines are indented by indent.
+fn fold(text: &str, column: usize, indent: usize, width: usize) -> String {
+    let bytes = text.as_bytes();
+    let breaks = (1..bytes.len().saturating_sub(1)).filter(|&index| {
+        bytes[index] == b' '
+            && !bytes[index - 1].is_ascii_whitespace()
+            && !bytes[index + 1].is_ascii_whitespace()
+            && !INDICATORS.contains(bytes[index + 1] as char)
+    });
+    let mut folded = String::with_capacity(text.len());
+    let mut line = column;
+    let mut start = 0;
+    for end in breaks.chain([text.len()]) {
+        let word = &text[start..end];
+        if start > 0 {
+            if line + 1 + word.len() > width {
+                folded.push('\n');
+                folded.push_str(&" ".repeat(indent));
+                line = indent;
+            } else {
+                folded.push(' ');
+                line += 1;
+            }
+        }
+        folded.push_str(word);
+        line += word.len();
+        start = end + 1;
+    }
+    folded
+}
+
+/// Returns true if a collection holds anything other than comments.
+fn has_data(node: &Node) -> bool {
+    match node {
+        Node::Array(items) | Node::Document(items) => items.iter().any(|item| !item.is_comment()),
+        Node::Dictionary(map) => map.values().any(|value| !value.is_comment()),
+        _ => false,
+    }
+}
+
+/// Returns true if a node or anything inside it is a comment.
+fn has_comments(node: &Node) -> bool {
+    match node {
+        Node::Comment(_) => true,
+        Node::Array(items) | Node::Document(items) => items.iter().any(has_comments),
+        Node::Dictionary(map) => map.values().any(has_comments),
+        _ => false,
+    }
+}
+
+/// YAML writer for a single destination.
+struct Emitter<'a> {
+    destination: &'a mut dyn IDestination,
+    options: &'a EmitterOptions,
+    comments: Option<&'a Comments>,
+    /// Anchors and aliases of repeated collections, keyed by node address
+    repeats: HashMap<*const Node, Repeat>,
+    /// Number of anchors defined so far
+    anchors: usize,
+}
+
+/// How one copy of a repeated collection is written.
+enum Repeat {
+    /// The first copy, written in full after "&name"
+    Anchor(String),
+    /// A later copy, written as "*name"
+    Alias(String),
+}
+
+/// A collection compared with Node::strict_eq, so copies only count as repeats when their
+/// numbers are stored in the same Numeric variants ([1, a] does not repeat [1.0, a]).
+#[derive(Clone, Copy)]
+struct Strict<'n>(&'n Node);
+
+impl PartialEq for Strict<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.strict_eq(other.0)
+    }
+}
+
+impl Eq for Strict<'_> {}
+
+/// Hashes by value, which strictly equal nodes always share.
+impl Hash for Strict<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+/// Counts how often each collection holding data appears in a tree.
+fn count_collections<'n>(node: &'n Node, counts: &mut HashMap<Strict<'n>, usize>) {
+    if !has_data(node) {
+        return;
+    }
+    *counts.entry(Strict(node)).or_insert(0) += 1;
+    match node {
+        Node::Array(items) | Node::Document(items) => items.iter().for_each(|item| count_collections(item, counts)),
+        Node::Dictionary(map) => map.values().for_each(|value| count_collections(value, counts)),
+        _ => {}
+    }
+}
+
+impl<'a> Emitter<'a> {
+    fn indent(&mut self, indent: usize) {
+        for _ in 0..indent {
+            self.destination.add_byte(b' ');
+        }
+    }
+
+    fn comment(&mut self, text: &str) {
+        let line = self.comment_text(text);
+        self.destination.add_bytes(&line);
+        self.destination.add_byte(b'\n');
+    }
+
+    fn comment_text(&self, text: &str) -> String {
+        comment_text(text, self.options)
+    }
+
+    /// Returns the anchor a repeated collection is written with the first time it appears.
+    fn anchor(&self, node: &Node) -> Option<&str> {
+        match self.repeats.get(&(node as *const Node)) {
+            Some(Repeat::Anchor(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Plans the anchors and aliases for the collections that appear more than once in a
+    /// document: walking in the order nodes are written, the first copy of a repeated
+    /// collection is anchored and later copies become aliases (and are not walked into, so
+    /// collections repeated only inside an aliased one get no anchor).
+    fn plan_repeats(&mut self, document: &Node) {
+        let mut counts: HashMap<Strict, usize> = HashMap::new();
+        count_collections(document, &mut counts);
+        let mut first: HashMap<Strict, *const Node> = HashMap::new();
+        let mut originals: Vec<*const Node> = Vec::new();
+        let mut aliases: Vec<(*const Node, *const Node)> = Vec::new();
+        let mut pending = vec![document];
+        while let Some(node) = pending.pop() {
+            if counts.get(&Strict(node)).is_some_and(|count| *count > 1) {
+                if let Some(original) = first.get(&Strict(node)) {
+                    aliases.push((node, *original));
+                    continue;
+                }
+                first.insert(Strict(node), node);
+                originals.push(node);
+            }
+            let children: Vec<&Node> = match node {
+                Node::Array(items) | Node::Document(items) => items.iter().collect(),
+                _ => self.entries(node).into_iter().map(|(_, value)| value).collect(),
+            };
+            pending.extend(children.into_iter().rev());
+        }
+        // Anchors are numbered in the order they are written
+        let mut names = HashMap::new();
+        for original in originals {
+            if aliases.iter().any(|(_, aliased)| *aliased == original) {
+                self.anchors += 1;
+                let name = format!("id{:03}", self.anchors);
+                names.insert(original, name.clone());
+                self.repeats.insert(original, Repeat::Anchor(name));
+            }
+        }
+        for (alias, original) in aliases {
+            self.repeats.insert(alias, Repeat::Alias(names[&original].clone()));
+        }
+    }
+
+    /// Marks the nodes an anchor table records as anchored or read as aliases, so they are
+    /// written with their "&name" or as "*name" again.
+    fn restore_anchors(&mut self, root: &Node, anchors: &Anchors) {
+        for (path, name) in anchors.iter() {
+            if let Some(node) = root.pointer(path) {
+                self.repeats.insert(node, Repeat::Anchor(name.to_string()));
+            }
+        }
+        for (path, target) in anchors.aliases() {
+            if let (Some(node), Some(name)) = (root.pointer(path), anchors.anchor_at(target)) {
+                self.repeats.insert(node, Repeat::Alias(name.to_string()));
+            }
+        }
+    }
+
+    /// Writes the leading comment lines of the node at a path, leaving the next line
+    /// indented by indent.
+    fn leading(&mut self, path: &str, indent: usize) {
+        let comments = self.comments;
+        for text in comments.map_or(&[][..], |comments| comments.leading(path)) {
+            self.comment(text);
+            self.indent(indent);
+        }
+    }
+
+    /// Returns the text of the trailing comment of the node at a path.
+    fn trailing_text(&self, path: &str) -> Option<&'a str> {
+        self.comments.and_then(|comments| comments.trailing(path))
+    }
+
+    /// Returns the trailing comment of the node at a path as it is written at the end of
+    /// its line, or an empty string.
+    fn trailing(&self, path: &str) -> String {
+        self.trailing_text(path).map_or_else(String::new, |text| format!(" {}", self.comment_text(text)))
+    }
+
+    /// Returns true if comment metadata is attached to anything below the node at a path.
+    fn has_nested_comments(&self, path: &str) -> bool {
+        let prefix = format!("{}/", path);
+        self.comments.is_some_and(|comments| comments.iter().any(|(other, _)| other.starts_with(&prefix)))
+    }
+
+    /// Returns the entries of a dictionary sorted by key, or in the dictionary's own order
+    /// when sort_keys is off.
+    fn entries<'n>(&self, node: &'n Node) -> Vec<(&'n str, &'n Node)> {
+        match self.options.sort_keys {
+            true => node.entries().collect(),
+            false => node.as_dict().into_iter().flatten().map(|(key, value)| (key.as_str(), value)).collect(),
+        }
+    }
+
+    /// Returns the quoting style for the string value at a path.
+    fn quote_style(&self, path: &str) -> QuoteStyle {
+        let overrides = &self.options.quote_overrides;
+        let matched = overrides.iter().find(|(pattern, _)| matches_glob(pattern, path));
+        matched.map_or(self.options.quote_style, |(_, style)| *style)
+    }
+
+    /// Returns a dictionary key as it is written.
+    fn key_text(&self, key: &str, flow: bool) -> String {
+        // A plain block key ends at its first ':', so keys containing one are quoted
+        let style = match self.options.quote_style {
+            QuoteStyle::Minimal | QuoteStyle::Plain if !flow && key.contains(':') => QuoteStyle::Double,
+            style => style,
+        };
+        quoted(key, style, flow)
+    }
+
+    /// Returns a dictionary key as it is written before its value: a "<<" key whose value
+    /// is an alias or a list of aliases is a merge key and stays plain.
+    fn entry_key(&self, key: &str, value: &Node, flow: bool) -> String {
+        let is_alias = |node: &Node| matches!(self.repeats.get(&(node as *const Node)), Some(Repeat::Alias(_)));
+        let merge = match value {
+            Node::Array(items) => !items.is_empty() && !is_alias(value) && items.iter().all(is_alias),
+            _ => is_alias(value),
+        };
+        if key == "<<" && merge { key.to_string() } else { self.key_text(key, flow) }
+    }
+
+    /// Returns the flow style text of a node that holds no comments.
+    fn flow_text(&self, node: &Node, path: &str) -> String {
+        match self.repeats.get(&(node as *const Node)) {
+            Some(Repeat::Alias(name)) => format!("*{}", name),
+            Some(Repeat::Anchor(name)) => format!("&{} {}", name, self.flow_collection(node, path)),
+            None => self.flow_collection(node, path),
+        }
+    }
+
+    /// Returns the flow style text of a node without any anchor.
+    fn flow_collection(&self, node: &Node, path: &str) -> String {
+        match node {
+            Node::Str(text) => quoted(text, self.quote_style(path), true),
+            Node::Array(items) | Node::Document(items) => {
+                let items: Vec<String> = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| self.flow_text(item, &join(path, &index.to_string())))
+                    .collect();
+                format!("[{}]", items.join(", "))
+            }
+            Node::Dictionary(_) => {
+                let entries: Vec<String> = self
+                    .entries(node)
+                    .into_iter()
+                    .map(|(key, value)| {
+                        format!("{}: {}", self.entry_key(key, value, true), self.flow_text(value, &join(path, key)))
+                    })
+                    .collect();
+                format!("{{{}}}", entries.join(", "))
+            }
+            // An empty null cannot stand between commas
+            Node::None if self.options.null_style == NullStyle::Empty => self.keyword("null"),
+            _ => self.inline_text(node, path),
+        }
+    }
+
+    /// Returns a boolean or null keyword in the configured case.
+    fn keyword(&self, word: &str) -> String {
+        match self.options.keyword_case {
+            KeywordCase::Lower => word.to_string(),
+            KeywordCase::Title => word[..1].to_uppercase() + &word[1..],
+            KeywordCase::Upper => word.to_uppercase(),
+        }
+    }
+
+    /// Returns the text of a scalar or empty collection written on a single line.
+    fn inline_text(&self, node: &Node, path: &str) -> String {
+        match node {
+            Node::Boolean(value) => self.keyword(match (self.options.bool_style, value) {
+                (BoolStyle::TrueFalse, true) => "true",
+                (BoolStyle::TrueFalse, false) => "false",
+                (BoolStyle::YesNo, true) => "yes",
+                (BoolStyle::YesNo, false) => "no",
+                (BoolStyle::OnOff, true) => "on",
+                (BoolStyle::OnOff, false) => "off",
+            }),
+            Node::Number(number) => format_number(number, self.options),
+            Node::Str(text) => quoted(text, self.quote_style(path), false),
+            Node::Array(_) | Node::Document(_) => "[]".to_string(),
+            Node::Dictionary(_) => "{}".to_string(),
+            Node::Comment(text) => self.comment_text(text),
+            Node::None => match self.options.null_style {
+                NullStyle::Null => self.keyword("null"),
+                NullStyle::Tilde => "~".to_string(),
+                NullStyle::Empty => String::new(),
+            },
+        }
+    }
+
+    /// Returns a multi-line string value with the header of the literal block scalar it is
+    /// written as (see literal_header), or None if it is written as a quoted or plain scalar.
+    fn literal<'n>(&self, node: &'n Node, path: &str, indicator: usize) -> Option<(&'n str, String)> {
+        let Node::Str(text) = node else {
+            return None;
+        };
+        if !self.options.literal_block_scalars
+            || matches!(self.repeats.get(&(node as *const Node)), Some(Repeat::Alias(_)))
+            || !matches!(self.quote_style(path), QuoteStyle::Minimal | QuoteStyle::Plain)
+            || (self.options.trim_trailing_whitespace && text.split('\n').any(|line| line.ends_with([' ', '\t'])))
+        {
+            return None;
+        }
+        let header = literal_header(text, indicator)?;
+        match self.anchor(node) {
+            Some(name) => Some((text, format!("&{} {}", name, header))),
+            None => Some((text, header)),
+        }
+    }
+
+    /// Writes a literal block scalar: its header and trailing comment, then its lines
+    /// indented by indent.
+    fn write_literal(&mut self, text: &str, header: &str, trailing: &str, indent: usize) {
+        self.destination.add_bytes(header);
+        self.destination.add_bytes(trailing);
+        self.destination.add_byte(b'\n');
+        write_literal_lines(text, indent, &mut |part| self.destination.add_bytes(part));
+    }
+
+    /// Returns true if a node is written inline without being measured against a width
+    /// limit, so it can be written straight to the destination: strings, and collections
+    /// in flow style, when there is no limit.
+    fn streams(&self, node: &Node, path: &str) -> bool {
+        if self.options.width.is_some() {
+            return false;
+        }
+        match node {
+            Node::Str(_) => true,
+            _ if has_data(node) => {
+                self.options.style == CollectionStyle::Flow && !has_comments(node) && !self.has_nested_comments(path)
+            }
+            _ => false,
+        }
+    }
+
+    /// Writes a node inline straight to the destination, collections in flow style, without
+    /// building its text first.
+    fn write_streamed(&mut self, node: &Node, path: &str, flow: bool) {
+        match self.repeats.get(&(node as *const Node)) {
+            Some(Repeat::Alias(name)) => {
+                self.destination.add_byte(b'*');
+                self.destination.add_bytes(name);
+                return;
+            }
+            Some(Repeat::Anchor(name)) => {
+                self.destination.add_byte(b'&');
+                self.destination.add_bytes(name);
+                self.destination.add_byte(b' ');
+            }
+            None => {}
+        }
+        match node {
+            Node::Str(text) => {
+                let style = self.quote_style(path);
+                let destination = &mut *self.destination;
+                write_quoted(text, style, flow, &mut |part| destination.add_bytes(part));
+            }
+            Node::Array(items) | Node::Document(items) if has_data(node) => {
+                self.destination.add_byte(b'[');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        self.destination.add_bytes(", ");
+                    }
+                    self.write_streamed(item, &join(path, &index.to_string()), true);
+                }
+                self.destination.add_byte(b']');
+            }
+            Node::Dictionary(_) if has_data(node) => {
+                self.destination.add_byte(b'{');
+                for (index, (key, value)) in self.entries(node).into_iter().enumerate() {
+                    if index > 0 {
+                        self.destination.add_bytes(", ");
+                    }
+                    let key_text = self.entry_key(key, value, true);
+                    self.destination.add_bytes(&key_text);
+                    self.destination.add_bytes(": ");
+                    self.write_streamed(value, &join(path, key), true);
+                }
+                self.destination.add_byte(b'}');
+            }
+            _ => {
+                let text = self.flow_collection(node, path);
+                self.destination.add_bytes(&text);
+            }
+        }
+    }
+
+    /// Returns the text of a node written inline, starting at the given column, or None if
+    /// it has to be written as a block. With a width limit long scalars are folded and
+    /// long flow collections wrapped onto following lines indented by indent.
+    fn inline(&self, node: &Node, path: &str, indent: usize, column: usize) -> Option<String> {
+        let width = self.options.width.unwrap_or(usize::MAX);
+        if let Some(Repeat::Alias(name)) = self.repeats.get(&(node as *const Node)) {
+            return Some(format!("*{}", name));
+        }
+        if !has_data(node) {
+            let anchor = self.anchor(node).map_or_else(String::new, |name| format!("&{} ", name));
+            let text = self.inline_text(node, path);
+            if column + anchor.len() + text.len() > width && node.is_str() {
+                let folded = fold(&text, column + anchor.len(), indent, width);
+                return Some(anchor + &folded);
+            }
+            return Some(format!("{}{}", anchor, text).trim_end().to_string());
+        }
+        if has_comments(node) || self.has_nested_comments(path) {
+            return None;
+        }
+        let text = match self.options.style {
+            CollectionStyle::Flow => self.flow_text(node, path),
+            CollectionStyle::Block => self.auto_flow(node, path)?,
+        };
+        if column + text.len() <= width {
+            Some(text)
+        } else if self.options.style == CollectionStyle::Flow {
+            Some(self.wrapped_flow(node, path, indent, column, width))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the flow text of a collection with its entries wrapped onto as many lines
+    /// (indented by indent) as are needed to fit the width; nested collections are kept
+    /// whole.
+    fn wrapped_flow(&self, node: &Node, path: &str, indent: usize, column: usize, width: usize) -> String {
+        let (open, close, entries): (char, char, Vec<String>) = match node {
+            Node::Dictionary(_) => {
+                let entries = self.entries(node).into_iter().map(|(key, value)| {
+                    format!("{}: {}", self.entry_key(key, value, true), self.flow_text(value, &join(path, key)))
+                });
+                ('{', '}', entries.collect())
+            }
+            Node::Array(items) | Node::Document(items) => {
+                let items = items.iter().enumerate();
+                ('[', ']', items.map(|(index, item)| self.flow_text(item, &join(path, &index.to_string()))).collect())
+            }
+            _ => return self.flow_text(node, path),
+        };
+        let mut text = match self.repeats.get(&(node as *const Node)) {
+            Some(Repeat::Anchor(name)) => format!("&{} {}", name, open),
+            _ => String::from(open),
+        };
+        let mut line = column + 1;
+        for (index, entry) in entries.iter().enumerate() {
+            if index > 0 {
+                if line + entry.len() + 2 > width {
+                    text.push('\n');
+                    text.push_str(&" ".repeat(indent));
+                    line = indent;
+                } else {
+                    text.push(' ');
+                    line += 1;
+                }
+            }
+            text.push_str(entry);
+            text.push(if index + 1 == entries.len() { close } else { ',' });
+            line += entry.len() + 1;
+        }
+        text
+    }
+
+    /// Returns the flow text of a collection small enough to be written in flow style by
+    /// auto flow: no more than auto_flow_items scalars and auto_flow_chars characters.
+    fn auto_flow(&self, node: &Node, path: &str) -> Option<String> {
+        let children: Vec<&Node> = match node {
+            Node::Array(items) | Node::Document(items) => items.iter().collect(),
+            Node::Dictionary(map) => map.values().collect(),
+            _ => return None,
+        };
+        if children.len() > self.options.auto_flow_items || children.iter().any(|child| has_data(child)) {
+            return None;
+        }
+        Some(self.flow_text(node, path)).filter(|text| text.len() <= self.options.auto_flow_chars)
+    }
+
+    /// Writes a node whose first line starts at the given column (after any "key:" or
+    /// "- " already written) and whose later lines are indented by indent. parent is the
+    /// column of the sequence entry holding the node, or 0 at the root.
+    fn node(&mut self, node: &Node, path: &str, indent: usize, column: usize, parent: usize) {
+        let content_indent = indent.max(self.options.indent.max(1));
+        let trailing = self.trailing(path);
+        if let Some((text, header)) = self.literal(node, path, content_indent - parent) {
+            self.write_literal(text, &header, &trailing, content_indent);
+        } else if self.streams(node, path) {
+            self.write_streamed(node, path, false);
+            self.destination.add_bytes(&trailing);
+            self.destination.add_byte(b'\n');
+        } else if let Some(text) = self.inline(node, path, content_indent, column) {
+            self.destination.add_bytes(&text);
+            self.destination.add_bytes(&trailing);
+            self.destination.add_byte(b'\n');
+        } else {
+            // A block collection starts on the next line after its anchor and trailing comment
+            if let Some(name) = self.anchor(node) {
+                self.destination.add_bytes(&format!("&{}{}\n", name, trailing));
+                self.indent(indent);
+            } else if let Some(text) = self.trailing_text(path) {
+                self.comment(text);
+                self.indent(indent);
+            }
+            self.block(node, path, indent);
+        }
+    }
+
+    /// Writes the lines of a block collection, the first at the current position and the
+    /// rest indented by indent.
+    fn block(&mut self, node: &Node, path: &str, indent: usize) {
+        let step = self.options.indent.max(1);
+        let mut first = true;
+        let mut line_start = |emitter: &mut Self| {
+            if !std::mem::take(&mut first) {
+                emitter.indent(indent);
+            }
+        };
+        match node {
+            Node::Array(items) | Node::Document(items) => {
+                let item_step = step.max(2);
+                for (index, item) in items.iter().enumerate() {
+                    line_start(self);
+                    if let Node::Comment(text) = item {
+                        self.comment(text);
+                        continue;
+                    }
+                    let item_path = join(path, &index.to_string());
+                    self.leading(&item_path, indent);
+                    self.destination.add_byte(b'-');
+                    let repeated = self.repeats.contains_key(&(item as *const Node));
+                    if item.is_null() && self.options.null_style == NullStyle::Empty && !repeated {
+                        self.destination.add_bytes(&self.trailing(&item_path));
+                        self.destination.add_byte(b'\n');
+                        continue;
+                    }
+                    self.indent(item_step - 1);
+                    self.node(item, &item_path, indent + item_step, indent + item_step, indent);
+                }
+            }
+            _ => {
+                for (key, value) in self.entries(node) {
+                    line_start(self);
+                    if let Node::Comment(text) = value {
+                        self.comment(text);
+                        continue;
+                    }
+                    let value_path = join(path, key);
+                    self.leading(&value_path, indent);
+                    let key = self.entry_key(key, value, false);
+                    self.destination.add_bytes(&key);
+                    self.destination.add_byte(b':');
+                    let content_indent = indent + step;
+                    let trailing = self.trailing(&value_path);
+                    if let Some((text, header)) = self.literal(value, &value_path, content_indent - indent) {
+                        self.destination.add_byte(b' ');
+                        self.write_literal(text, &header, &trailing, content_indent);
+                    } else if self.streams(value, &value_path) {
+                        self.destination.add_byte(b' ');
+                        self.write_streamed(value, &value_path, false);
+                        self.destination.add_bytes(&trailing);
+                        self.destination.add_byte(b'\n');
+                    } else if let Some(text) = self.inline(value, &value_path, content_indent, indent + key.len() + 2) {
+                        if !text.is_empty() {
+                            self.destination.add_byte(b' ');
+                        }
+                        self.destination.add_bytes(&text);
+                        self.destination.add_bytes(&trailing);
+                        self.destination.add_byte(b'\n');
+                    } else {
+                        if let Some(name) = self.anchor(value) {
+                            self.destination.add_bytes(&format!(" &{}", name));
+                        }
+                        self.destination.add_bytes(&trailing);
+                        self.destination.add_byte(b'\n');
+                        self.indent(indent + step);
+                        self.block(value, &value_path, indent + step);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+    use crate::io::sources::buffer::Buffer as Source;
+    use crate::nodes::node::Numeric;
+    use crate::parser::default::{parse, parse_detailed};
+    use crate::parser::options::ParserOptions;
+
+    fn emit(node: &Node) -> String {
+        let mut destination = Buffer::new();
+        stringify(node, &mut destination).unwrap();
+        destination.to_string()
+    }
+
+    fn emit_with(node: &Node, options: &EmitterOptions) -> String {
+        let mut destination = Buffer::new();
+        stringify_with_options(node, &mut destination, options).unwrap();
+        destination.to_string()
+    }
+
+    fn sample() -> Node {
+        Node::dict()
+            .key("name", "web, primary")
+            .key("ports", vec![80, 443])
+            .key("tls", Node::dict().key("enabled", true).key("hosts", vec!["a", "b"]))
+            .build()
+    }
+
+    #[test]
+    fn stringify_scalars_works() {
+        assert_eq!(emit(&Node::None), "null\n");
+        assert_eq!(emit(&Node::from(true)), "true\n");
+        assert_eq!(emit(&Node::from(42)), "42\n");
+        assert_eq!(emit(&Node::Number(Numeric::Float(2.0))), "2.0\n");
+        assert_eq!(emit(&Node::from("hello world")), "hello world\n");
+        assert_eq!(emit(&Node::Comment("note".to_string())), "# note\n");
+    }
+
+    #[test]
+    fn stringify_quotes_ambiguous_strings() {
+        let ambiguous = [
+            "", "true", "No", "null", "~", "42", "-1", "1.5", "nan", ".inf", " padded", "a: b", "a #b", "- x", "[x]",
+        ];
+        for text in ambiguous {
+            assert!(emit(&Node::from(text)).starts_with('"'), "{:?}", text);
+        }
+        assert_eq!(emit(&Node::from("line\rbreak")), "\"line\\rbreak\"\n");
+        assert_eq!(emit(&Node::from("web-01")), "web-01\n");
+        assert_eq!(emit(&Node::from("a:b")), "a:b\n");
+    }
+
+    #[test]
+    fn stringify_dictionary_works() {
+        let node = Node::dict()
+            .key("name", "web")
+            .key("server", Node::dict().key("port", 80).key("tls", Node::dict().key("enabled", true)))
+            .key("tags", Node::Array(vec![]))
+            .key("true", Node::None)
+            .build();
+        let expected = "name: web\nserver:\n  port: 80\n  tls:\n    enabled: true\ntags: []\n\"true\": null\n";
+        assert_eq!(emit(&node), expected);
+    }
+
+    #[test]
+    fn stringify_array_works() {
+        let node = Node::from(vec![
+            Node::from(1),
+            Node::from(vec![2, 3]),
+            Node::dict().key("a", 1).key("b", vec!["x"]).build(),
+            Node::dict().build(),
+        ]);
+        assert_eq!(emit(&node), "- 1\n- - 2\n  - 3\n- a: 1\n  b:\n    - x\n- {}\n");
+    }
+
+    #[test]
+    fn stringify_comments_and_documents_works() {
+        let node = Node::Document(vec![
+            Node::Comment("header".to_string()),
+            Node::dict().key("a", 1).key("__comment_1", Node::Comment("about b".to_string())).key("b", 2).build(),
+            Node::from(vec![Node::Comment("items".to_string()), Node::from("x")]),
+        ]);
+        assert_eq!(emit(&node), "# header\n---\na: 1\n# about b\nb: 2\n---\n# items\n- x\n");
+    }
+
+    #[test]
+    fn stringify_round_trips_through_parser() {
+        let node = Node::dict()
+            .key("name", "web server")
+            .key("port", 8080)
+            .key("ratio", 0.5)
+            .key("debug", false)
+            .key("version", "1.10")
+            .key("empty", Node::None)
+            .build();
+        let text = emit(&node);
+        assert_eq!(parse(&mut Source::new(text.as_bytes())).unwrap(), node);
+    }
+
+    #[test]
+    fn literal_block_scalars_round_trip_through_parser() {
+        let texts = ["echo one\n\necho two\n", "a\nb", "a\n\n", "  a\nb\n", "\n\nlead\n", "x\n    deep\ny"];
+        let node = Node::dict()
+            .key("texts", texts.to_vec())
+            .key("nested", Node::dict().key("script", texts[0]).key("lines", vec![texts[3]]).build())
+            .build();
+        for indent in [2, 4] {
+            let options = EmitterOptions { indent, ..EmitterOptions::new() };
+            let text = emit_with(&node, &options);
+            assert!(text.contains('|'), "{}", text);
+            assert_eq!(parse(&mut Source::new(text.as_bytes())).unwrap(), node, "{}", text);
+        }
+        for text in texts {
+            let emitted = emit(&Node::from(text));
+            assert_eq!(parse(&mut Source::new(emitted.as_bytes())).unwrap(), Node::from(text), "{}", emitted);
+        }
+    }
+
+    #[test]
+    fn literal_block_scalar_indicators_match_the_indent_used() {
+        let node = Node::dict()
+            .key("items", vec!["  x\ny", "a\n"])
+            .key("nested", Node::dict().key("text", "  x\ny").build())
+            .key("list", vec![Node::dict().key("text", "\t x\n").build()])
+            .build();
+        for indent in [0, 1, 3, 9] {
+            let options = EmitterOptions { indent, ..EmitterOptions::new() };
+            let text = emit_with(&node, &options);
+            assert_eq!(parse(&mut Source::new(text.as_bytes())).unwrap(), node, "{}", text);
+        }
+        let options = EmitterOptions { indent: 0, ..EmitterOptions::new() };
+        let text = emit_with(&sample(), &options);
+        assert_eq!(text, "name: web, primary\nports:\n - 80\n - 443\ntls:\n enabled: true\n hosts:\n  - a\n  - b\n");
+        assert_eq!(parse(&mut Source::new(text.as_bytes())).unwrap(), sample());
+        let options = EmitterOptions { indent: 1, ..EmitterOptions::new() };
+        assert_eq!(emit_with(&Node::from(vec!["  x\ny"]), &options), "- |2-\n    x\n  y\n");
+        assert_eq!(emit_with(&Node::from("  x\ny"), &options), "|1-\n   x\n y\n");
+    }
+
+    #[test]
+    fn control_characters_round_trip_through_parser() {
+        let node = Node::dict()
+            .key("bell", "ding\x07")
+            .key("escape", "\x1B[0m")
+            .key("mixed", "nul\0 del\x7F next\u{85} tab\t cr\r lf\n soh\x01")
+            .build();
+        let text = emit(&node);
+        assert!(text.contains("\\x07") && text.contains("\\x1B") && text.contains("\\x85"), "{}", text);
+        assert_eq!(parse(&mut Source::new(text.as_bytes())).unwrap(), node, "{}", text);
+        let escapes = "\"\\a\\b\\e\\f\\v\\N\\_\\L\\P\\x41\\u00e9\\U0001F600\\xZZ\"";
+        let decoded = "\x07\x08\x1B\x0C\x0B\u{85}\u{A0}\u{2028}\u{2029}Aé😀xZZ";
+        assert_eq!(parse(&mut Source::new(escapes.as_bytes())).unwrap(), Node::from(decoded));
+    }
+
+    #[test]
+    fn stringify_with_indent_works() {
+        let options = EmitterOptions { indent: 4, ..EmitterOptions::new() };
+        let expected = "name: web, primary\nports:\n    -   80\n    -   443\n\
+            tls:\n    enabled: true\n    hosts:\n        -   a\n        -   b\n";
+        assert_eq!(emit_with(&sample(), &options), expected);
+        let node = Node::from(vec![Node::dict().key("a", 1).key("b", 2).build()]);
+        assert_eq!(emit_with(&node, &EmitterOptions { indent: 1, ..options }), "- a: 1\n  b: 2\n");
+    }
+
+    #[test]
+    fn stringify_with_flow_style_works() {
+        let mut options = EmitterOptions { style: CollectionStyle::Flow, ..EmitterOptions::new() };
+        let flow = "{name: \"web, primary\", ports: [80, 443], tls: {enabled: true, hosts: [a, b]}}\n";
+        assert_eq!(emit_with(&sample(), &options), flow);
+        assert_eq!(parse(&mut Source::new(flow.as_bytes())).unwrap(), sample());
+        options.width = Some(40);
+        let expected = "{name: \"web, primary\", ports: [80, 443],\n  tls: {enabled: true, hosts: [a, b]}}\n";
+        assert_eq!(emit_with(&sample(), &options), expected);
+        let commented = Node::from(vec![Node::Comment("kept".to_string()), Node::from(1)]);
+        assert_eq!(emit_with(&commented, &options), "# kept\n- 1\n");
+    }
+
+    #[test]
+    fn stringify_with_explicit_start_works() {
+        let options = EmitterOptions { explicit_start: true, ..EmitterOptions::new() };
+        assert_eq!(emit_with(&Node::dict().key("a", 1).build(), &options), "---\na: 1\n");
+        let documents = Node::Document(vec![Node::from(1), Node::from(2)]);
+        assert_eq!(emit_with(&documents, &options), "---\n1\n---\n2\n");
+    }
+
+    #[test]
+    fn stringify_with_auto_flow_works() {
+        let mut options = EmitterOptions { auto_flow_items: 3, ..EmitterOptions::new() };
+        let node = Node::dict()
+            .key("point", Node::dict().key("x", 1).key("z", 2))
+            .key("ports", vec![80, 443])
+            .key("hosts", vec!["a", "b", "c", "d"])
+            .key("nested", vec![vec![1]])
+            .build();
+        let expected = "point: {x: 1, z: 2}\nports: [80, 443]\nhosts:\n  - a\n  - b\n  - c\n  - d\nnested:\n  - [1]\n";
+        assert_eq!(emit_with(&node, &options), expected);
+        let point = expected.lines().find(|line| line.starts_with("point")).unwrap();
+        assert_eq!(parse(&mut Source::new(point.as_bytes())).unwrap()["point"], node["point"]);
+        options.auto_flow_chars = 10;
+        let expected = "point:\n  x: 1\n  z: 2\nports: [80, 443]\nhosts:\n  - a\n  - b\n  - c\n  - d\n\
+            nested:\n  - [1]\n";
+        assert_eq!(emit_with(&node, &options), expected);
+    }
+
+    #[test]
+    fn stringify_with_quote_style_works() {
+        let node = Node::dict().key("name", "it's").key("count", "42").key("tags", vec!["a", "b,c"]).build();
+        let mut options = EmitterOptions { auto_flow_items: 2, ..EmitterOptions::new() };
+        options.quote_style = QuoteStyle::Double;
+        let expected = "\"name\": \"it's\"\n\"count\": \"42\"\n\"tags\": [\"a\", \"b,c\"]\n";
+        assert_eq!(emit_with(&node, &options), expected);
+        options.quote_style = QuoteStyle::Single;
+        assert_eq!(emit_with(&node, &options), "'name': 'it''s'\n'count': '42'\n'tags': ['a', 'b,c']\n");
+        assert_eq!(parse(&mut Source::new(emit_with(&node, &options).as_bytes())).unwrap(), node);
+        options.quote_style = QuoteStyle::Plain;
+        assert_eq!(emit_with(&node, &options), "name: it's\ncount: 42\ntags: [a, \"b,c\"]\n");
+        options.quote_style = QuoteStyle::Minimal;
+        options.quote_overrides = vec![("/tags/*".to_string(), QuoteStyle::Single)];
+        assert_eq!(emit_with(&node, &options), "name: it's\ncount: \"42\"\ntags: ['a', 'b,c']\n");
+        assert_eq!(emit_with(&Node::from("tab\there"), &options), "\"tab\\there\"\n");
+    }
+
+    #[test]
+    fn ambiguous_strings_round_trip_as_strings() {
+        let texts = [
+            "true", "False", "NULL", "~", "yes", "Off", "42", "-7", "+3", "0x1F", "0o17", "1_000", "1e3", ".5", "1.10",
+            "inf", "-.INF", ".NaN", "1:30", "2024-01-01", "<<", "=", "...", "--- x", "# note", "a #b", "a: b", "key:",
+            "'quoted'", "\"quoted\"", "%tag", "@at", "`tick", "|", ">", "!", "&a", "*a", "? q", "[", "{", "-",
+        ];
+        let node = Node::dict();
+        let node = texts.iter().fold(node, |node, text| node.key(format!("k{}", text), *text)).build();
+        let text = emit(&node);
+        assert_eq!(parse(&mut Source::new(text.as_bytes())).unwrap(), node, "{}", text);
+        for plain in ["web-01", "a:b", "1st", "x-1", "hello world", "C#", "café"] {
+            assert_eq!(emit(&Node::from(plain)), format!("{}\n", plain));
+        }
+    }
+
+    #[test]
+    fn stringify_literal_block_scalars_works() {
+        let node = Node::dict()
+            .key("script", "echo one\n\necho two\n")
+            .key("clipped", "a\nb")
+            .key("kept", "a\n\n")
+            .key("indented", "  a\nb\n")
+            .key("lines", vec!["x\ny"])
+            .build();
+        let expected = "script: |\n  echo one\n\n  echo two\nclipped: |-\n  a\n  b\nkept: |+\n  a\n\n\
+            indented: |2\n    a\n  b\nlines:\n  - |-\n    x\n    y\n";
+        assert_eq!(emit(&node), expected);
+        assert_eq!(emit(&Node::from("a\nb\n")), "|\n  a\n  b\n");
+        assert_eq!(emit(&Node::from("a\r\nb")), "\"a\\r\\nb\"\n");
+        let options = EmitterOptions { literal_block_scalars: false, ..EmitterOptions::new() };
+        assert_eq!(emit_with(&Node::from("a\nb"), &options), "\"a\\nb\"\n");
+    }
+
+    #[test]
+    fn stringify_with_width_folds_long_lines() {
+        let mut options = EmitterOptions { width: Some(20), ..EmitterOptions::new() };
+        let node = Node::dict()
+            .key("text", "the quick brown fox jumps over the lazy dog")
+            .key("quoted", "yes: a  spaced -dash value")
+            .key("word", "abcdefghijklmnopqrstuvwxyz")
+            .build();
+        let expected = "text: the quick\n  brown fox jumps\n  over the lazy dog\n\
+            quoted: \"yes:\n  a  spaced -dash\n  value\"\nword: abcdefghijklmnopqrstuvwxyz\n";
+        assert_eq!(emit_with(&node, &options), expected);
+        assert_eq!(parse(&mut Source::new(expected.as_bytes())).unwrap(), node);
+        options.style = CollectionStyle::Flow;
+        let node = Node::from(vec![8080, 8081, 8082, 8083, 8084]);
+        assert_eq!(emit_with(&node, &options), "[8080, 8081, 8082,\n  8083, 8084]\n");
+        let node = Node::dict().key("a", vec![8080, 8081, 8082, 8083]).key("b", 1).build();
+        let text = emit_with(&node, &options);
+        assert_eq!(text, "{a: [8080, 8081, 8082, 8083],\n  b: 1}\n");
+        assert_eq!(parse(&mut Source::new(text.as_bytes())).unwrap(), node);
+    }
+
+    #[test]
+    fn stringify_without_sort_keys_uses_map_order() {
+        let mut node = Node::dict().key("b", 1).key("a", 2).key("c", 3).build();
+        assert_eq!(emit(&node), "b: 1\na: 2\nc: 3\n");
+        let options = EmitterOptions { sort_keys: true, ..EmitterOptions::new() };
+        assert_eq!(emit_with(&node, &options), "a: 2\nb: 1\nc: 3\n");
+        assert_eq!(emit_with(&node, &EmitterOptions::reproducible()), "a: 2\nb: 1\nc: 3\n");
+        node.sort_keys_by(|a, b| b.cmp(a));
+        assert_eq!(emit(&node), "c: 3\nb: 1\na: 2\n");
+    }
+
+    #[test]
+    fn stringify_with_comments_works() {
+        let text = "name: web # service name\nport: 80 # http\ntags: [a, b] # labels\n";
+        let parsed = parse_detailed(&mut Source::new(text.as_bytes()), &mut ParserOptions::new()).unwrap();
+        let options = EmitterOptions { auto_flow_items: 3, ..EmitterOptions::new() };
+        let mut destination = Buffer::new();
+        stringify_with_comments(&parsed.node, &parsed.comments, &mut destination, &options).unwrap();
+        assert_eq!(destination.to_string(), text);
+
+        let node = Node::dict()
+            .key("server", Node::dict().key("hosts", vec!["a"]).key("script", "x\ny\n"))
+            .key("debug", true)
+            .build();
+        let mut comments = Comments::new();
+        comments.add_leading("", "generated");
+        comments.add_leading("/server/hosts/0", "primary");
+        comments.set_trailing("/server", "main server");
+        comments.set_trailing("/server/script", "run on start");
+        comments.set_trailing("/debug", "off in production");
+        let mut destination = Buffer::new();
+        stringify_with_comments(&node, &comments, &mut destination, &options).unwrap();
+        let expected = "# generated\nserver: # main server\n  hosts:\n    # primary\n    - a\n\
+            \x20 script: | # run on start\n    x\n    y\ndebug: true # off in production\n";
+        assert_eq!(destination.to_string(), expected);
+    }
+
+    #[test]
+    fn stringify_with_banner_works() {
+        let node = Node::dict().key("port", 80).key("tls", Node::dict().key("enabled", true)).build();
+        let mut comments = Comments::new();
+        comments.add_leading("", "service settings");
+        comments.set_trailing("/tls/enabled", "required in production");
+        let banner = "Generated by confgen from service.json.  \n\nDO NOT EDIT.".to_string();
+        let options = EmitterOptions {
+            banner: Some(banner),
+            explicit_start: true,
+            trim_trailing_whitespace: true,
+            ..EmitterOptions::new()
+        };
+        let mut destination = Buffer::new();
+        stringify_with_comments(&node, &comments, &mut destination, &options).unwrap();
+        let expected = "# Generated by confgen from service.json.\n#\n# DO NOT EDIT.\n---\n# service settings\n\
+            port: 80\ntls:\n  enabled: true # required in production\n";
+        assert_eq!(destination.to_string(), expected);
+        let options = EmitterOptions { banner: Some("generated".to_string()), ..EmitterOptions::minified() };
+        assert_eq!(emit_with(&Node::from(vec![1]), &options), "# generated\n[1]");
+    }
+
+    #[test]
+    fn stringify_with_metadata_restores_anchors() {
+        let text = "a: &x {k: &n 1}\nb: *x\nc: *n\nd:\n  <<: *x\n";
+        let parsed = parse_detailed(&mut Source::new(text.as_bytes()), &mut ParserOptions::new()).unwrap();
+        let options = EmitterOptions { style: CollectionStyle::Flow, anchor_repeats: true, ..EmitterOptions::new() };
+        let mut destination = Buffer::new();
+        stringify_with_metadata(&parsed.node, &parsed.comments, &parsed.anchors, &mut destination, &options).unwrap();
+        assert_eq!(destination.to_string(), "{a: &x {k: &n 1}, b: *x, c: *n, d: {<<: *x}}\n");
+        assert_eq!(emit(&parsed.node), "a:\n  k: 1\nb:\n  k: 1\nc: 1\nd:\n  \"<<\":\n    k: 1\n");
+    }
+
+    #[test]
+    fn stringify_with_anchor_repeats_works() {
+        let defaults = Node::dict().key("retries", 3).key("timeout", 30).build();
+        let node = Node::dict()
+            .key("base", defaults.clone())
+            .key("first", defaults.clone())
+            .key("second", Node::from(vec![defaults.clone(), defaults.clone()]))
+            .key("tags", vec!["a"])
+            .key("other", vec!["a"])
+            .build();
+        let mut options = EmitterOptions { anchor_repeats: true, ..EmitterOptions::new() };
+        let expected = "base: &id001\n  retries: 3\n  timeout: 30\nfirst: *id001\nsecond:\n  - *id001\n  - *id001\n\
+            tags: &id002\n  - a\nother: *id002\n";
+        assert_eq!(emit_with(&node, &options), expected);
+
+        options.auto_flow_items = 2;
+        let text = emit_with(&node, &options);
+        assert_eq!(text.lines().next(), Some("base: &id001 {retries: 3, timeout: 30}"));
+        let flat = Node::dict().key("base", defaults.clone()).key("first", defaults).build();
+        let text = emit_with(&flat, &options);
+        assert_eq!(text, "base: &id001 {retries: 3, timeout: 30}\nfirst: *id001\n");
+        assert_eq!(parse(&mut Source::new(text.as_bytes())).unwrap(), flat);
+
+        let repeated_inside = Node::from(vec![Node::from(vec![vec![1]]), Node::from(vec![vec![1]])]);
+        assert_eq!(emit_with(&repeated_inside, &options), "- &id001\n  - [1]\n- *id001\n");
+        let float = Node::from(vec![Node::from(1.0), Node::from("a")]);
+        let mixed = Node::from(vec![float, Node::from(vec![Node::from(1), Node::from("a")])]);
+        assert_eq!(emit_with(&mixed, &options), "- [1.0, a]\n- [1, a]\n");
+    }
+
+    #[test]
+    fn stringify_canonical_works() {
+        let node = Node::dict()
+            .key("name", "web")
+            .key("ports", vec![80])
+            .key("ratio", 0.5)
+            .key("__comment_1", Node::Comment("dropped".to_string()))
+            .key("tls", Node::dict().key("enabled", true).key("cert", Node::None))
+            .key("empty", Node::Array(vec![]))
+            .build();
+        let options = EmitterOptions { canonical: true, ..EmitterOptions::new() };
+        let expected = concat!(
+            "---\n",
+            "!!map {\n",
+            "  ? !!str \"empty\"\n",
+            "  : !!seq [],\n",
+            "  ? !!str \"name\"\n",
+            "  : !!str \"web\",\n",
+            "  ? !!str \"ports\"\n",
+            "  : !!seq [\n",
+            "    !!int \"80\",\n",
+            "  ],\n",
+            "  ? !!str \"ratio\"\n",
+            "  : !!float \"0.5\",\n",
+            "  ? !!str \"tls\"\n",
+            "  : !!map {\n",
+            "    ? !!str \"cert\"\n",
+            "    : !!null \"\",\n",
+            "    ? !!str \"enabled\"\n",
+            "    : !!bool \"true\",\n",
+            "  },\n",
+            "}\n",
+        );
+        assert_eq!(emit_with(&node, &options), expected);
+        let documents = Node::Document(vec![Node::from("a"), Node::Comment("x".to_string()), Node::from(1)]);
+        assert_eq!(emit_with(&documents, &options), "---\n!!str \"a\"\n---\n!!int \"1\"\n");
+    }
+
+    #[test]
+    fn stringify_minified_works() {
+        let node = Node::dict()
+            .key("name", "web, primary")
+            .key("ports", vec![80, 443])
+            .key("ratio", 1.0)
+            .key("__comment_1", Node::Comment("dropped".to_string()))
+            .key("tls", Node::dict().key("enabled", true).key("cert", Node::None))
+            .key("a: b", vec!["true", "x\ny", ""])
+            .key("empty", Node::dict().build())
+            .build();
+        let options = EmitterOptions::minified();
+        let text = emit_with(&node, &options);
+        let expected = "{\"a: b\":[\"true\",\"x\\ny\",\"\"],empty: {},name: \"web, primary\",ports: [80,443],\
+                        ratio: 1.0,tls: {cert: ~,enabled: true}}";
+        assert_eq!(text, expected);
+        let mut without_comments = node.clone();
+        without_comments.remove("__comment_1");
+        assert_eq!(parse(&mut Source::new(text.as_bytes())).unwrap(), without_comments);
+        let documents = Node::Document(vec![Node::from("a"), Node::Comment("x".to_string()), Node::from(vec![1])]);
+        assert_eq!(emit_with(&documents, &options), "---\na\n---\n[1]\n");
+    }
+
+    #[test]
+    fn stringify_with_null_style_works() {
+        let node = Node::dict().key("a", Node::None).key("b", vec![Node::None, Node::from(1)]).build();
+        let mut options = EmitterOptions { null_style: NullStyle::Tilde, ..EmitterOptions::new() };
+        assert_eq!(emit_with(&node, &options), "a: ~\nb:\n  - ~\n  - 1\n");
+        options.null_style = NullStyle::Empty;
+        let text = emit_with(&node, &options);
+        assert_eq!(text, "a:\nb:\n  -\n  - 1\n");
+        let flat = Node::dict().key("a", Node::None).key("b", 2).build();
+        assert_eq!(parse(&mut Source::new(emit_with(&flat, &options).as_bytes())).unwrap(), flat);
+        let flat = Node::from(vec![Node::None, Node::from(2)]);
+        assert_eq!(parse(&mut Source::new(emit_with(&flat, &options).as_bytes())).unwrap(), flat);
+        options.auto_flow_items = 2;
+        assert_eq!(emit_with(&node, &options), "a:\nb: [null, 1]\n");
+    }
+
+    #[test]
+    fn stringify_with_bool_style_and_keyword_case_works() {
+        let node = Node::from(vec![Node::from(true), Node::from(false), Node::None]);
+        let mut options = EmitterOptions { auto_flow_items: 3, ..EmitterOptions::new() };
+        assert_eq!(emit_with(&node, &options), "[true, false, null]\n");
+        options.keyword_case = KeywordCase::Title;
+        assert_eq!(emit_with(&node, &options), "[True, False, Null]\n");
+        options.bool_style = BoolStyle::YesNo;
+        options.keyword_case = KeywordCase::Upper;
+        assert_eq!(emit_with(&node, &options), "[YES, NO, NULL]\n");
+        options.bool_style = BoolStyle::OnOff;
+        options.keyword_case = KeywordCase::Lower;
+        options.null_style = NullStyle::Tilde;
+        assert_eq!(emit_with(&node, &options), "[on, off, ~]\n");
+    }
+
+    #[test]
+    fn stringify_floats_round_trip() {
+        let node = Node::dict().key("a", 2.0).key("b", 0.1 + 0.2).key("c", 1.5e20).key("d", -2.5e-7).build();
+        let options = EmitterOptions { float_exponent_threshold: Some(6), ..EmitterOptions::new() };
+        let text = emit_with(&node, &options);
+        assert_eq!(text, "a: 2.0\nb: 0.30000000000000004\nc: 1.5e+20\nd: -2.5e-7\n");
+        assert_eq!(parse(&mut Source::new(text.as_bytes())).unwrap(), node);
+        let node = Node::from(vec![Node::from(f64::INFINITY), Node::from(f64::NEG_INFINITY)]);
+        assert_eq!(emit(&node), "- .inf\n- -.inf\n");
+        assert!(parse(&mut Source::new(emit(&node).as_bytes())).unwrap().strict_eq(&node));
+        let nan = parse(&mut Source::new(emit(&Node::from(f64::NAN)).as_bytes())).unwrap();
+        assert!(matches!(nan, Node::Number(Numeric::Float(f)) if f.is_nan()), "{:?}", nan);
+        let root = parse(&mut Source::new(emit(&Node::from(f64::INFINITY)).as_bytes())).unwrap();
+        assert!(root.strict_eq(&Node::from(f64::INFINITY)));
+    }
+
+    #[test]
+    fn stringify_reproducible_has_no_trailing_whitespace() {
+        let node = Node::Document(vec![
+            Node::Comment(String::new()),
+            Node::dict()
+                .key("z", "tail  \nnext")
+                .key("a", Node::Comment("note \t".to_string()))
+                .key("m", "line\nbreak")
+                .key("f", 0.1 + 0.2)
+                .build(),
+        ]);
+        let text = emit_with(&node, &EmitterOptions::reproducible());
+        assert_eq!(text, "#\n---\n# note\nf: 0.30000000000000004\nm: |-\n  line\n  break\nz: \"tail  \\nnext\"\n");
+        assert!(text.lines().all(|line| !line.ends_with([' ', '\t'])));
+        assert_eq!(emit_with(&node.clone(), &EmitterOptions::reproducible()), text);
+        let options = EmitterOptions::new();
+        assert!(emit_with(&node, &options).starts_with("# \n"));
+    }
+
+    #[test]
+    fn stringify_streamed_matches_measured_output() {
+        let shared = Node::from(vec!["x", "y"]);
+        let node = Node::dict()
+            .key("a", shared.clone())
+            .key("b", shared)
+            .key("c", "it's \"quoted\"\u{7}")
+            .key("d", vec![Node::None, Node::from(1.5), Node::dict().build()])
+            .key("e", "two\nlines\n")
+            .build();
+        for style in [QuoteStyle::Minimal, QuoteStyle::Single, QuoteStyle::Double] {
+            for collection_style in [CollectionStyle::Block, CollectionStyle::Flow] {
+                let streamed = EmitterOptions {
+                    style: collection_style,
+                    quote_style: style,
+                    anchor_repeats: true,
+                    ..EmitterOptions::new()
+                };
+                let measured = EmitterOptions { width: Some(usize::MAX), ..streamed.clone() };
+                assert_eq!(emit_with(&node, &streamed), emit_with(&node, &measured));
+            }
+        }
+        let options = EmitterOptions { style: CollectionStyle::Flow, ..EmitterOptions::new() };
+        let text = emit_with(&sample(), &options);
+        assert_eq!(text, "{name: \"web, primary\", ports: [80, 443], tls: {enabled: true, hosts: [a, b]}}\n");
+    }
+
+    #[test]
+    fn stringify_to_writer_works() {
+        let mut bytes = Vec::new();
+        stringify_to_writer(&sample(), &mut bytes).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), emit(&sample()));
+        let mut text = String::new();
+        let mut destination = FmtWriter::new(&mut text);
+        stringify(&sample(), &mut destination).unwrap();
+        assert!(destination.into_inner().is_ok());
+        assert_eq!(text, emit(&sample()));
+    }
+}
@@ -0,0 +1,190 @@
+//! Graphviz DOT emitter.
+//! Writes a Node tree as a directed graph for rendering with Graphviz: each collection is
+//! a box labelled with its kind and size, each scalar an ellipse labelled with its value
+//! as flow style YAML and each comment a note, with edges from a collection to its
+//! children labelled by dictionary key (in sorted order) or array index. With repeats
+//! shared, a collection equal to one already drawn is not drawn again; an edge points to
+//! the first copy instead, so subtrees the parser expanded from an alias show as the one
+//! node the anchor defined, as the YAML emitter writes them.
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::Node;
+use crate::stringify::default::flow_scalar;
+use std::collections::HashMap;
+
+/// Default name of the emitted graph.
+pub const DEFAULT_DOT_GRAPH_NAME: &str = "yaml";
+
+/// Options used to configure the DOT emitter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DotOptions {
+    /// Name written after "digraph"
+    pub graph_name: String,
+    /// Draw collections that appear more than once as a single node with several parents
+    pub share_repeats: bool,
+    /// Lay the graph out from left to right rather than top to bottom
+    pub left_to_right: bool,
+}
+
+impl DotOptions {
+    /// Creates a new DotOptions instance with default settings.
+    ///
+    /// # Returns
+    /// DotOptions for a top to bottom graph named "yaml" with repeated collections shared.
+    pub fn new() -> Self {
+        Self { graph_name: DEFAULT_DOT_GRAPH_NAME.to_string(), share_repeats: true, left_to_right: false }
+    }
+}
+
+impl Default for DotOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes a Node tree to a destination as a Graphviz DOT graph.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the graph is written
+///
+/// # Returns
+/// Ok; every tree has a graph.
+pub fn stringify(node: &Node, destination: &mut dyn IDestination) -> Result<(), String> {
+    stringify_with_options(node, destination, &DotOptions::new())
+}
+
+/// Writes a Node tree to a destination as a Graphviz DOT graph laid out according to the
+/// supplied options.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the graph is written
+/// * `options` - Graph name, sharing and layout settings
+///
+/// # Returns
+/// Ok; every tree has a graph.
+pub fn stringify_with_options(
+    node: &Node,
+    destination: &mut dyn IDestination,
+    options: &DotOptions,
+) -> Result<(), String> {
+    destination.add_bytes("digraph ");
+    destination.add_bytes(&quoted(&options.graph_name));
+    destination.add_bytes(" {\n");
+    if options.left_to_right {
+        destination.add_bytes("  rankdir=LR;\n");
+    }
+    destination.add_bytes("  node [fontname=\"monospace\"];\n");
+    let mut emitter = DotEmitter { destination, options, drawn: HashMap::new(), next: 0 };
+    emitter.node(node);
+    emitter.destination.add_bytes("}\n");
+    Ok(())
+}
+
+/// Returns text as a DOT quoted string.
+fn quoted(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => {}
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// DOT writer for a single destination.
+struct DotEmitter<'a, 'n> {
+    destination: &'a mut dyn IDestination,
+    options: &'a DotOptions,
+    /// Identifiers of the collections drawn so far, when repeats are shared
+    drawn: HashMap<&'n Node, usize>,
+    /// Identifier of the next node drawn
+    next: usize,
+}
+
+impl<'n> DotEmitter<'_, 'n> {
+    /// Draws a node and everything below it, returning its identifier.
+    fn node(&mut self, node: &'n Node) -> usize {
+        let shared = self.options.share_repeats && !node.is_empty();
+        if shared && let Some(&id) = self.drawn.get(node) {
+            return id;
+        }
+        let id = self.next;
+        self.next += 1;
+        if shared {
+            self.drawn.insert(node, id);
+        }
+        let (label, shape) = match node {
+            Node::Array(_) | Node::Dictionary(_) | Node::Document(_) => {
+                (format!("{} ({})", node.kind(), node.len()), "box")
+            }
+            Node::Comment(text) => (format!("# {}", text), "note"),
+            _ => (flow_scalar(node), "ellipse"),
+        };
+        self.destination.add_bytes(&format!("  n{} [label={}, shape={}];\n", id, quoted(&label), shape));
+        let children: Vec<(String, &'n Node)> = match node {
+            Node::Array(items) | Node::Document(items) => {
+                items.iter().enumerate().map(|(index, item)| (index.to_string(), item)).collect()
+            }
+            Node::Dictionary(_) => node.entries().map(|(key, value)| (key.to_string(), value)).collect(),
+            _ => Vec::new(),
+        };
+        for (edge, child) in children {
+            let child_id = self.node(child);
+            self.destination.add_bytes(&format!("  n{} -> n{} [label={}];\n", id, child_id, quoted(&edge)));
+        }
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+
+    fn emit_with(node: &Node, options: &DotOptions) -> String {
+        let mut destination = Buffer::new();
+        stringify_with_options(node, &mut destination, options).unwrap();
+        destination.to_string()
+    }
+
+    #[test]
+    fn stringify_dot_works() {
+        let node = Node::dict()
+            .key("name", "web \"1\"")
+            .key("ports", vec![Node::from(80), Node::Comment("tls".to_string())])
+            .build();
+        let expected = "digraph \"yaml\" {\n  node [fontname=\"monospace\"];\n\
+                        \x20 n0 [label=\"dictionary (2)\", shape=box];\n\
+                        \x20 n1 [label=\"\\\"web \\\\\\\"1\\\\\\\"\\\"\", shape=ellipse];\n\
+                        \x20 n0 -> n1 [label=\"name\"];\n\
+                        \x20 n2 [label=\"array (2)\", shape=box];\n\
+                        \x20 n3 [label=\"80\", shape=ellipse];\n\
+                        \x20 n2 -> n3 [label=\"0\"];\n\
+                        \x20 n4 [label=\"# tls\", shape=note];\n\
+                        \x20 n2 -> n4 [label=\"1\"];\n\
+                        \x20 n0 -> n2 [label=\"ports\"];\n\
+                        }\n";
+        assert_eq!(emit_with(&node, &DotOptions::new()), expected);
+    }
+
+    #[test]
+    fn stringify_dot_shares_repeated_collections() {
+        let defaults = Node::dict().key("retries", 3).build();
+        let node = Node::dict().key("a", defaults.clone()).key("b", defaults).build();
+        let shared = emit_with(&node, &DotOptions::new());
+        assert!(shared.contains("  n0 -> n1 [label=\"a\"];\n  n0 -> n1 [label=\"b\"];\n"));
+        assert_eq!(shared.matches("shape=").count(), 3);
+        let options = DotOptions { share_repeats: false, left_to_right: true, ..DotOptions::new() };
+        let unshared = emit_with(&node, &options);
+        assert!(unshared.starts_with("digraph \"yaml\" {\n  rankdir=LR;\n"));
+        assert_eq!(unshared.matches("shape=").count(), 5);
+    }
+}
@@ -0,0 +1,241 @@
+//! Dotenv emitter.
+//! Writes a Dictionary node as a .env file of KEY=value lines in sorted key order. The
+//! keys of nested dictionaries and the indexes of arrays are joined with a separator
+//! (DB_HOST, PORTS_0), characters a variable name cannot hold become underscores and
+//! names are uppercased unless that is turned off. Values made only of letters, digits
+//! and _ . / : @ , + - are written bare, others in single quotes, or in double quotes with
+//! \n, \", \\ and \$ escapes when they hold a single quote or a control character. Nulls,
+//! empty arrays and empty dictionaries are written as empty values and comments as
+//! "# text" lines. A root that is not a dictionary, names starting with a digit and two
+//! keys ending up with the same name are errors, reported before anything is written.
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::Node;
+use crate::stringify::number::format_number;
+use crate::stringify::options::EmitterOptions;
+
+/// Default separator joining the keys of nested values.
+pub const DEFAULT_DOTENV_SEPARATOR: &str = "_";
+
+/// Options used to configure the dotenv emitter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DotenvOptions {
+    /// Uppercase variable names, as environment variables conventionally are
+    pub uppercase: bool,
+    /// Joins the keys of nested dictionaries and the indexes of arrays
+    pub separator: String,
+    /// Start each line with "export " so the file can also be sourced by a shell
+    pub export: bool,
+}
+
+impl DotenvOptions {
+    /// Creates a new DotenvOptions instance with default settings.
+    ///
+    /// # Returns
+    /// DotenvOptions for uppercased, "_" joined names without export prefixes.
+    pub fn new() -> Self {
+        Self { uppercase: true, separator: DEFAULT_DOTENV_SEPARATOR.to_string(), export: false }
+    }
+}
+
+impl Default for DotenvOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes a Dictionary node to a destination as a .env file.
+///
+/// # Arguments
+/// * `node` - The Dictionary to write
+/// * `destination` - Where the dotenv text is written
+///
+/// # Returns
+/// Ok, or an error if the root is not a dictionary or a key has no valid variable name.
+pub fn stringify(node: &Node, destination: &mut dyn IDestination) -> Result<(), String> {
+    stringify_with_options(node, destination, &DotenvOptions::new())
+}
+
+/// Writes a Dictionary node to a destination as a .env file laid out according to the
+/// supplied options.
+///
+/// # Arguments
+/// * `node` - The Dictionary to write
+/// * `destination` - Where the dotenv text is written
+/// * `options` - Naming and export settings
+///
+/// # Returns
+/// Ok, or an error if the root is not a dictionary or a key has no valid variable name;
+/// nothing is written when there is an error.
+pub fn stringify_with_options(
+    node: &Node,
+    destination: &mut dyn IDestination,
+    options: &DotenvOptions,
+) -> Result<(), String> {
+    if !matches!(node, Node::Dictionary(_)) {
+        return Err("A .env file can only be written from a dictionary".to_string());
+    }
+    let mut lines = Vec::new();
+    flatten(node, "", options, &mut lines);
+    let mut names: Vec<&str> = Vec::new();
+    for name in lines.iter().filter_map(|(name, _)| name.as_deref()) {
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(format!("'{}' is not a valid environment variable name", name));
+        }
+        if names.contains(&name) {
+            return Err(format!("More than one value would be written as {}", name));
+        }
+        names.push(name);
+    }
+    for (name, value) in lines {
+        match name {
+            Some(name) => {
+                if options.export {
+                    destination.add_bytes("export ");
+                }
+                destination.add_bytes(&name);
+                destination.add_byte(b'=');
+                write_value(&value, &mut |part| destination.add_bytes(part));
+            }
+            None => {
+                destination.add_bytes("# ");
+                destination.add_bytes(&value);
+            }
+        }
+        destination.add_byte(b'\n');
+    }
+    Ok(())
+}
+
+/// Returns a key as a variable name, with characters names cannot hold as underscores.
+fn variable_name(key: &str, options: &DotenvOptions) -> String {
+    key.chars()
+        .map(|c| match c {
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                if options.uppercase { c.to_ascii_uppercase() } else { c }
+            }
+            _ => '_',
+        })
+        .collect()
+}
+
+/// Collects the (name, value) lines for the entries of a collection, with comments as
+/// lines without a name.
+fn flatten(node: &Node, prefix: &str, options: &DotenvOptions, lines: &mut Vec<(Option<String>, String)>) {
+    let child = |key: &str| match prefix {
+        "" => variable_name(key, options),
+        _ => format!("{}{}{}", prefix, options.separator, variable_name(key, options)),
+    };
+    let entries: Vec<(String, &Node)> = match node {
+        Node::Dictionary(_) => node.entries().map(|(key, value)| (child(key), value)).collect(),
+        Node::Array(items) => {
+            let mut index = 0;
+            items
+                .iter()
+                .map(|item| {
+                    if item.is_comment() {
+                        return (String::new(), item);
+                    }
+                    index += 1;
+                    (child(&(index - 1).to_string()), item)
+                })
+                .collect()
+        }
+        _ => return,
+    };
+    for (name, value) in entries {
+        let text = match value {
+            Node::Comment(text) => {
+                lines.push((None, text.clone()));
+                continue;
+            }
+            Node::Array(items) if items.iter().any(|item| !item.is_comment()) => {
+                flatten(value, &name, options, lines);
+                continue;
+            }
+            Node::Dictionary(map) if !map.is_empty() => {
+                flatten(value, &name, options, lines);
+                continue;
+            }
+            Node::Str(text) => text.clone(),
+            Node::Number(number) => format_number(number, &EmitterOptions::new()),
+            Node::Boolean(value) => value.to_string(),
+            _ => String::new(),
+        };
+        lines.push((Some(name), text));
+    }
+}
+
+/// Passes a value to out bare, single quoted, or double quoted with escapes, whichever
+/// the value needs.
+fn write_value(text: &str, out: &mut dyn FnMut(&str)) {
+    let bare = |c: char| c.is_ascii_alphanumeric() || "_./:@,+-".contains(c);
+    if text.chars().all(bare) {
+        out(text);
+    } else if !text.contains(|c: char| c == '\'' || c.is_control()) {
+        out("'");
+        out(text);
+        out("'");
+    } else {
+        out("\"");
+        let mut start = 0;
+        for (index, c) in text.char_indices() {
+            let escape = match c {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '$' => "\\$",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\t' => "\\t",
+                _ => continue,
+            };
+            out(&text[start..index]);
+            out(escape);
+            start = index + c.len_utf8();
+        }
+        out(&text[start..]);
+        out("\"");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+
+    fn emit_with(node: &Node, options: &DotenvOptions) -> Result<String, String> {
+        let mut destination = Buffer::new();
+        stringify_with_options(node, &mut destination, options).map(|_| destination.to_string())
+    }
+
+    #[test]
+    fn stringify_dotenv_works() {
+        let node = Node::dict()
+            .key("app-name", "web app")
+            .key("debug", false)
+            .key("__comment_0", Node::Comment("database".to_string()))
+            .key("db", Node::dict().key("url", "postgres://u@h:5432/db").key("password", "it's $ecret\n").build())
+            .key("ports", vec![80, 443])
+            .key("empty", Node::None)
+            .build();
+        let expected = "# database\nAPP_NAME='web app'\nDB_PASSWORD=\"it's \\$ecret\\n\"\n\
+                        DB_URL=postgres://u@h:5432/db\nDEBUG=false\nEMPTY=\nPORTS_0=80\nPORTS_1=443\n";
+        assert_eq!(emit_with(&node, &DotenvOptions::new()).unwrap(), expected);
+        let options = DotenvOptions { uppercase: false, separator: "__".to_string(), export: true };
+        let node = Node::dict().key("db", Node::dict().key("host", "x").build()).build();
+        assert_eq!(emit_with(&node, &options).unwrap(), "export db__host=x\n");
+    }
+
+    #[test]
+    fn stringify_dotenv_rejects_invalid_names() {
+        let options = DotenvOptions::new();
+        let node = Node::dict().key("a-b", 1).key("a_b", 2).build();
+        assert_eq!(emit_with(&node, &options).unwrap_err(), "More than one value would be written as A_B");
+        let node = Node::dict().key("1a", 1).build();
+        assert_eq!(emit_with(&node, &options).unwrap_err(), "'1A' is not a valid environment variable name");
+        assert_eq!(
+            emit_with(&Node::from(1), &options).unwrap_err(),
+            "A .env file can only be written from a dictionary"
+        );
+    }
+}
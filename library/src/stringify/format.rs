@@ -0,0 +1,134 @@
+//! YAML reformatter.
+//! format_str re-lays out YAML text with consistent indentation, a single space after
+//! each ":" and optional line wrapping, keeping its comments, anchors and aliases:
+//! comment lines stay as comments, trailing comments stay at the end of their node's
+//! line, and anchored nodes, aliases and "<<" merge keys are written as they were read.
+//! The text is parsed into a tree with its comment and anchor metadata and written back
+//! with the default emitter, so scalars are requoted only where their meaning needs it, dictionary keys
+//! keep the order they were written in and multi-line strings become block scalars.
+//! Formatting already formatted text leaves it unchanged.
+
+use crate::io::destinations::buffer::Buffer as Destination;
+use crate::io::sources::buffer::Buffer as Source;
+use crate::parser::default::parse_detailed;
+use crate::parser::options::ParserOptions;
+use crate::stringify::default::stringify_with_metadata;
+use crate::stringify::options::{DEFAULT_INDENT, EmitterOptions};
+
+/// Options used to configure the reformatter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Number of spaces each nesting level is indented by (0 is taken as 1)
+    pub indent: usize,
+    /// Preferred maximum line width, or None for no limit; longer scalars are folded and
+    /// flow collections wrapped as the emitter's width option describes
+    pub width: Option<usize>,
+}
+
+impl FormatOptions {
+    /// Creates a new FormatOptions instance with default settings.
+    ///
+    /// # Returns
+    /// FormatOptions for two space indentation with no width limit.
+    pub fn new() -> Self {
+        Self { indent: DEFAULT_INDENT, width: None }
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reformats YAML text, keeping its comments, anchors and aliases.
+///
+/// # Arguments
+/// * `input` - The YAML text to reformat
+/// * `options` - Indentation and width settings
+///
+/// # Returns
+/// The reformatted text, or the error met parsing the input.
+pub fn format_str(input: &str, options: &FormatOptions) -> Result<String, String> {
+    let parsed = parse_detailed(&mut Source::new(input.as_bytes()), &mut ParserOptions::new())
+        .map_err(|error| error.to_string())?;
+    let emitter_options = EmitterOptions {
+        indent: options.indent,
+        width: options.width,
+        trim_trailing_whitespace: true,
+        ..EmitterOptions::new()
+    };
+    let mut destination = Destination::new();
+    stringify_with_metadata(&parsed.node, &parsed.comments, &parsed.anchors, &mut destination, &emitter_options)?;
+    Ok(destination.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::default::parse;
+
+    #[test]
+    fn format_str_works() {
+        let input = "name:    web   # the service\nports: [ 80,443 ]\ntls:   {enabled:  true}\n";
+        let expected = "name: web # the service\nports:\n    -   80\n    -   443\ntls:\n    enabled: true\n";
+        let options = FormatOptions { indent: 4, ..FormatOptions::new() };
+        assert_eq!(format_str(input, &options).unwrap(), expected);
+        let input = "# settings   \nretries:   3\n";
+        assert_eq!(format_str(input, &FormatOptions::new()).unwrap(), "# settings\nretries: 3\n");
+        assert!(format_str("a: [1\n", &FormatOptions::new()).is_err());
+    }
+
+    #[test]
+    fn format_str_keeps_key_order() {
+        let input = "zeta: 1\nalpha:\n  mid: 2\n  beta: 3\n";
+        assert_eq!(format_str(input, &FormatOptions::new()).unwrap(), input);
+    }
+
+    #[test]
+    fn format_str_is_idempotent() {
+        let input = concat!(
+            "# services\nservers:\n- name: web   # front\n  ports: [80, 443]\n  matrix:\n  - - 1\n    - [2, 3]\n",
+            "  - {a: 1, b: [x, y]}\n- name: db\n  script: |\n    echo one\n\n    echo  two\n",
+            "  note: \"first\n    second\"\n  kept: |+\n    k\n\n",
+            "summary: the quick brown fox jumps over the lazy dog and keeps on running\n"
+        );
+        for options in [
+            FormatOptions::new(),
+            FormatOptions { indent: 4, ..FormatOptions::new() },
+            FormatOptions { width: Some(24), ..FormatOptions::new() },
+        ] {
+            let once = format_str(input, &options).unwrap();
+            assert_eq!(format_str(&once, &options).unwrap(), once, "{:?}", options);
+            let tree = |text: &str| parse(&mut Source::new(text.as_bytes())).unwrap();
+            assert_eq!(tree(&once), tree(input), "{}", once);
+        }
+    }
+
+    #[test]
+    fn format_str_keeps_anchors_and_aliases() {
+        let input = concat!(
+            "base: &b [1, 2]\nother: *b\ndefaults: &defaults\n  retries: 3\n  tls: &on true\n",
+            "service:\n  <<: *defaults\n  name: web\n  secure: *on\nboth:\n  <<: [*defaults, *b]\n",
+            "text: &t |\n  one\n  two\ncopy: *t\n"
+        );
+        let expected = concat!(
+            "base: &b\n  - 1\n  - 2\nother: *b\ndefaults: &defaults\n  retries: 3\n  tls: &on true\n",
+            "service:\n  <<: *defaults\n  name: web\n  secure: *on\nboth:\n  <<:\n    - *defaults\n    - *b\n",
+            "text: &t |\n  one\n  two\ncopy: *t\n"
+        );
+        assert_eq!(format_str(input, &FormatOptions::new()).unwrap(), expected);
+        assert_eq!(format_str(expected, &FormatOptions::new()).unwrap(), expected);
+        let input = "---\na: &x 1\nb: *x\n---\n- &x\n  - 2\n- *x\n";
+        assert_eq!(format_str(input, &FormatOptions::new()).unwrap(), input);
+    }
+
+    #[test]
+    fn format_str_keeps_comments_after_block_scalars() {
+        let expected = "---\n|\n  a\n# after\n";
+        assert_eq!(format_str("|\n  a\n# after\n", &FormatOptions::new()).unwrap(), expected);
+        assert_eq!(format_str(expected, &FormatOptions::new()).unwrap(), expected);
+        let input = "script: |\n  run\n# next\nname: web\n";
+        assert_eq!(format_str(input, &FormatOptions::new()).unwrap(), input);
+    }
+}
@@ -0,0 +1,273 @@
+//! HTML emitter.
+//! Renders a Node tree as an HTML fragment for display: dictionaries as two column
+//! <table>s of keys and values (in sorted key order), arrays as <ul> lists and scalars as
+//! <span>s, each carrying a CSS class named after its kind ("yaml-string",
+//! "yaml-dictionary" and so on) so a stylesheet can colour them. With the collapsible
+//! option each collection is wrapped in <details> with a <summary> giving its kind and
+//! size, so the browser can fold it without any script; open_levels limits how many
+//! levels start unfolded. Comments are shown as "# text" in the comment class and each
+//! document of a stream in a <section>. Text is escaped, so any tree can be rendered.
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::Node;
+use crate::stringify::number::format_number;
+use crate::stringify::options::EmitterOptions;
+use crate::stringify::xml::write_escaped;
+
+/// Default prefix of the CSS classes written on elements.
+pub const DEFAULT_HTML_CLASS_PREFIX: &str = "yaml-";
+
+/// Options used to configure the HTML emitter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HtmlOptions {
+    /// Prefix of each CSS class, which is followed by the node kind ("string", "number",
+    /// "boolean", "null", "comment", "array", "dictionary", "document") or "key"
+    pub class_prefix: String,
+    /// Wrap collections in <details> elements the reader can fold
+    pub collapsible: bool,
+    /// Number of nesting levels of collapsible collections that start unfolded; None
+    /// unfolds them all
+    pub open_levels: Option<usize>,
+}
+
+impl HtmlOptions {
+    /// Creates a new HtmlOptions instance with default settings.
+    ///
+    /// # Returns
+    /// HtmlOptions for collapsible collections, all unfolded, with "yaml-" classes.
+    pub fn new() -> Self {
+        Self { class_prefix: DEFAULT_HTML_CLASS_PREFIX.to_string(), collapsible: true, open_levels: None }
+    }
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes a Node tree to a destination as an HTML fragment.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the HTML is written
+///
+/// # Returns
+/// Ok; every tree has an HTML rendering.
+pub fn stringify(node: &Node, destination: &mut dyn IDestination) -> Result<(), String> {
+    stringify_with_options(node, destination, &HtmlOptions::new())
+}
+
+/// Writes a Node tree to a destination as an HTML fragment laid out according to the
+/// supplied options.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the HTML is written
+/// * `options` - Class and folding settings
+///
+/// # Returns
+/// Ok; every tree has an HTML rendering.
+pub fn stringify_with_options(
+    node: &Node,
+    destination: &mut dyn IDestination,
+    options: &HtmlOptions,
+) -> Result<(), String> {
+    let mut emitter = HtmlEmitter { destination, options };
+    match node {
+        Node::Document(documents) => {
+            for document in documents {
+                emitter.start("section", &node.kind().to_string(), 0);
+                emitter.destination.add_byte(b'\n');
+                emitter.block(document, 1, 0);
+                emitter.line("</section>", 0);
+            }
+        }
+        _ => emitter.block(node, 0, 0),
+    }
+    Ok(())
+}
+
+/// HTML writer for a single destination.
+struct HtmlEmitter<'a> {
+    destination: &'a mut dyn IDestination,
+    options: &'a HtmlOptions,
+}
+
+impl HtmlEmitter<'_> {
+    fn indent(&mut self, level: usize) {
+        for _ in 0..level {
+            self.destination.add_bytes("  ");
+        }
+    }
+
+    fn line(&mut self, text: &str, level: usize) {
+        self.indent(level);
+        self.destination.add_bytes(text);
+        self.destination.add_byte(b'\n');
+    }
+
+    fn escaped(&mut self, text: &str) {
+        let destination = &mut *self.destination;
+        write_escaped(text, false, &mut |part| destination.add_bytes(part));
+    }
+
+    /// Writes an indented start tag with a class attribute.
+    fn start(&mut self, tag: &str, class: &str, level: usize) {
+        self.indent(level);
+        self.destination.add_byte(b'<');
+        self.destination.add_bytes(tag);
+        self.destination.add_bytes(" class=\"");
+        let options = self.options;
+        self.escaped(&options.class_prefix);
+        self.destination.add_bytes(class);
+        self.destination.add_bytes("\">");
+    }
+
+    /// Writes a scalar or comment as an inline <span>.
+    fn scalar(&mut self, node: &Node) {
+        let text = match node {
+            Node::Str(text) => text.clone(),
+            Node::Number(number) => format_number(number, &EmitterOptions::new()),
+            Node::Boolean(value) => value.to_string(),
+            Node::Comment(text) => format!("# {}", text),
+            _ => "null".to_string(),
+        };
+        self.start("span", &node.kind().to_string(), 0);
+        self.escaped(&text);
+        self.destination.add_bytes("</span>");
+    }
+
+    /// Writes a node on lines of its own, collections as (possibly folding) blocks; depth
+    /// counts the collections the node is nested in.
+    fn block(&mut self, node: &Node, level: usize, depth: usize) {
+        if !matches!(node, Node::Array(_) | Node::Dictionary(_)) {
+            self.indent(level);
+            self.scalar(node);
+            self.destination.add_byte(b'\n');
+            return;
+        }
+        let kind = node.kind().to_string();
+        let mut inner = level;
+        if self.options.collapsible {
+            self.indent(level);
+            self.destination.add_bytes("<details class=\"");
+            let options = self.options;
+            self.escaped(&options.class_prefix);
+            self.destination.add_bytes(&kind);
+            self.destination.add_byte(b'"');
+            if self.options.open_levels.is_none_or(|open| depth < open) {
+                self.destination.add_bytes(" open");
+            }
+            self.destination.add_bytes(">\n");
+            let count = match node {
+                Node::Array(items) => items.iter().filter(|item| !item.is_comment()).count(),
+                _ => node.entries().filter(|(_, value)| !value.is_comment()).count(),
+            };
+            let noun = match (node, count) {
+                (Node::Array(_), 1) => "item",
+                (Node::Array(_), _) => "items",
+                (_, 1) => "entry",
+                _ => "entries",
+            };
+            self.line(&format!("<summary>{} ({} {})</summary>", kind, count, noun), level + 1);
+            inner = level + 1;
+        }
+        match node {
+            Node::Array(items) => {
+                self.start("ul", &kind, inner);
+                self.destination.add_byte(b'\n');
+                for item in items {
+                    self.start("li", &item.kind().to_string(), inner + 1);
+                    self.cell(item, inner + 1, depth);
+                    self.destination.add_bytes("</li>\n");
+                }
+                self.line("</ul>", inner);
+            }
+            _ => {
+                self.start("table", &kind, inner);
+                self.destination.add_byte(b'\n');
+                for (key, value) in node.entries() {
+                    self.indent(inner + 1);
+                    if value.is_comment() {
+                        self.destination.add_bytes("<tr><td colspan=\"2\">");
+                    } else {
+                        self.destination.add_bytes("<tr>");
+                        self.start("th", "key", 0);
+                        self.escaped(key);
+                        self.destination.add_bytes("</th><td>");
+                    }
+                    self.cell(value, inner + 1, depth);
+                    self.destination.add_bytes("</td></tr>\n");
+                }
+                self.line("</table>", inner);
+            }
+        }
+        if self.options.collapsible {
+            self.line("</details>", level);
+        }
+    }
+
+    /// Writes the contents of a list item or table cell: scalars inline, collections as
+    /// blocks on the lines between the cell's tags.
+    fn cell(&mut self, node: &Node, level: usize, depth: usize) {
+        if matches!(node, Node::Array(_) | Node::Dictionary(_)) {
+            self.destination.add_byte(b'\n');
+            self.block(node, level + 1, depth + 1);
+            self.indent(level);
+        } else {
+            self.scalar(node);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+
+    fn emit_with(node: &Node, options: &HtmlOptions) -> String {
+        let mut destination = Buffer::new();
+        stringify_with_options(node, &mut destination, options).unwrap();
+        destination.to_string()
+    }
+
+    fn service() -> Node {
+        Node::dict()
+            .key("name", "<web> & \"api\"")
+            .key("ports", vec![Node::from(80), Node::Comment("tls".to_string()), Node::from(true)])
+            .key("owner", Node::None)
+            .build()
+    }
+
+    #[test]
+    fn stringify_html_works() {
+        let options = HtmlOptions { collapsible: false, ..HtmlOptions::new() };
+        let expected = "<table class=\"yaml-dictionary\">\n\
+                        \x20 <tr><th class=\"yaml-key\">name</th><td><span class=\"yaml-string\">\
+                        &lt;web&gt; &amp; &quot;api&quot;</span></td></tr>\n\
+                        \x20 <tr><th class=\"yaml-key\">owner</th><td><span class=\"yaml-null\">null</span></td></tr>\n\
+                        \x20 <tr><th class=\"yaml-key\">ports</th><td>\n\
+                        \x20   <ul class=\"yaml-array\">\n\
+                        \x20     <li class=\"yaml-number\"><span class=\"yaml-number\">80</span></li>\n\
+                        \x20     <li class=\"yaml-comment\"><span class=\"yaml-comment\"># tls</span></li>\n\
+                        \x20     <li class=\"yaml-boolean\"><span class=\"yaml-boolean\">true</span></li>\n\
+                        \x20   </ul>\n\
+                        \x20 </td></tr>\n\
+                        </table>\n";
+        assert_eq!(emit_with(&service(), &options), expected);
+    }
+
+    #[test]
+    fn stringify_html_collapsible_works() {
+        let options = HtmlOptions { class_prefix: "y-".to_string(), open_levels: Some(1), ..HtmlOptions::new() };
+        let html = emit_with(&service(), &options);
+        let start = "<details class=\"y-dictionary\" open>\n  <summary>dictionary (3 entries)</summary>\n";
+        assert!(html.starts_with(start));
+        assert!(html.contains("<td>\n      <details class=\"y-array\">\n        <summary>array (2 items)</summary>\n"));
+        assert!(html.ends_with("  </table>\n</details>\n"));
+        let stream = Node::Document(vec![Node::from("a")]);
+        let expected = "<section class=\"y-document\">\n  <span class=\"y-string\">a</span>\n</section>\n";
+        assert_eq!(emit_with(&stream, &options), expected);
+    }
+}
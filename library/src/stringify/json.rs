@@ -0,0 +1,538 @@
+//! JSON emitter.
+//! Writes a Node tree as compact JSON ({"a":[1,2]}) or, with the pretty option, as indented
+//! JSON with one entry per line. Keys keep the dictionary's own order unless sort_keys is
+//! turned on, in which case they are written in sorted order. Strings are escaped
+//! as JSON requires, with control characters as \u00XX.
+//! Comments, NaN and infinite floats and streams of more than one document have no JSON
+//! form: by default they are an error, reported before anything is written, and with
+//! JsonUnsupported::Coerce comments are dropped, NaN and infinities become null and a
+//! stream becomes an array of its documents.
+//! The json5 option writes JSON5 instead: keys that are identifiers are left unquoted,
+//! pretty output puts a comma after every entry, comments are written as "// text" lines
+//! ("/* text */" in compact output) and NaN and infinite floats as NaN and Infinity.
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::{Node, Numeric};
+use crate::nodes::path::join;
+use crate::stringify::loss::{LossKind, LossReport};
+use crate::stringify::number::format_number;
+use crate::stringify::options::EmitterOptions;
+
+/// What the JSON emitter does with values that have no JSON form.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JsonUnsupported {
+    /// Fail with an error naming the path of the first such value
+    #[default]
+    Error,
+    /// Drop comments, write NaN and infinite floats as null and a stream of documents as
+    /// an array
+    Coerce,
+}
+
+/// Default number of spaces each nesting level of pretty JSON is indented by.
+pub const DEFAULT_JSON_INDENT: usize = 2;
+
+/// Options used to configure the JSON emitter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonOptions {
+    /// Write one entry per line with nested entries indented, rather than compact JSON
+    pub pretty: bool,
+    /// Number of spaces each nesting level is indented by in pretty output
+    pub indent: usize,
+    /// Write dictionary keys in sorted order rather than the order the dictionary holds
+    /// them in (insertion order, or as set by Node::sort_keys_by); off by default
+    pub sort_keys: bool,
+    /// What to do with comments, NaN and infinite floats and multi-document streams; with
+    /// json5 only streams are unsupported
+    pub unsupported: JsonUnsupported,
+    /// Write JSON5, with unquoted keys, trailing commas and comments
+    pub json5: bool,
+}
+
+impl JsonOptions {
+    /// Creates a new JsonOptions instance with default settings.
+    ///
+    /// # Returns
+    /// JsonOptions for compact output in dictionary key order that fails on values JSON cannot
+    /// represent.
+    pub fn new() -> Self {
+        Self {
+            pretty: false,
+            indent: DEFAULT_JSON_INDENT,
+            sort_keys: false,
+            unsupported: JsonUnsupported::Error,
+            json5: false,
+        }
+    }
+
+    /// Creates JsonOptions for pretty printed output.
+    ///
+    /// # Returns
+    /// JsonOptions for two space indented output in dictionary key order.
+    pub fn pretty() -> Self {
+        Self { pretty: true, ..Self::new() }
+    }
+
+    /// Creates JsonOptions for pretty printed JSON5, for configuration files people edit.
+    ///
+    /// # Returns
+    /// JsonOptions for two space indented JSON5 in dictionary key order with comments kept.
+    pub fn json5() -> Self {
+        Self { pretty: true, json5: true, ..Self::new() }
+    }
+}
+
+impl Default for JsonOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes a Node tree to a destination as compact JSON.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the JSON text is written
+///
+/// # Returns
+/// Ok, or an error naming the path of a value that has no JSON form.
+pub fn stringify(node: &Node, destination: &mut dyn IDestination) -> Result<(), String> {
+    stringify_with_options(node, destination, &JsonOptions::new())
+}
+
+/// Writes a Node tree to a destination as JSON laid out according to the supplied options.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the JSON text is written
+/// * `options` - Layout and coercion settings
+///
+/// # Returns
+/// Ok, or an error naming the path of a value that has no JSON form; nothing is written
+/// when there is an error.
+pub fn stringify_with_options(
+    node: &Node,
+    destination: &mut dyn IDestination,
+    options: &JsonOptions,
+) -> Result<(), String> {
+    let root = match node {
+        Node::Document(documents) => {
+            let data: Vec<&Node> = documents.iter().filter(|document| !document.is_comment()).collect();
+            if options.unsupported == JsonUnsupported::Error {
+                if data.len() > 1 {
+                    return Err("A stream of more than one document cannot be written as JSON".to_string());
+                }
+                if let Some(index) = documents.iter().position(Node::is_comment)
+                    && !options.json5
+                {
+                    return Err(format!("Comment at /{} cannot be written as JSON", index));
+                }
+            }
+            match data.as_slice() {
+                [document] => document,
+                _ => node,
+            }
+        }
+        _ => node,
+    };
+    if options.unsupported == JsonUnsupported::Error {
+        check(root, "", options.json5)?;
+    }
+    let mut emitter = JsonEmitter { destination, options };
+    // Comments before the single document of a stream become header comments in JSON5
+    if let Node::Document(documents) = node
+        && !std::ptr::eq(root, node)
+        && options.json5
+    {
+        for document in documents.iter().filter(|document| document.is_comment()) {
+            if let Node::Comment(text) = document {
+                emitter.comment(text, 0);
+                emitter.line(0);
+            }
+        }
+    }
+    emitter.node(root, 0);
+    Ok(())
+}
+
+/// Writes a Node tree to a destination as JSON laid out according to the supplied options
+/// and reports what the JSON does not hold exactly.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the JSON text is written
+/// * `options` - Layout and coercion settings
+///
+/// # Returns
+/// A report of the values coerced, dropped or at risk of rounding, or the error
+/// stringify_with_options returns.
+pub fn stringify_with_report(
+    node: &Node,
+    destination: &mut dyn IDestination,
+    options: &JsonOptions,
+) -> Result<LossReport, String> {
+    stringify_with_options(node, destination, options)?;
+    let mut report = LossReport::new();
+    match node {
+        // The single document of a stream is written as the root
+        Node::Document(documents) if documents.iter().filter(|document| !document.is_comment()).count() == 1 => {
+            for (index, document) in documents.iter().enumerate() {
+                if !document.is_comment() {
+                    losses(document, "", options.json5, &mut report);
+                } else if !options.json5 {
+                    report.add(&join("", &index.to_string()), LossKind::CommentDropped);
+                }
+            }
+        }
+        _ => losses(node, "", options.json5, &mut report),
+    }
+    Ok(report)
+}
+
+/// Records the values below a node that JSON (or JSON5, when json5 is set) does not
+/// hold exactly.
+fn losses(node: &Node, path: &str, json5: bool, report: &mut LossReport) {
+    match node {
+        Node::Comment(_) if !json5 => report.add(path, LossKind::CommentDropped),
+        Node::Number(Numeric::Float(value)) if !value.is_finite() && !json5 => {
+            report.add(path, LossKind::NonFiniteFloat)
+        }
+        Node::Number(number) if number.as_wide_integer().is_some_and(|value| value.unsigned_abs() > 1 << 53) => {
+            report.add(path, LossKind::IntegerPrecision)
+        }
+        Node::Array(items) | Node::Document(items) => {
+            if node.is_document() {
+                report.add(path, LossKind::StreamAsArray);
+            }
+            for (index, item) in items.iter().enumerate() {
+                losses(item, &join(path, &index.to_string()), json5, report);
+            }
+        }
+        Node::Dictionary(_) => {
+            for (key, value) in node.entries() {
+                losses(value, &join(path, key), json5, report);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns an error naming the first value below a node that has no JSON form (or JSON5
+/// form, when json5 is set).
+fn check(node: &Node, path: &str, json5: bool) -> Result<(), String> {
+    match node {
+        Node::Comment(_) if !json5 => Err(format!("Comment at {} cannot be written as JSON", display_path(path))),
+        Node::Document(_) => {
+            Err(format!("A stream of documents at {} cannot be written as JSON", display_path(path)))
+        }
+        Node::Number(Numeric::Float(value)) if !value.is_finite() && !json5 => {
+            Err(format!("Float {} at {} cannot be written as JSON", value, display_path(path)))
+        }
+        Node::Array(items) => items
+            .iter()
+            .enumerate()
+            .try_for_each(|(index, item)| check(item, &join(path, &index.to_string()), json5)),
+        Node::Dictionary(_) => node.entries().try_for_each(|(key, value)| check(value, &join(path, key), json5)),
+        _ => Ok(()),
+    }
+}
+
+/// Returns true for keys JSON5 allows without quotes: ASCII identifiers.
+fn is_identifier(key: &str) -> bool {
+    key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() { "/" } else { path }
+}
+
+/// Passes a string written as a JSON string to out, escaping quotes, backslashes and
+/// control characters; runs of characters that need no escape are passed as single slices.
+pub(crate) fn write_json_string(text: &str, out: &mut dyn FnMut(&str)) {
+    out("\"");
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        let escape = match c {
+            '"' => "\\\"",
+            '\\' => "\\\\",
+            '\n' => "\\n",
+            '\r' => "\\r",
+            '\t' => "\\t",
+            '\u{8}' => "\\b",
+            '\u{c}' => "\\f",
+            c if c < ' ' => "",
+            _ => continue,
+        };
+        out(&text[start..index]);
+        if escape.is_empty() {
+            out(&format!("\\u{:04x}", c as u32));
+        } else {
+            out(escape);
+        }
+        start = index + c.len_utf8();
+    }
+    out(&text[start..]);
+    out("\"");
+}
+
+/// JSON writer for a single destination.
+struct JsonEmitter<'a> {
+    destination: &'a mut dyn IDestination,
+    options: &'a JsonOptions,
+}
+
+impl JsonEmitter<'_> {
+    /// Starts a new line indented to a level in pretty output.
+    fn line(&mut self, level: usize) {
+        if self.options.pretty {
+            self.destination.add_byte(b'\n');
+            for _ in 0..level * self.options.indent {
+                self.destination.add_byte(b' ');
+            }
+        }
+    }
+
+    fn string(&mut self, text: &str) {
+        let destination = &mut *self.destination;
+        write_json_string(text, &mut |part| destination.add_bytes(part));
+    }
+
+    /// Writes a comment: "// text" lines in pretty output, "/* text */" in compact output.
+    fn comment(&mut self, text: &str, level: usize) {
+        if self.options.pretty {
+            for (index, line) in text.lines().enumerate() {
+                if index > 0 {
+                    self.line(level);
+                }
+                self.destination.add_bytes("// ");
+                self.destination.add_bytes(line.trim_end());
+            }
+        } else {
+            self.destination.add_bytes("/* ");
+            self.destination.add_bytes(&text.replace("*/", "* /"));
+            self.destination.add_bytes(" */");
+        }
+    }
+
+    /// Writes one entry of a collection (a key and value, an item or a comment) on its own
+    /// line, followed by a comma if more follow or trailing commas are written.
+    fn entry(&mut self, key: Option<&str>, value: &Node, level: usize, more: bool) {
+        self.line(level);
+        if let Node::Comment(text) = value {
+            self.comment(text, level);
+            return;
+        }
+        if let Some(key) = key {
+            if self.options.json5 && is_identifier(key) {
+                self.destination.add_bytes(key);
+            } else {
+                self.string(key);
+            }
+            self.destination.add_bytes(if self.options.pretty { ": " } else { ":" });
+        }
+        self.node(value, level);
+        if more || (self.options.json5 && self.options.pretty) {
+            self.destination.add_byte(b',');
+        }
+    }
+
+    /// Writes the entries of a collection between its brackets, the closing bracket on a
+    /// line at level. Comments are kept only in JSON5.
+    fn collection(&mut self, open: u8, close: u8, entries: Vec<(Option<&str>, &Node)>, level: usize) {
+        let json5 = self.options.json5;
+        let entries: Vec<_> = entries.into_iter().filter(|(_, value)| json5 || !value.is_comment()).collect();
+        let mut remaining = entries.iter().filter(|(_, value)| !value.is_comment()).count();
+        self.destination.add_byte(open);
+        for (key, value) in &entries {
+            if !value.is_comment() {
+                remaining -= 1;
+            }
+            self.entry(*key, value, level + 1, remaining > 0);
+        }
+        if !entries.is_empty() {
+            self.line(level);
+        }
+        self.destination.add_byte(close);
+    }
+
+    /// Writes a node whose first line is already indented and whose closing line is at level.
+    fn node(&mut self, node: &Node, level: usize) {
+        match node {
+            Node::Array(items) | Node::Document(items) => {
+                self.collection(b'[', b']', items.iter().map(|item| (None, item)).collect(), level);
+            }
+            Node::Dictionary(map) => {
+                let mut entries: Vec<(Option<&str>, &Node)> =
+                    map.iter().map(|(key, value)| (Some(key.as_str()), value)).collect();
+                if self.options.sort_keys {
+                    entries.sort_by(|a, b| a.0.cmp(&b.0));
+                }
+                self.collection(b'{', b'}', entries, level);
+            }
+            Node::Str(text) => self.string(text),
+            Node::Boolean(value) => self.destination.add_bytes(if *value { "true" } else { "false" }),
+            Node::Number(Numeric::Float(value)) if !value.is_finite() => self.destination.add_bytes(
+                match (self.options.json5, value.is_nan(), *value > 0.0) {
+                    (false, _, _) => "null",
+                    (true, true, _) => "NaN",
+                    (true, false, true) => "Infinity",
+                    (true, false, false) => "-Infinity",
+                },
+            ),
+            Node::Number(number) => self.destination.add_bytes(&format_number(number, &EmitterOptions::new())),
+            Node::None | Node::Comment(_) => self.destination.add_bytes("null"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+    use crate::stringify::loss::Loss;
+
+    fn emit_with(node: &Node, options: &JsonOptions) -> Result<String, String> {
+        let mut destination = Buffer::new();
+        stringify_with_options(node, &mut destination, options).map(|_| destination.to_string())
+    }
+
+    fn sample() -> Node {
+        Node::dict()
+            .key("name", "web \"primary\"\n")
+            .key("ports", vec![80, 443])
+            .key("tls", Node::dict().key("enabled", true).key("ratio", 0.5).key("none", Node::None))
+            .key("empty", Node::from(Vec::<Node>::new()))
+            .build()
+    }
+
+    #[test]
+    fn stringify_compact_works() {
+        let mut destination = Buffer::new();
+        stringify(&sample(), &mut destination).unwrap();
+        let expected = concat!(
+            r#"{"name":"web \"primary\"\n","ports":[80,443],"#,
+            r#""tls":{"enabled":true,"ratio":0.5,"none":null},"empty":[]}"#
+        );
+        assert_eq!(destination.to_string(), expected);
+        let parsed: serde_json::Value = serde_json::from_str(expected).unwrap();
+        assert_eq!(parsed["name"], "web \"primary\"\n");
+        let control = emit_with(&Node::from("a\u{1}\u{8}\\"), &JsonOptions::new()).unwrap();
+        assert_eq!(control, r#""a\u0001\b\\""#);
+    }
+
+    #[test]
+    fn stringify_with_sort_keys_sorts_dictionaries() {
+        let options = JsonOptions { sort_keys: true, ..JsonOptions::new() };
+        let expected = concat!(
+            r#"{"empty":[],"name":"web \"primary\"\n","ports":[80,443],"#,
+            r#""tls":{"enabled":true,"none":null,"ratio":0.5}}"#
+        );
+        assert_eq!(emit_with(&sample(), &options).unwrap(), expected);
+        let options = JsonOptions::new();
+        let mut node = sample();
+        node.sort_keys_by(|a, b| b.len().cmp(&a.len()));
+        let expected = concat!(
+            r#"{"ports":[80,443],"empty":[],"name":"web \"primary\"\n","#,
+            r#""tls":{"enabled":true,"ratio":0.5,"none":null}}"#
+        );
+        assert_eq!(emit_with(&node, &options).unwrap(), expected);
+    }
+
+    #[test]
+    fn stringify_pretty_works() {
+        let expected = concat!(
+            "{\n",
+            "  \"name\": \"web \\\"primary\\\"\\n\",\n",
+            "  \"ports\": [\n",
+            "    80,\n",
+            "    443\n",
+            "  ],\n",
+            "  \"tls\": {\n",
+            "    \"enabled\": true,\n",
+            "    \"ratio\": 0.5,\n",
+            "    \"none\": null\n",
+            "  },\n",
+            "  \"empty\": []\n",
+            "}",
+        );
+        assert_eq!(emit_with(&sample(), &JsonOptions::pretty()).unwrap(), expected);
+    }
+
+    #[test]
+    fn stringify_unsupported_values_fail_or_coerce() {
+        let node = Node::dict()
+            .key("a", Node::from(vec![Node::Comment("note".to_string()), Node::from(f64::NAN)]))
+            .key("__comment_1", Node::Comment("about a".to_string()))
+            .build();
+        let error = emit_with(&node, &JsonOptions::new()).unwrap_err();
+        assert_eq!(error, "Comment at /__comment_1 cannot be written as JSON");
+        let infinite = Node::from(vec![Node::from(1), Node::from(f64::INFINITY)]);
+        assert_eq!(emit_with(&infinite, &JsonOptions::new()).unwrap_err(), "Float inf at /1 cannot be written as JSON");
+        let coerce = JsonOptions { unsupported: JsonUnsupported::Coerce, ..JsonOptions::new() };
+        assert_eq!(emit_with(&node, &coerce).unwrap(), r#"{"a":[null]}"#);
+        let stream = Node::Document(vec![Node::Comment("header".to_string()), Node::from(1), Node::from("x")]);
+        assert!(emit_with(&stream, &JsonOptions::new()).is_err());
+        assert_eq!(emit_with(&stream, &coerce).unwrap(), r#"[1,"x"]"#);
+        let single = Node::Document(vec![Node::Comment("header".to_string()), Node::from(1)]);
+        assert!(emit_with(&single, &JsonOptions::new()).is_err());
+        assert_eq!(emit_with(&single, &coerce).unwrap(), "1");
+    }
+
+    #[test]
+    fn stringify_with_report_works() {
+        let node = Node::dict()
+            .key("a", Node::from(vec![Node::Comment("note".to_string()), Node::from(f64::NAN)]))
+            .key("id", u64::MAX)
+            .key("b", 1)
+            .build();
+        let coerce = JsonOptions { unsupported: JsonUnsupported::Coerce, ..JsonOptions::new() };
+        let mut destination = Buffer::new();
+        let report = stringify_with_report(&node, &mut destination, &coerce).unwrap();
+        let messages: Vec<String> = report.iter().map(|loss| loss.to_string()).collect();
+        let expected = vec![
+            "Comment at /a/0 was dropped",
+            "Float at /a/1 was written as null",
+            "Integer at /id is too wide for 64-bit float readers",
+        ];
+        assert_eq!(messages, expected);
+        let stream = Node::Document(vec![Node::Comment("header".to_string()), Node::from(1)]);
+        let report = stringify_with_report(&stream, &mut Buffer::new(), &coerce).unwrap();
+        assert_eq!(report.losses, vec![Loss { path: "/0".to_string(), kind: LossKind::CommentDropped }]);
+        assert!(stringify_with_report(&stream, &mut Buffer::new(), &JsonOptions::json5()).unwrap().is_lossless());
+        let stream = Node::Document(vec![Node::from(1), Node::from(2)]);
+        let report = stringify_with_report(&stream, &mut Buffer::new(), &coerce).unwrap();
+        assert_eq!(report.losses, vec![Loss { path: String::new(), kind: LossKind::StreamAsArray }]);
+    }
+
+    #[test]
+    fn stringify_json5_works() {
+        let node = Node::dict()
+            .key("name", "web")
+            .key("__comment_1", Node::Comment("ports to open".to_string()))
+            .key("ports", vec![Node::from(80), Node::Comment("tls".to_string()), Node::from(443)])
+            .key("max-ratio", f64::INFINITY)
+            .key("$ref", Node::None)
+            .build();
+        let stream = Node::Document(vec![Node::Comment("generated".to_string()), node.clone()]);
+        let expected = concat!(
+            "// generated\n",
+            "{\n",
+            "  name: \"web\",\n",
+            "  // ports to open\n",
+            "  ports: [\n",
+            "    80,\n",
+            "    // tls\n",
+            "    443,\n",
+            "  ],\n",
+            "  \"max-ratio\": Infinity,\n",
+            "  $ref: null,\n",
+            "}",
+        );
+        assert_eq!(emit_with(&stream, &JsonOptions::json5()).unwrap(), expected);
+        let compact = JsonOptions { pretty: false, ..JsonOptions::json5() };
+        let expected = r#"{name:"web",/* ports to open */ports:[80,/* tls */443],"max-ratio":Infinity,$ref:null}"#;
+        assert_eq!(emit_with(&node, &compact).unwrap(), expected);
+        assert!(emit_with(&Node::Document(vec![Node::from(1), Node::from(2)]), &compact).is_err());
+    }
+}
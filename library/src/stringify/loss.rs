@@ -0,0 +1,120 @@
+//! Reports of data lost by cross-format emitters.
+//! The JSON, TOML, bencode and XML emitters each have a stringify_with_report that writes
+//! the tree as stringify_with_options does and also returns a LossReport listing, by
+//! node path, every value the format could not carry over exactly: comments left out,
+//! non-finite floats written as null, integers too wide for double based JSON readers,
+//! streams written as arrays, values whose type the output does not record, keys renamed
+//! to fit the format and text altered to be written at all. An empty report means the
+//! output reads back to an equal tree (apart from key order).
+
+use crate::nodes::kind::NodeKind;
+use crate::nodes::node::Node;
+use std::fmt;
+
+/// What was lost writing one value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LossKind {
+    /// A comment was left out
+    CommentDropped,
+    /// A NaN or infinite float was written as null
+    NonFiniteFloat,
+    /// An integer beyond 2^53 was written, which readers holding numbers as doubles
+    /// round
+    IntegerPrecision,
+    /// A stream of documents was written as an array
+    StreamAsArray,
+    /// A value of this kind was written in a form that does not record its type, such as
+    /// the text of an XML element or a bencode integer for a boolean
+    TypeLost(NodeKind),
+    /// A dictionary key was written under this name instead
+    KeyRenamed(String),
+    /// Characters of a string or comment were replaced or spaced out so it could be
+    /// written
+    TextAltered,
+}
+
+/// One value an emitter could not write exactly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Loss {
+    /// Path of the value ("" for the root)
+    pub path: String,
+    /// What was lost
+    pub kind: LossKind,
+}
+
+impl fmt::Display for Loss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let at = if self.path.is_empty() { "/" } else { &self.path };
+        match &self.kind {
+            LossKind::CommentDropped => write!(f, "Comment at {} was dropped", at),
+            LossKind::NonFiniteFloat => write!(f, "Float at {} was written as null", at),
+            LossKind::IntegerPrecision => write!(f, "Integer at {} is too wide for 64-bit float readers", at),
+            LossKind::StreamAsArray => write!(f, "Stream of documents at {} was written as an array", at),
+            LossKind::TypeLost(kind) => write!(f, "Value at {} lost its {} type", at, kind),
+            LossKind::KeyRenamed(name) => write!(f, "Key at {} was renamed to {}", at, name),
+            LossKind::TextAltered => write!(f, "Text at {} was altered", at),
+        }
+    }
+}
+
+/// The values an emitter could not write exactly, in the order it met them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LossReport {
+    /// Each loss, by node path
+    pub losses: Vec<Loss>,
+}
+
+impl LossReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        Self { losses: Vec::new() }
+    }
+
+    /// Records a loss at a path.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the value
+    /// * `kind` - What was lost
+    pub fn add(&mut self, path: &str, kind: LossKind) {
+        self.losses.push(Loss { path: path.to_string(), kind });
+    }
+
+    /// Returns true if nothing was lost.
+    pub fn is_lossless(&self) -> bool {
+        self.losses.is_empty()
+    }
+
+    /// Iterates over the losses in the order they were recorded.
+    pub fn iter(&self) -> impl Iterator<Item = &Loss> {
+        self.losses.iter()
+    }
+
+    /// Records every comment in a subtree that is left out as a whole.
+    ///
+    /// # Arguments
+    /// * `node` - Root of the subtree
+    /// * `path` - Path of the subtree's root
+    pub(crate) fn comments_dropped(&mut self, node: &Node, path: &str) {
+        for (below, node) in node.walk() {
+            if node.is_comment() {
+                self.add(&format!("{}{}", path, below), LossKind::CommentDropped);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loss_report_works() {
+        let mut report = LossReport::new();
+        assert!(report.is_lossless());
+        let node = Node::dict().key("a", vec![Node::Comment("x".to_string())]).build();
+        report.comments_dropped(&node, "/b");
+        report.add("", LossKind::TypeLost(NodeKind::Number));
+        let messages: Vec<String> = report.iter().map(|loss| loss.to_string()).collect();
+        assert_eq!(messages, vec!["Comment at /b/a/0 was dropped", "Value at / lost its number type"]);
+    }
+}
@@ -0,0 +1,68 @@
+//! Minified YAML emitter.
+//! Writes a tree as the most compact YAML that reads back to an equal tree: collections
+//! in flow style with no space after commas, strings plain wherever that keeps their
+//! meaning and double quoted (with escaped line breaks) otherwise, a double quoted key
+//! followed directly by its value, nulls as "~" and comments dropped. A single root is
+//! written on one line without a line break after it, so it can be embedded in an
+//! annotation or environment variable; each document of a stream is written on its own
+//! line after a "---" marker.
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::Node;
+use crate::stringify::default::write_quoted;
+use crate::stringify::number::format_number;
+use crate::stringify::options::{EmitterOptions, QuoteStyle};
+
+/// Writes a Node tree to a destination as minified YAML.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the YAML text is written
+/// * `options` - Number formatting settings
+pub(crate) fn write_minified(node: &Node, destination: &mut dyn IDestination, options: &EmitterOptions) {
+    match node {
+        Node::Document(documents) => {
+            for document in documents.iter().filter(|document| !document.is_comment()) {
+                destination.add_bytes("---\n");
+                write_node(document, destination, options);
+                destination.add_byte(b'\n');
+            }
+        }
+        _ => write_node(node, destination, options),
+    }
+}
+
+fn write_node(node: &Node, destination: &mut dyn IDestination, options: &EmitterOptions) {
+    match node {
+        Node::Array(items) | Node::Document(items) => {
+            destination.add_byte(b'[');
+            for (index, item) in items.iter().filter(|item| !item.is_comment()).enumerate() {
+                if index > 0 {
+                    destination.add_byte(b',');
+                }
+                write_node(item, destination, options);
+            }
+            destination.add_byte(b']');
+        }
+        Node::Dictionary(_) => {
+            destination.add_byte(b'{');
+            for (index, (key, value)) in node.entries().filter(|(_, value)| !value.is_comment()).enumerate() {
+                if index > 0 {
+                    destination.add_byte(b',');
+                }
+                let mut key_text = String::with_capacity(key.len());
+                write_quoted(key, QuoteStyle::Minimal, true, &mut |part| key_text.push_str(part));
+                destination.add_bytes(&key_text);
+                // A plain key needs a space after its ':', a quoted one does not
+                destination.add_bytes(if key_text.starts_with('"') { ":" } else { ": " });
+                write_node(value, destination, options);
+            }
+            destination.add_byte(b'}');
+        }
+        Node::Str(text) => write_quoted(text, QuoteStyle::Minimal, true, &mut |part| destination.add_bytes(part)),
+        Node::Number(number) => destination.add_bytes(&format_number(number, options)),
+        Node::Boolean(value) => destination.add_bytes(if *value { "true" } else { "false" }),
+        Node::None => destination.add_byte(b'~'),
+        Node::Comment(_) => {}
+    }
+}
@@ -0,0 +1,60 @@
+//! Stringify module for writing Node trees back out as text
+//! Each submodule renders a tree to an IDestination in one output format
+
+/// Default YAML emitter
+/// Writes block style YAML for every Node variant
+pub mod default;
+/// Options controlling emitted YAML layout
+pub mod options;
+/// YAML reformatter
+/// Re-lays out YAML text with consistent indentation, keeping its comments
+pub mod format;
+/// Loss reports for cross-format emitters
+/// Lists the values an emitter could not write exactly, by path
+pub mod loss;
+/// JSON emitter
+/// Writes compact or pretty printed JSON
+pub mod json;
+/// XML emitter
+/// Writes a tree as elements and attributes under a single root element
+pub mod xml;
+/// TOML emitter
+/// Writes dictionaries as TOML tables and arrays of tables
+pub mod toml;
+/// Bencode emitter
+/// Writes integers, byte strings, lists and sorted dictionaries as bencode
+pub mod bencode;
+/// Properties and INI emitter
+/// Flattens dictionaries to key=value lines, optionally grouped into INI sections
+pub mod properties;
+/// Dotenv emitter
+/// Flattens dictionaries to KEY=value lines for .env files
+pub mod dotenv;
+/// Property list emitter
+/// Writes Node trees as Apple XML property lists
+pub mod plist;
+/// HTML emitter
+/// Renders Node trees as foldable HTML tables and lists for display
+pub mod html;
+/// Graphviz DOT emitter
+/// Draws Node trees as directed graphs, with repeated collections shared
+pub mod dot;
+/// CSV emitter
+/// Writes arrays of flat dictionaries as rows under a header of their keys
+pub mod csv;
+/// CBOR emitter
+/// Writes Node trees in the Concise Binary Object Representation
+#[cfg(feature = "cbor")]
+pub mod cbor;
+/// MessagePack emitter
+/// Writes Node trees in the compact MessagePack binary format
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+/// Canonical YAML emitter
+/// Writes explicitly tagged, fully quoted flow style YAML
+mod canonical;
+/// Minified YAML emitter
+/// Writes the most compact flow style YAML, without comments
+mod minify;
+/// Number formatting shared by the emitters
+pub(crate) mod number;
@@ -0,0 +1,197 @@
+//! MessagePack emitter.
+//! Writes a Node tree as MessagePack using the smallest encoding for each value: integers
+//! as fixints or the narrowest int/uint family member that holds them, floats as float 64,
+//! strings, arrays and maps with fix, 16 or 32 bit lengths. Dictionary keys are written in
+//! sorted order so equal trees encode identically, and comments are left out. A stream of
+//! documents is an error, as are values too long for a 32 bit length; errors are reported
+//! before anything is written.
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::{Node, Numeric};
+use crate::nodes::path::join;
+
+/// Writes a Node tree to a destination as MessagePack.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the MessagePack bytes are written
+///
+/// # Returns
+/// Ok, or an error naming the path of a value MessagePack cannot represent; nothing is
+/// written when there is an error.
+pub fn stringify(node: &Node, destination: &mut dyn IDestination) -> Result<(), String> {
+    check(node, "")?;
+    write_node(node, destination);
+    Ok(())
+}
+
+/// Returns an error naming the first value below a node that MessagePack cannot represent.
+fn check(node: &Node, path: &str) -> Result<(), String> {
+    let at = if path.is_empty() { "/" } else { path };
+    let too_long = |length: usize| length > u32::MAX as usize;
+    match node {
+        Node::Document(_) => Err(format!("A stream of documents at {} cannot be written as MessagePack", at)),
+        Node::Str(text) if too_long(text.len()) => Err(format!("String at {} is too long for MessagePack", at)),
+        Node::Array(items) => {
+            if too_long(items.len()) {
+                return Err(format!("Array at {} is too long for MessagePack", at));
+            }
+            items.iter().enumerate().try_for_each(|(index, item)| check(item, &join(path, &index.to_string())))
+        }
+        Node::Dictionary(_) => {
+            node.entries().try_for_each(|(key, value)| {
+                if too_long(key.len()) {
+                    return Err(format!("Key at {} is too long for MessagePack", at));
+                }
+                check(value, &join(path, key))
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+fn add(destination: &mut dyn IDestination, bytes: &[u8]) {
+    for &byte in bytes {
+        destination.add_byte(byte);
+    }
+}
+
+/// Writes a type marker followed by a length in the fix, 16 or 32 bit form.
+///
+/// # Arguments
+/// * `length` - The length to write
+/// * `fix` - The fix form marker and the exclusive upper bound of lengths it holds
+/// * `markers` - The 8 (if any), 16 and 32 bit form markers
+fn write_length(length: usize, fix: (u8, usize), markers: (Option<u8>, u8, u8), destination: &mut dyn IDestination) {
+    let (fix_marker, fix_limit) = fix;
+    let (marker8, marker16, marker32) = markers;
+    if length < fix_limit {
+        destination.add_byte(fix_marker | length as u8);
+    } else if let Some(marker8) = marker8.filter(|_| length <= u8::MAX as usize) {
+        add(destination, &[marker8, length as u8]);
+    } else if length <= u16::MAX as usize {
+        destination.add_byte(marker16);
+        add(destination, &(length as u16).to_be_bytes());
+    } else {
+        destination.add_byte(marker32);
+        add(destination, &(length as u32).to_be_bytes());
+    }
+}
+
+fn write_integer(value: i128, destination: &mut dyn IDestination) {
+    match value {
+        0..=0x7f => destination.add_byte(value as u8),
+        -32..=-1 => destination.add_byte(value as i8 as u8),
+        0x80..=0xff => add(destination, &[0xcc, value as u8]),
+        0x100..=0xffff => {
+            destination.add_byte(0xcd);
+            add(destination, &(value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            destination.add_byte(0xce);
+            add(destination, &(value as u32).to_be_bytes());
+        }
+        0x1_0000_0000.. => {
+            destination.add_byte(0xcf);
+            add(destination, &(value as u64).to_be_bytes());
+        }
+        -128..=-33 => add(destination, &[0xd0, value as i8 as u8]),
+        -32768..=-129 => {
+            destination.add_byte(0xd1);
+            add(destination, &(value as i16).to_be_bytes());
+        }
+        -2_147_483_648..=-32769 => {
+            destination.add_byte(0xd2);
+            add(destination, &(value as i32).to_be_bytes());
+        }
+        _ => {
+            destination.add_byte(0xd3);
+            add(destination, &(value as i64).to_be_bytes());
+        }
+    }
+}
+
+fn write_string(text: &str, destination: &mut dyn IDestination) {
+    write_length(text.len(), (0xa0, 32), (Some(0xd9), 0xda, 0xdb), destination);
+    destination.add_bytes(text);
+}
+
+fn write_node(node: &Node, destination: &mut dyn IDestination) {
+    match node {
+        Node::None => destination.add_byte(0xc0),
+        Node::Boolean(value) => destination.add_byte(if *value { 0xc3 } else { 0xc2 }),
+        Node::Number(Numeric::Float(value)) => {
+            destination.add_byte(0xcb);
+            add(destination, &value.to_be_bytes());
+        }
+        Node::Number(number) => write_integer(number.as_wide_integer().unwrap_or_default(), destination),
+        Node::Str(text) => write_string(text, destination),
+        Node::Array(items) => {
+            let items: Vec<&Node> = items.iter().filter(|item| !item.is_comment()).collect();
+            write_length(items.len(), (0x90, 16), (None, 0xdc, 0xdd), destination);
+            for item in items {
+                write_node(item, destination);
+            }
+        }
+        Node::Dictionary(_) => {
+            let entries: Vec<(&str, &Node)> = node.entries().filter(|(_, value)| !value.is_comment()).collect();
+            write_length(entries.len(), (0x80, 16), (None, 0xde, 0xdf), destination);
+            for (key, value) in entries {
+                write_string(key, destination);
+                write_node(value, destination);
+            }
+        }
+        Node::Comment(_) | Node::Document(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+
+    fn emit(node: &Node) -> Result<Vec<u8>, String> {
+        let mut destination = Buffer::new();
+        stringify(node, &mut destination).map(|_| destination.buffer)
+    }
+
+    #[test]
+    fn stringify_msgpack_works() {
+        let node = Node::dict()
+            .key("b", vec![Node::from(true), Node::None, Node::Comment("skipped".to_string())])
+            .key("a", 1.5)
+            .build();
+        let mut expected = vec![0x82, 0xa1, b'a', 0xcb];
+        expected.extend_from_slice(&1.5f64.to_be_bytes());
+        expected.extend_from_slice(&[0xa1, b'b', 0x92, 0xc3, 0xc0]);
+        assert_eq!(emit(&node).unwrap(), expected);
+        let long = "x".repeat(40);
+        assert_eq!(emit(&Node::from(long.as_str())).unwrap()[..2], [0xd9, 40]);
+        assert_eq!(emit(&Node::Array(vec![Node::None; 16])).unwrap()[..3], [0xdc, 0, 16]);
+    }
+
+    #[test]
+    fn stringify_msgpack_uses_smallest_integers() {
+        let cases: [(i64, &[u8]); 8] = [
+            (5, &[0x05]),
+            (-5, &[0xfb]),
+            (200, &[0xcc, 0xc8]),
+            (-100, &[0xd0, 0x9c]),
+            (1000, &[0xcd, 0x03, 0xe8]),
+            (-1000, &[0xd1, 0xfc, 0x18]),
+            (70000, &[0xce, 0x00, 0x01, 0x11, 0x70]),
+            (i64::MIN, &[0xd3, 0x80, 0, 0, 0, 0, 0, 0, 0]),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(emit(&Node::from(value)).unwrap(), expected, "{}", value);
+        }
+        let max = emit(&Node::Number(Numeric::UInteger(u64::MAX))).unwrap();
+        assert_eq!(max, [0xcf, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+    }
+
+    #[test]
+    fn stringify_msgpack_rejects_documents() {
+        let node = Node::Document(vec![Node::from(1)]);
+        assert_eq!(emit(&node).unwrap_err(), "A stream of documents at / cannot be written as MessagePack");
+    }
+}
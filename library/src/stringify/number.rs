@@ -0,0 +1,163 @@
+//! Number formatting for the emitters.
+//! Integers are written in full. Floats are written with the shortest digits that read
+//! back to the same value unless a precision is set, switch to scientific notation
+//! ("1.5e+20") when their decimal exponent reaches a threshold, keep a ".0" on whole
+//! values so they read back as floats, and use the YAML forms ".inf", "-.inf" and ".nan".
+//! With the fast-numbers feature integers and shortest floats are formatted by itoa and
+//! ryu, which give the same text as the standard formatting at a fraction of the cost.
+
+use crate::nodes::node::Numeric;
+use crate::stringify::options::EmitterOptions;
+
+/// Returns a number as the emitters write it.
+///
+/// # Arguments
+/// * `number` - The number to format
+/// * `options` - Float precision, scientific notation and whole float settings
+pub(crate) fn format_number(number: &Numeric, options: &EmitterOptions) -> String {
+    match number {
+        Numeric::Float(value) => format_float(*value, options),
+        _ => format_integer(number),
+    }
+}
+
+#[cfg(feature = "fast-numbers")]
+fn format_integer(number: &Numeric) -> String {
+    match number.as_wide_integer() {
+        Some(value) => itoa::Buffer::new().format(value).to_string(),
+        None => number.to_string(),
+    }
+}
+
+#[cfg(not(feature = "fast-numbers"))]
+fn format_integer(number: &Numeric) -> String {
+    number.to_string()
+}
+
+/// Returns the fewest digits that read back as a finite float, written in full ("0.25",
+/// "100000000000000000000", "2").
+#[cfg(feature = "fast-numbers")]
+fn shortest_plain(value: f64) -> String {
+    let mut buffer = ryu::Buffer::new();
+    let text = buffer.format_finite(value);
+    if text.contains('e') {
+        // ryu switches to scientific notation for very large and very small values
+        return value.to_string();
+    }
+    text.strip_suffix(".0").unwrap_or(text).to_string()
+}
+
+#[cfg(not(feature = "fast-numbers"))]
+fn shortest_plain(value: f64) -> String {
+    value.to_string()
+}
+
+/// Returns the fewest digits that read back as a finite float, in scientific notation
+/// ("2.5e-7", "1e20").
+#[cfg(feature = "fast-numbers")]
+fn shortest_scientific(value: f64) -> String {
+    let mut buffer = ryu::Buffer::new();
+    let text = buffer.format_finite(value);
+    if text.contains('e') { text.to_string() } else { format!("{:e}", value) }
+}
+
+#[cfg(not(feature = "fast-numbers"))]
+fn shortest_scientific(value: f64) -> String {
+    format!("{:e}", value)
+}
+
+/// Returns a float as the emitters write it.
+fn format_float(value: f64, options: &EmitterOptions) -> String {
+    if value.is_nan() {
+        return ".nan".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { ".inf" } else { "-.inf" }.to_string();
+    }
+    let exponent = if value == 0.0 { 0 } else { value.abs().log10().floor() as i32 };
+    let scientific = options.float_exponent_threshold.is_some_and(|threshold| exponent.abs() >= threshold);
+    let mut text = match (scientific, options.float_precision) {
+        (true, Some(precision)) => format!("{:.*e}", precision, value),
+        (true, None) => shortest_scientific(value),
+        (false, Some(precision)) => format!("{:.*}", precision, value),
+        (false, None) => shortest_plain(value),
+    };
+    let (mantissa, exponent) = match text.split_once('e') {
+        Some((mantissa, exponent)) => (mantissa.to_string(), Some(exponent.to_string())),
+        None => (text.clone(), None),
+    };
+    if options.whole_float_suffix && !mantissa.contains('.') {
+        text = format!("{}.0", mantissa);
+    } else {
+        text = mantissa;
+    }
+    if let Some(exponent) = exponent {
+        // YAML 1.1 readers need the exponent's sign
+        let sign = if exponent.starts_with('-') { "" } else { "+" };
+        text = format!("{}e{}{}", text, sign, exponent);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn float(value: f64, options: &EmitterOptions) -> String {
+        format_number(&Numeric::Float(value), options)
+    }
+
+    #[test]
+    fn default_float_formatting_is_lossless() {
+        let options = EmitterOptions::new();
+        assert_eq!(float(2.0, &options), "2.0");
+        assert_eq!(float(0.1, &options), "0.1");
+        assert_eq!(float(-1.5, &options), "-1.5");
+        assert_eq!(float(f64::INFINITY, &options), ".inf");
+        assert_eq!(float(f64::NEG_INFINITY, &options), "-.inf");
+        assert_eq!(float(f64::NAN, &options), ".nan");
+        for value in [0.1 + 0.2, 1e-7, 123456.789, f64::MAX, f64::MIN_POSITIVE] {
+            assert_eq!(float(value, &options).parse::<f64>().unwrap(), value);
+        }
+        assert_eq!(format_number(&Numeric::Integer(-42), &options), "-42");
+    }
+
+    #[test]
+    fn float_options_work() {
+        let mut options = EmitterOptions { float_precision: Some(2), ..EmitterOptions::new() };
+        assert_eq!(float(1.23456, &options), "1.23");
+        assert_eq!(float(2.0, &options), "2.00");
+        options.float_precision = Some(0);
+        assert_eq!(float(2.4, &options), "2.0");
+        options.whole_float_suffix = false;
+        assert_eq!(float(2.4, &options), "2");
+        options.float_precision = None;
+        options.float_exponent_threshold = Some(6);
+        assert_eq!(float(1.5e20, &options), "1.5e+20");
+        assert_eq!(float(2.5e-7, &options), "2.5e-7");
+        assert_eq!(float(123.0, &options), "123");
+        options.whole_float_suffix = true;
+        assert_eq!(float(1e20, &options), "1.0e+20");
+        options.float_precision = Some(3);
+        assert_eq!(float(-1.23456e-9, &options), "-1.235e-9");
+        for value in [1e20, -2.5e-7, 6.02214076e23] {
+            options.float_precision = None;
+            assert_eq!(float(value, &options).parse::<f64>().unwrap(), value);
+        }
+    }
+
+    #[cfg(feature = "fast-numbers")]
+    #[test]
+    fn fast_formatting_matches_standard_formatting() {
+        let values = [0.0, -0.0, 2.0, 0.1, 0.1 + 0.2, 1e-5, 1e-7, 123456.789, 1e15, 1e16, 1e17, 1.5e20, -2.5e-7];
+        for value in values.into_iter().chain([f64::MAX, f64::MIN_POSITIVE, f64::EPSILON]) {
+            assert_eq!(shortest_plain(value), value.to_string());
+            assert_eq!(shortest_scientific(value), format!("{:e}", value));
+        }
+        for value in [0i64, -1, 42, i64::MIN, i64::MAX] {
+            assert_eq!(format_integer(&Numeric::Integer(value)), value.to_string());
+        }
+        assert_eq!(format_integer(&Numeric::UInteger(u64::MAX)), u64::MAX.to_string());
+        assert_eq!(format_integer(&Numeric::Int8(-8)), "-8");
+    }
+}
@@ -0,0 +1,208 @@
+//! Options controlling the layout of emitted YAML.
+//! Lets callers match the indentation and collection style conventions of the files
+//! they are writing into.
+
+/// The style collections are written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CollectionStyle {
+    /// Indented "key: value" and "- item" lines
+    #[default]
+    Block,
+    /// Inline [a, b] and {key: value} collections, wrapped across lines where they do not
+    /// fit the width limit; collections holding comments are still written in block style
+    Flow,
+}
+
+/// The style strings are quoted in. A style that cannot represent a string (plain or single
+/// quoted text with control characters, or plain text starting with an indicator) falls
+/// back to double quotes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum QuoteStyle {
+    /// Plain unless the string would read back differently, then double quoted
+    #[default]
+    Minimal,
+    /// Always double quoted
+    Double,
+    /// Always single quoted
+    Single,
+    /// Plain whenever the text allows it, even if it then reads back as another type
+    /// ("true" or "42")
+    Plain,
+}
+
+/// How null values are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NullStyle {
+    /// "key: null"
+    #[default]
+    Null,
+    /// "key: ~"
+    Tilde,
+    /// "key:" with nothing after it; nulls inside flow collections are still written as
+    /// "null"
+    Empty,
+}
+
+/// The words booleans are written as.
+///
+/// Only true/false are booleans in YAML 1.2; yes/no and on/off are YAML 1.1 forms that
+/// YAML 1.2 readers, this crate's parser included, read back as strings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BoolStyle {
+    /// true and false
+    #[default]
+    TrueFalse,
+    /// yes and no (YAML 1.1)
+    YesNo,
+    /// on and off (YAML 1.1)
+    OnOff,
+}
+
+/// The case boolean and null keywords are written in.
+///
+/// YAML 1.2 accepts True/TRUE and Null/NULL alongside the lower case forms, but some
+/// readers, this crate's parser included, only recognise lower case.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeywordCase {
+    /// true, null
+    #[default]
+    Lower,
+    /// True, Null
+    Title,
+    /// TRUE, NULL
+    Upper,
+}
+
+/// Default number of spaces each nesting level is indented by.
+pub const DEFAULT_INDENT: usize = 2;
+
+/// Default maximum length of a collection written in flow style by auto flow.
+pub const DEFAULT_AUTO_FLOW_CHARS: usize = 60;
+
+/// Options used to configure the YAML emitter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmitterOptions {
+    /// Number of spaces each nesting level is indented by (0 is taken as 1, since nested
+    /// collections must be indented to be read back, and array items are always indented
+    /// by at least two so the "- " indicator fits)
+    pub indent: usize,
+    /// Preferred style for collections
+    pub style: CollectionStyle,
+    /// Write a "---" marker before the root even when it is not a Document
+    pub explicit_start: bool,
+    /// Preferred maximum line width, or None for no limit. Longer scalars are folded at
+    /// spaces and flow collections wrapped, in ways the parser reads back to the same
+    /// values
+    pub width: Option<usize>,
+    /// In block style, write collections of at most this many scalars in flow style
+    /// ([1, 2, 3] and {x: 1}); 0 disables auto flow
+    pub auto_flow_items: usize,
+    /// Maximum length of the flow text of a collection written in flow style by auto flow
+    pub auto_flow_chars: usize,
+    /// Quoting style for dictionary keys and string values
+    pub quote_style: QuoteStyle,
+    /// Quoting styles for string values at paths matching a glob pattern (see
+    /// nodes::path::matches_glob), overriding quote_style; the first match is used
+    pub quote_overrides: Vec<(String, QuoteStyle)>,
+    /// Write multi-line strings as literal block scalars ("|") rather than quoted strings
+    /// with escaped line breaks, where their quoting style allows; the parser reads them
+    /// back unchanged
+    pub literal_block_scalars: bool,
+    /// Write dictionary keys in sorted order. Off by default, when keys are written in the
+    /// order the dictionary holds them: insertion order (source order for parsed YAML), or
+    /// the order set by Node::sort_keys_by
+    pub sort_keys: bool,
+    /// Write a collection that appears more than once in a document in full only the first
+    /// time, with an anchor, and as an alias to it everywhere else
+    pub anchor_repeats: bool,
+    /// Write canonical YAML (explicit "---" markers and tags, double quoted scalars and
+    /// flow collections with one entry per line) for byte comparable output; the other
+    /// layout options are then ignored
+    pub canonical: bool,
+    /// Write minified YAML (flow style without spaces or comments, minimal quoting and a
+    /// single root on one line) for embedding where space is short; number formatting
+    /// options still apply but the other layout options are ignored
+    pub minify: bool,
+    /// How null values are written
+    pub null_style: NullStyle,
+    /// The words booleans are written as
+    pub bool_style: BoolStyle,
+    /// The case of boolean and null keywords
+    pub keyword_case: KeywordCase,
+    /// Digits written after the decimal point of floats, or None for the fewest digits
+    /// that read back to the same value
+    pub float_precision: Option<usize>,
+    /// Write floats in scientific notation ("1.5e+20") when their decimal exponent is at
+    /// least this far from zero, or None to always write them in full
+    pub float_exponent_threshold: Option<i32>,
+    /// Write whole floats with a ".0" so they read back as floats rather than integers
+    pub whole_float_suffix: bool,
+    /// Never end a line in spaces or tabs: trailing whitespace is dropped from comments and
+    /// strings with whitespace at the end of a line are double quoted rather than written
+    /// as literal block scalars
+    pub trim_trailing_whitespace: bool,
+    /// Comment written at the very top of the output, one "# " line per line of text, such
+    /// as a generated-file notice; written in canonical and minified output too
+    pub banner: Option<String>,
+}
+
+impl EmitterOptions {
+    /// Creates a new EmitterOptions instance with default settings.
+    ///
+    /// # Returns
+    /// EmitterOptions for two space indented block style with no width limit, auto flow
+    /// disabled and strings quoted only where needed.
+    pub fn new() -> Self {
+        Self {
+            indent: DEFAULT_INDENT,
+            style: CollectionStyle::Block,
+            explicit_start: false,
+            width: None,
+            auto_flow_items: 0,
+            auto_flow_chars: DEFAULT_AUTO_FLOW_CHARS,
+            quote_style: QuoteStyle::Minimal,
+            quote_overrides: Vec::new(),
+            literal_block_scalars: true,
+            sort_keys: false,
+            anchor_repeats: false,
+            canonical: false,
+            minify: false,
+            null_style: NullStyle::Null,
+            bool_style: BoolStyle::TrueFalse,
+            keyword_case: KeywordCase::Lower,
+            float_precision: None,
+            float_exponent_threshold: None,
+            whole_float_suffix: true,
+            trim_trailing_whitespace: false,
+            banner: None,
+        }
+    }
+
+    /// Creates EmitterOptions for reproducible output.
+    ///
+    /// Equal trees are written byte for byte the same on every run and platform: keys are
+    /// sorted, lines end in "\n" with no trailing whitespace and floats are written with
+    /// the fewest digits that read back to the same value, which does not depend on the
+    /// platform or on the fast-numbers feature. Other layout options can still be changed
+    /// on the returned value.
+    ///
+    /// # Returns
+    /// EmitterOptions with the default layout plus sorted keys and trimmed lines.
+    pub fn reproducible() -> Self {
+        Self { sort_keys: true, float_precision: None, trim_trailing_whitespace: true, ..Self::new() }
+    }
+
+    /// Creates EmitterOptions for minified output.
+    ///
+    /// # Returns
+    /// EmitterOptions writing the most compact YAML that reads back to an equal tree.
+    pub fn minified() -> Self {
+        Self { minify: true, ..Self::new() }
+    }
+}
+
+impl Default for EmitterOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
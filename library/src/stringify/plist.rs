@@ -0,0 +1,232 @@
+//! Property list emitter.
+//! Writes a Node tree as an Apple XML property list: dictionaries as <dict> with their
+//! keys in sorted order, arrays as <array>, strings as <string>, integers as <integer>,
+//! floats as <real> (with nan, +infinity and -infinity for the special values) and
+//! booleans as <true/> and <false/>, laid out with tabs as Apple's tools write them.
+//! With the dates option, strings in the plist date form (2024-01-31T12:00:00Z) are
+//! written as <date>. Comments become XML comments. Property lists have no null and hold
+//! a single root, so nulls and streams of documents are errors, reported before anything
+//! is written.
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::{Node, Numeric};
+use crate::nodes::path::join;
+use crate::stringify::number::format_number;
+use crate::stringify::options::EmitterOptions;
+use crate::stringify::xml::{comment_text, write_escaped};
+
+/// The XML declaration and document type every property list starts with.
+const PLIST_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE plist PUBLIC \
+                            \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n";
+
+/// Options used to configure the property list emitter.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlistOptions {
+    /// Write strings in the plist date form, such as 2024-01-31T12:00:00Z, as <date>
+    /// rather than <string>
+    pub dates: bool,
+}
+
+impl PlistOptions {
+    /// Creates a new PlistOptions instance with default settings.
+    ///
+    /// # Returns
+    /// PlistOptions writing every string as <string>.
+    pub fn new() -> Self {
+        Self { dates: false }
+    }
+}
+
+/// Writes a Node tree to a destination as an XML property list.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the property list is written
+///
+/// # Returns
+/// Ok, or an error naming the path of a value a property list cannot represent.
+pub fn stringify(node: &Node, destination: &mut dyn IDestination) -> Result<(), String> {
+    stringify_with_options(node, destination, &PlistOptions::new())
+}
+
+/// Writes a Node tree to a destination as an XML property list according to the supplied
+/// options.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the property list is written
+/// * `options` - Date settings
+///
+/// # Returns
+/// Ok, or an error naming the path of a value a property list cannot represent; nothing
+/// is written when there is an error.
+pub fn stringify_with_options(
+    node: &Node,
+    destination: &mut dyn IDestination,
+    options: &PlistOptions,
+) -> Result<(), String> {
+    check(node, "")?;
+    destination.add_bytes(PLIST_HEADER);
+    destination.add_bytes("<plist version=\"1.0\">\n");
+    let mut emitter = PlistEmitter { destination, options };
+    emitter.node(node, 0);
+    emitter.destination.add_bytes("</plist>\n");
+    Ok(())
+}
+
+/// Returns an error naming the first value below a node that a property list cannot
+/// represent.
+fn check(node: &Node, path: &str) -> Result<(), String> {
+    let at = if path.is_empty() { "/" } else { path };
+    match node {
+        Node::None => Err(format!("Null at {} cannot be written as a property list", at)),
+        Node::Document(_) => Err(format!("A stream of documents at {} cannot be written as a property list", at)),
+        Node::Array(items) => {
+            items.iter().enumerate().try_for_each(|(index, item)| check(item, &join(path, &index.to_string())))
+        }
+        Node::Dictionary(_) => node.entries().try_for_each(|(key, value)| check(value, &join(path, key))),
+        _ => Ok(()),
+    }
+}
+
+/// Returns true for text in the form of a plist date, YYYY-MM-DDTHH:MM:SSZ.
+fn is_date(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() == 20
+        && bytes.iter().enumerate().all(|(index, &byte)| match index {
+            4 | 7 => byte == b'-',
+            10 => byte == b'T',
+            13 | 16 => byte == b':',
+            19 => byte == b'Z',
+            _ => byte.is_ascii_digit(),
+        })
+}
+
+/// Property list writer for a single destination.
+struct PlistEmitter<'a> {
+    destination: &'a mut dyn IDestination,
+    options: &'a PlistOptions,
+}
+
+impl PlistEmitter<'_> {
+    fn indent(&mut self, level: usize) {
+        for _ in 0..level {
+            self.destination.add_byte(b'\t');
+        }
+    }
+
+    /// Writes an element holding escaped text on its own line.
+    fn element(&mut self, name: &str, text: &str, level: usize) {
+        self.indent(level);
+        self.destination.add_byte(b'<');
+        self.destination.add_bytes(name);
+        self.destination.add_byte(b'>');
+        let destination = &mut *self.destination;
+        write_escaped(text, false, &mut |part| destination.add_bytes(part));
+        self.destination.add_bytes("</");
+        self.destination.add_bytes(name);
+        self.destination.add_bytes(">\n");
+    }
+
+    /// Writes a node on its own line(s) at a nesting level.
+    fn node(&mut self, node: &Node, level: usize) {
+        match node {
+            Node::Comment(text) => {
+                self.indent(level);
+                self.destination.add_bytes("<!-- ");
+                self.destination.add_bytes(&comment_text(text));
+                self.destination.add_bytes(" -->\n");
+            }
+            Node::Str(text) if self.options.dates && is_date(text) => self.element("date", text, level),
+            Node::Str(text) => self.element("string", text, level),
+            Node::Boolean(value) => {
+                self.indent(level);
+                self.destination.add_bytes(if *value { "<true/>\n" } else { "<false/>\n" });
+            }
+            Node::Number(Numeric::Float(value)) => {
+                let text = match *value {
+                    value if value.is_nan() => "nan".to_string(),
+                    f64::INFINITY => "+infinity".to_string(),
+                    f64::NEG_INFINITY => "-infinity".to_string(),
+                    _ => format_number(&Numeric::Float(*value), &EmitterOptions::new()),
+                };
+                self.element("real", &text, level);
+            }
+            Node::Number(number) => self.element("integer", &format_number(number, &EmitterOptions::new()), level),
+            Node::Array(items) => self.collection("array", items.is_empty(), level, |emitter| {
+                for item in items {
+                    emitter.node(item, level + 1);
+                }
+            }),
+            Node::Dictionary(map) => self.collection("dict", map.is_empty(), level, |emitter| {
+                for (key, value) in node.entries() {
+                    if !value.is_comment() {
+                        emitter.element("key", key, level + 1);
+                    }
+                    emitter.node(value, level + 1);
+                }
+            }),
+            Node::None | Node::Document(_) => {}
+        }
+    }
+
+    /// Writes an <array> or <dict> element, or its empty form, around its contents.
+    fn collection(&mut self, name: &str, empty: bool, level: usize, contents: impl FnOnce(&mut Self)) {
+        self.indent(level);
+        self.destination.add_byte(b'<');
+        self.destination.add_bytes(name);
+        if empty {
+            self.destination.add_bytes("/>\n");
+            return;
+        }
+        self.destination.add_bytes(">\n");
+        contents(self);
+        self.indent(level);
+        self.destination.add_bytes("</");
+        self.destination.add_bytes(name);
+        self.destination.add_bytes(">\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+
+    fn emit_with(node: &Node, options: &PlistOptions) -> Result<String, String> {
+        let mut destination = Buffer::new();
+        stringify_with_options(node, &mut destination, options).map(|_| destination.to_string())
+    }
+
+    #[test]
+    fn stringify_plist_works() {
+        let node = Node::dict()
+            .key("Label", "com.example.<agent>")
+            .key("RunAtLoad", true)
+            .key("__comment_0", Node::Comment("timing -- seconds".to_string()))
+            .key("StartInterval", 300)
+            .key("Ratio", 0.5)
+            .key("Arguments", vec![Node::from("run"), Node::from(f64::NEG_INFINITY)])
+            .key("Empty", Node::dict().build())
+            .key("Since", "2024-01-31T12:00:00Z")
+            .build();
+        let expected = format!(
+            "{}<plist version=\"1.0\">\n<dict>\n\t<key>Arguments</key>\n\t<array>\n\t\t<string>run</string>\n\
+             \t\t<real>-infinity</real>\n\t</array>\n\t<key>Empty</key>\n\t<dict/>\n\t<key>Label</key>\n\
+             \t<string>com.example.&lt;agent&gt;</string>\n\t<key>Ratio</key>\n\t<real>0.5</real>\n\
+             \t<key>RunAtLoad</key>\n\t<true/>\n\t<key>Since</key>\n\t<string>2024-01-31T12:00:00Z</string>\n\
+             \t<key>StartInterval</key>\n\t<integer>300</integer>\n\t<!-- timing - - seconds -->\n</dict>\n</plist>\n",
+            PLIST_HEADER
+        );
+        assert_eq!(emit_with(&node, &PlistOptions::new()).unwrap(), expected);
+        let dated = emit_with(&node, &PlistOptions { dates: true }).unwrap();
+        assert!(dated.contains("\t<key>Since</key>\n\t<date>2024-01-31T12:00:00Z</date>\n"));
+    }
+
+    #[test]
+    fn stringify_plist_rejects_nulls() {
+        let node = Node::dict().key("a", vec![Node::None]).build();
+        let error = emit_with(&node, &PlistOptions::new()).unwrap_err();
+        assert_eq!(error, "Null at /a/0 cannot be written as a property list");
+    }
+}
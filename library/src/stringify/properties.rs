@@ -0,0 +1,303 @@
+//! Properties and INI emitter.
+//! Flattens a Dictionary node into "key=value" lines, joining the keys of nested
+//! dictionaries and the indexes of arrays with a separator ("server.ports.0=80"), in
+//! sorted key order. In Java properties style every value is a line of its own; in INI
+//! style top-level scalars come first and each top-level dictionary becomes a [section]
+//! holding its flattened entries. Nulls, empty arrays and empty dictionaries are written
+//! as empty values, and comments as "# text" (properties) or "; text" (INI) lines.
+//! With escaping on, properties are escaped as java.util.Properties reads them (with
+//! non-ASCII characters as \uXXXX) and INI values have backslashes, tabs and line breaks
+//! escaped; with it off, keys and values are written as they are and values holding line
+//! breaks are errors. A root that is not a dictionary is an error, reported before
+//! anything is written.
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::Node;
+use crate::nodes::path::join;
+use crate::stringify::number::format_number;
+use crate::stringify::options::EmitterOptions;
+
+/// The file format written by the properties emitter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PropertiesStyle {
+    /// Java properties: one flattened key=value line per value
+    #[default]
+    Properties,
+    /// INI: top-level dictionaries become [section]s of flattened key=value lines
+    Ini,
+}
+
+/// Default separator joining the keys of nested values.
+pub const DEFAULT_KEY_SEPARATOR: &str = ".";
+
+/// Default text written between a key and its value.
+pub const DEFAULT_ASSIGNMENT: &str = "=";
+
+/// Options used to configure the properties emitter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropertiesOptions {
+    /// Java properties or INI output
+    pub style: PropertiesStyle,
+    /// Joins the keys of nested dictionaries and the indexes of arrays
+    pub key_separator: String,
+    /// Written between a key and its value, such as "=", " = " or ": "
+    pub assignment: String,
+    /// Escape keys and values so they read back unchanged
+    pub escape: bool,
+}
+
+impl PropertiesOptions {
+    /// Creates a new PropertiesOptions instance with default settings.
+    ///
+    /// # Returns
+    /// PropertiesOptions for escaped Java properties with "." separated keys.
+    pub fn new() -> Self {
+        Self {
+            style: PropertiesStyle::Properties,
+            key_separator: DEFAULT_KEY_SEPARATOR.to_string(),
+            assignment: DEFAULT_ASSIGNMENT.to_string(),
+            escape: true,
+        }
+    }
+
+    /// Creates PropertiesOptions for INI files.
+    ///
+    /// # Returns
+    /// PropertiesOptions for escaped INI sections with "." separated keys.
+    pub fn ini() -> Self {
+        Self { style: PropertiesStyle::Ini, ..Self::new() }
+    }
+}
+
+impl Default for PropertiesOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes a Dictionary node to a destination as Java properties.
+///
+/// # Arguments
+/// * `node` - The Dictionary to write
+/// * `destination` - Where the properties text is written
+///
+/// # Returns
+/// Ok, or an error if the root is not a dictionary.
+pub fn stringify(node: &Node, destination: &mut dyn IDestination) -> Result<(), String> {
+    stringify_with_options(node, destination, &PropertiesOptions::new())
+}
+
+/// Writes a Dictionary node to a destination as properties or INI laid out according to
+/// the supplied options.
+///
+/// # Arguments
+/// * `node` - The Dictionary to write
+/// * `destination` - Where the text is written
+/// * `options` - Style, separator and escaping settings
+///
+/// # Returns
+/// Ok, or an error if the root is not a dictionary or, with escaping off, naming the path
+/// of a value holding a line break; nothing is written when there is an error.
+pub fn stringify_with_options(
+    node: &Node,
+    destination: &mut dyn IDestination,
+    options: &PropertiesOptions,
+) -> Result<(), String> {
+    if !matches!(node, Node::Dictionary(_)) {
+        return Err("Properties can only be written from a dictionary".to_string());
+    }
+    if !options.escape {
+        check(node, "")?;
+    }
+    let mut emitter = PropertiesEmitter { destination, options };
+    match options.style {
+        PropertiesStyle::Properties => emitter.flatten(node, ""),
+        PropertiesStyle::Ini => {
+            let is_section = |value: &Node| matches!(value, Node::Dictionary(map) if !map.is_empty());
+            for (key, value) in node.entries().filter(|(_, value)| !is_section(value)) {
+                emitter.entry(key, value);
+            }
+            let mut started = node.entries().any(|(_, value)| !is_section(value));
+            for (key, value) in node.entries().filter(|(_, value)| is_section(value)) {
+                if started {
+                    emitter.destination.add_byte(b'\n');
+                }
+                emitter.destination.add_byte(b'[');
+                emitter.text(key, true);
+                emitter.destination.add_bytes("]\n");
+                emitter.flatten(value, "");
+                started = true;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns an error naming the first value below a node that holds a line break.
+fn check(node: &Node, path: &str) -> Result<(), String> {
+    match node {
+        Node::Str(text) if text.contains(['\n', '\r']) => {
+            Err(format!("Value at {} holds a line break, which needs escaping", path))
+        }
+        Node::Array(items) => {
+            items.iter().enumerate().try_for_each(|(index, item)| check(item, &join(path, &index.to_string())))
+        }
+        Node::Dictionary(_) => node.entries().try_for_each(|(key, value)| check(value, &join(path, key))),
+        _ => Ok(()),
+    }
+}
+
+/// Properties and INI writer for a single destination.
+struct PropertiesEmitter<'a> {
+    destination: &'a mut dyn IDestination,
+    options: &'a PropertiesOptions,
+}
+
+impl PropertiesEmitter<'_> {
+    /// Writes the entries of a dictionary or array as flattened lines under a key prefix.
+    fn flatten(&mut self, node: &Node, prefix: &str) {
+        let child = |key: &str| match prefix {
+            "" => key.to_string(),
+            _ => format!("{}{}{}", prefix, self.options.key_separator, key),
+        };
+        match node {
+            Node::Dictionary(_) => {
+                for (key, value) in node.entries() {
+                    self.entry(&child(key), value);
+                }
+            }
+            Node::Array(items) => {
+                let mut index = 0;
+                for item in items {
+                    if item.is_comment() {
+                        self.entry("", item);
+                    } else {
+                        self.entry(&child(&index.to_string()), item);
+                        index += 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Writes a comment line, a key=value line, or the flattened entries of a collection.
+    fn entry(&mut self, key: &str, value: &Node) {
+        let scalar = match value {
+            Node::Comment(text) => {
+                self.destination.add_bytes(if self.options.style == PropertiesStyle::Ini { "; " } else { "# " });
+                self.destination.add_bytes(text);
+                self.destination.add_byte(b'\n');
+                return;
+            }
+            Node::Array(items) if items.iter().any(|item| !item.is_comment()) => return self.flatten(value, key),
+            Node::Dictionary(map) if !map.is_empty() => return self.flatten(value, key),
+            Node::Str(text) => text.clone(),
+            Node::Number(number) => format_number(number, &EmitterOptions::new()),
+            Node::Boolean(value) => value.to_string(),
+            _ => String::new(),
+        };
+        self.text(key, true);
+        self.destination.add_bytes(&self.options.assignment);
+        self.text(&scalar, false);
+        self.destination.add_byte(b'\n');
+    }
+
+    /// Writes a key or value, escaped when the options ask for it.
+    fn text(&mut self, text: &str, key: bool) {
+        if !self.options.escape {
+            self.destination.add_bytes(text);
+            return;
+        }
+        let java = self.options.style == PropertiesStyle::Properties;
+        let mut start = 0;
+        for (index, c) in text.char_indices() {
+            let escape = match c {
+                '\\' => "\\\\",
+                '\t' => "\\t",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\u{c}' if java => "\\f",
+                // Leading spaces are skipped when a value is read, and spaces end a key
+                ' ' if java && (key || index == 0) => "\\ ",
+                '=' if java && key => "\\=",
+                ':' if java && key => "\\:",
+                '#' if java && index == 0 => "\\#",
+                '!' if java && index == 0 => "\\!",
+                c if java && (c.is_control() || !c.is_ascii()) => "",
+                _ => continue,
+            };
+            self.destination.add_bytes(&text[start..index]);
+            if escape.is_empty() {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    self.destination.add_bytes(&format!("\\u{:04X}", unit));
+                }
+            } else {
+                self.destination.add_bytes(escape);
+            }
+            start = index + c.len_utf8();
+        }
+        self.destination.add_bytes(&text[start..]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+
+    fn emit_with(node: &Node, options: &PropertiesOptions) -> Result<String, String> {
+        let mut destination = Buffer::new();
+        stringify_with_options(node, &mut destination, options).map(|_| destination.to_string())
+    }
+
+    fn config() -> Node {
+        Node::dict()
+            .key("name", "web")
+            .key("debug", false)
+            .key(
+                "server",
+                Node::dict()
+                    .key("__comment_0", Node::Comment("listener".to_string()))
+                    .key("host", "::1")
+                    .key("ports", vec![80, 443])
+                    .key("tls", Node::dict().key("cert", Node::None).build())
+                    .build(),
+            )
+            .key("db", Node::dict().key("url", "a=b c").build())
+            .build()
+    }
+
+    #[test]
+    fn stringify_properties_works() {
+        let expected = "db.url=a=b c\ndebug=false\nname=web\n# listener\nserver.host=::1\nserver.ports.0=80\n\
+                        server.ports.1=443\nserver.tls.cert=\n";
+        assert_eq!(emit_with(&config(), &PropertiesOptions::new()).unwrap(), expected);
+        let node = Node::dict().key("a key:=", " café\n#").key("#k", "!v").key("empty", Node::Array(vec![])).build();
+        let expected = "\\#k=\\!v\na\\ key\\:\\==\\ caf\\u00E9\\n#\nempty=\n";
+        assert_eq!(emit_with(&node, &PropertiesOptions::new()).unwrap(), expected);
+    }
+
+    #[test]
+    fn stringify_ini_works() {
+        let options = PropertiesOptions { assignment: " = ".to_string(), ..PropertiesOptions::ini() };
+        let expected = "debug = false\nname = web\n\n[db]\nurl = a=b c\n\n[server]\n; listener\nhost = ::1\n\
+                        ports.0 = 80\nports.1 = 443\ntls.cert = \n";
+        assert_eq!(emit_with(&config(), &options).unwrap(), expected);
+        let node = Node::dict().key("s", Node::dict().key("k", "a\\b\tc é").build()).build();
+        assert_eq!(emit_with(&node, &PropertiesOptions::ini()).unwrap(), "[s]\nk=a\\\\b\\tc é\n");
+    }
+
+    #[test]
+    fn stringify_properties_without_escaping() {
+        let options = PropertiesOptions { key_separator: "_".to_string(), escape: false, ..PropertiesOptions::new() };
+        let node = Node::dict().key("a b", Node::dict().key("c", "x\\y é").build()).build();
+        assert_eq!(emit_with(&node, &options).unwrap(), "a b_c=x\\y é\n");
+        let node = Node::dict().key("a", vec!["x\ny"]).build();
+        assert_eq!(emit_with(&node, &options).unwrap_err(), "Value at /a/0 holds a line break, which needs escaping");
+        assert_eq!(
+            emit_with(&Node::from(1), &options).unwrap_err(),
+            "Properties can only be written from a dictionary"
+        );
+    }
+}
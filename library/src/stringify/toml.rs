@@ -0,0 +1,487 @@
+//! TOML emitter.
+//! Writes a Dictionary node as a TOML document: scalar and array values as "key = value"
+//! lines, nested dictionaries as [table] sections (named by their dotted key path) and
+//! arrays of dictionaries as [[array-of-tables]] sections, in the dictionary's own key
+//! order unless sort_keys is turned on, in which case keys are written sorted. Small
+//! dictionaries of scalars can be written as inline tables ({ x = 1, y = 2 }) instead of
+//! sections, and dictionaries inside arrays always are. Comment values become "# text"
+//! lines. TOML has no null and a document must be a table, so nulls, streams of
+//! documents and a root that is not a dictionary are errors, as are arrays mixing value
+//! types when mixed_arrays is off (TOML before 1.0). Errors are reported before anything
+//! is written.
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::{Node, Numeric};
+use crate::nodes::path::join;
+use crate::stringify::loss::{LossKind, LossReport};
+use crate::stringify::number::format_number;
+use crate::stringify::options::EmitterOptions;
+
+/// Options used to configure the TOML emitter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TomlOptions {
+    /// Write dictionaries of at most this many scalars as inline tables rather than
+    /// [table] sections; 0 writes every dictionary outside an array as a section
+    pub inline_table_items: usize,
+    /// Allow arrays whose items have different types, as TOML 1.0 does; with this off
+    /// such arrays are an error, as they were before TOML 1.0
+    pub mixed_arrays: bool,
+    /// Write dictionary keys in sorted order rather than the order the dictionary holds
+    /// them in (insertion order, or as set by Node::sort_keys_by); off by default
+    pub sort_keys: bool,
+}
+
+impl TomlOptions {
+    /// Creates a new TomlOptions instance with default settings.
+    ///
+    /// # Returns
+    /// TomlOptions for TOML 1.0 with every dictionary written as a section.
+    pub fn new() -> Self {
+        Self { inline_table_items: 0, mixed_arrays: true, sort_keys: false }
+    }
+}
+
+impl Default for TomlOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes a Node tree to a destination as a TOML document.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write, which must be a Dictionary
+/// * `destination` - Where the TOML text is written
+///
+/// # Returns
+/// Ok, or an error naming the path of a value TOML cannot represent.
+pub fn stringify(node: &Node, destination: &mut dyn IDestination) -> Result<(), String> {
+    stringify_with_options(node, destination, &TomlOptions::new())
+}
+
+/// Writes a Node tree to a destination as a TOML document laid out according to the
+/// supplied options.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write, which must be a Dictionary
+/// * `destination` - Where the TOML text is written
+/// * `options` - Inline table, array and key order settings
+///
+/// # Returns
+/// Ok, or an error naming the path of a value TOML cannot represent; nothing is written
+/// when there is an error.
+pub fn stringify_with_options(
+    node: &Node,
+    destination: &mut dyn IDestination,
+    options: &TomlOptions,
+) -> Result<(), String> {
+    if !matches!(node, Node::Dictionary(_)) {
+        return Err("A TOML document must be a dictionary".to_string());
+    }
+    check(node, "", options)?;
+    let mut emitter = TomlEmitter { destination, options, started: false };
+    emitter.table(node, &[]);
+    Ok(())
+}
+
+/// Writes a Node tree to a destination as a TOML document laid out according to the
+/// supplied options and reports what the TOML does not hold.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write, which must be a Dictionary
+/// * `destination` - Where the TOML text is written
+/// * `options` - Inline table, array and key order settings
+///
+/// # Returns
+/// A report of the comments left out, or the error stringify_with_options returns.
+pub fn stringify_with_report(
+    node: &Node,
+    destination: &mut dyn IDestination,
+    options: &TomlOptions,
+) -> Result<LossReport, String> {
+    stringify_with_options(node, destination, options)?;
+    let mut report = LossReport::new();
+    losses(node, "", options, &mut report);
+    Ok(report)
+}
+
+/// Records the comments below a table that are left out: those inside values written
+/// inline and those between the tables of an array of tables.
+fn losses(node: &Node, path: &str, options: &TomlOptions, report: &mut LossReport) {
+    for (key, value) in node.entries() {
+        let child = join(path, key);
+        if is_section(value, options) {
+            losses(value, &child, options, report);
+        } else if let Node::Array(items) = value
+            && is_array_of_tables(value)
+        {
+            for (index, item) in items.iter().enumerate() {
+                let item_path = join(&child, &index.to_string());
+                if item.is_comment() {
+                    report.add(&item_path, LossKind::CommentDropped);
+                } else {
+                    losses(item, &item_path, options, report);
+                }
+            }
+        } else if !value.is_comment() {
+            report.comments_dropped(value, &child);
+        }
+    }
+}
+
+/// Returns an error naming the first value below a node that TOML cannot represent.
+fn check(node: &Node, path: &str, options: &TomlOptions) -> Result<(), String> {
+    let at = if path.is_empty() { "/" } else { path };
+    match node {
+        Node::None => Err(format!("Null at {} cannot be written as TOML", at)),
+        Node::Document(_) => Err(format!("A stream of documents at {} cannot be written as TOML", at)),
+        Node::Array(items) => {
+            let items: Vec<&Node> = items.iter().filter(|item| !item.is_comment()).collect();
+            if !options.mixed_arrays && items.windows(2).any(|pair| value_type(pair[0]) != value_type(pair[1])) {
+                return Err(format!("Array at {} mixes value types", at));
+            }
+            items.iter().enumerate().try_for_each(|(index, item)| check(item, &join(path, &index.to_string()), options))
+        }
+        Node::Dictionary(_) => node.entries().try_for_each(|(key, value)| check(value, &join(path, key), options)),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the name of the TOML type a value is written as.
+fn value_type(node: &Node) -> &'static str {
+    match node {
+        Node::Str(_) => "string",
+        Node::Boolean(_) => "boolean",
+        Node::Number(Numeric::Float(_)) => "float",
+        Node::Number(_) => "integer",
+        Node::Array(_) => "array",
+        _ => "table",
+    }
+}
+
+/// Returns true for keys TOML allows without quotes.
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Passes a string written as a TOML basic string to out, escaping quotes, backslashes and
+/// control characters; runs of characters that need no escape are passed as single slices.
+fn write_basic_string(text: &str, out: &mut dyn FnMut(&str)) {
+    out("\"");
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        let escape = match c {
+            '"' => "\\\"",
+            '\\' => "\\\\",
+            '\u{8}' => "\\b",
+            '\t' => "\\t",
+            '\n' => "\\n",
+            '\u{c}' => "\\f",
+            '\r' => "\\r",
+            c if c.is_control() => "",
+            _ => continue,
+        };
+        out(&text[start..index]);
+        if escape.is_empty() {
+            out(&format!("\\u{:04X}", c as u32));
+        } else {
+            out(escape);
+        }
+        start = index + c.len_utf8();
+    }
+    out(&text[start..]);
+    out("\"");
+}
+
+/// Returns true if a dictionary value is written as a section rather than inline.
+fn is_section(node: &Node, options: &TomlOptions) -> bool {
+    let Node::Dictionary(map) = node else {
+        return false;
+    };
+    let scalars = map.values().all(|value| !matches!(value, Node::Array(_) | Node::Dictionary(_)));
+    !(map.is_empty() || (scalars && map.len() <= options.inline_table_items))
+}
+
+/// Returns true if an array value is written as [[array-of-tables]] sections.
+fn is_array_of_tables(node: &Node) -> bool {
+    let Node::Array(items) = node else {
+        return false;
+    };
+    let items: Vec<&Node> = items.iter().filter(|item| !item.is_comment()).collect();
+    !items.is_empty() && items.iter().all(|item| matches!(item, Node::Dictionary(_)))
+}
+
+/// TOML writer for a single destination.
+struct TomlEmitter<'a> {
+    destination: &'a mut dyn IDestination,
+    options: &'a TomlOptions,
+    /// Something has been written, so the next section header is preceded by a blank line
+    started: bool,
+}
+
+impl TomlEmitter<'_> {
+    /// Returns the entries of a dictionary sorted by key, or in the dictionary's own order
+    /// when sort_keys is off.
+    fn entries<'n>(&self, node: &'n Node) -> Vec<(&'n str, &'n Node)> {
+        match self.options.sort_keys {
+            true => node.entries().collect(),
+            false => node.as_dict().into_iter().flatten().map(|(key, value)| (key.as_str(), value)).collect(),
+        }
+    }
+
+    fn key(&mut self, key: &str) {
+        if is_bare_key(key) {
+            self.destination.add_bytes(key);
+        } else {
+            self.string(key);
+        }
+    }
+
+    fn string(&mut self, text: &str) {
+        let destination = &mut *self.destination;
+        write_basic_string(text, &mut |part| destination.add_bytes(part));
+    }
+
+    /// Writes a section header line ("[a.b]" or "[[a.b]]").
+    fn header(&mut self, path: &[&str], open: &str, close: &str) {
+        if self.started {
+            self.destination.add_byte(b'\n');
+        }
+        self.destination.add_bytes(open);
+        for (index, key) in path.iter().enumerate() {
+            if index > 0 {
+                self.destination.add_byte(b'.');
+            }
+            self.key(key);
+        }
+        self.destination.add_bytes(close);
+        self.destination.add_byte(b'\n');
+        self.started = true;
+    }
+
+    /// Writes the entries of a table whose header (if any) has been written: its key/value
+    /// lines, then its sub-tables and arrays of tables as sections.
+    fn table(&mut self, node: &Node, path: &[&str]) {
+        let entries = self.entries(node);
+        for (key, value) in &entries {
+            if let Node::Comment(text) = value {
+                self.destination.add_bytes("# ");
+                self.destination.add_bytes(text);
+                self.destination.add_byte(b'\n');
+            } else if !is_section(value, self.options) && !is_array_of_tables(value) {
+                self.key(key);
+                self.destination.add_bytes(" = ");
+                self.value(value);
+                self.destination.add_byte(b'\n');
+            } else {
+                continue;
+            }
+            self.started = true;
+        }
+        for (key, value) in entries {
+            let mut child_path = path.to_vec();
+            child_path.push(key);
+            if is_section(value, self.options) {
+                // A table holding only sections needs no header of its own
+                if value.entries().any(|(_, child)| !is_section(child, self.options) && !is_array_of_tables(child)) {
+                    self.header(&child_path, "[", "]");
+                }
+                self.table(value, &child_path);
+            } else if let Node::Array(items) = value
+                && is_array_of_tables(value)
+            {
+                for item in items.iter().filter(|item| !item.is_comment()) {
+                    self.header(&child_path, "[[", "]]");
+                    self.table(item, &child_path);
+                }
+            }
+        }
+    }
+
+    /// Writes a value inline: scalars, arrays ([1, 2]) and inline tables ({ x = 1 }).
+    fn value(&mut self, node: &Node) {
+        match node {
+            Node::Str(text) => self.string(text),
+            Node::Boolean(value) => self.destination.add_bytes(if *value { "true" } else { "false" }),
+            Node::Number(Numeric::Float(value)) if value.is_nan() => self.destination.add_bytes("nan"),
+            Node::Number(Numeric::Float(value)) if value.is_infinite() => {
+                self.destination.add_bytes(if *value > 0.0 { "inf" } else { "-inf" })
+            }
+            Node::Number(number) => self.destination.add_bytes(&format_number(number, &EmitterOptions::new())),
+            Node::Array(items) => {
+                self.destination.add_byte(b'[');
+                for (index, item) in items.iter().filter(|item| !item.is_comment()).enumerate() {
+                    if index > 0 {
+                        self.destination.add_bytes(", ");
+                    }
+                    self.value(item);
+                }
+                self.destination.add_byte(b']');
+            }
+            Node::Dictionary(_) => {
+                let mut entries = self.entries(node);
+                entries.retain(|(_, value)| !value.is_comment());
+                if entries.is_empty() {
+                    self.destination.add_bytes("{}");
+                    return;
+                }
+                self.destination.add_bytes("{ ");
+                for (index, (key, value)) in entries.into_iter().enumerate() {
+                    if index > 0 {
+                        self.destination.add_bytes(", ");
+                    }
+                    self.key(key);
+                    self.destination.add_bytes(" = ");
+                    self.value(value);
+                }
+                self.destination.add_bytes(" }");
+            }
+            Node::None | Node::Comment(_) | Node::Document(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+
+    fn emit_with(node: &Node, options: &TomlOptions) -> Result<String, String> {
+        let mut destination = Buffer::new();
+        stringify_with_options(node, &mut destination, options).map(|_| destination.to_string())
+    }
+
+    fn sample() -> Node {
+        Node::dict()
+            .key("title", "Example \"app\"")
+            .key("__comment_1", Node::Comment("ports to open".to_string()))
+            .key("ports", vec![80, 443])
+            .key(
+                "server",
+                Node::dict()
+                    .key("host", "example.com")
+                    .key("tls", Node::dict().key("enabled", true).key("ratio", 0.5))
+                    .key("limits", Node::dict().key("cpu", 2)),
+            )
+            .key("owner", Node::dict().key("details", Node::dict().key("name", "ops")))
+            .key(
+                "users",
+                vec![
+                    Node::dict().key("name", "a").key("roles", vec!["admin"]).build(),
+                    Node::dict().key("name", "b").key("home", Node::dict().key("dir", "/b")).build(),
+                ],
+            )
+            .key("my key", f64::INFINITY)
+            .build()
+    }
+
+    #[test]
+    fn stringify_toml_works() {
+        let mut destination = Buffer::new();
+        stringify(&sample(), &mut destination).unwrap();
+        let expected = concat!(
+            "title = \"Example \\\"app\\\"\"\n",
+            "# ports to open\n",
+            "ports = [80, 443]\n",
+            "\"my key\" = inf\n",
+            "\n",
+            "[server]\n",
+            "host = \"example.com\"\n",
+            "\n",
+            "[server.tls]\n",
+            "enabled = true\n",
+            "ratio = 0.5\n",
+            "\n",
+            "[server.limits]\n",
+            "cpu = 2\n",
+            "\n",
+            "[owner.details]\n",
+            "name = \"ops\"\n",
+            "\n",
+            "[[users]]\n",
+            "name = \"a\"\n",
+            "roles = [\"admin\"]\n",
+            "\n",
+            "[[users]]\n",
+            "name = \"b\"\n",
+            "\n",
+            "[users.home]\n",
+            "dir = \"/b\"\n",
+        );
+        assert_eq!(destination.to_string(), expected);
+    }
+
+    #[test]
+    fn stringify_toml_inline_tables_work() {
+        let options = TomlOptions { inline_table_items: 2, ..TomlOptions::new() };
+        let node = Node::dict()
+            .key("point", Node::dict().key("x", 1).key("y", 2))
+            .key("empty", Node::dict().build())
+            .key("list", vec![Node::dict().key("a", 1).build(), Node::from(vec![1, 2])])
+            .build();
+        let expected = "point = { x = 1, y = 2 }\nempty = {}\nlist = [{ a = 1 }, [1, 2]]\n";
+        assert_eq!(emit_with(&node, &options).unwrap(), expected);
+        let expected = "empty = {}\nlist = [{ a = 1 }, [1, 2]]\n\n[point]\nx = 1\ny = 2\n";
+        assert_eq!(emit_with(&node, &TomlOptions::new()).unwrap(), expected);
+    }
+
+    #[test]
+    fn stringify_toml_keeps_dictionary_order() {
+        let options = TomlOptions { inline_table_items: 2, ..TomlOptions::new() };
+        let node = Node::dict()
+            .key("name", "web")
+            .key("server", Node::dict().key("port", 80).key("host", "a").key("tls", Node::dict().key("on", true)))
+            .key("point", Node::dict().key("y", 2).key("x", 1))
+            .build();
+        let expected = concat!(
+            "name = \"web\"\n",
+            "point = { y = 2, x = 1 }\n",
+            "\n",
+            "[server]\n",
+            "port = 80\n",
+            "host = \"a\"\n",
+            "tls = { on = true }\n",
+        );
+        assert_eq!(emit_with(&node, &options).unwrap(), expected);
+        let options = TomlOptions { sort_keys: true, ..options };
+        let expected = concat!(
+            "name = \"web\"\n",
+            "point = { x = 1, y = 2 }\n",
+            "\n",
+            "[server]\n",
+            "host = \"a\"\n",
+            "port = 80\n",
+            "tls = { on = true }\n",
+        );
+        assert_eq!(emit_with(&node, &options).unwrap(), expected);
+    }
+
+    #[test]
+    fn stringify_toml_with_report_works() {
+        let comment = |text: &str| Node::Comment(text.to_string());
+        let node = Node::dict()
+            .key("__comment_0", comment("kept"))
+            .key("ports", vec![Node::from(80), comment("tls")])
+            .key("servers", vec![comment("between"), Node::dict().key("__comment_0", comment("in table")).build()])
+            .build();
+        let mut destination = Buffer::new();
+        let report = stringify_with_report(&node, &mut destination, &TomlOptions::new()).unwrap();
+        let paths: Vec<&str> = report.iter().map(|loss| loss.path.as_str()).collect();
+        assert_eq!(paths, vec!["/ports/1", "/servers/0"]);
+        assert!(destination.to_string().contains("# kept\n") && destination.to_string().contains("# in table\n"));
+    }
+
+    #[test]
+    fn stringify_toml_rejects_unrepresentable_values() {
+        let options = TomlOptions::new();
+        assert_eq!(emit_with(&Node::from(vec![1]), &options).unwrap_err(), "A TOML document must be a dictionary");
+        let null = Node::dict().key("a", Node::dict().key("b", Node::None)).build();
+        assert_eq!(emit_with(&null, &options).unwrap_err(), "Null at /a/b cannot be written as TOML");
+        let mixed = Node::dict().key("a", vec![Node::from(1), Node::from("x")]).build();
+        assert_eq!(emit_with(&mixed, &options).unwrap(), "a = [1, \"x\"]\n");
+        let strict = TomlOptions { mixed_arrays: false, ..TomlOptions::new() };
+        assert_eq!(emit_with(&mixed, &strict).unwrap_err(), "Array at /a mixes value types");
+        let text = Node::dict().key("a", "tab\tbell\u{7}").build();
+        assert_eq!(emit_with(&text, &options).unwrap(), "a = \"tab\\tbell\\u0007\"\n");
+    }
+}
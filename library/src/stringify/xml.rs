@@ -0,0 +1,492 @@
+//! XML emitter.
+//! Writes a Node tree as an XML document inside a single root element: dictionary entries
+//! become child elements named after their keys (in the dictionary's own order, or
+//! sorted when sort_keys is on), array items become repeated item elements and
+//! scalars become element text. Keys starting with the attribute prefix ("@" by default)
+//! whose values are scalars become attributes of the element instead. Nulls and empty
+//! collections are written as empty elements ("<key/>"), comments as "<!-- text -->" and
+//! a stream of documents as one item element per document.
+//! Keys that are not valid XML names have their invalid characters replaced by '_'.
+//! Text is escaped so the document is always well formed, and long strings can be
+//! written as CDATA sections instead (see XmlOptions::cdata_min_length).
+
+use crate::io::traits::IDestination;
+use crate::nodes::node::Node;
+use crate::nodes::path::join;
+use crate::stringify::loss::{LossKind, LossReport};
+use crate::stringify::number::format_number;
+use crate::stringify::options::EmitterOptions;
+
+/// Default name of the element wrapping the whole tree.
+pub const DEFAULT_ROOT_NAME: &str = "root";
+
+/// Default name of the elements array items are written as.
+pub const DEFAULT_ITEM_NAME: &str = "item";
+
+/// Default prefix marking dictionary keys written as attributes.
+pub const DEFAULT_ATTRIBUTE_PREFIX: &str = "@";
+
+/// Options used to configure the XML emitter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XmlOptions {
+    /// Name of the element wrapping the whole tree
+    pub root_name: String,
+    /// Name of the elements array items (and the documents of a stream) are written as
+    pub item_name: String,
+    /// Prefix marking dictionary keys whose scalar values are written as attributes of
+    /// the enclosing element, or None to write every key as an element
+    pub attribute_prefix: Option<String>,
+    /// Write an "<?xml version="1.0" encoding="UTF-8"?>" declaration first
+    pub declaration: bool,
+    /// Write each element on its own line with nested elements indented
+    pub pretty: bool,
+    /// Number of spaces each nesting level is indented by in pretty output
+    pub indent: usize,
+    /// Write string values at least this many bytes long as CDATA sections rather than
+    /// escaping their markup characters, or None to always escape. Strings holding
+    /// carriage returns or characters XML cannot hold are always escaped, as a CDATA
+    /// section cannot keep them
+    pub cdata_min_length: Option<usize>,
+    /// Write dictionary keys in sorted order rather than the order the dictionary holds
+    /// them in (insertion order, or as set by Node::sort_keys_by); off by default
+    pub sort_keys: bool,
+}
+
+impl XmlOptions {
+    /// Creates a new XmlOptions instance with default settings.
+    ///
+    /// # Returns
+    /// XmlOptions for a pretty printed document with a declaration, a "root" element,
+    /// "item" array elements and "@" attribute keys.
+    pub fn new() -> Self {
+        Self {
+            root_name: DEFAULT_ROOT_NAME.to_string(),
+            item_name: DEFAULT_ITEM_NAME.to_string(),
+            attribute_prefix: Some(DEFAULT_ATTRIBUTE_PREFIX.to_string()),
+            declaration: true,
+            pretty: true,
+            indent: 2,
+            cdata_min_length: None,
+            sort_keys: false,
+        }
+    }
+}
+
+impl Default for XmlOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes a Node tree to a destination as an XML document.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the XML text is written
+///
+/// # Returns
+/// Ok, or an error describing why the tree could not be written.
+pub fn stringify(node: &Node, destination: &mut dyn IDestination) -> Result<(), String> {
+    stringify_with_options(node, destination, &XmlOptions::new())
+}
+
+/// Writes a Node tree to a destination as an XML document laid out according to the
+/// supplied options.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the XML text is written
+/// * `options` - Element naming, attribute and layout settings
+///
+/// # Returns
+/// Ok, or an error describing why the tree could not be written.
+pub fn stringify_with_options(
+    node: &Node,
+    destination: &mut dyn IDestination,
+    options: &XmlOptions,
+) -> Result<(), String> {
+    if !is_name(&options.root_name) || !is_name(&options.item_name) {
+        return Err(format!("Invalid XML element name: {} or {}", options.root_name, options.item_name));
+    }
+    let mut emitter = XmlEmitter { destination, options };
+    if options.declaration {
+        emitter.destination.add_bytes("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+        emitter.line();
+    }
+    emitter.element(&options.root_name, node, 0);
+    Ok(())
+}
+
+/// Writes a Node tree to a destination as an XML document laid out according to the
+/// supplied options and reports what the XML does not hold exactly.
+///
+/// # Arguments
+/// * `node` - Root of the tree to write
+/// * `destination` - Where the XML text is written
+/// * `options` - Naming, layout and CDATA settings
+///
+/// # Returns
+/// A report of the values written as untyped text or empty elements, the keys renamed
+/// and the text altered, or the error stringify_with_options returns.
+pub fn stringify_with_report(
+    node: &Node,
+    destination: &mut dyn IDestination,
+    options: &XmlOptions,
+) -> Result<LossReport, String> {
+    stringify_with_options(node, destination, options)?;
+    let mut report = LossReport::new();
+    losses(node, "", options, &mut report);
+    Ok(report)
+}
+
+/// Records the values below a node that XML does not hold exactly.
+fn losses(node: &Node, path: &str, options: &XmlOptions, report: &mut LossReport) {
+    match node {
+        Node::Str(text) if !text.chars().all(is_xml_char) => report.add(path, LossKind::TextAltered),
+        Node::Comment(text) if comment_text(text) != *text => report.add(path, LossKind::TextAltered),
+        Node::Str(_) | Node::Comment(_) => {}
+        Node::Array(items) | Node::Document(items) => {
+            if node.is_document() {
+                report.add(path, LossKind::StreamAsArray);
+            } else if items.is_empty() {
+                report.add(path, LossKind::TypeLost(node.kind()));
+            }
+            for (index, item) in items.iter().enumerate() {
+                losses(item, &join(path, &index.to_string()), options, report);
+            }
+        }
+        Node::Dictionary(map) => {
+            if map.is_empty() {
+                report.add(path, LossKind::TypeLost(node.kind()));
+            }
+            for (key, value) in node.entries() {
+                let child = join(path, key);
+                let written = attribute(key, value, options).unwrap_or(key);
+                let name = element_name(written);
+                if name != written && !value.is_comment() {
+                    report.add(&child, LossKind::KeyRenamed(name));
+                }
+                losses(value, &child, options, report);
+            }
+        }
+        _ => report.add(path, LossKind::TypeLost(node.kind())),
+    }
+}
+
+/// Returns true for valid XML names, limited to ASCII letters, digits and "_-." after
+/// an initial letter or '_'.
+fn is_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Returns a key as a valid XML name, replacing invalid characters with '_' and adding a
+/// leading '_' where the key does not start with a letter.
+fn element_name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '_' | '-' | '.') { c } else { '_' })
+        .collect();
+    if name.starts_with(|c: char| c.is_alphabetic() || c == '_') { name } else { format!("_{}", name) }
+}
+
+/// Returns true for characters XML 1.0 allows in a document, escaped or not.
+fn is_xml_char(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r') || (c >= ' ' && !matches!(c, '\u{FFFE}' | '\u{FFFF}'))
+}
+
+/// Returns comment text made safe to write between "<!-- " and " -->": invalid characters
+/// are replaced with U+FFFD and runs of dashes, as "--" may not appear in a comment, are
+/// split with spaces.
+pub(crate) fn comment_text(text: &str) -> String {
+    let mut safe = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '-' && safe.ends_with('-') {
+            safe.push(' ');
+        }
+        safe.push(if is_xml_char(c) { c } else { '\u{FFFD}' });
+    }
+    safe
+}
+
+/// Passes text escaped for XML element content or (with attribute set) a double quoted
+/// attribute value to out; runs of characters that need no escape are passed as single
+/// slices. Markup characters and quotes become entity references, carriage returns, DEL
+/// and C1 controls (and tabs and line feeds in attributes) character references, so
+/// readers do not normalize them away, and characters XML cannot hold at all become
+/// U+FFFD.
+pub(crate) fn write_escaped(text: &str, attribute: bool, out: &mut dyn FnMut(&str)) {
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        let escape = match c {
+            '&' => "&amp;",
+            '<' => "&lt;",
+            '>' => "&gt;",
+            '"' => "&quot;",
+            '\'' => "&apos;",
+            '\r' => "&#13;",
+            '\t' if attribute => "&#9;",
+            '\n' if attribute => "&#10;",
+            c if !is_xml_char(c) => "\u{FFFD}",
+            '\u{7F}'..='\u{9F}' => "",
+            _ => continue,
+        };
+        out(&text[start..index]);
+        if escape.is_empty() {
+            out(&format!("&#x{:X};", c as u32));
+        } else {
+            out(escape);
+        }
+        start = index + c.len_utf8();
+    }
+    out(&text[start..]);
+}
+
+/// Passes text as one or more CDATA sections to out, splitting any "]]>" in it across two
+/// sections.
+fn write_cdata(text: &str, out: &mut dyn FnMut(&str)) {
+    out("<![CDATA[");
+    for (index, part) in text.split("]]>").enumerate() {
+        if index > 0 {
+            out("]]]]><![CDATA[>");
+        }
+        out(part);
+    }
+    out("]]>");
+}
+
+/// Returns the text a scalar is written as.
+fn scalar_text(node: &Node) -> Option<String> {
+    match node {
+        Node::Boolean(value) => Some(value.to_string()),
+        Node::Number(number) => Some(format_number(number, &EmitterOptions::new())),
+        Node::Str(text) => Some(text.clone()),
+        _ => None,
+    }
+}
+
+/// Returns the attribute name of a dictionary entry written as an attribute.
+fn attribute<'k>(key: &'k str, value: &Node, options: &XmlOptions) -> Option<&'k str> {
+    let prefix = options.attribute_prefix.as_deref()?;
+    key.strip_prefix(prefix).filter(|_| scalar_text(value).is_some() || value.is_null())
+}
+
+/// XML writer for a single destination.
+struct XmlEmitter<'a> {
+    destination: &'a mut dyn IDestination,
+    options: &'a XmlOptions,
+}
+
+impl XmlEmitter<'_> {
+    /// Returns the entries of a dictionary sorted by key, or in the dictionary's own order
+    /// when sort_keys is off.
+    fn entries<'n>(&self, node: &'n Node) -> Vec<(&'n str, &'n Node)> {
+        match self.options.sort_keys {
+            true => node.entries().collect(),
+            false => node.as_dict().into_iter().flatten().map(|(key, value)| (key.as_str(), value)).collect(),
+        }
+    }
+
+    /// Ends a line in pretty output.
+    fn line(&mut self) {
+        if self.options.pretty {
+            self.destination.add_byte(b'\n');
+        }
+    }
+
+    fn indent(&mut self, level: usize) {
+        if self.options.pretty {
+            for _ in 0..level * self.options.indent {
+                self.destination.add_byte(b' ');
+            }
+        }
+    }
+
+    fn escaped(&mut self, text: &str, attribute: bool) {
+        let destination = &mut *self.destination;
+        write_escaped(text, attribute, &mut |part| destination.add_bytes(part));
+    }
+
+    /// Writes the text of an element, as a CDATA section if it is long enough.
+    fn content(&mut self, text: &str) {
+        let cdata = self.options.cdata_min_length.is_some_and(|length| text.len() >= length)
+            && text.chars().all(|c| c != '\r' && is_xml_char(c));
+        if cdata {
+            let destination = &mut *self.destination;
+            write_cdata(text, &mut |part| destination.add_bytes(part));
+        } else {
+            self.escaped(text, false);
+        }
+    }
+
+    /// Writes a node as an element on its own line (or a comment for comment nodes).
+    fn element(&mut self, name: &str, node: &Node, level: usize) {
+        self.indent(level);
+        if let Node::Comment(text) = node {
+            self.destination.add_bytes("<!-- ");
+            self.destination.add_bytes(&comment_text(text));
+            self.destination.add_bytes(" -->");
+            self.line();
+            return;
+        }
+        self.destination.add_byte(b'<');
+        self.destination.add_bytes(name);
+        let mut children: Vec<(String, &Node)> = Vec::new();
+        match node {
+            Node::Dictionary(_) => {
+                for (key, value) in self.entries(node) {
+                    if let Some(attribute) = attribute(key, value, self.options) {
+                        self.destination.add_byte(b' ');
+                        self.destination.add_bytes(&element_name(attribute));
+                        self.destination.add_bytes("=\"");
+                        self.escaped(&scalar_text(value).unwrap_or_default(), true);
+                        self.destination.add_byte(b'"');
+                    } else {
+                        children.push((element_name(key), value));
+                    }
+                }
+            }
+            Node::Array(items) | Node::Document(items) => {
+                let item_name = &self.options.item_name;
+                children.extend(items.iter().map(|item| (item_name.clone(), item)));
+            }
+            _ => {}
+        }
+        if let Some(text) = scalar_text(node) {
+            self.destination.add_byte(b'>');
+            self.content(&text);
+        } else if children.is_empty() {
+            self.destination.add_bytes("/>");
+            self.line();
+            return;
+        } else {
+            self.destination.add_byte(b'>');
+            self.line();
+            for (child_name, child) in children {
+                self.element(&child_name, child, level + 1);
+            }
+            self.indent(level);
+        }
+        self.destination.add_bytes("</");
+        self.destination.add_bytes(name);
+        self.destination.add_byte(b'>');
+        self.line();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::destinations::buffer::Buffer;
+
+    fn emit_with(node: &Node, options: &XmlOptions) -> String {
+        let mut destination = Buffer::new();
+        stringify_with_options(node, &mut destination, options).unwrap();
+        destination.to_string()
+    }
+
+    fn sample() -> Node {
+        Node::dict()
+            .key("name", "a < b & \"c\"")
+            .key("ports", vec![80, 443])
+            .key("server", Node::dict().key("@host", "example.com").key("@port", 8080).key("tls", true))
+            .key("empty", Node::None)
+            .key("2nd key", "x")
+            .key("__comment_1", Node::Comment("note".to_string()))
+            .build()
+    }
+
+    #[test]
+    fn stringify_xml_works() {
+        let mut destination = Buffer::new();
+        stringify(&sample(), &mut destination).unwrap();
+        let expected = concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<root>\n",
+            "  <name>a &lt; b &amp; &quot;c&quot;</name>\n",
+            "  <ports>\n",
+            "    <item>80</item>\n",
+            "    <item>443</item>\n",
+            "  </ports>\n",
+            "  <server host=\"example.com\" port=\"8080\">\n",
+            "    <tls>true</tls>\n",
+            "  </server>\n",
+            "  <empty/>\n",
+            "  <_2nd_key>x</_2nd_key>\n",
+            "  <!-- note -->\n",
+            "</root>\n",
+        );
+        assert_eq!(destination.to_string(), expected);
+    }
+
+    #[test]
+    fn stringify_xml_with_report_works() {
+        let mut destination = Buffer::new();
+        let report = stringify_with_report(&sample(), &mut destination, &XmlOptions::new()).unwrap();
+        let messages: Vec<String> = report.iter().map(|loss| loss.to_string()).collect();
+        let expected = vec![
+            "Key at /2nd key was renamed to _2nd_key",
+            "Value at /empty lost its null type",
+            "Value at /ports/0 lost its number type",
+            "Value at /ports/1 lost its number type",
+            "Value at /server/@port lost its number type",
+            "Value at /server/tls lost its boolean type",
+        ];
+        assert_eq!(messages, expected);
+        let node = Node::dict().key("a", "x\u{0}").key("b", Node::Comment("--".to_string())).build();
+        let report = stringify_with_report(&node, &mut Buffer::new(), &XmlOptions::new()).unwrap();
+        let kinds: Vec<LossKind> = report.losses.into_iter().map(|loss| loss.kind).collect();
+        assert_eq!(kinds, vec![LossKind::TextAltered, LossKind::TextAltered]);
+    }
+
+    #[test]
+    fn stringify_xml_options_work() {
+        let options = XmlOptions {
+            root_name: "config".to_string(),
+            item_name: "port".to_string(),
+            attribute_prefix: None,
+            declaration: false,
+            pretty: false,
+            ..XmlOptions::new()
+        };
+        let node = Node::dict().key("ports", vec![80, 443]).key("@id", 1).build();
+        let expected = "<config><ports><port>80</port><port>443</port></ports><_id>1</_id></config>";
+        assert_eq!(emit_with(&node, &options), expected);
+        let options = XmlOptions { declaration: false, pretty: false, ..XmlOptions::new() };
+        assert_eq!(emit_with(&node, &options), "<root id=\"1\"><ports><item>80</item><item>443</item></ports></root>");
+        assert_eq!(emit_with(&Node::from("text"), &options), "<root>text</root>");
+        let invalid = XmlOptions { root_name: "1root".to_string(), ..XmlOptions::new() };
+        assert!(stringify_with_options(&node, &mut Buffer::new(), &invalid).is_err());
+    }
+
+    #[test]
+    fn stringify_xml_keeps_dictionary_order() {
+        let options = XmlOptions { declaration: false, pretty: false, ..XmlOptions::new() };
+        let node = Node::dict().key("b", 1).key("@z", 2).key("a", 3).key("@y", 4).build();
+        assert_eq!(emit_with(&node, &options), "<root z=\"2\" y=\"4\"><b>1</b><a>3</a></root>");
+        let sorted = XmlOptions { sort_keys: true, ..options };
+        assert_eq!(emit_with(&node, &sorted), "<root y=\"4\" z=\"2\"><a>3</a><b>1</b></root>");
+    }
+
+    #[test]
+    fn stringify_xml_escapes_text() {
+        let options = XmlOptions { declaration: false, pretty: false, ..XmlOptions::new() };
+        let node = Node::dict().key("@a", "tab\there\n'q'").key("t", "x\r\n\u{1}\u{85}<&>").build();
+        let expected = "<root a=\"tab&#9;here&#10;&apos;q&apos;\"><t>x&#13;\n\u{FFFD}&#x85;&lt;&amp;&gt;</t></root>";
+        assert_eq!(emit_with(&node, &options), expected);
+        let comment = Node::from(vec![Node::Comment("a -- b\u{0} ---".to_string())]);
+        assert_eq!(emit_with(&comment, &options), "<root><!-- a - - b\u{FFFD} - - - --></root>");
+    }
+
+    #[test]
+    fn stringify_xml_cdata_works() {
+        let options = XmlOptions { declaration: false, pretty: false, cdata_min_length: Some(8), ..XmlOptions::new() };
+        let node = Node::dict().key("short", "<b>").key("long", "<p>a]]>b & c</p>").key("cr", "<p>\r\n</p>").build();
+        let expected = concat!(
+            "<root><short>&lt;b&gt;</short>",
+            "<long><![CDATA[<p>a]]]]><![CDATA[>b & c</p>]]></long>",
+            "<cr>&lt;p&gt;&#13;\n&lt;/p&gt;</cr></root>"
+        );
+        assert_eq!(emit_with(&node, &options), expected);
+    }
+}
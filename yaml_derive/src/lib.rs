@@ -0,0 +1,314 @@
+//! yaml_derive - Derive macros for the yaml_lib ToNode and FromNode traits
+//!
+//! Structs with named fields map to dictionaries, newtype structs to their inner value,
+//! tuple structs to arrays and unit structs to null. Enum unit variants map to their
+//! name as a string and other variants to a single entry dictionary keyed by the name.
+//!
+//! Supported attributes:
+//! - `#[yaml(rename = "name")]` on a field or variant uses a different key
+//! - `#[yaml(default)]` on a field uses Default::default() when the key is missing or null
+//! - `#[yaml(flatten)]` on a field merges its dictionary into the parent
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Fields, Generics, Ident, LitStr};
+
+/// Options set with #[yaml(...)] on a field or variant.
+#[derive(Default)]
+struct Options {
+    /// Key used instead of the field or variant name
+    rename: Option<String>,
+    /// Use Default::default() for a missing or null value
+    default: bool,
+    /// Merge the field's dictionary into the parent
+    flatten: bool,
+}
+
+/// Reads the #[yaml(...)] attributes of a field or variant.
+fn parse_options(attributes: &[Attribute]) -> syn::Result<Options> {
+    let mut options = Options::default();
+    for attribute in attributes.iter().filter(|a| a.path().is_ident("yaml")) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                options.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("default") {
+                options.default = true;
+            } else if meta.path.is_ident("flatten") {
+                options.flatten = true;
+            } else {
+                return Err(meta.error("unsupported yaml attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+/// Returns the key for a field or variant, honouring any rename.
+fn key_of(ident: &Ident, options: &Options) -> String {
+    options.rename.clone().unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string())
+}
+
+/// Adds a bound on the given trait to every type parameter.
+fn add_bounds(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    for parameter in generics.type_params_mut() {
+        parameter.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+/// Derives yaml_lib::ToNode.
+#[proc_macro_derive(ToNode, attributes(yaml))]
+pub fn derive_to_node(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_to_node(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Derives yaml_lib::FromNode.
+#[proc_macro_derive(FromNode, attributes(yaml))]
+pub fn derive_from_node(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_node(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Returns statements inserting named fields (accessed through `access`) into `map`.
+fn insert_named_fields(fields: &Fields, access: impl Fn(&Ident) -> TokenStream2) -> syn::Result<TokenStream2> {
+    let mut statements = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let options = parse_options(&field.attrs)?;
+        let value = access(ident);
+        if options.flatten {
+            statements.push(quote! {
+                if let ::yaml_lib::Node::Dictionary(inner) = ::yaml_lib::ToNode::to_node(#value) {
+                    map.extend(inner);
+                }
+            });
+        } else {
+            let key = key_of(ident, &options);
+            statements.push(quote! {
+                map.insert(::std::string::String::from(#key), ::yaml_lib::ToNode::to_node(#value));
+            });
+        }
+    }
+    Ok(quote! {
+        let mut map = ::yaml_lib::IndexMap::new();
+        #(#statements)*
+        ::yaml_lib::Node::Dictionary(map)
+    })
+}
+
+fn expand_to_node(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, quote!(::yaml_lib::ToNode));
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let body = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(_) => insert_named_fields(&data.fields, |ident| quote!(&self.#ident))?,
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote!(::yaml_lib::ToNode::to_node(&self.0)),
+            Fields::Unnamed(fields) => {
+                let indices = (0..fields.unnamed.len()).map(syn::Index::from);
+                quote!(::yaml_lib::Node::Array(::std::vec![#(::yaml_lib::ToNode::to_node(&self.#indices)),*]))
+            }
+            Fields::Unit => quote!(::yaml_lib::Node::None),
+        },
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let ident = &variant.ident;
+                let key = key_of(ident, &parse_options(&variant.attrs)?);
+                let arm = match &variant.fields {
+                    Fields::Unit => quote! {
+                        #name::#ident => ::yaml_lib::Node::Str(::std::string::String::from(#key)),
+                    },
+                    Fields::Unnamed(fields) => {
+                        let bindings: Vec<Ident> = (0..fields.unnamed.len()).map(|i| format_ident!("field{}", i)).collect();
+                        let value = if bindings.len() == 1 {
+                            quote!(::yaml_lib::ToNode::to_node(field0))
+                        } else {
+                            quote!(::yaml_lib::Node::Array(::std::vec![#(::yaml_lib::ToNode::to_node(#bindings)),*]))
+                        };
+                        quote! {
+                            #name::#ident(#(#bindings),*) => {
+                                let mut map = ::yaml_lib::IndexMap::new();
+                                map.insert(::std::string::String::from(#key), #value);
+                                ::yaml_lib::Node::Dictionary(map)
+                            }
+                        }
+                    }
+                    Fields::Named(_) => {
+                        let bindings = variant.fields.iter().map(|f| f.ident.as_ref().expect("named field"));
+                        let inner = insert_named_fields(&variant.fields, |ident| quote!(#ident))?;
+                        quote! {
+                            #name::#ident { #(#bindings),* } => {
+                                let value = { #inner };
+                                let mut map = ::yaml_lib::IndexMap::new();
+                                map.insert(::std::string::String::from(#key), value);
+                                ::yaml_lib::Node::Dictionary(map)
+                            }
+                        }
+                    }
+                };
+                arms.push(arm);
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => return Err(syn::Error::new_spanned(name, "ToNode cannot be derived for unions")),
+    };
+    Ok(quote! {
+        impl #impl_generics ::yaml_lib::ToNode for #name #type_generics #where_clause {
+            fn to_node(&self) -> ::yaml_lib::Node {
+                #body
+            }
+        }
+    })
+}
+
+/// Returns a guard checking that `source` is a dictionary, and field initializers reading
+/// named fields from it.
+fn read_named_fields(fields: &Fields, source: TokenStream2) -> syn::Result<(TokenStream2, TokenStream2)> {
+    let mut initializers = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let options = parse_options(&field.attrs)?;
+        let key = key_of(ident, &options);
+        let value = if options.flatten {
+            quote!(::yaml_lib::FromNode::from_node(#source)?)
+        } else if options.default {
+            quote! {
+                match #source.get(#key) {
+                    ::std::option::Option::None | ::std::option::Option::Some(::yaml_lib::Node::None) => {
+                        ::std::default::Default::default()
+                    }
+                    ::std::option::Option::Some(value) => {
+                        ::yaml_lib::FromNode::from_node(value).map_err(|e| e.within(#key))?
+                    }
+                }
+            }
+        } else {
+            quote!(::yaml_lib::from_field(#source, #key)?)
+        };
+        initializers.push(quote!(#ident: #value));
+    }
+    let guard = quote! {
+        if #source.as_dict().is_none() {
+            return ::std::result::Result::Err(::yaml_lib::ConversionError::new("dictionary", #source));
+        }
+    };
+    Ok((guard, quote!(#(#initializers),*)))
+}
+
+/// Returns an expression converting the items of the array `source` into a tuple-like constructor.
+fn read_unnamed_fields(constructor: TokenStream2, count: usize, source: TokenStream2) -> TokenStream2 {
+    let indices = 0..count;
+    let segments = (0..count).map(|i| i.to_string());
+    quote! {{
+        let items = match #source.as_array() {
+            ::std::option::Option::Some(items) if items.len() == #count => items,
+            _ => {
+                let expected = ::std::format!("array of {} items", #count);
+                return ::std::result::Result::Err(::yaml_lib::ConversionError::new(&expected, #source));
+            }
+        };
+        #constructor(#(::yaml_lib::FromNode::from_node(&items[#indices]).map_err(|e| e.within(#segments))?),*)
+    }}
+}
+
+fn expand_from_node(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let type_name = name.to_string();
+    let generics = add_bounds(&input.generics, quote!(::yaml_lib::FromNode));
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let body = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(_) => {
+                let (guard, initializers) = read_named_fields(&data.fields, quote!(node))?;
+                quote! {
+                    #guard
+                    ::std::result::Result::Ok(#name { #initializers })
+                }
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                quote!(::std::result::Result::Ok(#name(::yaml_lib::FromNode::from_node(node)?)))
+            }
+            Fields::Unnamed(fields) => {
+                let value = read_unnamed_fields(quote!(#name), fields.unnamed.len(), quote!(node));
+                quote!(::std::result::Result::Ok(#value))
+            }
+            Fields::Unit => quote! {
+                match node {
+                    ::yaml_lib::Node::None => ::std::result::Result::Ok(#name),
+                    _ => ::std::result::Result::Err(::yaml_lib::ConversionError::new("null", node)),
+                }
+            },
+        },
+        Data::Enum(data) => {
+            let mut unit_arms = Vec::new();
+            let mut entry_arms = Vec::new();
+            for variant in &data.variants {
+                let ident = &variant.ident;
+                let key = key_of(ident, &parse_options(&variant.attrs)?);
+                match &variant.fields {
+                    Fields::Unit => unit_arms.push(quote!(#key => return ::std::result::Result::Ok(#name::#ident),)),
+                    Fields::Unnamed(fields) if fields.unnamed.len() == 1 => entry_arms.push(quote! {
+                        #key => ::yaml_lib::FromNode::from_node(value).map(#name::#ident).map_err(|e| e.within(#key)),
+                    }),
+                    Fields::Unnamed(fields) => {
+                        let value = read_unnamed_fields(quote!(#name::#ident), fields.unnamed.len(), quote!(value));
+                        entry_arms.push(quote! {
+                            #key => (|| ::std::result::Result::Ok(#value))().map_err(|e: ::yaml_lib::ConversionError| e.within(#key)),
+                        });
+                    }
+                    Fields::Named(_) => {
+                        let (guard, initializers) = read_named_fields(&variant.fields, quote!(value))?;
+                        entry_arms.push(quote! {
+                            #key => (|| {
+                                #guard
+                                ::std::result::Result::Ok(#name::#ident { #initializers })
+                            })().map_err(|e: ::yaml_lib::ConversionError| e.within(#key)),
+                        });
+                    }
+                }
+            }
+            quote! {
+                let unknown = |variant: &str| ::yaml_lib::ConversionError {
+                    expected: ::std::format!("variant of {}", #type_name),
+                    found: ::std::format!("unknown variant {}", variant),
+                    path: ::std::string::String::new(),
+                };
+                match node {
+                    ::yaml_lib::Node::Str(variant) => {
+                        match variant.as_str() {
+                            #(#unit_arms)*
+                            _ => {}
+                        }
+                        ::std::result::Result::Err(unknown(variant))
+                    }
+                    ::yaml_lib::Node::Dictionary(map) if map.len() == 1 => {
+                        let (variant, value) = map.iter().next().expect("single entry");
+                        match variant.as_str() {
+                            #(#entry_arms)*
+                            _ => ::std::result::Result::Err(unknown(variant)),
+                        }
+                    }
+                    _ => ::std::result::Result::Err(::yaml_lib::ConversionError::new(#type_name, node)),
+                }
+            }
+        }
+        Data::Union(_) => return Err(syn::Error::new_spanned(name, "FromNode cannot be derived for unions")),
+    };
+    Ok(quote! {
+        impl #impl_generics ::yaml_lib::FromNode for #name #type_generics #where_clause {
+            #[allow(unused_variables, unreachable_code)]
+            fn from_node(node: &::yaml_lib::Node) -> ::std::result::Result<Self, ::yaml_lib::ConversionError> {
+                #body
+            }
+        }
+    })
+}